@@ -18,10 +18,7 @@ use symphonia_core::io::{ReadBitsLtr, BitReaderLtr};
 use symphonia_core::support_codec;
 use symphonia_core::util::bits::sign_extend_leq32_to_i32;
 
-use log::info;
-
 use super::frame::*;
-use super::validate::Validator;
 
 fn decorrelate_left_side(left: &[i32], side: &mut [i32]) {
     for (s, l) in side.iter_mut().zip(left) {
@@ -78,14 +75,12 @@ fn decorrelate_right_side(right: &[i32], side: &mut [i32]) {
 /// Free Lossless Audio Codec (FLAC) decoder.
 pub struct FlacDecoder {
     params: CodecParameters,
-    is_validating: bool,
-    validator: Validator,
     buf: AudioBuffer<i32>,
 }
 
 impl Decoder for FlacDecoder {
 
-    fn try_new(params: &CodecParameters, options: &DecoderOptions) -> Result<Self> {
+    fn try_new(params: &CodecParameters, _options: &DecoderOptions) -> Result<Self> {
         // Initialize the AudioBuffer.
         //
         // TODO: Some of the required parameters are not necessarily provided in the StreamInfo
@@ -116,8 +111,6 @@ impl Decoder for FlacDecoder {
 
         Ok(FlacDecoder {
             params: params.clone(),
-            is_validating: options.verify,
-            validator: Default::default(),
             buf: AudioBuffer::new(frames, spec),
         })
     }
@@ -198,11 +191,6 @@ impl Decoder for FlacDecoder {
             }
         }
 
-        // Feed the validator if validation is enabled.
-        if self.is_validating {
-            self.validator.update(&self.buf, bits_per_sample);
-        }
-
         // The decoder uses a 32bit sample format as a common denominator, but that doesn't mean
         // the encoded audio samples are actually 32bit. Shift all samples in the output buffer
         // so that regardless the encoded bits/sample, the output is always 32bits/sample.
@@ -214,12 +202,12 @@ impl Decoder for FlacDecoder {
         Ok(self.buf.as_audio_buffer_ref())
     }
 
-    fn close(&mut self) {
-        if self.is_validating {
-            info!("output md5 = {:x?}", self.validator.md5());
-        }
+    fn reset(&mut self) {
+        // All FLAC frames are independently decodable, there is no state to reset.
     }
 
+    fn close(&mut self) {}
+
 }
 
 // Subframe business
@@ -10,11 +10,12 @@ use std::io::{Seek, SeekFrom};
 use symphonia_core::support_format;
 
 use symphonia_core::codecs::{CODEC_TYPE_FLAC, CodecParameters};
-use symphonia_core::errors::{Result, decode_error, seek_error, unsupported_error, SeekErrorKind};
+use symphonia_core::errors::{Error, Result, SeekErrorKind};
+use symphonia_core::errors::{decode_error, seek_error, unsupported_error};
 use symphonia_core::formats::prelude::*;
 use symphonia_core::formats::util::{SeekIndex, SeekSearchResult};
 use symphonia_core::io::*;
-use symphonia_core::meta::{Metadata, MetadataLog, MetadataBuilder};
+use symphonia_core::meta::{Metadata, MetadataLog, MetadataBuilder, MetadataOptions};
 use symphonia_core::probe::{Descriptor, Instantiate, QueryDescriptor};
 
 use symphonia_utils_xiph::flac::metadata::*;
@@ -40,7 +41,7 @@ pub struct FlacReader {
 
 impl FlacReader {
     /// Reads all the metadata blocks, returning a fully populated `FlacReader`.
-    fn init_with_metadata(source: MediaSourceStream) -> Result<Self> {
+    fn init_with_metadata(source: MediaSourceStream, options: &MetadataOptions) -> Result<Self> {
         let mut metadata_builder = MetadataBuilder::new();
 
         let mut reader = source;
@@ -75,7 +76,10 @@ impl FlacReader {
                         return decode_error("found more than one seek table block");
                     }
                 },
-                // VorbisComment blocks are parsed into Tags.
+                // VorbisComment blocks are parsed into Tags, unless tags are not to be read.
+                MetadataBlockType::VorbisComment if !options.read_tags => {
+                    block_stream.ignore_bytes(u64::from(header.block_len))?;
+                },
                 MetadataBlockType::VorbisComment => {
                     read_comment_block(&mut block_stream, &mut metadata_builder)?;
                 },
@@ -83,7 +87,10 @@ impl FlacReader {
                 MetadataBlockType::Cuesheet => {
                     read_cuesheet_block(&mut block_stream, &mut cues)?;
                 },
-                // Picture blocks are read as Visuals.
+                // Picture blocks are read as Visuals, unless visuals are not to be read.
+                MetadataBlockType::Picture if !options.read_visuals => {
+                    block_stream.ignore_bytes(u64::from(header.block_len))?;
+                },
                 MetadataBlockType::Picture => {
                     read_picture_block(&mut block_stream, &mut metadata_builder)?;
                 },
@@ -155,7 +162,7 @@ impl QueryDescriptor for FlacReader {
 
 impl FormatReader for FlacReader {
 
-    fn try_new(mut source: MediaSourceStream, _options: &FormatOptions) -> Result<Self> {
+    fn try_new(mut source: MediaSourceStream, options: &FormatOptions) -> Result<Self> {
         // Read the first 4 bytes of the stream. Ideally this will be the FLAC stream marker.
         let marker = source.read_quad_bytes()?;
 
@@ -167,7 +174,7 @@ impl FormatReader for FlacReader {
         // no technical need for this from the reader's point of view. Additionally, if the
         // reader is fed a stream mid-way there is no StreamInfo block. Therefore, just read
         // all metadata blocks and handle the StreamInfo block as it comes.
-        let mut flac = Self::init_with_metadata(source)?;
+        let mut flac = Self::init_with_metadata(source, &options.metadata)?;
 
         // Make sure that there is atleast one StreamInfo block.
         if flac.tracks.is_empty() {
@@ -182,7 +189,8 @@ impl FormatReader for FlacReader {
     }
 
     fn next_packet(&mut self) -> Result<Packet> {
-        let parsed = self.parser.parse(&mut self.reader)?;
+        // The parser only fails to read more data once all buffered frames have been returned.
+        let parsed = self.parser.parse(&mut self.reader).map_err(Error::into_end_of_stream)?;
 
         Ok(Packet::new_from_boxed_slice(0, parsed.ts, parsed.dur, parsed.buf))
     }
@@ -199,6 +207,16 @@ impl FormatReader for FlacReader {
         &self.tracks
     }
 
+    fn seek_caps(&self) -> SeekCaps {
+        match self.tracks.first() {
+            Some(track) => {
+                let granularity = track.codec_params.max_frames_per_packet;
+                SeekCaps::sample_accurate(self.reader.is_seekable(), granularity)
+            }
+            None => SeekCaps::unsupported(),
+        }
+    }
+
     fn seek(&mut self, _mode: SeekMode, to: SeekTo) -> Result<SeekedTo> {
         if self.tracks.is_empty() {
             return seek_error(SeekErrorKind::Unseekable);
@@ -368,6 +386,11 @@ fn read_stream_info_block<B : ReadBytes>(
             .with_channels(info.channels)
             .with_packet_data_integrity(true);
 
+        // An MD5 checksum of all zeros indicates the encoder did not calculate a checksum.
+        if info.md5 != [0; 16] {
+            codec_params.with_md5(info.md5);
+        }
+
         // Total samples (per channel) aka frames may or may not be stated in StreamInfo.
         if let Some(n_frames) = info.n_samples {
             codec_params.with_n_frames(n_frames);
@@ -385,3 +408,113 @@ fn read_stream_info_block<B : ReadBytes>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use symphonia_core::checksum::{Crc8Ccitt, Crc16Ansi, Md5};
+    use symphonia_core::codecs::{CodecRegistry, DecoderOptions};
+    use symphonia_core::formats::FormatReader;
+    use symphonia_core::io::{MediaSourceStream, Monitor};
+
+    use crate::FlacDecoder;
+
+    use super::FlacReader;
+
+    /// The number of samples in each frame.
+    const BLOCK_LEN: usize = 16;
+
+    /// Builds a mono, 16-bit, 44.1kHz FLAC stream of verbatim frames with the given samples, and
+    /// the given MD5 checksum in the stream information block.
+    fn make_flac(samples: &[i16], md5: [u8; 16]) -> Vec<u8> {
+        let mut flac = b"fLaC".to_vec();
+
+        // The stream information block is the last metadata block.
+        flac.extend_from_slice(&[0x80, 0x00, 0x00, 34]);
+        flac.extend_from_slice(&(BLOCK_LEN as u16).to_be_bytes());
+        flac.extend_from_slice(&(BLOCK_LEN as u16).to_be_bytes());
+        flac.extend_from_slice(&[0; 6]);
+        // 44.1kHz, 1 channel, 16 bits per sample.
+        let info = (44_100u64 << 44) | (15 << 36) | samples.len() as u64;
+        flac.extend_from_slice(&info.to_be_bytes());
+        flac.extend_from_slice(&md5);
+
+        for (i, block) in samples.chunks(BLOCK_LEN).enumerate() {
+            let start = flac.len();
+
+            // Fixed blocking, an 8-bit block length, 44.1kHz, mono, 16 bits per sample, and the
+            // frame number.
+            flac.extend_from_slice(&[0xff, 0xf8, 0x69, 0x08, i as u8, block.len() as u8 - 1]);
+
+            let mut crc8 = Crc8Ccitt::new(0);
+            crc8.process_buf_bytes(&flac[start..]);
+            flac.push(crc8.crc());
+
+            // A verbatim subframe.
+            flac.push(0x02);
+
+            for sample in block {
+                flac.extend_from_slice(&sample.to_be_bytes());
+            }
+
+            let mut crc16 = Crc16Ansi::new(0);
+            crc16.process_buf_bytes(&flac[start..]);
+            flac.extend_from_slice(&crc16.crc().to_be_bytes());
+        }
+
+        flac
+    }
+
+    /// Decodes the FLAC stream with verification, and returns the verification result.
+    fn decode_and_verify(flac: Vec<u8>) -> Option<bool> {
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(flac)), Default::default());
+
+        let mut reader = FlacReader::try_new(mss, &Default::default()).unwrap();
+
+        let mut codecs = CodecRegistry::new();
+        codecs.register_all::<FlacDecoder>();
+
+        let options = DecoderOptions { verify: true, ..Default::default() };
+        let mut decoder = codecs.make(&reader.tracks()[0].codec_params, &options).unwrap();
+
+        let mut n_frames = 0;
+
+        while let Ok(packet) = reader.next_packet() {
+            n_frames += decoder.decode(&packet).unwrap().frames();
+        }
+
+        assert_eq!(n_frames, 3 * BLOCK_LEN);
+
+        decoder.finalize().unwrap()
+    }
+
+    #[test]
+    fn verify_stream_md5() {
+        let samples = (0..3 * BLOCK_LEN as i16).map(|i| i * 600 - 15_000).collect::<Vec<_>>();
+
+        let mut md5 = Md5::default();
+
+        for sample in &samples {
+            md5.process_buf_bytes(&sample.to_le_bytes());
+        }
+
+        let md5 = md5.md5();
+
+        let flac = make_flac(&samples, md5);
+
+        // The checksum is provided in the codec parameters.
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(flac.clone())), Default::default());
+        let reader = FlacReader::try_new(mss, &Default::default()).unwrap();
+
+        assert_eq!(reader.tracks()[0].codec_params.md5, Some(md5));
+
+        assert_eq!(decode_and_verify(flac), Some(true));
+
+        // A stream with a corrupted sample, but otherwise valid frames, fails verification.
+        let mut corrupted = samples.clone();
+        corrupted[20] ^= 0x100;
+
+        assert_eq!(decode_and_verify(make_flac(&corrupted, md5)), Some(false));
+    }
+}
@@ -12,7 +12,6 @@ mod decoder;
 mod demuxer;
 mod frame;
 mod parser;
-mod validate;
 
 pub use decoder::FlacDecoder;
 pub use demuxer::FlacReader;
\ No newline at end of file
@@ -393,6 +393,11 @@ impl BitResevoir {
     pub fn bytes_ref(&self) -> &[u8] {
         &self.buf[..self.len]
     }
+
+    /// Discards all bytes in the resevoir.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
 }
 
 /// MP3 depends on the state of the previous frame to decode the next. `State` is a structure
@@ -413,4 +418,12 @@ impl State {
             resevoir: BitResevoir::new(),
         }
     }
+
+    /// Resets the state to that of a newly instantiated decoder without reallocating the resevoir.
+    pub fn reset(&mut self) {
+        self.samples = [[[0f32; 576]; 2]; 2];
+        self.overlap = [[[0f32; 18]; 32]; 2];
+        self.synthesis = Default::default();
+        self.resevoir.clear();
+    }
 }
\ No newline at end of file
@@ -68,7 +68,118 @@ impl Decoder for Mp3Decoder {
         Ok(self.buf.as_audio_buffer_ref())
     }
 
+    fn max_frames_per_packet(&self) -> Option<u64> {
+        // A MPEG1 frame contains 2 granules. MPEG2 and MPEG2.5 frames contain 1 granule.
+        Some(2 * SAMPLES_PER_GRANULE)
+    }
+
+    fn reset(&mut self) {
+        self.state.reset();
+    }
+
     fn close(&mut self) {
 
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+
+    use symphonia_core::audio::SampleBuffer;
+    use symphonia_core::formats::{FormatReader, Packets, SeekMode, SeekTo};
+    use symphonia_core::io::MediaSourceStream;
+
+    use crate::Mp3Reader;
+
+    /// The total length of a 128 kbps, 44.1 kHz, joint stereo, MPEG1 Layer 3 frame without padding.
+    const FRAME_LEN: usize = 417;
+
+    /// Builds a frame whose granules contain a burst of unit samples scaled by the global gain.
+    fn make_frame(global_gain: u32) -> Vec<u8> {
+        let mut bits = Vec::new();
+
+        let mut push = |value: u32, len: u32| {
+            bits.extend((0..len).rev().map(|i| (value >> i) & 1 == 1));
+        };
+
+        // The side information: main_data_begin, private bits, and scfsi.
+        push(0, 9);
+        push(0, 3);
+        push(0, 8);
+
+        for _ in 0..4 {
+            // part2_3_length, big_values, global_gain, and scalefac_compress.
+            push(64, 12);
+            push(0, 9);
+            push(global_gain, 8);
+            push(0, 4);
+            // No window switching, table_select, region0_count, and region1_count.
+            push(0, 1);
+            push(0, 15);
+            push(0, 7);
+            // preflag, scalefac_scale, and count1table_select (table B).
+            push(0, 2);
+            push(1, 1);
+        }
+
+        let mut frame = vec![0xff, 0xfb, 0x90, 0x64];
+
+        for byte in bits.chunks(8) {
+            frame.push(byte.iter().fold(0, |acc, &bit| acc << 1 | u8::from(bit)));
+        }
+
+        // The main data is all zeros, which in count1 table B decodes to quads of positive unit
+        // samples.
+        frame.resize(FRAME_LEN, 0);
+        frame
+    }
+
+    fn decode_all(reader: &mut Mp3Reader, decoder: &mut Mp3Decoder) -> Vec<f32> {
+        let mut samples = Vec::new();
+
+        for packet in Packets::new(reader) {
+            let decoded = decoder.decode(&packet.unwrap()).unwrap();
+
+            let mut buf = SampleBuffer::<f32>::new(decoded.capacity() as u64, *decoded.spec());
+            buf.copy_planar_ref(decoded);
+
+            samples.extend_from_slice(buf.samples());
+        }
+
+        samples
+    }
+
+    #[test]
+    fn verify_reset_after_seek() {
+        let data = (0..8).flat_map(|i| make_frame(200 + 2 * i)).collect::<Vec<u8>>();
+
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(data)), Default::default());
+        let mut reader = Mp3Reader::try_new(mss, &Default::default()).unwrap();
+
+        let params = reader.tracks()[0].codec_params.clone();
+
+        let mut decoder = Mp3Decoder::try_new(&params, &Default::default()).unwrap();
+
+        let fresh = decode_all(&mut reader, &mut decoder);
+
+        assert!(fresh.iter().any(|&s| s != 0.0));
+
+        let seek_to = SeekTo::TimeStamp { ts: 0, track_id: 0 };
+
+        // Without a reset, the state left over from the last frame affects the first frames.
+        reader.seek(SeekMode::Accurate, seek_to).unwrap();
+
+        assert!(decode_all(&mut reader, &mut decoder) != fresh);
+
+        // After a reset, the decoded audio is identical to that of a fresh decoder.
+        let seek_to = SeekTo::TimeStamp { ts: 0, track_id: 0 };
+
+        reader.seek(SeekMode::Accurate, seek_to).unwrap();
+        decoder.reset();
+
+        assert!(decode_all(&mut reader, &mut decoder) == fresh);
+    }
+}
@@ -8,11 +8,12 @@
 use symphonia_core::support_format;
 
 use symphonia_core::codecs::{CodecParameters, CODEC_TYPE_MP3};
-use symphonia_core::errors::{Result, SeekErrorKind, seek_error};
+use symphonia_core::errors::{Error, Result, SeekErrorKind, seek_error};
 use symphonia_core::formats::prelude::*;
 use symphonia_core::io::*;
 use symphonia_core::meta::{Metadata, MetadataLog};
 use symphonia_core::probe::{Descriptor, Instantiate, QueryDescriptor};
+use symphonia_core::units::Time;
 
 use std::io::{Seek, SeekFrom};
 
@@ -20,6 +21,10 @@ use log::debug;
 
 use super::{header, common::FrameHeader, common::SAMPLES_PER_GRANULE};
 
+/// The maximum number of frames that may be seeked to before the frame containing the desired
+/// timestamp so that the bit reservoir is filled.
+const MAX_REF_FRAMES: usize = 4;
+
 /// MPEG1 and MPEG2 audio frame reader.
 ///
 /// `Mp3Reader` implements a demuxer for the MPEG1 and MPEG2 audio frame format.
@@ -29,6 +34,11 @@ pub struct Mp3Reader {
     cues: Vec<Cue>,
     metadata: MetadataLog,
     first_frame_pos: u64,
+    /// The length of the first frame in bytes, used to estimate positions in a constant bitrate
+    /// stream.
+    first_frame_len: u64,
+    /// The duration of the first frame in frames (samples per channel).
+    first_frame_dur: u64,
     next_packet_ts: u64,
 }
 
@@ -49,8 +59,15 @@ impl QueryDescriptor for Mp3Reader {
         ]
     }
 
-    fn score(_context: &[u8]) -> u8 {
-        255
+    fn score(context: &[u8]) -> u8 {
+        // The markers only cover the frame sync and part of the version. A valid frame header is
+        // much less likely to be a false sync.
+        let header_buf = u32::from_be_bytes([context[0], context[1], context[2], context[3]]);
+
+        match header::parse_frame_header(header_buf) {
+            Ok(_) => 255,
+            Err(_) => 0,
+        }
     }
 }
 
@@ -63,14 +80,18 @@ impl FormatReader for Mp3Reader {
         // Use the header to populate the codec parameters.
         let mut params = CodecParameters::new();
 
+        // The bit rate is assumed to be constant, like the estimated duration of the stream.
         params.for_codec(CODEC_TYPE_MP3)
               .with_sample_rate(header.sample_rate)
+              .with_bit_rate(header.bitrate)
               .with_channels(header.channel_mode.channels());
 
         // Rewind back to the start of the frame.
         source.rewind(std::mem::size_of::<u32>());
 
         let first_frame_offset = source.pos();
+        let first_frame_len = (header.frame_size + std::mem::size_of::<u32>()) as u64;
+        let first_frame_dur = SAMPLES_PER_GRANULE * header.n_granules() as u64;
 
         Ok(Mp3Reader {
             reader: source,
@@ -78,13 +99,15 @@ impl FormatReader for Mp3Reader {
             cues: Vec::new(),
             metadata: Default::default(),
             first_frame_pos: first_frame_offset,
+            first_frame_len,
+            first_frame_dur,
             next_packet_ts: 0,
         })
     }
 
     fn next_packet(&mut self) -> Result<Packet> {
-        // Sync to the next frame header.
-        let header_buf = header::sync_frame(&mut self.reader)?;
+        // Sync to the next frame header. If there is no more data, then there are no more frames.
+        let header_buf = header::sync_frame(&mut self.reader).map_err(Error::into_end_of_stream)?;
 
         // Parse the header to get the calculated frame size.
         let header = header::parse_frame_header(header_buf)?;
@@ -116,8 +139,28 @@ impl FormatReader for Mp3Reader {
         &self.tracks
     }
 
-    fn seek(&mut self, _mode: SeekMode, to: SeekTo) -> Result<SeekedTo> {
-        const MAX_REF_FRAMES: usize = 4;
+    fn total_duration(&self) -> Option<Time> {
+        // The elementary stream has no header containing the duration of the stream. Therefore,
+        // estimate the duration from the length of the stream assuming all frames are the same size
+        // as the first frame (i.e., a constant bitrate).
+        let len = self.reader.len()?.saturating_sub(self.first_frame_pos);
+
+        let n_frames = len / self.first_frame_len * self.first_frame_dur;
+
+        let sample_rate = self.tracks[0].codec_params.sample_rate?;
+
+        Some(TimeBase::new(1, sample_rate).calc_time(n_frames))
+    }
+
+    fn seek_caps(&self) -> SeekCaps {
+        // A seek lands on the frame containing the desired timestamp, or on one of the reference
+        // frames preceeding it. A frame has at most 2 granules.
+        let granularity = MAX_REF_FRAMES as u64 * 2 * SAMPLES_PER_GRANULE;
+
+        SeekCaps::sample_accurate(self.reader.is_seekable(), Some(granularity))
+    }
+
+    fn seek(&mut self, mode: SeekMode, to: SeekTo) -> Result<SeekedTo> {
         const REF_FRAMES_MASK: usize = MAX_REF_FRAMES - 1;
 
         // Get the timestamp of the desired audio frame.
@@ -139,6 +182,14 @@ impl FormatReader for Mp3Reader {
 
         debug!("seeking to ts={}", required_ts);
 
+        // A coarse seek estimates the position of the desired frame instead of parsing every frame
+        // preceeding it, but can only be performed if the reader is seekable.
+        if let SeekMode::Coarse = mode {
+            if self.reader.is_seekable() {
+                return self.seek_coarse(required_ts);
+            }
+        }
+
         // If the desired timestamp is less-than the next packet timestamp, attempt to seek
         // to the start of the stream.
         if required_ts < self.next_packet_ts {
@@ -250,11 +301,60 @@ impl FormatReader for Mp3Reader {
         })
     }
 
+    fn seek_byte(&mut self, offset: u64) -> Result<()> {
+        // There are no frames before the first frame.
+        let pos = offset.max(self.first_frame_pos);
+
+        if self.reader.is_seekable() {
+            self.reader.seek(SeekFrom::Start(pos))?;
+        }
+        else if pos >= self.reader.pos() {
+            self.reader.ignore_bytes(pos - self.reader.pos())?;
+        }
+        else {
+            return seek_error(SeekErrorKind::ForwardOnly);
+        }
+
+        // Sync to the next frame, and then rewind back to the start of its header.
+        let header = header::parse_frame_header(header::sync_frame(&mut self.reader)?)?;
+
+        self.reader.rewind(std::mem::size_of::<u32>());
+
+        // Since the elementary stream has no timestamp information, estimate the timestamp of the
+        // frame assuming all frames are the same size as this one (i.e., a constant bitrate).
+        let frame_len = (header.frame_size + std::mem::size_of::<u32>()) as u64;
+        let duration = SAMPLES_PER_GRANULE * header.n_granules() as u64;
+
+        self.next_packet_ts = (self.reader.pos() - self.first_frame_pos) / frame_len * duration;
+
+        debug!("seeked to pos={} with estimated ts={}", self.reader.pos(), self.next_packet_ts);
+
+        Ok(())
+    }
+
     fn into_inner(self: Box<Self>) -> MediaSourceStream {
         self.reader
     }
 }
 
+impl Mp3Reader {
+    /// Seeks to an estimated position of the frame containing the desired timestamp assuming all
+    /// frames are the same size as the first frame (i.e., a constant bitrate).
+    fn seek_coarse(&mut self, required_ts: u64) -> Result<SeekedTo> {
+        // Back-off by the maximum number of reference frames so that the bit reservoir can be
+        // filled before the frame containing the desired timestamp is decoded.
+        let n_frames = (required_ts / self.first_frame_dur).saturating_sub(MAX_REF_FRAMES as u64);
+
+        self.seek_byte(self.first_frame_pos + n_frames * self.first_frame_len)?;
+
+        Ok(SeekedTo {
+            track_id: 0,
+            required_ts,
+            actual_ts: self.next_packet_ts,
+        })
+    }
+}
+
 #[derive(Default)]
 struct FramePos {
     ts: u64,
@@ -278,4 +378,134 @@ fn read_main_data_begin<B: ReadBytes>(reader: &mut B, header: &FrameHeader) -> R
     };
 
     Ok(main_data_begin)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+
+    /// The header of a 128 kbps, 44.1 kHz, MPEG1 Layer 3 frame without padding.
+    const FRAME_HEADER: [u8; 4] = [0xff, 0xfb, 0x90, 0x64];
+
+    /// The total length of a frame with `FRAME_HEADER`.
+    const FRAME_LEN: usize = 417;
+
+    fn make_reader(n_frames: usize) -> Mp3Reader {
+        let mut buf = Vec::new();
+
+        for _ in 0..n_frames {
+            buf.extend_from_slice(&FRAME_HEADER);
+            buf.resize(buf.len() + FRAME_LEN - FRAME_HEADER.len(), 0);
+        }
+
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(buf)), Default::default());
+
+        Mp3Reader::try_new(mss, &Default::default()).unwrap()
+    }
+
+    #[test]
+    fn verify_seek_byte() {
+        const FRAME_DUR: u64 = 2 * SAMPLES_PER_GRANULE;
+
+        let mut reader = make_reader(10);
+
+        // Seek into the middle of the 6th frame, the next packet should be the 7th frame.
+        reader.seek_byte(5 * FRAME_LEN as u64 + 100).unwrap();
+
+        let mut prev_ts = None;
+
+        for i in 6..10 {
+            let packet = reader.next_packet().unwrap();
+
+            assert_eq!(packet.pts(), i * FRAME_DUR);
+            assert_eq!(&packet.buf()[..4], &FRAME_HEADER);
+
+            // Timestamps must be monotonic after the seek.
+            if let Some(prev_ts) = prev_ts {
+                assert!(packet.pts() > prev_ts);
+            }
+
+            prev_ts = Some(packet.pts());
+        }
+
+        // Seek backwards to exactly the start of the 2nd frame.
+        reader.seek_byte(FRAME_LEN as u64).unwrap();
+
+        assert_eq!(reader.next_packet().unwrap().pts(), FRAME_DUR);
+    }
+
+    #[test]
+    fn verify_seek_modes() {
+        const FRAME_DUR: u64 = 2 * SAMPLES_PER_GRANULE;
+
+        let seek_to = |ts| SeekTo::TimeStamp { ts, track_id: 0 };
+
+        let required_ts = 8 * FRAME_DUR + 100;
+
+        // An accurate seek lands on the frame containing the desired timestamp since no frames
+        // reference the bit reservoir.
+        let mut reader = make_reader(12);
+
+        let seeked_to = reader.seek(SeekMode::Accurate, seek_to(required_ts)).unwrap();
+
+        assert_eq!(seeked_to.required_ts, required_ts);
+        assert!(seeked_to.actual_ts <= required_ts);
+        assert!(required_ts - seeked_to.actual_ts < FRAME_DUR);
+        assert_eq!(reader.next_packet().unwrap().pts(), seeked_to.actual_ts);
+
+        // A coarse seek may land earlier, but never after, the desired timestamp.
+        let mut reader = make_reader(12);
+
+        let seeked_to = reader.seek(SeekMode::Coarse, seek_to(required_ts)).unwrap();
+
+        assert_eq!(seeked_to.actual_ts, (8 - MAX_REF_FRAMES as u64) * FRAME_DUR);
+        assert_eq!(reader.next_packet().unwrap().pts(), seeked_to.actual_ts);
+    }
+
+    #[test]
+    fn verify_total_duration() {
+        let reader = make_reader(10);
+
+        // 10 frames of 1152 samples at 44.1 kHz.
+        let expected = TimeBase::new(1, 44_100).calc_time(10 * 2 * SAMPLES_PER_GRANULE);
+
+        assert_eq!(reader.total_duration(), Some(expected));
+        assert_eq!(expected.seconds, 0);
+        assert!((expected.frac - 11_520.0 / 44_100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn verify_score() {
+        assert_eq!(Mp3Reader::score(&FRAME_HEADER), 255);
+
+        // A free-format bitrate is not supported.
+        assert_eq!(Mp3Reader::score(&[0xff, 0xfb, 0x00, 0x64]), 0);
+    }
+
+    #[test]
+    fn verify_packets() {
+        let mut reader = make_reader(10);
+
+        assert_eq!(Packets::new(&mut reader).count(), 10);
+
+        // A truncated final frame is an error, not the end of the stream.
+        let mut buf = Vec::new();
+
+        for _ in 0..3 {
+            buf.extend_from_slice(&FRAME_HEADER);
+            buf.resize(buf.len() + FRAME_LEN - FRAME_HEADER.len(), 0);
+        }
+
+        buf.truncate(buf.len() - 100);
+
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(buf)), Default::default());
+        let mut reader = Mp3Reader::try_new(mss, &Default::default()).unwrap();
+
+        let packets = Packets::new(&mut reader).take(3).collect::<Vec<_>>();
+
+        assert!(packets[0].is_ok() && packets[1].is_ok());
+        assert!(packets[2].is_err());
+    }
+}
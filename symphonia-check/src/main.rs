@@ -296,7 +296,7 @@ fn main() {
     }
 
     match run_test(path, &opts, &mut res) {
-        Err(Error::IoError(err)) if err.kind() == std::io::ErrorKind::UnexpectedEof => (),
+        Err(Error::EndOfStream) => (),
         Err(err) => {
             eprintln!("Test interrupted by error: {}", err);
             std::process::exit(2);
@@ -712,6 +712,14 @@ impl ICS {
         }
     }
 
+    /// Resets the overlap and window state carried over from the previous frame, and the noise
+    /// generator, to their initial values.
+    fn reset(&mut self) {
+        self.info = ICSInfo::new();
+        self.delay = [0.0; 1024];
+        self.lcg = Lcg::new(0x1bad1dea);
+    }
+
     fn decode_section_data<B: ReadBitsLtr>(
         &mut self,
         bs: &mut B
@@ -1607,12 +1615,12 @@ impl AacDecoder {
         Ok(())
     }
 
-    // fn flush(&mut self) {
-    //     for pair in self.pairs.iter_mut() {
-    //         pair.ics[0].delay = [0.0; 1024];
-    //         pair.ics[1].delay = [0.0; 1024];
-    //     }
-    // }
+    fn flush(&mut self) {
+        for pair in self.pairs.iter_mut() {
+            pair.ics0.reset();
+            pair.ics1.reset();
+        }
+    }
 
 }
 
@@ -1687,6 +1695,14 @@ impl Decoder for AacDecoder {
         Ok(self.buf.as_audio_buffer_ref())
     }
 
+    fn max_frames_per_packet(&self) -> Option<u64> {
+        Some(self.m4ainfo.samples as u64)
+    }
+
+    fn reset(&mut self) {
+        self.flush();
+    }
+
     fn close(&mut self) {
 
     }
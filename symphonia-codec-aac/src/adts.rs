@@ -10,7 +10,7 @@ use symphonia_core::support_format;
 
 use symphonia_core::audio::Channels;
 use symphonia_core::codecs::{CodecParameters, CODEC_TYPE_AAC};
-use symphonia_core::errors::{Result, SeekErrorKind, decode_error, seek_error};
+use symphonia_core::errors::{Error, Result, SeekErrorKind, decode_error, seek_error};
 use symphonia_core::formats::prelude::*;
 use symphonia_core::io::*;
 use symphonia_core::meta::{Metadata, MetadataLog};
@@ -161,8 +161,9 @@ impl FormatReader for AdtsReader {
     }
 
     fn next_packet(&mut self) -> Result<Packet> {
-        // Parse the header to get the calculated frame size.
-        let header = AdtsHeader::read(&mut self.reader)?;
+        // Parse the header to get the calculated frame size. If there is no more data, then there
+        // are no more frames.
+        let header = AdtsHeader::read(&mut self.reader).map_err(Error::into_end_of_stream)?;
 
         // TODO: Support multiple AAC packets per ADTS packet.
 
@@ -190,6 +191,10 @@ impl FormatReader for AdtsReader {
         &self.tracks
     }
 
+    fn seek_caps(&self) -> SeekCaps {
+        SeekCaps::sample_accurate(self.reader.is_seekable(), Some(SAMPLES_PER_AAC_PACKET))
+    }
+
     fn seek(&mut self, _mode: SeekMode, to: SeekTo) -> Result<SeekedTo> {
         // Get the timestamp of the desired audio frame.
         let required_ts = match to {
@@ -264,6 +269,37 @@ impl FormatReader for AdtsReader {
         })
     }
 
+    fn seek_byte(&mut self, offset: u64) -> Result<()> {
+        // There are no frames before the first frame.
+        let pos = offset.max(self.first_frame_pos);
+
+        if self.reader.is_seekable() {
+            self.reader.seek(SeekFrom::Start(pos))?;
+        }
+        else if pos >= self.reader.pos() {
+            self.reader.ignore_bytes(pos - self.reader.pos())?;
+        }
+        else {
+            return seek_error(SeekErrorKind::ForwardOnly);
+        }
+
+        // Sync to the next frame, and then rewind back to the start of its header.
+        let header = AdtsHeader::read(&mut self.reader)?;
+
+        self.reader.rewind(AdtsHeader::SIZE);
+
+        // Since the elementary stream has no timestamp information, estimate the timestamp of the
+        // frame assuming all frames are the same size as this one (i.e., a constant bitrate).
+        let frame_len = (header.frame_len + AdtsHeader::SIZE) as u64;
+
+        self.next_packet_ts =
+            (self.reader.pos() - self.first_frame_pos) / frame_len * SAMPLES_PER_AAC_PACKET;
+
+        debug!("seeked to pos={} with estimated ts={}", self.reader.pos(), self.next_packet_ts);
+
+        Ok(())
+    }
+
     fn into_inner(self: Box<Self>) -> MediaSourceStream {
         self.reader
     }
@@ -30,6 +30,10 @@ use symphonia_core::errors::{Result, unsupported_error};
 use symphonia_core::formats::Packet;
 use symphonia_core::io::ReadBytes;
 
+mod raw;
+
+pub use raw::RawPcmReader;
+
 macro_rules! read_pcm_signed {
     ($buf:expr, $read:expr, $shift:expr) => {
         $buf.fill(| audio_planes, idx | -> Result<()> {
@@ -56,7 +60,7 @@ macro_rules! read_pcm_floating {
     ($buf:expr, $read:expr) => {
         $buf.fill(| audio_planes, idx | -> Result<()> {
             for plane in audio_planes.planes() {
-                plane[idx] = i32::from_sample($read);
+                plane[idx] = $read;
             }
             Ok(())
         })
@@ -110,10 +114,15 @@ fn mulaw_to_linear(mut mu_val: u8) -> i16 {
 }
 
 /// Pulse Code Modulation (PCM) decoder for all raw PCM, and log-PCM codecs.
+///
+/// Integer and log-PCM codecs are decoded into signed 32-bit integer audio buffers, while floating
+/// point codecs are decoded into floating point audio buffers of the same precision.
 pub struct PcmDecoder {
     params: CodecParameters,
     sample_width: u32,
     buf: AudioBuffer<i32>,
+    f32_buf: AudioBuffer<f32>,
+    f64_buf: AudioBuffer<f64>,
 }
 
 impl Decoder for PcmDecoder {
@@ -158,11 +167,26 @@ impl Decoder for PcmDecoder {
             }
         }
 
-        Ok(PcmDecoder {
+        let mut decoder = PcmDecoder {
             params: params.clone(),
             sample_width,
-            buf: AudioBuffer::new(frames, spec),
-        })
+            buf: AudioBuffer::unused(),
+            f32_buf: AudioBuffer::unused(),
+            f64_buf: AudioBuffer::unused(),
+        };
+
+        // Only allocate the audio buffer for the sample format the codec decodes to.
+        match params.codec {
+            CODEC_TYPE_PCM_F32LE | CODEC_TYPE_PCM_F32BE => {
+                decoder.f32_buf = AudioBuffer::new(frames, spec)
+            }
+            CODEC_TYPE_PCM_F64LE | CODEC_TYPE_PCM_F64BE => {
+                decoder.f64_buf = AudioBuffer::new(frames, spec)
+            }
+            _ => decoder.buf = AudioBuffer::new(frames, spec),
+        }
+
+        Ok(decoder)
     }
 
     fn supported_codecs() -> &'static [CodecDescriptor] {
@@ -387,10 +411,22 @@ impl Decoder for PcmDecoder {
             CODEC_TYPE_PCM_U16LE => read_pcm_unsigned!(self.buf, stream.read_u16()?,    int_shift),
             CODEC_TYPE_PCM_U16BE => read_pcm_unsigned!(self.buf, stream.read_be_u16()?, int_shift),
             CODEC_TYPE_PCM_U8    => read_pcm_unsigned!(self.buf, stream.read_u8()?,     int_shift),
-            CODEC_TYPE_PCM_F32LE => read_pcm_floating!(self.buf, stream.read_f32()?),
-            CODEC_TYPE_PCM_F32BE => read_pcm_floating!(self.buf, stream.read_be_f32()?),
-            CODEC_TYPE_PCM_F64LE => read_pcm_floating!(self.buf, stream.read_f64()?),
-            CODEC_TYPE_PCM_F64BE => read_pcm_floating!(self.buf, stream.read_be_f64()?),
+            CODEC_TYPE_PCM_F32LE => {
+                let _ = read_pcm_floating!(self.f32_buf, stream.read_f32()?);
+                return Ok(self.f32_buf.as_audio_buffer_ref());
+            },
+            CODEC_TYPE_PCM_F32BE => {
+                let _ = read_pcm_floating!(self.f32_buf, stream.read_be_f32()?);
+                return Ok(self.f32_buf.as_audio_buffer_ref());
+            },
+            CODEC_TYPE_PCM_F64LE => {
+                let _ = read_pcm_floating!(self.f64_buf, stream.read_f64()?);
+                return Ok(self.f64_buf.as_audio_buffer_ref());
+            },
+            CODEC_TYPE_PCM_F64BE => {
+                let _ = read_pcm_floating!(self.f64_buf, stream.read_be_f64()?);
+                return Ok(self.f64_buf.as_audio_buffer_ref());
+            },
             CODEC_TYPE_PCM_ALAW  => {
                 read_pcm_transfer_func!(self.buf, alaw_to_linear(stream.read_u8()?))
             },
@@ -421,7 +457,112 @@ impl Decoder for PcmDecoder {
         Ok(self.buf.as_audio_buffer_ref())
     }
 
+    fn reset(&mut self) {
+        // PCM is stateless, there is nothing to reset.
+    }
+
     fn close(&mut self) {
         // Intentionally left empty.
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use symphonia_core::audio::{AudioBufferRef, Channels, Signal};
+    use symphonia_core::codecs::{CodecParameters, Decoder, DecoderOptions, CodecType};
+    use symphonia_core::codecs::CodecRegistry;
+    use symphonia_core::codecs::{CODEC_TYPE_PCM_F32LE, CODEC_TYPE_PCM_S24LE, CODEC_TYPE_PCM_S32LE};
+    use symphonia_core::formats::Packet;
+    use symphonia_core::sample::SampleFormat;
+
+    use super::PcmDecoder;
+
+    fn make_decoder(codec: CodecType, sample_format: SampleFormat) -> PcmDecoder {
+        let mut params = CodecParameters::new();
+        params
+            .for_codec(codec)
+            .with_sample_rate(48_000)
+            .with_sample_format(sample_format)
+            .with_bits_per_sample(32)
+            .with_max_frames_per_packet(4)
+            .with_channels(Channels::FRONT_LEFT | Channels::FRONT_RIGHT);
+
+        PcmDecoder::try_new(&params, &DecoderOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn verify_float_pcm_decodes_to_f32() {
+        let mut decoder = make_decoder(CODEC_TYPE_PCM_F32LE, SampleFormat::F32);
+
+        let data = [0.5f32, -0.25, 1.0, -1.0]
+            .iter()
+            .flat_map(|s| s.to_le_bytes().to_vec())
+            .collect::<Vec<u8>>();
+
+        match decoder.decode(&Packet::new_from_slice(0, 0, 2, &data)).unwrap() {
+            AudioBufferRef::F32(buf) => {
+                assert_eq!(buf.frames(), 2);
+                assert_eq!(buf.chan(0), &[0.5, 1.0]);
+                assert_eq!(buf.chan(1), &[-0.25, -1.0]);
+            }
+            _ => panic!("expected a f32 audio buffer"),
+        }
+    }
+
+    #[test]
+    fn verify_int_pcm_decodes_to_s32() {
+        let mut decoder = make_decoder(CODEC_TYPE_PCM_S32LE, SampleFormat::S32);
+
+        let data = [i32::MAX, i32::MIN, 1, -1]
+            .iter()
+            .flat_map(|s| s.to_le_bytes().to_vec())
+            .collect::<Vec<u8>>();
+
+        match decoder.decode(&Packet::new_from_slice(0, 0, 2, &data)).unwrap() {
+            AudioBufferRef::S32(buf) => {
+                assert_eq!(buf.frames(), 2);
+                assert_eq!(buf.chan(0), &[i32::MAX, 1]);
+                assert_eq!(buf.chan(1), &[i32::MIN, -1]);
+            }
+            _ => panic!("expected a s32 audio buffer"),
+        }
+    }
+
+    #[test]
+    fn verify_output_format_converts_s24_to_s16() {
+        let mut registry = CodecRegistry::new();
+        registry.register_all::<PcmDecoder>();
+
+        let mut params = CodecParameters::new();
+        params
+            .for_codec(CODEC_TYPE_PCM_S24LE)
+            .with_sample_rate(48_000)
+            .with_sample_format(SampleFormat::S24)
+            .with_bits_per_sample(24)
+            .with_max_frames_per_packet(4)
+            .with_channels(Channels::FRONT_LEFT | Channels::FRONT_RIGHT);
+
+        let options = DecoderOptions {
+            output_format: Some(SampleFormat::S16),
+            ..Default::default()
+        };
+
+        let mut decoder = registry.make(&params, &options).unwrap();
+
+        assert_eq!(decoder.codec_params().sample_format, Some(SampleFormat::S16));
+
+        let data = [0x12_3400i32, -0x12_3400, 0x7f_ff00, -0x80_0000]
+            .iter()
+            .flat_map(|s| s.to_le_bytes()[..3].to_vec())
+            .collect::<Vec<u8>>();
+
+        match decoder.decode(&Packet::new_from_slice(0, 0, 2, &data)).unwrap() {
+            AudioBufferRef::S16(buf) => {
+                assert_eq!(buf.frames(), 2);
+                assert_eq!(buf.chan(0), &[0x1234, 0x7fff]);
+                assert_eq!(buf.chan(1), &[-0x1234, -0x8000]);
+            }
+            _ => panic!("expected a s16 audio buffer"),
+        }
+    }
+}
@@ -0,0 +1,419 @@
+// Symphonia
+// Copyright (c) 2019 The Project Symphonia Developers.
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::io::{Seek, SeekFrom};
+
+use symphonia_core::support_format;
+
+use symphonia_core::codecs::{CodecParameters, CodecType};
+use symphonia_core::codecs::{CODEC_TYPE_PCM_S8, CODEC_TYPE_PCM_S16LE, CODEC_TYPE_PCM_S16BE};
+use symphonia_core::codecs::{CODEC_TYPE_PCM_S24LE, CODEC_TYPE_PCM_S24BE};
+use symphonia_core::codecs::{CODEC_TYPE_PCM_S32LE, CODEC_TYPE_PCM_S32BE};
+use symphonia_core::codecs::{CODEC_TYPE_PCM_U8, CODEC_TYPE_PCM_U16LE, CODEC_TYPE_PCM_U16BE};
+use symphonia_core::codecs::{CODEC_TYPE_PCM_U24LE, CODEC_TYPE_PCM_U24BE};
+use symphonia_core::codecs::{CODEC_TYPE_PCM_U32LE, CODEC_TYPE_PCM_U32BE};
+use symphonia_core::codecs::{CODEC_TYPE_PCM_F32LE, CODEC_TYPE_PCM_F32BE};
+use symphonia_core::codecs::{CODEC_TYPE_PCM_F64LE, CODEC_TYPE_PCM_F64BE};
+use symphonia_core::codecs::{CODEC_TYPE_PCM_ALAW, CODEC_TYPE_PCM_MULAW};
+use symphonia_core::errors::{Error, Result, SeekErrorKind, seek_error, unsupported_error};
+use symphonia_core::formats::prelude::*;
+use symphonia_core::io::*;
+use symphonia_core::meta::{Metadata, MetadataLog};
+use symphonia_core::probe::{Descriptor, Instantiate, QueryDescriptor};
+use symphonia_core::sample::SampleFormat;
+
+use log::debug;
+
+/// The maximum number of frames that will be in a packet.
+const RAW_MAX_FRAMES_PER_PACKET: u64 = 1152;
+
+/// Raw PCM format reader.
+///
+/// `RawPcmReader` implements a reader for headerless, raw PCM audio. Since raw PCM has no header,
+/// the parameters of the audio must be provided in the `FormatOptions`, and the format must be
+/// forced when probing.
+pub struct RawPcmReader {
+    reader: MediaSourceStream,
+    tracks: Vec<Track>,
+    cues: Vec<Cue>,
+    metadata: MetadataLog,
+    frame_len: u64,
+    data_start_pos: u64,
+}
+
+impl QueryDescriptor for RawPcmReader {
+    fn query() -> &'static [Descriptor] {
+        &[
+            // Raw PCM has no stream marker, and is never detected.
+            support_format!(
+                "raw",
+                "Raw PCM",
+                &[ "raw", "pcm" ],
+                &[ ],
+                &[ ]
+            ),
+        ]
+    }
+
+    fn score(_context: &[u8]) -> u8 {
+        255
+    }
+}
+
+/// Gets the coded sample width in bits, and the sample format of the decoded audio, for a PCM
+/// codec.
+fn pcm_sample_width(codec: CodecType) -> Option<(u32, SampleFormat)> {
+    match codec {
+        CODEC_TYPE_PCM_U8    => Some(( 8, SampleFormat::U8)),
+        CODEC_TYPE_PCM_S8    => Some(( 8, SampleFormat::S8)),
+        CODEC_TYPE_PCM_U16LE | CODEC_TYPE_PCM_U16BE => Some((16, SampleFormat::U16)),
+        CODEC_TYPE_PCM_S16LE | CODEC_TYPE_PCM_S16BE => Some((16, SampleFormat::S16)),
+        CODEC_TYPE_PCM_U24LE | CODEC_TYPE_PCM_U24BE => Some((24, SampleFormat::U24)),
+        CODEC_TYPE_PCM_S24LE | CODEC_TYPE_PCM_S24BE => Some((24, SampleFormat::S24)),
+        CODEC_TYPE_PCM_U32LE | CODEC_TYPE_PCM_U32BE => Some((32, SampleFormat::U32)),
+        CODEC_TYPE_PCM_S32LE | CODEC_TYPE_PCM_S32BE => Some((32, SampleFormat::S32)),
+        CODEC_TYPE_PCM_F32LE | CODEC_TYPE_PCM_F32BE => Some((32, SampleFormat::F32)),
+        CODEC_TYPE_PCM_F64LE | CODEC_TYPE_PCM_F64BE => Some((64, SampleFormat::F64)),
+        // A-law and Mu-law expand to 16-bit linear PCM.
+        CODEC_TYPE_PCM_ALAW | CODEC_TYPE_PCM_MULAW => Some(( 8, SampleFormat::S16)),
+        _ => None,
+    }
+}
+
+impl FormatReader for RawPcmReader {
+
+    fn try_new(source: MediaSourceStream, options: &FormatOptions) -> Result<Self> {
+        let raw = match options.raw_pcm {
+            Some(raw) => raw,
+            _         => return unsupported_error("raw pcm: parameters were not provided"),
+        };
+
+        let (width, sample_format) = match pcm_sample_width(raw.format) {
+            Some(width) => width,
+            _           => return unsupported_error("raw pcm: format is not a pcm codec"),
+        };
+
+        if raw.rate == 0 || raw.channels.count() == 0 {
+            return unsupported_error("raw pcm: sample rate and channels must not be 0");
+        }
+
+        // Raw PCM has a constant bit rate.
+        let bit_rate = u64::from(raw.rate) * raw.channels.count() as u64 * u64::from(width);
+
+        let mut codec_params = CodecParameters::new();
+
+        codec_params
+            .for_codec(raw.format)
+            .with_sample_rate(raw.rate)
+            .with_bit_rate(bit_rate.min(u64::from(u32::MAX)) as u32)
+            .with_time_base(TimeBase::new(1, raw.rate))
+            .with_channels(raw.channels)
+            .with_bits_per_coded_sample(width)
+            .with_bits_per_sample(width)
+            .with_sample_format(sample_format)
+            .with_max_frames_per_packet(RAW_MAX_FRAMES_PER_PACKET);
+
+        // Audio starts wherever the stream is positioned.
+        let data_start_pos = source.pos();
+
+        Ok(RawPcmReader {
+            reader: source,
+            tracks: vec![ Track::new(0, codec_params) ],
+            cues: Vec::new(),
+            metadata: Default::default(),
+            frame_len: u64::from(width / 8) * raw.channels.count() as u64,
+            data_start_pos,
+        })
+    }
+
+    fn next_packet(&mut self) -> Result<Packet> {
+        // The packet timestamp is the position of the first byte of the first frame in the packet
+        // divided by the length per frame.
+        let pts = (self.reader.pos() - self.data_start_pos) / self.frame_len;
+
+        // Read up-to RAW_MAX_FRAMES_PER_PACKET number of frames per packet.
+        let packet_len = RAW_MAX_FRAMES_PER_PACKET * self.frame_len;
+        let packet_buf = self.reader.read_boxed_slice(packet_len as usize)
+                                    .map_err(|err| Error::from(err).into_end_of_stream())?;
+
+        // A trailing partial frame cannot be decoded, and is dropped.
+        let dur = packet_buf.len() as u64 / self.frame_len;
+
+        if dur == 0 {
+            return Err(Error::EndOfStream);
+        }
+
+        let packet_buf = Box::from(&packet_buf[..(dur * self.frame_len) as usize]);
+
+        Ok(Packet::new_from_boxed_slice(0, pts, dur, packet_buf))
+    }
+
+    fn try_next_packet(&mut self) -> Result<Option<Packet>> {
+        // Like WAVE, give back the bytes of an incomplete packet, which is only possible if the
+        // whole packet remains buffered.
+        let packet_len = RAW_MAX_FRAMES_PER_PACKET * self.frame_len;
+
+        if packet_len > self.reader.max_read_buffer_len() as u64 {
+            return unsupported_error("raw pcm: packets are larger than the stream buffer");
+        }
+
+        let pos = self.reader.pos();
+
+        match self.next_packet() {
+            Ok(packet) => Ok(Some(packet)),
+            Err(err) if err.is_would_block() => {
+                if self.reader.seek_buffered(pos) != pos {
+                    return unsupported_error("raw pcm: incomplete packet is no longer buffered");
+                }
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn metadata(&mut self) -> Metadata<'_> {
+        self.metadata.metadata()
+    }
+
+    fn cues(&self) -> &[Cue] {
+        &self.cues
+    }
+
+    fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+
+    fn seek_caps(&self) -> SeekCaps {
+        SeekCaps::sample_accurate(self.reader.is_seekable(), Some(RAW_MAX_FRAMES_PER_PACKET))
+    }
+
+    fn seek(&mut self, _mode: SeekMode, to: SeekTo) -> Result<SeekedTo> {
+        let ts = match to {
+            SeekTo::TimeStamp { ts, .. } => ts,
+            SeekTo::Time { time, .. } => {
+                // The time base is always known for raw PCM.
+                self.tracks[0].codec_params.time_base.unwrap().calc_timestamp(time)
+            }
+        };
+
+        debug!("seeking to frame_ts={}", ts);
+
+        // Like WAVE, raw PCM is not packetized. Seek to a packet boundary so that packets have the
+        // same timestamps regardless if the stream was seeked or not.
+        let actual_ts = (ts / RAW_MAX_FRAMES_PER_PACKET) * RAW_MAX_FRAMES_PER_PACKET;
+
+        let seek_pos = self.data_start_pos + (actual_ts * self.frame_len);
+
+        if self.reader.is_seekable() {
+            self.reader.seek(SeekFrom::Start(seek_pos))?;
+        }
+        else {
+            let current_pos = self.reader.pos();
+            if seek_pos >= current_pos {
+                self.reader.ignore_bytes(seek_pos - current_pos)?;
+            }
+            else {
+                return seek_error(SeekErrorKind::ForwardOnly)
+            }
+        }
+
+        Ok(SeekedTo { track_id: 0, actual_ts, required_ts: ts })
+    }
+
+    fn into_inner(self: Box<Self>) -> MediaSourceStream {
+        self.reader
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Cursor, Read, Seek, SeekFrom};
+    use std::sync::{Arc, Mutex};
+
+    use symphonia_core::audio::{Channels, SampleBuffer};
+    use symphonia_core::codecs::{CodecRegistry, DecoderOptions};
+    use symphonia_core::codecs::{CODEC_TYPE_PCM_F64LE, CODEC_TYPE_PCM_S16LE};
+    use symphonia_core::errors::Error;
+    use symphonia_core::formats::{FormatOptions, FormatReader, RawPcmOptions};
+    use symphonia_core::io::{MediaSource, MediaSourceStream};
+    use symphonia_core::probe::{Hint, Probe};
+
+    use crate::PcmDecoder;
+
+    use super::RawPcmReader;
+
+    #[test]
+    fn verify_raw_pcm_reader() {
+        // Two full packets, and part of a third, of stereo samples, followed by half of a frame.
+        let samples = (0..2 * 3000).map(|i| (i * 7 - 20_000) as i16).collect::<Vec<_>>();
+
+        let mut data = samples.iter().flat_map(|s| s.to_le_bytes()).collect::<Vec<u8>>();
+        data.extend_from_slice(&[0x12, 0x34]);
+
+        let mut probe = Probe::default();
+        probe.register_all::<RawPcmReader>();
+
+        let mut registry = CodecRegistry::new();
+        registry.register_all::<PcmDecoder>();
+
+        let format_opts = FormatOptions {
+            raw_pcm: Some(RawPcmOptions {
+                rate: 8_000,
+                channels: Channels::FRONT_LEFT | Channels::FRONT_RIGHT,
+                format: CODEC_TYPE_PCM_S16LE,
+            }),
+            ..Default::default()
+        };
+
+        let mut hint = Hint::new();
+        hint.force_format("raw");
+
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(data.clone())), Default::default());
+        let mut probed = probe.format(&hint, mss, &format_opts, &Default::default()).unwrap();
+
+        let params = &probed.format.tracks()[0].codec_params;
+        assert_eq!(params.bit_rate, Some(8_000 * 2 * 16));
+
+        let mut decoder = registry.make(params, &DecoderOptions::default()).unwrap();
+
+        let mut decoded = Vec::new();
+
+        let result = loop {
+            let packet = match probed.format.next_packet() {
+                Ok(packet) => packet,
+                Err(err) => break err,
+            };
+
+            let buf = decoder.decode(&packet).unwrap();
+
+            let mut sample_buf = SampleBuffer::<i16>::new(buf.capacity() as u64, *buf.spec());
+            sample_buf.copy_interleaved_ref(buf);
+
+            decoded.extend_from_slice(sample_buf.samples());
+        };
+
+        assert!(matches!(result, Error::EndOfStream));
+        assert_eq!(decoded, samples);
+
+        // Raw PCM is never detected without being forced.
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(data.clone())), Default::default());
+        assert!(probe.format(&Hint::new(), mss, &format_opts, &Default::default()).is_err());
+
+        // Raw PCM cannot be read without its parameters.
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(data)), Default::default());
+        assert!(probe.format(&hint, mss, &Default::default(), &Default::default()).is_err());
+    }
+
+    /// The data received so far by a `Trickle` source, and if no more data will be received.
+    type Received = Arc<Mutex<(Vec<u8>, bool)>>;
+
+    /// A media source that receives data incrementally, like a network stream. Reading past the
+    /// data received so far is a `WouldBlock` error until the source is closed.
+    struct Trickle {
+        received: Received,
+        pos: usize,
+    }
+
+    impl Read for Trickle {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let received = self.received.lock().unwrap();
+            let (data, closed) = &*received;
+
+            if self.pos == data.len() && !closed {
+                return Err(io::Error::from(io::ErrorKind::WouldBlock));
+            }
+
+            let len = buf.len().min(data.len() - self.pos);
+            buf[..len].copy_from_slice(&data[self.pos..self.pos + len]);
+            self.pos += len;
+            Ok(len)
+        }
+    }
+
+    impl Seek for Trickle {
+        fn seek(&mut self, _: SeekFrom) -> io::Result<u64> {
+            Err(io::Error::other("not seekable"))
+        }
+    }
+
+    impl MediaSource for Trickle {
+        fn is_seekable(&self) -> bool {
+            false
+        }
+
+        fn len(&self) -> Option<u64> {
+            None
+        }
+    }
+
+    #[test]
+    fn verify_try_next_packet() {
+        // One full packet, and part of a second, of mono samples.
+        let data = (0..1500).flat_map(|i| (i as i16).to_le_bytes()).collect::<Vec<u8>>();
+
+        let received = Received::default();
+        let source = Trickle { received: received.clone(), pos: 0 };
+
+        let format_opts = FormatOptions {
+            raw_pcm: Some(RawPcmOptions {
+                rate: 8_000,
+                channels: Channels::FRONT_LEFT,
+                format: CODEC_TYPE_PCM_S16LE,
+            }),
+            ..Default::default()
+        };
+
+        let mss = MediaSourceStream::new(Box::new(source), Default::default());
+        let mut reader = RawPcmReader::try_new(mss, &format_opts).unwrap();
+
+        // No packet is returned until all of its bytes have been received.
+        for chunk in data[..2 * 1152].chunks(500) {
+            assert!(reader.try_next_packet().unwrap().is_none());
+            received.lock().unwrap().0.extend_from_slice(chunk);
+        }
+
+        let packet = reader.try_next_packet().unwrap().unwrap();
+        assert_eq!((packet.pts(), packet.duration()), (0, 1152));
+        assert_eq!(packet.buf(), &data[..2 * 1152]);
+
+        // The rest of the data is only a partial packet, which is returned once the source is
+        // closed.
+        received.lock().unwrap().0.extend_from_slice(&data[2 * 1152..]);
+        assert!(reader.try_next_packet().unwrap().is_none());
+
+        received.lock().unwrap().1 = true;
+
+        let packet = reader.try_next_packet().unwrap().unwrap();
+        assert_eq!((packet.pts(), packet.duration()), (1152, 348));
+        assert_eq!(packet.buf(), &data[2 * 1152..]);
+
+        assert!(matches!(reader.try_next_packet(), Err(Error::EndOfStream)));
+    }
+
+    #[test]
+    fn verify_try_next_packet_larger_than_buffer() {
+        let received = Received::default();
+        let source = Trickle { received, pos: 0 };
+
+        // A packet of 8 channels of 64-bit samples is larger than can be kept buffered.
+        let format_opts = FormatOptions {
+            raw_pcm: Some(RawPcmOptions {
+                rate: 48_000,
+                channels: Channels::from_bits(0xff).unwrap(),
+                format: CODEC_TYPE_PCM_F64LE,
+            }),
+            ..Default::default()
+        };
+
+        let mss = MediaSourceStream::new(Box::new(source), Default::default());
+        let mut reader = RawPcmReader::try_new(mss, &format_opts).unwrap();
+
+        // The read is refused, instead of returning a retryable result forever.
+        assert!(matches!(reader.try_next_packet(), Err(Error::Unsupported(_))));
+    }
+}
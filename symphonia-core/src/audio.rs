@@ -17,8 +17,11 @@ use std::vec::Vec;
 use arrayvec::ArrayVec;
 use bitflags::bitflags;
 
-use crate::conv::{ConvertibleSample, IntoSample};
-use crate::errors::Result;
+use crate::conv::{ConvertibleSample, IntoSample, ReversibleSample};
+use crate::conv::dither::{AddNoise, Dither, DitherType, Identity, MaybeDither, RandomNoise};
+use crate::conv::dither::{Rectangular, Triangular};
+use crate::dsp::downmix::{positioned_channels, stereo_coefficients};
+use crate::errors::{Result, decode_error, limit_error, unsupported_error};
 use crate::sample::{Sample, i24, u24};
 use crate::units::Duration;
 
@@ -26,6 +29,13 @@ use crate::units::Duration;
 /// stack before storing the slices on the heap.
 const AUDIO_PLANES_STORAGE_STACK_LIMIT: usize = 8;
 
+/// The maximum number of audio planes summarized by the `Debug` implementation of `AudioBuffer`.
+const AUDIO_BUFFER_DEBUG_MAX_PLANES: usize = 16;
+
+/// The number of leading samples per audio plane printed by the `Debug` implementation of
+/// `AudioBuffer`.
+const AUDIO_BUFFER_DEBUG_MAX_SAMPLES: usize = 8;
+
 bitflags! {
     /// Channels is a bit mask of all channels contained in a signal.
     #[derive(Default)]
@@ -82,24 +92,140 @@ bitflags! {
         const TOP_REAR_CENTRE    = 0x0100_0000;
         /// Top rear-right channel.
         const TOP_REAR_RIGHT     = 0x0200_0000;
+        /// The channels are discrete and do not have a position. If set, the remaining bits of the
+        /// mask store the number of channels instead of channel positions. Use
+        /// `Channels::discrete` to instantiate a discrete channel mask, and
+        /// `Channels::has_positions` or `Channels::has_any_position` rather than `contains` or
+        /// `intersects` to query the channel positions of a mask that may be discrete.
+        const DISCRETE           = 0x8000_0000;
     }
 }
 
 impl Channels {
+    /// The bits of a discrete channel mask that store the number of channels.
+    const DISCRETE_COUNT_MASK: u32 = 0x03ff_ffff;
+
+    /// The maximum number of channels a discrete channel mask may store.
+    pub const MAX_DISCRETE: usize = Channels::DISCRETE_COUNT_MASK as usize;
+
+    /// Instantiate a discrete channel mask of `count` channels. A discrete channel mask is used for
+    /// streams where the channels do not map to, or exceed, the named channel positions (e.g.,
+    /// ambisonics or object audio). Discrete channels are stored in index order. Panics if `count`
+    /// exceeds `Channels::MAX_DISCRETE`.
+    pub fn discrete(count: usize) -> Channels {
+        assert!(count <= Channels::MAX_DISCRETE, "too many discrete channels");
+        Channels::from_bits_truncate(Channels::DISCRETE.bits | count as u32)
+    }
+
+    /// Returns `true` if the channels are discrete. The channel position flags of a discrete
+    /// channel mask are meaningless and should not be queried.
+    pub fn is_discrete(self) -> bool {
+        self.contains(Channels::DISCRETE)
+    }
+
+    /// Returns `true` if all the channel positions in `positions` are present. Discrete channels
+    /// do not have positions, therefore this is always `false` if either mask is discrete.
+    pub fn has_positions(self, positions: Channels) -> bool {
+        !self.is_discrete() && !positions.is_discrete() && self.contains(positions)
+    }
+
+    /// Returns `true` if any of the channel positions in `positions` are present. Discrete channels
+    /// do not have positions, therefore this is always `false` if either mask is discrete.
+    pub fn has_any_position(self, positions: Channels) -> bool {
+        !self.is_discrete() && !positions.is_discrete() && self.intersects(positions)
+    }
+
     /// Gets the number of channels.
     pub fn count(self) -> usize {
-        self.bits.count_ones() as usize
+        if self.is_discrete() {
+            (self.bits & Channels::DISCRETE_COUNT_MASK) as usize
+        }
+        else {
+            self.bits.count_ones() as usize
+        }
+    }
+
+    /// Gets the planar index of a single channel within the channels, that is, the number of
+    /// channels that precede it in canonical channel order. The index may be used to address the
+    /// channel in an audio buffer (e.g., `buf.chan(index)`). Returns `None` if `channel` is not a
+    /// single channel, is not present, or the channels are discrete.
+    pub fn index_of(&self, channel: Channels) -> Option<u8> {
+        if channel.bits.count_ones() != 1 || !self.has_positions(channel) {
+            return None;
+        }
+
+        Some((self.bits & (channel.bits - 1)).count_ones() as u8)
+    }
+
+    /// Gets the well-known channel `Layout` that exactly matches the channels, if one exists.
+    pub fn as_layout(&self) -> Option<Layout> {
+        const LAYOUTS: [Layout; 8] = [
+            Layout::Mono,
+            Layout::Stereo,
+            Layout::TwoPointOne,
+            Layout::Quad,
+            Layout::FivePointZero,
+            Layout::FivePointOne,
+            Layout::SixPointOne,
+            Layout::SevenPointOne,
+        ];
+
+        LAYOUTS.iter().copied().find(|layout| layout.into_channels() == *self)
+    }
+
+    /// Gets a comma-separated list of the abbreviated names of each channel (e.g., "FL, FR, FC") in
+    /// canonical channel order. Use the `fmt::Binary` formatter to get the raw channel mask instead.
+    pub fn to_names(&self) -> String {
+        if self.is_discrete() {
+            return format!("discrete({})", self.count());
+        }
+
+        CHANNEL_NAMES.iter()
+                     .filter(|(channel, _)| self.has_positions(*channel))
+                     .map(|(_, name)| *name)
+                     .collect::<Vec<&str>>()
+                     .join(", ")
     }
 }
 
+/// The abbreviated names of each channel position, in canonical channel order.
+const CHANNEL_NAMES: [(Channels, &str); 26] = [
+    (Channels::FRONT_LEFT, "FL"),
+    (Channels::FRONT_RIGHT, "FR"),
+    (Channels::FRONT_CENTRE, "FC"),
+    (Channels::REAR_LEFT, "RL"),
+    (Channels::REAR_CENTRE, "RC"),
+    (Channels::REAR_RIGHT, "RR"),
+    (Channels::LFE1, "LFE1"),
+    (Channels::FRONT_LEFT_CENTRE, "FLC"),
+    (Channels::FRONT_RIGHT_CENTRE, "FRC"),
+    (Channels::REAR_LEFT_CENTRE, "RLC"),
+    (Channels::REAR_RIGHT_CENTRE, "RRC"),
+    (Channels::FRONT_LEFT_WIDE, "FLW"),
+    (Channels::FRONT_RIGHT_WIDE, "FRW"),
+    (Channels::FRONT_LEFT_HIGH, "FLH"),
+    (Channels::FRONT_CENTRE_HIGH, "FCH"),
+    (Channels::FRONT_RIGHT_HIGH, "FRH"),
+    (Channels::LFE2, "LFE2"),
+    (Channels::SIDE_LEFT, "SL"),
+    (Channels::SIDE_RIGHT, "SR"),
+    (Channels::TOP_CENTRE, "TC"),
+    (Channels::TOP_FRONT_LEFT, "TFL"),
+    (Channels::TOP_FRONT_CENTRE, "TFC"),
+    (Channels::TOP_FRONT_RIGHT, "TFR"),
+    (Channels::TOP_REAR_LEFT, "TRL"),
+    (Channels::TOP_REAR_CENTRE, "TRC"),
+    (Channels::TOP_REAR_RIGHT, "TRR"),
+];
+
 impl fmt::Display for Channels {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:#032b}", self.bits)
+        f.write_str(&self.to_names())
     }
 }
 
 /// `Layout` describes common audio channel configurations.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Layout {
     /// Single centre channel.
     Mono,
@@ -107,8 +233,18 @@ pub enum Layout {
     Stereo,
     /// Left and Right channels with a single low-frequency channel.
     TwoPointOne,
+    /// Front Left and Right, and Rear Left and Right channels.
+    Quad,
+    /// Front Left, Right, and Centre, and Rear Left and Right channels.
+    FivePointZero,
     /// Front Left and Right, Rear Left and Right, and a single low-frequency channel.
     FivePointOne,
+    /// Front Left, Right, and Centre, Side Left and Right, Rear Centre, and a single low-frequency
+    /// channel.
+    SixPointOne,
+    /// Front Left, Right, and Centre, Side Left and Right, Rear Left and Right, and a single
+    /// low-frequency channel.
+    SevenPointOne,
 }
 
 impl Layout {
@@ -127,6 +263,19 @@ impl Layout {
                     | Channels::FRONT_RIGHT
                     | Channels::LFE1
             },
+            Layout::Quad => {
+                Channels::FRONT_LEFT
+                    | Channels::FRONT_RIGHT
+                    | Channels::REAR_LEFT
+                    | Channels::REAR_RIGHT
+            },
+            Layout::FivePointZero => {
+                Channels::FRONT_LEFT
+                    | Channels::FRONT_RIGHT
+                    | Channels::FRONT_CENTRE
+                    | Channels::REAR_LEFT
+                    | Channels::REAR_RIGHT
+            },
             Layout::FivePointOne => {
                 Channels::FRONT_LEFT
                     | Channels::FRONT_RIGHT
@@ -134,14 +283,38 @@ impl Layout {
                     | Channels::REAR_LEFT
                     | Channels::REAR_RIGHT
                     | Channels::LFE1
+            },
+            Layout::SixPointOne => {
+                Channels::FRONT_LEFT
+                    | Channels::FRONT_RIGHT
+                    | Channels::FRONT_CENTRE
+                    | Channels::REAR_CENTRE
+                    | Channels::SIDE_LEFT
+                    | Channels::SIDE_RIGHT
+                    | Channels::LFE1
+            },
+            Layout::SevenPointOne => {
+                Channels::FRONT_LEFT
+                    | Channels::FRONT_RIGHT
+                    | Channels::FRONT_CENTRE
+                    | Channels::REAR_LEFT
+                    | Channels::REAR_RIGHT
+                    | Channels::SIDE_LEFT
+                    | Channels::SIDE_RIGHT
+                    | Channels::LFE1
             }
         }
     }
 
+    /// Gets the number of channels in the channel `Layout`.
+    pub fn channel_count(self) -> usize {
+        self.into_channels().count()
+    }
+
 }
 
 /// `SignalSpec` describes the characteristics of a Signal.
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct SignalSpec {
     /// The signal sampling rate in hertz (Hz).
     pub rate: u32,
@@ -271,30 +444,81 @@ pub struct AudioBuffer<S : Sample> {
     n_capacity: usize,
 }
 
+/// Gets the number of samples a buffer of `duration` frames of `channels` stores, or an error if
+/// the buffer would exceed the addressable memory for samples of type `T`.
+fn buffer_len<T>(duration: Duration, channels: Channels) -> Result<usize> {
+    // Practically speaking, it is not possible to allocate more than usize::MAX bytes of samples.
+    let max_len = (usize::MAX / mem::size_of::<T>()) as u64;
+
+    match duration.checked_mul(channels.count() as u64) {
+        Some(n_samples) if n_samples <= max_len => Ok(n_samples as usize),
+        _ => limit_error("duration too large"),
+    }
+}
+
+/// Allocates a buffer of `len` copies of `value`, or returns an error if the memory could not be
+/// allocated.
+fn try_alloc<T: Copy>(len: usize, value: T) -> Result<Vec<T>> {
+    let mut buf = Vec::new();
+
+    if buf.try_reserve_exact(len).is_err() {
+        return limit_error("failed to allocate buffer");
+    }
+
+    buf.resize(len, value);
+
+    Ok(buf)
+}
+
 impl<S : Sample> AudioBuffer<S> {
     /// Instantiate a new `AudioBuffer` using the specified signal specification and of the given
-    /// duration.
+    /// duration. Panics if the buffer could not be allocated. See `try_new` for a non-panicking
+    /// alternative.
     pub fn new(duration: Duration, spec: SignalSpec) -> Self {
-        // The number of channels * duration cannot exceed u64::MAX.
-        assert!(duration <= u64::MAX / spec.channels.count() as u64, "duration too large");
-
-        // The total number of samples the buffer will store.
-        let n_samples = duration * spec.channels.count() as u64;
+        AudioBuffer::try_new(duration, spec).unwrap()
+    }
 
-        // Practically speaking, it is not possible to allocate more than usize::MAX bytes of
-        // samples. This assertion ensures the potential downcast of n_samples to usize below is
-        // safe.
-        assert!(n_samples <= (usize::MAX / mem::size_of::<S>()) as u64, "duration too large");
+    /// Instantiate a new `AudioBuffer` using the specified signal specification and of the given
+    /// duration. Returns an error if the duration is too large, or the buffer could not be
+    /// allocated.
+    pub fn try_new(duration: Duration, spec: SignalSpec) -> Result<Self> {
+        let n_samples = buffer_len::<S>(duration, spec.channels)?;
 
         // Allocate sample buffer and default initialize all samples to silence.
-        let buf = vec![S::MID; n_samples as usize];
+        let buf = try_alloc(n_samples, S::MID)?;
 
-        AudioBuffer {
+        Ok(AudioBuffer {
             buf,
             spec,
             n_frames: 0,
             n_capacity: duration as usize,
+        })
+    }
+
+    /// Instantiate a new `AudioBuffer` using the specified signal specification containing the
+    /// `n_frames` frames of interleaved samples in `data`. The buffer has a capacity of exactly
+    /// `n_frames` frames. Panics if the length of `data` is not `n_frames` multiplied by the number
+    /// of channels.
+    pub fn from_interleaved(spec: &SignalSpec, n_frames: usize, data: &[S]) -> Self {
+        assert!(
+            data.len() == n_frames * spec.channels.count(),
+            "interleaved data length does not match the number of frames and channels"
+        );
+
+        let mut buf = AudioBuffer::new(n_frames as Duration, *spec);
+
+        if n_frames > 0 {
+            let n_channels = spec.channels.count();
+
+            for (ch, plane) in buf.buf.chunks_exact_mut(n_frames).enumerate() {
+                for (d, &s) in plane.iter_mut().zip(data[ch..].iter().step_by(n_channels)) {
+                    *d = s;
+                }
+            }
         }
+
+        buf.n_frames = n_frames;
+        buf
     }
 
     /// Instantiates an unused `AudioBuffer`. An unused `AudioBuffer` will not allocate any memory,
@@ -379,759 +603,3211 @@ impl<S : Sample> AudioBuffer<S> {
         }
     }
 
-    /// Makes an equivalent AudioBuffer of a different type.
-    pub fn make_equivalent<E: Sample>(&self) -> AudioBuffer<E> {
-        AudioBuffer::<E>::new(self.n_capacity as Duration, self.spec)
-    }
-}
+    /// Copies all written frames into the destination slice in interleaved order without any
+    /// intermediate allocation. Returns the number of samples written to the destination slice.
+    /// Panics if the destination slice is too small to hold all written frames.
+    pub fn copy_interleaved_into(&self, dest: &mut [S]) -> usize {
+        let n_channels = self.spec.channels.count();
+        let n_samples = self.n_frames * n_channels;
 
-/// `AudioBufferRef` is a copy-on-write reference to an `AudioBuffer` of any type.
-pub enum AudioBufferRef<'a> {
-    U8(Cow<'a, AudioBuffer<u8>>),
-    U16(Cow<'a, AudioBuffer<u16>>),
-    U24(Cow<'a, AudioBuffer<u24>>),
-    U32(Cow<'a, AudioBuffer<u32>>),
-    S8(Cow<'a, AudioBuffer<i8>>),
-    S16(Cow<'a, AudioBuffer<i16>>),
-    S24(Cow<'a, AudioBuffer<i24>>),
-    S32(Cow<'a, AudioBuffer<i32>>),
-    F32(Cow<'a, AudioBuffer<f32>>),
-    F64(Cow<'a, AudioBuffer<f64>>),
-}
+        assert!(dest.len() >= n_samples, "destination slice is too small");
 
-impl<'a> AudioBufferRef<'a> {
-    /// Gets the signal specification for the buffer.
-    pub fn spec(&self) -> &SignalSpec {
-        match self {
-            AudioBufferRef::U8(buf)  => buf.spec(),
-            AudioBufferRef::U16(buf) => buf.spec(),
-            AudioBufferRef::U24(buf) => buf.spec(),
-            AudioBufferRef::U32(buf) => buf.spec(),
-            AudioBufferRef::S8(buf)  => buf.spec(),
-            AudioBufferRef::S16(buf) => buf.spec(),
-            AudioBufferRef::S24(buf) => buf.spec(),
-            AudioBufferRef::S32(buf) => buf.spec(),
-            AudioBufferRef::F32(buf) => buf.spec(),
-            AudioBufferRef::F64(buf) => buf.spec(),
+        if n_samples > 0 {
+            for (ch, plane) in self.buf.chunks_exact(self.n_capacity).enumerate() {
+                let src = &plane[..self.n_frames];
+
+                for (d, &s) in dest[ch..n_samples].iter_mut().step_by(n_channels).zip(src) {
+                    *d = s;
+                }
+            }
         }
+
+        n_samples
     }
 
-    /// Gets the total capacity of the buffer. The capacity is the maximum number of audio frames
-    /// a buffer can store.
-    pub fn capacity(&self) -> usize {
-        match self {
-            AudioBufferRef::U8(buf)  => buf.capacity(),
-            AudioBufferRef::U16(buf) => buf.capacity(),
-            AudioBufferRef::U24(buf) => buf.capacity(),
-            AudioBufferRef::U32(buf) => buf.capacity(),
-            AudioBufferRef::S8(buf)  => buf.capacity(),
-            AudioBufferRef::S16(buf) => buf.capacity(),
-            AudioBufferRef::S24(buf) => buf.capacity(),
-            AudioBufferRef::S32(buf) => buf.capacity(),
-            AudioBufferRef::F32(buf) => buf.capacity(),
-            AudioBufferRef::F64(buf) => buf.capacity(),
-        }
+    /// Shortens the buffer to `n_frames` frames, keeping the first `n_frames` written frames. If
+    /// `n_frames` is greater than or equal to the number of written frames, this has no effect.
+    /// The underlying audio data is neither modified nor reallocated.
+    pub fn truncate(&mut self, n_frames: usize) {
+        assert!(n_frames <= self.n_capacity);
+        self.n_frames = self.n_frames.min(n_frames);
     }
-}
 
-/// `AsAudioBufferRef` is a trait implemented for `AudioBuffer`s that may be referenced in an
-/// `AudioBufferRef`.
-pub trait AsAudioBufferRef {
-    /// Get an `AudioBufferRef` reference.
-    fn as_audio_buffer_ref(&self) -> AudioBufferRef;
-}
+    /// Trims the buffer to the `len` written frames starting at frame `start`. The trimmed frames
+    /// are moved to the start of the buffer without reallocating, and the samples are preserved.
+    /// The trimmed range must be within the written frames.
+    pub fn trim(&mut self, start: usize, len: usize) {
+        let end = start + len;
+        assert!(end <= self.n_frames, "trimmed range exceeds the written frames");
 
-macro_rules! impl_as_audio_buffer_ref {
-    ($fmt:ty, $ref:path) => (
-        impl AsAudioBufferRef for AudioBuffer<$fmt> {
-            fn as_audio_buffer_ref(&self) -> AudioBufferRef {
-                $ref(Cow::Borrowed(self))
+        if start > 0 && len > 0 {
+            for plane in self.buf.chunks_exact_mut(self.n_capacity) {
+                plane.copy_within(start..end, 0);
             }
         }
-    )
-}
 
-impl_as_audio_buffer_ref!(u8,  AudioBufferRef::U8);
-impl_as_audio_buffer_ref!(u16, AudioBufferRef::U16);
-impl_as_audio_buffer_ref!(u24, AudioBufferRef::U24);
-impl_as_audio_buffer_ref!(u32, AudioBufferRef::U32);
-impl_as_audio_buffer_ref!(i8,  AudioBufferRef::S8);
-impl_as_audio_buffer_ref!(i16, AudioBufferRef::S16);
-impl_as_audio_buffer_ref!(i24, AudioBufferRef::S24);
-impl_as_audio_buffer_ref!(i32, AudioBufferRef::S32);
-impl_as_audio_buffer_ref!(f32, AudioBufferRef::F32);
-impl_as_audio_buffer_ref!(f64, AudioBufferRef::F64);
+        self.n_frames = len;
+    }
 
-/// The `Signal` trait provides methods for rendering and transforming contiguous buffers of audio
-/// data.
-pub trait Signal<S : Sample> {
-    /// Gets the number of actual frames written to the buffer. Conversely, this also is the number
-    /// of written samples in any one channel.
-    fn frames(&self) -> usize;
+    /// Splits the buffer into two at frame `at`. Returns a new buffer containing the written frames
+    /// `[at, frames)`, and leaves the buffer containing the frames `[0, at)`. The returned buffer
+    /// has the same signal specification, and the remainder of the capacity, `capacity - at`.
+    /// Panics if `at` exceeds the number of written frames.
+    pub fn split_off(&mut self, at: usize) -> AudioBuffer<S> {
+        assert!(at <= self.n_frames, "split point exceeds the written frames");
 
-    /// Clears all written frames from the buffer. This is a cheap operation and does not zero the
-    /// underlying audio data.
-    fn clear(&mut self);
+        let mut other = AudioBuffer::new((self.n_capacity - at) as Duration, self.spec);
 
-    /// Gets an immutable reference to all the written samples in the specified channel.
-    fn chan(&self, channel: usize) -> &[S];
+        if other.n_capacity > 0 {
+            let len = self.n_frames - at;
 
-    /// Gets a mutable reference to all the written samples in the specified channel.
-    fn chan_mut(&mut self, channel: usize) -> &mut [S];
+            let src_planes = self.buf.chunks_exact(self.n_capacity);
+            let dst_planes = other.buf.chunks_exact_mut(other.n_capacity);
 
-    /// Gets two mutable references to two different channels.
-    fn chan_pair_mut(&mut self, first: usize, second: usize) -> (&mut [S], &mut [S]);
+            for (src, dst) in src_planes.zip(dst_planes) {
+                dst[..len].copy_from_slice(&src[at..self.n_frames]);
+            }
 
-    /// Renders a reserved number of frames. This is a cheap operation and simply advances the frame
-    /// counter. The underlying audio data is not modified and should be overwritten through other
-    /// means.
-    ///
-    /// If `n_frames` is `None`, the remaining number of samples will be used. If `n_frames` is too
-    /// large, this function will assert.
-    fn render_reserved(&mut self, n_frames: Option<usize>);
+            other.n_frames = len;
+        }
 
-    /// Renders a number of frames using the provided render function. The number of frames to
-    /// render is specified by `n_frames`. If `n_frames` is `None`, the remaining number of frames
-    /// in the buffer will be rendered. If the render function returns an error, the render
-    /// operation is terminated prematurely.
-    fn render<'a, F>(&'a mut self, n_frames: Option<usize>, render: F) -> Result<()>
-    where
-        F: FnMut(&mut AudioPlanesMut<'a, S>, usize) -> Result<()>;
+        self.n_frames = at;
 
-    /// Clears, and then renders the entire buffer using the fill function. This is a convenience
-    /// wrapper around `render` and exhibits the same behaviour as `render` in regards to the fill
-    /// function.
-    #[inline]
-    fn fill<'a, F>(&'a mut self, fill: F) -> Result<()>
-    where
-        F: FnMut(&mut AudioPlanesMut<'a, S>, usize) -> Result<()>
-    {
-        self.clear();
-        self.render(None, fill)
+        other
     }
 
-    /// Transforms every written sample in the signal using the transformation function provided.
-    /// This function does not guarantee an order in which the samples are transformed.
-    fn transform<F>(&mut self, f: F)
-    where
-        F: Fn(S) -> S;
-}
-
-impl<S: Sample> Signal<S> for AudioBuffer<S> {
-
-    fn clear(&mut self) {
-        self.n_frames = 0;
-    }
+    /// Appends the written frames of another `AudioBuffer` after the frames written to this
+    /// buffer. Both buffers must have the same signal specification, and this buffer must have
+    /// enough remaining capacity for the appended frames, otherwise an error is returned.
+    pub fn append(&mut self, other: &AudioBuffer<S>) -> Result<()> {
+        if self.spec != other.spec {
+            return unsupported_error("cannot append audio buffers with different signal specs");
+        }
 
-    fn frames(&self) -> usize {
-        self.n_frames
-    }
+        let end = self.n_frames + other.n_frames;
 
-    fn chan(&self, channel: usize) -> &[S]{
-        let start = channel * self.n_capacity;
-        let end = start + self.n_frames;
+        if end > self.n_capacity {
+            return unsupported_error("not enough capacity to append audio buffer");
+        }
 
-        // Do not exceed the audio buffer.
-        assert!(end <= self.buf.len());
+        if other.n_frames == 0 {
+            return Ok(());
+        }
 
-        &self.buf[start..end]
-    }
+        let dst_planes = self.buf.chunks_exact_mut(self.n_capacity);
+        let src_planes = other.buf.chunks_exact(other.n_capacity);
 
-    fn chan_mut(&mut self, channel: usize) -> &mut [S] {
-        let start = channel * self.n_capacity;
-        let end = start + self.n_frames;
+        for (dst, src) in dst_planes.zip(src_planes) {
+            dst[self.n_frames..end].copy_from_slice(&src[..other.n_frames]);
+        }
 
-        // Do not exceed the audio buffer.
-        assert!(end <= self.buf.len());
+        self.n_frames = end;
 
-        &mut self.buf[start..end]
+        Ok(())
     }
 
-    fn chan_pair_mut(&mut self, first: usize, second: usize) -> (&mut [S], &mut [S]) {
-        // Both channels in the pair must be unique.
-        assert!(first != second);
-
-        let first_idx = self.n_capacity * first;
-        let second_idx = self.n_capacity * second;
-
-        if first_idx < second_idx {
-            let (a, b) = self.buf.split_at_mut(second_idx);
+    /// Transforms every written sample in the buffer using the fallible transformation function
+    /// provided. The samples of each channel are transformed in order, one channel after another.
+    /// Transformation stops at the first error, which is returned. Samples transformed before the
+    /// error keep their transformed values.
+    pub fn try_transform<F>(&mut self, mut f: F) -> Result<()>
+    where
+        F: FnMut(S) -> Result<S>
+    {
+        debug_assert!(self.n_frames <= self.n_capacity);
 
-            (&mut a[first_idx..first_idx + self.n_frames], &mut b[..self.n_frames])
+        if self.n_frames == 0 {
+            return Ok(());
         }
-        else {
-            let (a, b) = self.buf.split_at_mut(first_idx);
 
-            (&mut b[..self.n_frames], &mut a[second_idx..second_idx + self.n_frames])
+        for plane in self.buf.chunks_mut(self.n_capacity) {
+            for sample in &mut plane[0..self.n_frames] {
+                *sample = f(*sample)?;
+            }
         }
+
+        Ok(())
     }
 
-    fn render_reserved(&mut self, n_frames: Option<usize>) {
-        let n_reserved_frames = n_frames.unwrap_or(self.n_capacity - self.n_frames);
-        // Do not render past the end of the audio buffer.
-        assert!(self.n_frames + n_reserved_frames <= self.n_capacity);
-        self.n_frames += n_reserved_frames;
+    /// Makes an equivalent AudioBuffer of a different type.
+    pub fn make_equivalent<E: Sample>(&self) -> AudioBuffer<E> {
+        AudioBuffer::<E>::new(self.n_capacity as Duration, self.spec)
     }
 
-    fn render<'a, F>(&'a mut self, n_frames: Option<usize>, mut render: F) -> Result<()>
+    /// Copies interleaved samples from the source slice into the buffer, converting them to the
+    /// buffer's sample format. The copied frames are appended after any frames already written to
+    /// the buffer. The length of the source slice must be a multiple of the number of channels,
+    /// and the number of frames it contains must not exceed the remaining capacity of the buffer.
+    pub fn copy_from_interleaved<F>(&mut self, src: &[F])
     where
-        F: FnMut(&mut AudioPlanesMut<'a, S>, usize) -> Result<()>
+        F: Sample + IntoSample<S>,
     {
-        // The number of frames to be rendered is the amount requested, if specified, or the
-        // remainder of the audio buffer.
-        let n_render_frames = n_frames.unwrap_or(self.n_capacity - self.n_frames);
+        let n_channels = self.spec.channels.count();
 
-        // Do not render past the end of the audio buffer.
-        let end = self.n_frames + n_render_frames;
-        assert!(end <= self.n_capacity);
+        if n_channels == 0 {
+            assert!(src.is_empty(), "source samples provided for a buffer without channels");
+            return;
+        }
 
-        // At this point, n_render_frames can be considered "reserved". Create an audio plane
-        // structure and fill each plane entry with a reference to the "reserved" samples in each
-        // channel respectively.
-        let mut planes = AudioPlanesMut::new(self.spec.channels);
+        // The source slice must contain whole frames.
+        assert!(src.len() % n_channels == 0, "source length is not a multiple of channel count");
 
-        for channel in self.buf.chunks_exact_mut(self.n_capacity) {
-            planes.push(&mut channel[self.n_frames..end]);
+        let begin = self.n_frames;
+        let end = begin + src.len() / n_channels;
+
+        // Do not write past the end of the audio buffer.
+        assert!(end <= self.n_capacity);
+
+        if begin == end {
+            return;
         }
 
-        // Attempt to render the into the reserved frames, one-by-one, exiting only if there is an
-        // error in the render function.
-        while self.n_frames < end {
-            render(&mut planes, self.n_frames)?;
-            self.n_frames += 1;
+        // Provide slightly optimized deinterleave algorithms for Mono and Stereo buffers.
+        match n_channels {
+            // Mono
+            1 => {
+                for (d, &s) in self.buf[begin..end].iter_mut().zip(src) {
+                    *d = s.into_sample();
+                }
+            }
+            // Stereo
+            2 => {
+                let (l_buf, r_buf) = self.buf.split_at_mut(self.n_capacity);
+
+                let l_iter = l_buf[begin..end].iter_mut();
+                let r_iter = r_buf[begin..end].iter_mut();
+
+                for ((l, r), s) in l_iter.zip(r_iter).zip(src.chunks_exact(2)) {
+                    *l = s[0].into_sample();
+                    *r = s[1].into_sample();
+                }
+            }
+            // 3+ channels
+            _ => {
+                for (ch, plane) in self.buf.chunks_exact_mut(self.n_capacity).enumerate() {
+                    let src_ch_iter = src[ch..].iter().step_by(n_channels);
+
+                    for (d, &s) in plane[begin..end].iter_mut().zip(src_ch_iter) {
+                        *d = s.into_sample();
+                    }
+                }
+            }
         }
 
-        Ok(())
+        self.n_frames = end;
     }
 
-    fn transform<F>(&mut self, f: F)
+    /// Mixes the samples of another `AudioBuffer` into this buffer by summing them frame-for-frame.
+    /// Both buffers must have the same signal specification and number of frames, otherwise an
+    /// error is returned. Samples are summed using a double-precision floating point intermediate,
+    /// and integer samples saturate at the minimum or maximum value of the sample format instead of
+    /// wrapping.
+    pub fn mix(&mut self, other: &AudioBuffer<S>) -> Result<()>
     where
-        F: Fn(S) -> S
+        S: ReversibleSample<f64>
     {
-        debug_assert!(self.n_frames <= self.n_capacity);
+        if self.spec != other.spec {
+            return unsupported_error("cannot mix audio buffers with different signal specs");
+        }
 
-        // Apply the transformation function over each sample in each plane.
-        for plane in self.buf.chunks_mut(self.n_capacity) {
-            for sample in &mut plane[0..self.n_frames] {
-                *sample = f(*sample);
+        if self.n_frames != other.n_frames {
+            return unsupported_error("cannot mix audio buffers with different frame counts");
+        }
+
+        if self.n_frames == 0 {
+            return Ok(());
+        }
+
+        let dst_planes = self.buf.chunks_exact_mut(self.n_capacity);
+        let src_planes = other.buf.chunks_exact(other.n_capacity);
+
+        for (dst, src) in dst_planes.zip(src_planes) {
+            for (d, &s) in dst[..self.n_frames].iter_mut().zip(&src[..other.n_frames]) {
+                let a: f64 = (*d).into_sample();
+                let b: f64 = s.into_sample();
+                *d = S::from_sample(a + b);
             }
         }
+
+        Ok(())
     }
 
-}
+    /// Downmixes the buffer to a new mono buffer with the same capacity and sample rate.
+    ///
+    /// Each source channel is scaled by the average of its left and right coefficients in the
+    /// standard (ITU-R BS.775) stereo downmix. Therefore, a stereo signal is downmixed to
+    /// `(L + R) / 2`, and for 5.1, the centre and surround channels are additionally attenuated by
+    /// 3 dB while the LFE channel is dropped. Samples are summed using a double-precision floating
+    /// point intermediate, and integer samples saturate instead of wrapping. An error is returned
+    /// if the buffer has discrete channels since they do not have a standard downmix.
+    pub fn downmix_to_mono(&self) -> Result<AudioBuffer<S>>
+    where
+        S: ReversibleSample<f64>
+    {
+        let channels = self.spec.channels;
 
-/// A `SampleBuffer`, is a sample oriented buffer. It is agnostic to the ordering/layout of samples
-/// within the buffer. `SampleBuffer` is mean't for safely importing and exporting sample data to
-/// and from Symphonia using the sample's in-memory data-type.
-pub struct SampleBuffer<S: Sample> {
-    buf: Vec<S>,
-    n_written: usize,
-}
+        if channels.is_discrete() || channels.is_empty() {
+            return unsupported_error("no standard mono downmix exists for the channels");
+        }
 
-impl<S: Sample> SampleBuffer<S> {
-    /// Instantiate a new `SampleBuffer` using the specified signal specification and of the given
-    /// duration.
-    pub fn new(duration: Duration, spec: SignalSpec) -> SampleBuffer<S> {
-        // The number of channels * duration cannot exceed u64::MAX.
-        assert!(duration <= u64::MAX / spec.channels.count() as u64, "duration too large");
+        let coeffs = positioned_channels(channels)
+            .map(|channel| {
+                let (l, r) = stereo_coefficients(channel);
+                0.5 * f64::from(l + r)
+            })
+            .collect::<Vec<f64>>();
 
-        // The total number of samples the buffer will store.
-        let n_samples = duration * spec.channels.count() as u64;
+        let spec = SignalSpec::new(self.spec.rate, Channels::FRONT_LEFT);
 
-        // Practically speaking, it is not possible to allocate more than usize::MAX bytes of
-        // samples. This assertion ensures the potential downcast of n_samples to usize below is
-        // safe.
-        assert!(n_samples <= (usize::MAX / mem::size_of::<S>()) as u64, "duration too large");
+        let mut mono = AudioBuffer::<S>::new(self.n_capacity as Duration, spec);
+        mono.render_reserved(Some(self.n_frames));
 
-        SampleBuffer {
-            buf: vec![S::MID; n_samples as usize],
-            n_written: 0,
+        if self.n_frames == 0 {
+            return Ok(mono);
         }
-    }
 
-    /// Gets the number of written samples.
-    pub fn len(&self) -> usize {
-        self.n_written
-    }
+        // A mono source is copied as-is rather than halved.
+        if channels.count() == 1 {
+            mono.buf[..self.n_frames].copy_from_slice(&self.buf[..self.n_frames]);
+            return Ok(mono);
+        }
 
-    /// Gets an immutable slice of all written samples.
-    pub fn samples(&self) -> &[S] {
-        &self.buf[..self.n_written]
-    }
+        let mut sums = vec![0.0f64; self.n_frames];
 
-    /// Gets the maximum number of samples the `SampleBuffer` may store.
-    pub fn capacity(&self) -> usize {
-        self.buf.len()
-    }
+        for (plane, &coeff) in self.buf.chunks_exact(self.n_capacity).zip(&coeffs) {
+            if coeff == 0.0 {
+                continue;
+            }
 
-    /// Copies all audio data from the source `AudioBufferRef` in planar channel order into the
-    /// `SampleBuffer`. The two buffers must be equivalent.
-    pub fn copy_planar_ref(&mut self, src: AudioBufferRef)
-    where
-        S: ConvertibleSample,
-    {
-        match src {
-            AudioBufferRef::U8(buf)  => self.copy_planar_typed(&buf),
-            AudioBufferRef::U16(buf) => self.copy_planar_typed(&buf),
-            AudioBufferRef::U24(buf) => self.copy_planar_typed(&buf),
-            AudioBufferRef::U32(buf) => self.copy_planar_typed(&buf),
-            AudioBufferRef::S8(buf)  => self.copy_planar_typed(&buf),
-            AudioBufferRef::S16(buf) => self.copy_planar_typed(&buf),
-            AudioBufferRef::S24(buf) => self.copy_planar_typed(&buf),
-            AudioBufferRef::S32(buf) => self.copy_planar_typed(&buf),
-            AudioBufferRef::F32(buf) => self.copy_planar_typed(&buf),
-            AudioBufferRef::F64(buf) => self.copy_planar_typed(&buf),
+            for (sum, &s) in sums.iter_mut().zip(&plane[..self.n_frames]) {
+                let s: f64 = s.into_sample();
+                *sum += coeff * s;
+            }
+        }
+
+        for (d, &sum) in mono.buf[..self.n_frames].iter_mut().zip(&sums) {
+            *d = S::from_sample(sum);
         }
+
+        Ok(mono)
     }
 
-    /// Copies all audio data from a source `AudioBuffer` into the `SampleBuffer` in planar
-    /// channel order. The two buffers must be equivalent.
-    pub fn copy_planar_typed<F>(&mut self, src: &AudioBuffer<F>)
-    where
-        F: Sample + IntoSample<S>,
-    {
-        let n_frames = src.frames();
-        let n_channels = src.spec.channels.count();
-        let n_samples = n_frames * n_channels;
+    /// Upmixes the buffer to a new buffer with the given channel layout, and the same capacity and
+    /// sample rate.
+    ///
+    /// A mono channel is duplicated into both the front left and right channels of the new layout.
+    /// Otherwise, every channel of the buffer must also be present in the new layout, and is copied
+    /// into its plane. All remaining channels of the new layout are silent. An error is returned if
+    /// the buffer has discrete channels, or cannot be upmixed to the new layout.
+    pub fn upmix(&self, to: Layout) -> Result<AudioBuffer<S>> {
+        let channels = self.spec.channels;
+        let to_channels = to.into_channels();
+
+        if channels.is_discrete() || channels.is_empty() {
+            return unsupported_error("cannot upmix discrete channels");
+        }
 
-        // Ensure that the capacity of the sample buffer is greater than or equal to the number
-        // of samples that will be copied from the source buffer.
-        assert!(self.capacity() >= n_samples);
+        // Pairs of a source plane and the channel of the upmixed buffer it is copied into.
+        let mut routes = Vec::new();
 
-        for ch in 0..n_channels {
-            let ch_slice = src.chan(ch);
+        if channels.count() == 1 && to_channels.count() > 1 {
+            let front = Channels::FRONT_LEFT | Channels::FRONT_RIGHT;
 
-            for (dst, src) in self.buf[ch * n_frames..].iter_mut().zip(ch_slice) {
-                *dst = (*src).into_sample();
+            if !to_channels.has_positions(front) {
+                return unsupported_error("cannot upmix mono to a layout without front channels");
             }
+
+            routes.push((0, Channels::FRONT_LEFT));
+            routes.push((0, Channels::FRONT_RIGHT));
         }
+        else if to_channels.count() == 1 {
+            // Mono can only be "upmixed" from mono.
+            if channels.count() != 1 {
+                return unsupported_error("cannot upmix to a layout with fewer channels");
+            }
 
-        // Commit the written samples.
-        self.n_written = n_samples;
-    }
+            routes.push((0, Channels::FRONT_LEFT));
+        }
+        else {
+            if !to_channels.has_positions(channels) {
+                return unsupported_error("cannot upmix to a layout missing source channels");
+            }
 
-    /// Copies all audio data from the source `AudioBufferRef` in interleaved channel order into the
-    /// `SampleBuffer`. The two buffers must be equivalent.
-    pub fn copy_interleaved_ref(&mut self, src: AudioBufferRef)
-    where
-        S: ConvertibleSample,
-    {
-        match src {
-            AudioBufferRef::U8(buf)  => self.copy_interleaved_typed(&buf),
-            AudioBufferRef::U16(buf) => self.copy_interleaved_typed(&buf),
-            AudioBufferRef::U24(buf) => self.copy_interleaved_typed(&buf),
-            AudioBufferRef::U32(buf) => self.copy_interleaved_typed(&buf),
-            AudioBufferRef::S8(buf)  => self.copy_interleaved_typed(&buf),
-            AudioBufferRef::S16(buf) => self.copy_interleaved_typed(&buf),
-            AudioBufferRef::S24(buf) => self.copy_interleaved_typed(&buf),
-            AudioBufferRef::S32(buf) => self.copy_interleaved_typed(&buf),
-            AudioBufferRef::F32(buf) => self.copy_interleaved_typed(&buf),
-            AudioBufferRef::F64(buf) => self.copy_interleaved_typed(&buf),
+            routes.extend(positioned_channels(channels).enumerate());
         }
-    }
 
-    /// Copies all audio samples from a source `AudioBuffer` into the `SampleBuffer` in interleaved
-    /// channel order. The two buffers must be equivalent.
-    pub fn copy_interleaved_typed<F>(&mut self, src: &AudioBuffer<F>)
-    where
-        F: Sample + IntoSample<S>,
-    {
-        let n_channels = src.spec.channels.count();
-        let n_samples = src.frames() * n_channels;
+        let spec = SignalSpec::new(self.spec.rate, to_channels);
 
-        // Ensure that the capacity of the sample buffer is greater than or equal to the number
-        // of samples that will be copied from the source buffer.
-        assert!(self.capacity() >= n_samples);
+        let mut upmixed = AudioBuffer::<S>::new(self.n_capacity as Duration, spec);
+        upmixed.render_reserved(Some(self.n_frames));
 
-        // Interleave the source buffer channels into the sample buffer.
-        for ch in 0..n_channels {
-            let ch_slice = src.chan(ch);
+        for (src, channel) in routes {
+            let dst = positioned_channels(to_channels).position(|c| c == channel).unwrap();
 
-            for (dst, src) in self.buf[ch..].iter_mut().step_by(n_channels).zip(ch_slice) {
-                *dst = (*src).into_sample();
-            }
+            upmixed.chan_mut(dst).copy_from_slice(self.chan(src));
         }
 
-        // Commit the written samples.
-        self.n_written = n_samples;
+        Ok(upmixed)
     }
-}
 
-/// This non-public module contains the trait `Sealed` which is used to constrain
-/// `RawSample::RawType` with `bytemuck::Pod`. This is a trade-off to hide `bytemuck` from the public
-/// interface. The downside is that `RawSample::RawType` is locked to the types we implement
-/// `Sealed` on. To compensate, we implement `Sealed` on all primitive numeric data types, and byte
-/// arrays up to 8 bytes long.
-mod sealed {
-    pub trait Sealed : bytemuck::Pod {}
-}
+    /// Reorders the audio planes of the buffer. The `from` slice lists the channel stored in each
+    /// plane of the buffer, and the `to` slice lists the channel each plane should store after
+    /// reordering. Each entry must be a single channel, and both slices must list the same set of
+    /// channels, one for every plane in the buffer, otherwise an error is returned.
+    ///
+    /// Reordering is performed by swapping whole planes, therefore a plane is moved at most once.
+    pub fn remap_channels(&mut self, from: &[Channels], to: &[Channels]) -> Result<()> {
+        let n_channels = self.spec.channels.count();
 
-impl sealed::Sealed for u8 {}
-impl sealed::Sealed for i8 {}
-impl sealed::Sealed for u16 {}
-impl sealed::Sealed for i16 {}
-impl sealed::Sealed for u32 {}
-impl sealed::Sealed for i32 {}
-impl sealed::Sealed for u64 {}
-impl sealed::Sealed for i64 {}
-impl sealed::Sealed for f32 {}
-impl sealed::Sealed for f64 {}
-impl sealed::Sealed for [u8; 1] {}
-impl sealed::Sealed for [u8; 2] {}
-impl sealed::Sealed for [u8; 3] {}
-impl sealed::Sealed for [u8; 4] {}
-impl sealed::Sealed for [u8; 5] {}
-impl sealed::Sealed for [u8; 6] {}
-impl sealed::Sealed for [u8; 7] {}
-impl sealed::Sealed for [u8; 8] {}
+        if from.len() != n_channels || to.len() != n_channels {
+            return unsupported_error("channel map does not match the number of channels");
+        }
 
-/// `RawSample` provides a typed interface for converting a `Sample` from it's in-memory data type to
-/// actual binary type.
-pub trait RawSample : Sample {
-    /// The `RawType` is a primitive data type, or fixed-size byte array, that is the final binary
-    /// representation of the sample when written out to a byte-buffer.
-    type RawType : Copy + Default + sealed::Sealed;
+        // Each entry in the channel maps must be a unique positioned channel.
+        let mut from_set = Channels::empty();
 
-    fn into_raw_sample(self) -> Self::RawType;
-}
+        for &channel in from {
+            if channel.count() != 1 || channel.is_discrete() || from_set.has_any_position(channel) {
+                return unsupported_error("channel map entries must be unique single channels");
+            }
+            from_set |= channel;
+        }
 
-impl RawSample for u8 {
-    type RawType = u8;
+        // Since the lengths match, if every channel in the destination map can be found in the
+        // source map, then both maps contain the same set of channels.
+        let mut to_set = Channels::empty();
 
-    #[inline(always)]
-    fn into_raw_sample(self) -> Self::RawType {
-        self
-    }
-}
+        for &channel in to {
+            if !from_set.has_positions(channel) || to_set.has_any_position(channel) {
+                return unsupported_error("channel maps do not contain the same channels");
+            }
+            to_set |= channel;
+        }
 
-impl RawSample for i8 {
-    type RawType = i8;
+        // The channel currently stored in each plane.
+        let mut current = from.to_vec();
 
-    #[inline(always)]
-    fn into_raw_sample(self) -> Self::RawType {
-        self
-    }
-}
+        for (dst, &channel) in to.iter().enumerate() {
+            if current[dst] != channel {
+                // The plane storing the desired channel can only be after the destination plane
+                // since all preceding planes are already in their final position.
+                let src = dst + current[dst..].iter().position(|&c| c == channel).unwrap();
 
-impl RawSample for u16 {
-    type RawType = u16;
+                let (a, b) = self.chan_pair_mut(dst, src);
+                a.swap_with_slice(b);
 
-    #[inline(always)]
-    fn into_raw_sample(self) -> Self::RawType {
-        self
+                current.swap(dst, src);
+            }
+        }
+
+        Ok(())
     }
 }
 
-impl RawSample for i16 {
-    type RawType = i16;
+/// Summarizes the contents of an audio plane for the `Debug` implementation of `AudioBuffer`.
+struct AudioPlaneSummary<'a, S: Sample>(&'a [S]);
 
-    #[inline(always)]
-    fn into_raw_sample(self) -> Self::RawType {
-        self
-    }
-}
+impl<S: Sample + fmt::Debug> fmt::Debug for AudioPlaneSummary<'_, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut min = None;
+        let mut max = None;
+
+        for &s in self.0 {
+            min = match min {
+                Some(min) if min <= s => Some(min),
+                _ => Some(s),
+            };
+            max = match max {
+                Some(max) if max >= s => Some(max),
+                _ => Some(s),
+            };
+        }
 
-impl RawSample for u24 {
-    type RawType = [u8; 3];
+        let n_first = self.0.len().min(AUDIO_BUFFER_DEBUG_MAX_SAMPLES);
 
-    #[inline(always)]
-    fn into_raw_sample(self) -> Self::RawType {
-        self.to_ne_bytes()
+        f.debug_struct("Plane")
+            .field("min", &min)
+            .field("max", &max)
+            .field("first", &&self.0[..n_first])
+            .finish()
     }
 }
 
-impl RawSample for i24 {
-    type RawType = [u8; 3];
-
-    #[inline(always)]
-    fn into_raw_sample(self) -> Self::RawType {
-        self.to_ne_bytes()
+/// Prints a bounded summary of the buffer instead of all samples. Each written audio plane is
+/// summarized by its minimum and maximum sample, and its first few samples.
+impl<S: Sample + fmt::Debug> fmt::Debug for AudioBuffer<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let n_channels = self.spec.channels.count();
+
+        let planes: Vec<AudioPlaneSummary<S>> = (0..n_channels.min(AUDIO_BUFFER_DEBUG_MAX_PLANES))
+            .map(|ch| AudioPlaneSummary(self.chan(ch)))
+            .collect();
+
+        f.debug_struct("AudioBuffer")
+            .field("spec", &self.spec)
+            .field("channels", &n_channels)
+            .field("frames", &self.n_frames)
+            .field("capacity", &self.n_capacity)
+            .field("planes", &planes)
+            .finish()
     }
 }
 
-impl RawSample for u32 {
-    type RawType = u32;
+/// Two audio buffers are equal if they have the same signal specification, and the same written
+/// frames. The capacity of the buffers, and any samples beyond the written frames, are ignored.
+impl<S: Sample> PartialEq for AudioBuffer<S> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.spec != other.spec || self.n_frames != other.n_frames {
+            return false;
+        }
 
-    #[inline(always)]
-    fn into_raw_sample(self) -> Self::RawType {
-        self
+        (0..self.spec.channels.count()).all(|ch| self.chan(ch) == other.chan(ch))
     }
 }
 
-impl RawSample for i32 {
+/// `AudioBufferRef` is a copy-on-write reference to an `AudioBuffer` of any type.
+pub enum AudioBufferRef<'a> {
+    U8(Cow<'a, AudioBuffer<u8>>),
+    U16(Cow<'a, AudioBuffer<u16>>),
+    U24(Cow<'a, AudioBuffer<u24>>),
+    U32(Cow<'a, AudioBuffer<u32>>),
+    S8(Cow<'a, AudioBuffer<i8>>),
+    S16(Cow<'a, AudioBuffer<i16>>),
+    S24(Cow<'a, AudioBuffer<i24>>),
+    S32(Cow<'a, AudioBuffer<i32>>),
+    F32(Cow<'a, AudioBuffer<f32>>),
+    F64(Cow<'a, AudioBuffer<f64>>),
+}
+
+impl<'a> AudioBufferRef<'a> {
+    /// Gets the signal specification for the buffer.
+    pub fn spec(&self) -> &SignalSpec {
+        match self {
+            AudioBufferRef::U8(buf)  => buf.spec(),
+            AudioBufferRef::U16(buf) => buf.spec(),
+            AudioBufferRef::U24(buf) => buf.spec(),
+            AudioBufferRef::U32(buf) => buf.spec(),
+            AudioBufferRef::S8(buf)  => buf.spec(),
+            AudioBufferRef::S16(buf) => buf.spec(),
+            AudioBufferRef::S24(buf) => buf.spec(),
+            AudioBufferRef::S32(buf) => buf.spec(),
+            AudioBufferRef::F32(buf) => buf.spec(),
+            AudioBufferRef::F64(buf) => buf.spec(),
+        }
+    }
+
+    /// Gets the total capacity of the buffer. The capacity is the maximum number of audio frames
+    /// a buffer can store.
+    pub fn capacity(&self) -> usize {
+        match self {
+            AudioBufferRef::U8(buf)  => buf.capacity(),
+            AudioBufferRef::U16(buf) => buf.capacity(),
+            AudioBufferRef::U24(buf) => buf.capacity(),
+            AudioBufferRef::U32(buf) => buf.capacity(),
+            AudioBufferRef::S8(buf)  => buf.capacity(),
+            AudioBufferRef::S16(buf) => buf.capacity(),
+            AudioBufferRef::S24(buf) => buf.capacity(),
+            AudioBufferRef::S32(buf) => buf.capacity(),
+            AudioBufferRef::F32(buf) => buf.capacity(),
+            AudioBufferRef::F64(buf) => buf.capacity(),
+        }
+    }
+
+    /// Gets the number of actual frames written to the buffer.
+    pub fn frames(&self) -> usize {
+        match self {
+            AudioBufferRef::U8(buf)  => buf.frames(),
+            AudioBufferRef::U16(buf) => buf.frames(),
+            AudioBufferRef::U24(buf) => buf.frames(),
+            AudioBufferRef::U32(buf) => buf.frames(),
+            AudioBufferRef::S8(buf)  => buf.frames(),
+            AudioBufferRef::S16(buf) => buf.frames(),
+            AudioBufferRef::S24(buf) => buf.frames(),
+            AudioBufferRef::S32(buf) => buf.frames(),
+            AudioBufferRef::F32(buf) => buf.frames(),
+            AudioBufferRef::F64(buf) => buf.frames(),
+        }
+    }
+
+    /// Converts the buffer into a newly allocated `AudioBuffer` of sample format `T`, regardless
+    /// of the sample format of the buffer. If the conversion is lossy, the samples are dithered
+    /// using the given type of dither, the noise generator of which is seeded by `seed`. To avoid
+    /// repeating the same dither noise, a different seed should be used for each buffer of a
+    /// stream.
+    pub fn convert_to<T>(&self, dither: DitherType, seed: u64) -> AudioBuffer<T>
+    where
+        T: ConvertibleSample,
+        u8: MaybeDither<T>,
+        u16: MaybeDither<T>,
+        u24: MaybeDither<T>,
+        u32: MaybeDither<T>,
+        i8: MaybeDither<T>,
+        i16: MaybeDither<T>,
+        i24: MaybeDither<T>,
+        i32: MaybeDither<T>,
+        f32: MaybeDither<T>,
+        f64: MaybeDither<T>,
+    {
+        let mut dest = AudioBuffer::unused();
+
+        match self {
+            AudioBufferRef::U8(buf)  => convert_with_dither(buf, &mut dest, dither, seed),
+            AudioBufferRef::U16(buf) => convert_with_dither(buf, &mut dest, dither, seed),
+            AudioBufferRef::U24(buf) => convert_with_dither(buf, &mut dest, dither, seed),
+            AudioBufferRef::U32(buf) => convert_with_dither(buf, &mut dest, dither, seed),
+            AudioBufferRef::S8(buf)  => convert_with_dither(buf, &mut dest, dither, seed),
+            AudioBufferRef::S16(buf) => convert_with_dither(buf, &mut dest, dither, seed),
+            AudioBufferRef::S24(buf) => convert_with_dither(buf, &mut dest, dither, seed),
+            AudioBufferRef::S32(buf) => convert_with_dither(buf, &mut dest, dither, seed),
+            AudioBufferRef::F32(buf) => convert_with_dither(buf, &mut dest, dither, seed),
+            AudioBufferRef::F64(buf) => convert_with_dither(buf, &mut dest, dither, seed),
+        }
+
+        dest
+    }
+}
+
+/// Converts the source buffer into the destination buffer, reallocating the destination buffer if
+/// the signal specification differs or the capacity is too small. Each sample is dithered using the
+/// provided dither if the conversion is lossy.
+fn convert_dithered<S, T, D>(src: &AudioBuffer<S>, dest: &mut AudioBuffer<T>, dither: &mut D)
+where
+    S: Sample + IntoSample<T> + MaybeDither<T>,
+    T: Sample,
+    D: Dither<S, T>,
+{
+    if dest.spec() != src.spec() || dest.capacity() < src.frames() {
+        *dest = AudioBuffer::new(src.capacity() as Duration, *src.spec());
+    }
+
+    dest.clear();
+    dest.render_reserved(Some(src.frames()));
+
+    for c in 0..src.spec().channels.count() {
+        for (d, &s) in dest.chan_mut(c).iter_mut().zip(src.chan(c)) {
+            *d = s.maybe_dither(dither).into_sample();
+        }
+    }
+}
+
+/// Converts the source buffer into the destination buffer using the given type of dither. The
+/// noise generator of the dither is seeded by `seed`.
+pub(crate) fn convert_with_dither<S, T>(
+    src: &AudioBuffer<S>,
+    dest: &mut AudioBuffer<T>,
+    dither: DitherType,
+    seed: u64,
+)
+where
+    S: Sample + IntoSample<T> + MaybeDither<T>,
+    T: Sample,
+    RandomNoise: AddNoise<S>,
+{
+    match dither {
+        DitherType::Identity => convert_dithered(src, dest, &mut Identity::new()),
+        DitherType::Rectangular => convert_dithered(src, dest, &mut Rectangular::with_seed(seed)),
+        DitherType::Triangular => convert_dithered(src, dest, &mut Triangular::with_seed(seed)),
+    }
+}
+
+/// `AsAudioBufferRef` is a trait implemented for `AudioBuffer`s that may be referenced in an
+/// `AudioBufferRef`.
+pub trait AsAudioBufferRef {
+    /// Get an `AudioBufferRef` reference.
+    fn as_audio_buffer_ref(&self) -> AudioBufferRef;
+}
+
+macro_rules! impl_as_audio_buffer_ref {
+    ($fmt:ty, $ref:path) => (
+        impl AsAudioBufferRef for AudioBuffer<$fmt> {
+            fn as_audio_buffer_ref(&self) -> AudioBufferRef {
+                $ref(Cow::Borrowed(self))
+            }
+        }
+    )
+}
+
+impl_as_audio_buffer_ref!(u8,  AudioBufferRef::U8);
+impl_as_audio_buffer_ref!(u16, AudioBufferRef::U16);
+impl_as_audio_buffer_ref!(u24, AudioBufferRef::U24);
+impl_as_audio_buffer_ref!(u32, AudioBufferRef::U32);
+impl_as_audio_buffer_ref!(i8,  AudioBufferRef::S8);
+impl_as_audio_buffer_ref!(i16, AudioBufferRef::S16);
+impl_as_audio_buffer_ref!(i24, AudioBufferRef::S24);
+impl_as_audio_buffer_ref!(i32, AudioBufferRef::S32);
+impl_as_audio_buffer_ref!(f32, AudioBufferRef::F32);
+impl_as_audio_buffer_ref!(f64, AudioBufferRef::F64);
+
+/// `AudioFrames` is an iterator over the written frames of a signal. Each frame is itself an
+/// iterator over the samples of all channels in that frame.
+pub struct AudioFrames<'a, S: Sample> {
+    buf: &'a [S],
+    stride: usize,
+    n_channels: usize,
+    n_frames: usize,
+    next: usize,
+}
+
+impl<'a, S: Sample> Iterator for AudioFrames<'a, S> {
+    type Item = AudioFrame<'a, S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.n_frames {
+            return None;
+        }
+
+        let frame = AudioFrame {
+            buf: &self.buf[self.next..],
+            stride: self.stride,
+            remaining: self.n_channels,
+        };
+
+        self.next += 1;
+
+        Some(frame)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.n_frames - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, S: Sample> ExactSizeIterator for AudioFrames<'a, S> {}
+
+/// `AudioFrame` is an iterator over the samples of all channels, in channel order, of a single
+/// frame of a signal.
+pub struct AudioFrame<'a, S: Sample> {
+    buf: &'a [S],
+    stride: usize,
+    remaining: usize,
+}
+
+impl<'a, S: Sample> Iterator for AudioFrame<'a, S> {
+    type Item = S;
+
+    fn next(&mut self) -> Option<S> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let sample = self.buf[0];
+
+        self.remaining -= 1;
+
+        // Advance to the same frame in the next channel, unless this was the last channel.
+        if self.remaining > 0 {
+            self.buf = &self.buf[self.stride..];
+        }
+
+        Some(sample)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, S: Sample> ExactSizeIterator for AudioFrame<'a, S> {}
+
+/// The `Signal` trait provides methods for rendering and transforming contiguous buffers of audio
+/// data.
+pub trait Signal<S : Sample> {
+    /// Gets the number of actual frames written to the buffer. Conversely, this also is the number
+    /// of written samples in any one channel.
+    fn frames(&self) -> usize;
+
+    /// Clears all written frames from the buffer. This is a cheap operation and does not zero the
+    /// underlying audio data.
+    fn clear(&mut self);
+
+    /// Gets an immutable reference to all the written samples in the specified channel.
+    fn chan(&self, channel: usize) -> &[S];
+
+    /// Gets a mutable reference to all the written samples in the specified channel.
+    fn chan_mut(&mut self, channel: usize) -> &mut [S];
+
+    /// Gets two mutable references to two different channels.
+    fn chan_pair_mut(&mut self, first: usize, second: usize) -> (&mut [S], &mut [S]);
+
+    /// Gets an iterator over all written frames in the signal. Each frame yields the samples of
+    /// all channels in channel order. The frames are read lazily from the underlying audio data.
+    fn frames_iter(&self) -> AudioFrames<'_, S>;
+
+    /// Renders a reserved number of frames. This is a cheap operation and simply advances the frame
+    /// counter. The underlying audio data is not modified and should be overwritten through other
+    /// means.
+    ///
+    /// If `n_frames` is `None`, the remaining number of samples will be used. If `n_frames` is too
+    /// large, this function will assert.
+    fn render_reserved(&mut self, n_frames: Option<usize>);
+
+    /// Renders a number of frames of silence. For unsigned sample formats, silence is the midpoint
+    /// of the sample format rather than zero.
+    ///
+    /// If `n_frames` is `None`, the remaining number of frames will be used. If `n_frames` is too
+    /// large, this function will assert.
+    fn silence(&mut self, n_frames: Option<usize>) {
+        // Rendering silence never fails.
+        let _ = self.render(n_frames, |planes, idx| {
+            for plane in planes.planes() {
+                plane[idx] = S::MID;
+            }
+            Ok(())
+        });
+    }
+
+    /// Renders a number of frames using the provided render function. The number of frames to
+    /// render is specified by `n_frames`. If `n_frames` is `None`, the remaining number of frames
+    /// in the buffer will be rendered. If the render function returns an error, the render
+    /// operation is terminated prematurely.
+    ///
+    /// The render function is called once per frame with the audio planes of the frames being
+    /// rendered, and the index of the frame to render within those planes. The index is 0-based
+    /// for each call to `render`, regardless of the number of frames previously written to the
+    /// buffer. Frames rendered before an error is returned remain written.
+    fn render<'a, F>(&'a mut self, n_frames: Option<usize>, render: F) -> Result<()>
+    where
+        F: FnMut(&mut AudioPlanesMut<'a, S>, usize) -> Result<()>;
+
+    /// Clears, and then renders the entire buffer using the fill function. This is a convenience
+    /// wrapper around `render` and exhibits the same behaviour as `render` in regards to the fill
+    /// function.
+    #[inline]
+    fn fill<'a, F>(&'a mut self, fill: F) -> Result<()>
+    where
+        F: FnMut(&mut AudioPlanesMut<'a, S>, usize) -> Result<()>
+    {
+        self.clear();
+        self.render(None, fill)
+    }
+
+    /// Transforms every written sample in the signal using the transformation function provided.
+    /// This function does not guarantee an order in which the samples are transformed.
+    fn transform<F>(&mut self, f: F)
+    where
+        F: Fn(S) -> S;
+
+    /// Applies a gain, in decibels, to every written sample in the signal. Each sample is scaled
+    /// using a double-precision floating point intermediate, and integer samples saturate at the
+    /// minimum or maximum value of the sample format instead of wrapping.
+    fn apply_gain(&mut self, gain_db: f32)
+    where
+        S: ReversibleSample<f64>
+    {
+        let gain = 10f64.powf(f64::from(gain_db) / 20.0);
+
+        self.transform(|s| {
+            let sample: f64 = s.into_sample();
+            let scaled = sample * gain;
+
+            // An infinite gain applied to a silent sample is undefined, keep it silent.
+            if scaled.is_nan() {
+                S::MID
+            }
+            else {
+                S::from_sample(scaled)
+            }
+        });
+    }
+
+    /// Limits the magnitude of every written sample in the signal to the ceiling, in decibels
+    /// relative to full scale. Samples exceeding the ceiling are clamped to it, and all other
+    /// samples are left unchanged. Samples are compared using a double-precision floating point
+    /// intermediate.
+    ///
+    /// This is a hard clipper intended to avoid clipping, or wrapping, after a gain or mix. It is
+    /// not a mastering limiter: there is no lookahead or release, and clamped samples are
+    /// distorted rather than smoothly attenuated.
+    fn limit(&mut self, ceiling_db: f32)
+    where
+        S: ReversibleSample<f64>
+    {
+        let ceiling = 10f64.powf(f64::from(ceiling_db) / 20.0);
+
+        self.transform(|s| {
+            let sample: f64 = s.into_sample();
+
+            if sample.abs() > ceiling {
+                S::from_sample(ceiling.copysign(sample))
+            }
+            else {
+                s
+            }
+        });
+    }
+
+    /// Gets the peak magnitude of the written samples in the specified channel. The peak is the
+    /// absolute maximum distance of any sample from silence, expressed as a sample of the same
+    /// format. Returns silence if there are no written frames.
+    fn peak(&self, channel: usize) -> S
+    where
+        S: ReversibleSample<f64>
+    {
+        let peak = self.chan(channel).iter().fold(0.0, |peak: f64, &s| {
+            let sample: f64 = s.into_sample();
+            peak.max(sample.abs())
+        });
+
+        S::from_sample(peak)
+    }
+
+    /// Gets the root-mean-square level of the written samples in the specified channel. The level is
+    /// normalized such that a full-scale square wave has a level of 1.0. Returns 0.0 if there are no
+    /// written frames.
+    fn rms(&self, channel: usize) -> f64
+    where
+        S: ReversibleSample<f64>
+    {
+        let samples = self.chan(channel);
+
+        if samples.is_empty() {
+            return 0.0;
+        }
+
+        // Accumulate in double-precision to avoid overflow and loss of precision.
+        let sum = samples.iter().fold(0.0, |sum: f64, &s| {
+            let sample: f64 = s.into_sample();
+            sum + sample * sample
+        });
+
+        (sum / samples.len() as f64).sqrt()
+    }
+
+    /// Normalizes the signal such that the peak magnitude across all channels is the target peak,
+    /// in decibels relative to full scale. A single gain is applied to all channels, therefore the
+    /// balance between channels is preserved. A silent signal is left unchanged.
+    fn normalize(&mut self, target_peak_db: f32)
+    where
+        S: ReversibleSample<f64>
+    {
+        let peak = self.frames_iter().flatten().fold(0.0, |peak: f64, s| {
+            let sample: f64 = s.into_sample();
+            peak.max(sample.abs())
+        });
+
+        if peak > 0.0 {
+            let gain_db = f64::from(target_peak_db) - 20.0 * peak.log10();
+            self.apply_gain(gain_db as f32);
+        }
+    }
+}
+
+impl<S: Sample> Signal<S> for AudioBuffer<S> {
+
+    fn clear(&mut self) {
+        self.n_frames = 0;
+    }
+
+    fn frames(&self) -> usize {
+        self.n_frames
+    }
+
+    fn chan(&self, channel: usize) -> &[S]{
+        let start = channel * self.n_capacity;
+        let end = start + self.n_frames;
+
+        // Do not exceed the audio buffer.
+        assert!(end <= self.buf.len());
+
+        &self.buf[start..end]
+    }
+
+    fn chan_mut(&mut self, channel: usize) -> &mut [S] {
+        let start = channel * self.n_capacity;
+        let end = start + self.n_frames;
+
+        // Do not exceed the audio buffer.
+        assert!(end <= self.buf.len());
+
+        &mut self.buf[start..end]
+    }
+
+    fn chan_pair_mut(&mut self, first: usize, second: usize) -> (&mut [S], &mut [S]) {
+        // Both channels in the pair must be unique.
+        assert!(first != second);
+
+        let first_idx = self.n_capacity * first;
+        let second_idx = self.n_capacity * second;
+
+        if first_idx < second_idx {
+            let (a, b) = self.buf.split_at_mut(second_idx);
+
+            (&mut a[first_idx..first_idx + self.n_frames], &mut b[..self.n_frames])
+        }
+        else {
+            let (a, b) = self.buf.split_at_mut(first_idx);
+
+            (&mut b[..self.n_frames], &mut a[second_idx..second_idx + self.n_frames])
+        }
+    }
+
+    fn frames_iter(&self) -> AudioFrames<'_, S> {
+        AudioFrames {
+            buf: &self.buf,
+            stride: self.n_capacity,
+            n_channels: self.spec.channels.count(),
+            n_frames: self.n_frames,
+            next: 0,
+        }
+    }
+
+    fn render_reserved(&mut self, n_frames: Option<usize>) {
+        let n_reserved_frames = n_frames.unwrap_or(self.n_capacity - self.n_frames);
+        // Do not render past the end of the audio buffer.
+        assert!(self.n_frames + n_reserved_frames <= self.n_capacity);
+        self.n_frames += n_reserved_frames;
+    }
+
+    fn silence(&mut self, n_frames: Option<usize>) {
+        let n_silence_frames = n_frames.unwrap_or(self.n_capacity - self.n_frames);
+
+        // Do not render past the end of the audio buffer.
+        let end = self.n_frames + n_silence_frames;
+        assert!(end <= self.n_capacity);
+
+        if n_silence_frames == 0 {
+            return;
+        }
+
+        for plane in self.buf.chunks_exact_mut(self.n_capacity) {
+            plane[self.n_frames..end].iter_mut().for_each(|s| *s = S::MID);
+        }
+
+        self.n_frames = end;
+    }
+
+    fn render<'a, F>(&'a mut self, n_frames: Option<usize>, mut render: F) -> Result<()>
+    where
+        F: FnMut(&mut AudioPlanesMut<'a, S>, usize) -> Result<()>
+    {
+        // The number of frames to be rendered is the amount requested, if specified, or the
+        // remainder of the audio buffer.
+        let n_render_frames = n_frames.unwrap_or(self.n_capacity - self.n_frames);
+
+        // Do not render past the end of the audio buffer.
+        let end = self.n_frames + n_render_frames;
+        assert!(end <= self.n_capacity);
+
+        if n_render_frames == 0 {
+            return Ok(());
+        }
+
+        // At this point, n_render_frames can be considered "reserved". Create an audio plane
+        // structure and fill each plane entry with a reference to the "reserved" samples in each
+        // channel respectively.
+        let mut planes = AudioPlanesMut::new(self.spec.channels);
+
+        for channel in self.buf.chunks_exact_mut(self.n_capacity) {
+            planes.push(&mut channel[self.n_frames..end]);
+        }
+
+        // Attempt to render the into the reserved frames, one-by-one, exiting only if there is an
+        // error in the render function. The planes only span the reserved frames, therefore the
+        // index is relative to the first reserved frame.
+        for idx in 0..n_render_frames {
+            render(&mut planes, idx)?;
+            self.n_frames += 1;
+        }
+
+        Ok(())
+    }
+
+    fn transform<F>(&mut self, f: F)
+    where
+        F: Fn(S) -> S
+    {
+        debug_assert!(self.n_frames <= self.n_capacity);
+
+        if self.n_frames == 0 {
+            return;
+        }
+
+        // Apply the transformation function over each sample in each plane.
+        for plane in self.buf.chunks_mut(self.n_capacity) {
+            for sample in &mut plane[0..self.n_frames] {
+                *sample = f(*sample);
+            }
+        }
+    }
+
+}
+
+/// A `SampleBuffer`, is a sample oriented buffer. It is agnostic to the ordering/layout of samples
+/// within the buffer. `SampleBuffer` is mean't for safely importing and exporting sample data to
+/// and from Symphonia using the sample's in-memory data-type.
+pub struct SampleBuffer<S: Sample> {
+    buf: Vec<S>,
+    n_written: usize,
+    n_channels: usize,
+}
+
+impl<S: Sample> SampleBuffer<S> {
+    /// Instantiate a new `SampleBuffer` using the specified signal specification and of the given
+    /// duration. Panics if the buffer could not be allocated. See `try_new` for a non-panicking
+    /// alternative.
+    pub fn new(duration: Duration, spec: SignalSpec) -> SampleBuffer<S> {
+        SampleBuffer::try_new(duration, spec).unwrap()
+    }
+
+    /// Instantiate a new `SampleBuffer` using the specified signal specification and of the given
+    /// duration. Returns an error if the duration is too large, or the buffer could not be
+    /// allocated.
+    pub fn try_new(duration: Duration, spec: SignalSpec) -> Result<SampleBuffer<S>> {
+        let n_samples = buffer_len::<S>(duration, spec.channels)?;
+
+        Ok(SampleBuffer {
+            buf: try_alloc(n_samples, S::MID)?,
+            n_written: 0,
+            n_channels: spec.channels.count(),
+        })
+    }
+
+    /// Gets the number of written samples.
+    pub fn len(&self) -> usize {
+        self.n_written
+    }
+
+    /// Gets the number of written samples per channel. If the samples were copied in planar
+    /// channel order, this is the stride between the first samples of consecutive channels.
+    pub fn samples_per_channel(&self) -> usize {
+        self.n_written.checked_div(self.n_channels).unwrap_or(0)
+    }
+
+    /// Gets an immutable slice of all written samples.
+    pub fn samples(&self) -> &[S] {
+        &self.buf[..self.n_written]
+    }
+
+    /// Gets the maximum number of samples the `SampleBuffer` may store.
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Copies all audio data from the source `AudioBufferRef` in planar channel order into the
+    /// `SampleBuffer`. The two buffers must be equivalent.
+    pub fn copy_planar_ref(&mut self, src: AudioBufferRef)
+    where
+        S: ConvertibleSample,
+    {
+        match src {
+            AudioBufferRef::U8(buf)  => self.copy_planar_typed(&buf),
+            AudioBufferRef::U16(buf) => self.copy_planar_typed(&buf),
+            AudioBufferRef::U24(buf) => self.copy_planar_typed(&buf),
+            AudioBufferRef::U32(buf) => self.copy_planar_typed(&buf),
+            AudioBufferRef::S8(buf)  => self.copy_planar_typed(&buf),
+            AudioBufferRef::S16(buf) => self.copy_planar_typed(&buf),
+            AudioBufferRef::S24(buf) => self.copy_planar_typed(&buf),
+            AudioBufferRef::S32(buf) => self.copy_planar_typed(&buf),
+            AudioBufferRef::F32(buf) => self.copy_planar_typed(&buf),
+            AudioBufferRef::F64(buf) => self.copy_planar_typed(&buf),
+        }
+    }
+
+    /// Copies all audio data from a source `AudioBuffer` into the `SampleBuffer` in planar
+    /// channel order. The two buffers must be equivalent.
+    pub fn copy_planar_typed<F>(&mut self, src: &AudioBuffer<F>)
+    where
+        F: Sample + IntoSample<S>,
+    {
+        let n_frames = src.frames();
+        let n_channels = src.spec.channels.count();
+        let n_samples = n_frames * n_channels;
+
+        // Ensure that the capacity of the sample buffer is greater than or equal to the number
+        // of samples that will be copied from the source buffer.
+        assert!(self.capacity() >= n_samples);
+
+        for ch in 0..n_channels {
+            let ch_slice = src.chan(ch);
+
+            for (dst, src) in self.buf[ch * n_frames..].iter_mut().zip(ch_slice) {
+                *dst = (*src).into_sample();
+            }
+        }
+
+        // Commit the written samples.
+        self.n_written = n_samples;
+        self.n_channels = n_channels;
+    }
+
+    /// Copies all audio data from the source `AudioBuffer` to the `SampleBuffer` in planar order.
+    /// Since the sample formats are the same, no conversion is performed. The two buffers must be
+    /// equivalent.
+    pub fn copy_planar(&mut self, src: &AudioBuffer<S>) {
+        let n_frames = src.frames();
+        let n_channels = src.spec.channels.count();
+        let n_samples = n_frames * n_channels;
+
+        // Ensure that the capacity of the sample buffer is greater than or equal to the number
+        // of samples that will be copied from the source buffer.
+        assert!(self.capacity() >= n_samples);
+
+        if n_frames > 0 {
+            for (ch, dst_ch) in self.buf[..n_samples].chunks_exact_mut(n_frames).enumerate() {
+                dst_ch.copy_from_slice(src.chan(ch));
+            }
+        }
+
+        // Commit the written samples.
+        self.n_written = n_samples;
+        self.n_channels = n_channels;
+    }
+
+    /// Copies all samples from an interleaved source slice containing the given channels into the
+    /// `SampleBuffer` in planar channel order. The length of the source slice must be a multiple of
+    /// the number of channels.
+    pub fn copy_from_slice_interleaved(&mut self, src: &[S], channels: Channels) {
+        let n_channels = channels.count();
+        let n_samples = src.len();
+
+        // Ensure that the capacity of the sample buffer is greater than or equal to the number
+        // of samples that will be copied from the source slice.
+        assert!(self.capacity() >= n_samples);
+
+        match n_channels {
+            // No channels, nothing can be copied.
+            0 => assert!(src.is_empty(), "source samples provided without channels"),
+            // Mono
+            1 => self.buf[..n_samples].copy_from_slice(src),
+            // Stereo
+            2 => {
+                assert!(n_samples % 2 == 0, "source length is not a multiple of channel count");
+
+                let (l_buf, r_buf) = self.buf[..n_samples].split_at_mut(n_samples / 2);
+
+                for ((l, r), s) in l_buf.iter_mut().zip(r_buf).zip(src.chunks_exact(2)) {
+                    *l = s[0];
+                    *r = s[1];
+                }
+            }
+            // 3+ channels
+            _ => {
+                assert!(
+                    n_samples % n_channels == 0,
+                    "source length is not a multiple of channel count"
+                );
+
+                let n_frames = n_samples / n_channels;
+
+                if n_frames > 0 {
+                    let dst_buf = &mut self.buf[..n_samples];
+
+                    for (ch, dst_ch) in dst_buf.chunks_exact_mut(n_frames).enumerate() {
+                        for (d, &s) in dst_ch.iter_mut().zip(src[ch..].iter().step_by(n_channels)) {
+                            *d = s;
+                        }
+                    }
+                }
+            }
+        }
+
+        // Commit the written samples.
+        self.n_written = n_samples;
+        self.n_channels = n_channels;
+    }
+
+    /// Writes `n_frames` frames of the given channels into the `SampleBuffer` in interleaved order,
+    /// replacing any previously written samples. The value of each sample is obtained by calling
+    /// `f` with the frame index and channel index of the sample.
+    pub fn write_interleaved_with<F>(&mut self, n_frames: usize, channels: Channels, mut f: F)
+    where
+        F: FnMut(usize, usize) -> S,
+    {
+        let n_channels = channels.count();
+        let n_samples = n_frames * n_channels;
+
+        // Ensure that the capacity of the sample buffer is greater than or equal to the number
+        // of samples that will be written.
+        assert!(self.capacity() >= n_samples);
+
+        if n_channels > 0 {
+            for (frame, dst) in self.buf[..n_samples].chunks_exact_mut(n_channels).enumerate() {
+                for (ch, d) in dst.iter_mut().enumerate() {
+                    *d = f(frame, ch);
+                }
+            }
+        }
+
+        // Commit the written samples.
+        self.n_written = n_samples;
+        self.n_channels = n_channels;
+    }
+
+    /// Copies all audio data from the source `AudioBufferRef` in interleaved channel order into the
+    /// `SampleBuffer`. The two buffers must be equivalent.
+    pub fn copy_interleaved_ref(&mut self, src: AudioBufferRef)
+    where
+        S: ConvertibleSample,
+    {
+        match src {
+            AudioBufferRef::U8(buf)  => self.copy_interleaved_typed(&buf),
+            AudioBufferRef::U16(buf) => self.copy_interleaved_typed(&buf),
+            AudioBufferRef::U24(buf) => self.copy_interleaved_typed(&buf),
+            AudioBufferRef::U32(buf) => self.copy_interleaved_typed(&buf),
+            AudioBufferRef::S8(buf)  => self.copy_interleaved_typed(&buf),
+            AudioBufferRef::S16(buf) => self.copy_interleaved_typed(&buf),
+            AudioBufferRef::S24(buf) => self.copy_interleaved_typed(&buf),
+            AudioBufferRef::S32(buf) => self.copy_interleaved_typed(&buf),
+            AudioBufferRef::F32(buf) => self.copy_interleaved_typed(&buf),
+            AudioBufferRef::F64(buf) => self.copy_interleaved_typed(&buf),
+        }
+    }
+
+    /// Copies all audio samples from a source `AudioBuffer` into the `SampleBuffer` in interleaved
+    /// channel order. The two buffers must be equivalent.
+    pub fn copy_interleaved_typed<F>(&mut self, src: &AudioBuffer<F>)
+    where
+        F: Sample + IntoSample<S>,
+    {
+        let n_channels = src.spec.channels.count();
+        let n_samples = src.frames() * n_channels;
+
+        // Ensure that the capacity of the sample buffer is greater than or equal to the number
+        // of samples that will be copied from the source buffer.
+        assert!(self.capacity() >= n_samples);
+
+        // Interleave the source buffer channels into the sample buffer.
+        for ch in 0..n_channels {
+            let ch_slice = src.chan(ch);
+
+            for (dst, src) in self.buf[ch..].iter_mut().step_by(n_channels).zip(ch_slice) {
+                *dst = (*src).into_sample();
+            }
+        }
+
+        // Commit the written samples.
+        self.n_written = n_samples;
+        self.n_channels = n_channels;
+    }
+
+    /// Copies all audio data from the source `AudioBuffer` to the `SampleBuffer` in interleaved
+    /// channel order. Since the sample formats are the same, no conversion is performed. The two
+    /// buffers must be equivalent.
+    pub fn copy_interleaved(&mut self, src: &AudioBuffer<S>) {
+        let n_channels = src.spec.channels.count();
+        let n_samples = src.frames() * n_channels;
+
+        // Ensure that the capacity of the sample buffer is greater than or equal to the number
+        // of samples that will be copied from the source buffer.
+        assert!(self.capacity() >= n_samples);
+
+        // Interleave the source buffer channels into the sample buffer.
+        for ch in 0..n_channels {
+            let ch_slice = src.chan(ch);
+
+            for (dst, src) in self.buf[ch..].iter_mut().step_by(n_channels).zip(ch_slice) {
+                *dst = *src;
+            }
+        }
+
+        // Commit the written samples.
+        self.n_written = n_samples;
+        self.n_channels = n_channels;
+    }
+}
+
+impl<S: Sample + RawSample> SampleBuffer<S> {
+    /// Copies raw sample bytes in the given byte order, such as those obtained from a
+    /// `RawSampleBuffer`, into the `SampleBuffer`, replacing any previously written samples. The
+    /// length of the source slice must be a multiple of the size of a raw sample, and must not
+    /// exceed the capacity of the buffer.
+    pub fn copy_from_bytes(&mut self, bytes: &[u8], byte_order: ByteOrder) -> Result<()> {
+        let sample_size = mem::size_of::<S::RawType>();
+
+        if !bytes.len().is_multiple_of(sample_size) {
+            return decode_error("raw sample bytes are not a multiple of the sample size");
+        }
+
+        let n_samples = bytes.len() / sample_size;
+
+        if n_samples > self.capacity() {
+            return decode_error("raw sample bytes exceed the buffer capacity");
+        }
+
+        // The bytes of a raw sample must be reversed if they are not in the native byte order.
+        let is_reversed = match byte_order {
+            ByteOrder::Native => false,
+            ByteOrder::LittleEndian => cfg!(target_endian = "big"),
+            ByteOrder::BigEndian => cfg!(target_endian = "little"),
+        };
+
+        let mut raw = S::RawType::default();
+
+        for (dst, src) in self.buf.iter_mut().zip(bytes.chunks_exact(sample_size)) {
+            // Since the raw type is plain-old-data, any bytes form a valid raw sample.
+            let raw_bytes = bytemuck::bytes_of_mut(&mut raw);
+            raw_bytes.copy_from_slice(src);
+
+            if is_reversed {
+                raw_bytes.reverse();
+            }
+
+            *dst = S::from_raw_sample(raw);
+        }
+
+        // Commit the written samples.
+        self.n_written = n_samples;
+
+        Ok(())
+    }
+}
+
+/// This non-public module contains the trait `Sealed` which is used to constrain
+/// `RawSample::RawType` with `bytemuck::Pod`. This is a trade-off to hide `bytemuck` from the public
+/// interface. The downside is that `RawSample::RawType` is locked to the types we implement
+/// `Sealed` on. To compensate, we implement `Sealed` on all primitive numeric data types, and byte
+/// arrays up to 8 bytes long.
+mod sealed {
+    pub trait Sealed : bytemuck::Pod {}
+}
+
+impl sealed::Sealed for u8 {}
+impl sealed::Sealed for i8 {}
+impl sealed::Sealed for u16 {}
+impl sealed::Sealed for i16 {}
+impl sealed::Sealed for u32 {}
+impl sealed::Sealed for i32 {}
+impl sealed::Sealed for u64 {}
+impl sealed::Sealed for i64 {}
+impl sealed::Sealed for f32 {}
+impl sealed::Sealed for f64 {}
+impl sealed::Sealed for [u8; 1] {}
+impl sealed::Sealed for [u8; 2] {}
+impl sealed::Sealed for [u8; 3] {}
+impl sealed::Sealed for [u8; 4] {}
+impl sealed::Sealed for [u8; 5] {}
+impl sealed::Sealed for [u8; 6] {}
+impl sealed::Sealed for [u8; 7] {}
+impl sealed::Sealed for [u8; 8] {}
+
+/// `RawSample` provides a typed interface for converting a `Sample` from it's in-memory data type to
+/// actual binary type.
+pub trait RawSample : Sample {
+    /// The `RawType` is a primitive data type, or fixed-size byte array, that is the final binary
+    /// representation of the sample when written out to a byte-buffer.
+    type RawType : Copy + Default + sealed::Sealed;
+
+    /// Converts the sample into its native-endian raw representation.
+    fn into_raw_sample(self) -> Self::RawType;
+
+    /// Converts the sample into its little-endian raw representation.
+    fn into_raw_sample_le(self) -> Self::RawType;
+
+    /// Converts the sample into its big-endian raw representation.
+    fn into_raw_sample_be(self) -> Self::RawType;
+
+    /// Converts a native-endian raw representation into the sample.
+    fn from_raw_sample(raw: Self::RawType) -> Self;
+}
+
+impl RawSample for u8 {
+    type RawType = u8;
+
+    #[inline(always)]
+    fn into_raw_sample(self) -> Self::RawType {
+        self
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_le(self) -> Self::RawType {
+        self
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_be(self) -> Self::RawType {
+        self
+    }
+
+    #[inline(always)]
+    fn from_raw_sample(raw: Self::RawType) -> Self {
+        raw
+    }
+}
+
+impl RawSample for i8 {
+    type RawType = i8;
+
+    #[inline(always)]
+    fn into_raw_sample(self) -> Self::RawType {
+        self
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_le(self) -> Self::RawType {
+        self
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_be(self) -> Self::RawType {
+        self
+    }
+
+    #[inline(always)]
+    fn from_raw_sample(raw: Self::RawType) -> Self {
+        raw
+    }
+}
+
+impl RawSample for u16 {
+    type RawType = u16;
+
+    #[inline(always)]
+    fn into_raw_sample(self) -> Self::RawType {
+        self
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_le(self) -> Self::RawType {
+        self.to_le()
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_be(self) -> Self::RawType {
+        self.to_be()
+    }
+
+    #[inline(always)]
+    fn from_raw_sample(raw: Self::RawType) -> Self {
+        raw
+    }
+}
+
+impl RawSample for i16 {
+    type RawType = i16;
+
+    #[inline(always)]
+    fn into_raw_sample(self) -> Self::RawType {
+        self
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_le(self) -> Self::RawType {
+        self.to_le()
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_be(self) -> Self::RawType {
+        self.to_be()
+    }
+
+    #[inline(always)]
+    fn from_raw_sample(raw: Self::RawType) -> Self {
+        raw
+    }
+}
+
+impl RawSample for u24 {
+    type RawType = [u8; 3];
+
+    #[inline(always)]
+    fn into_raw_sample(self) -> Self::RawType {
+        self.to_ne_bytes()
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_le(self) -> Self::RawType {
+        self.to_le_bytes()
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_be(self) -> Self::RawType {
+        self.to_be_bytes()
+    }
+
+    #[inline(always)]
+    fn from_raw_sample(raw: Self::RawType) -> Self {
+        u24::from_ne_bytes(raw)
+    }
+}
+
+impl RawSample for i24 {
+    type RawType = [u8; 3];
+
+    #[inline(always)]
+    fn into_raw_sample(self) -> Self::RawType {
+        self.to_ne_bytes()
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_le(self) -> Self::RawType {
+        self.to_le_bytes()
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_be(self) -> Self::RawType {
+        self.to_be_bytes()
+    }
+
+    #[inline(always)]
+    fn from_raw_sample(raw: Self::RawType) -> Self {
+        i24::from_ne_bytes(raw)
+    }
+}
+
+impl RawSample for u32 {
+    type RawType = u32;
+
+    #[inline(always)]
+    fn into_raw_sample(self) -> Self::RawType {
+        self
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_le(self) -> Self::RawType {
+        self.to_le()
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_be(self) -> Self::RawType {
+        self.to_be()
+    }
+
+    #[inline(always)]
+    fn from_raw_sample(raw: Self::RawType) -> Self {
+        raw
+    }
+}
+
+impl RawSample for i32 {
     type RawType = i32;
 
-    #[inline(always)]
-    fn into_raw_sample(self) -> Self::RawType {
-        self
-    }
-}
+    #[inline(always)]
+    fn into_raw_sample(self) -> Self::RawType {
+        self
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_le(self) -> Self::RawType {
+        self.to_le()
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_be(self) -> Self::RawType {
+        self.to_be()
+    }
+
+    #[inline(always)]
+    fn from_raw_sample(raw: Self::RawType) -> Self {
+        raw
+    }
+}
+
+impl RawSample for f32 {
+    type RawType = f32;
+
+    #[inline(always)]
+    fn into_raw_sample(self) -> Self::RawType {
+        self
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_le(self) -> Self::RawType {
+        f32::from_bits(self.to_bits().to_le())
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_be(self) -> Self::RawType {
+        f32::from_bits(self.to_bits().to_be())
+    }
+
+    #[inline(always)]
+    fn from_raw_sample(raw: Self::RawType) -> Self {
+        raw
+    }
+}
+
+impl RawSample for f64 {
+    type RawType = f64;
+
+    #[inline(always)]
+    fn into_raw_sample(self) -> Self::RawType {
+        self
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_le(self) -> Self::RawType {
+        f64::from_bits(self.to_bits().to_le())
+    }
+
+    #[inline(always)]
+    fn into_raw_sample_be(self) -> Self::RawType {
+        f64::from_bits(self.to_bits().to_be())
+    }
+
+    #[inline(always)]
+    fn from_raw_sample(raw: Self::RawType) -> Self {
+        raw
+    }
+}
+
+/// `ByteOrder` is the order of the bytes of a multi-byte raw sample.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ByteOrder {
+    /// The byte order of the target platform.
+    #[default]
+    Native,
+    /// Least-significant byte first.
+    LittleEndian,
+    /// Most-significant byte first.
+    BigEndian,
+}
+
+/// A `RawSampleBuffer`, is a byte-oriented sample buffer. All samples copied to this buffer are
+/// converted into their packed data-type and stored as a stream of bytes. `RawSampleBuffer` is
+/// mean't for safely importing and exporting sample data to and from Symphonia as raw bytes.
+pub struct RawSampleBuffer<S: Sample + RawSample> {
+    buf: Vec<S::RawType>,
+    n_written: usize,
+    n_channels: usize,
+    /// If `true`, the written samples were copied in planar channel order.
+    is_planar: bool,
+    byte_order: ByteOrder,
+    // Might take your heart.
+    sample_format: PhantomData<S>,
+}
+
+impl<S: Sample + RawSample> RawSampleBuffer<S> {
+    /// Instantiate a new `RawSampleBuffer` using the specified signal specification and of the given
+    /// duration. Panics if the buffer could not be allocated. See `try_new` for a non-panicking
+    /// alternative.
+    pub fn new(duration: Duration, spec: SignalSpec) -> RawSampleBuffer<S> {
+        RawSampleBuffer::try_new(duration, spec).unwrap()
+    }
+
+    /// Instantiate a new `RawSampleBuffer` using the specified signal specification and of the
+    /// given duration. Returns an error if the duration is too large, or the buffer could not be
+    /// allocated.
+    pub fn try_new(duration: Duration, spec: SignalSpec) -> Result<RawSampleBuffer<S>> {
+        let n_samples = buffer_len::<S::RawType>(duration, spec.channels)?;
+
+        // Allocate enough memory for all the samples and fill the buffer with silence.
+        let buf = try_alloc(n_samples, S::MID.into_raw_sample())?;
+
+        Ok(RawSampleBuffer {
+            buf,
+            n_written: 0,
+            n_channels: spec.channels.count(),
+            is_planar: false,
+            byte_order: ByteOrder::Native,
+            sample_format: PhantomData,
+        })
+    }
+
+    /// Gets the number of written samples.
+    pub fn len(&self) -> usize {
+        self.n_written
+    }
+
+    /// Gets the maximum number of samples the `RawSampleBuffer` may store.
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Gets the byte order samples are written in.
+    pub fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+
+    /// Sets the byte order all subsequently copied samples are written in. The default byte order
+    /// is native.
+    pub fn with_byte_order(&mut self, byte_order: ByteOrder) -> &mut Self {
+        self.byte_order = byte_order;
+        self
+    }
+
+    /// Gets an immutable slice to the bytes of the sample's written in the `RawSampleBuffer`.
+    pub fn as_bytes(&self) -> &[u8] {
+        // Get a slice to the written raw samples in the buffer, and convert from &[RawType] to
+        // &[u8]. Since &[u8] has the least strict alignment requirements, this should always be
+        // safe and therefore cast_slice should never panic.
+        bytemuck::cast_slice(&self.buf[..self.n_written])
+    }
+
+    /// Gets an immutable slice to the bytes of the samples written in the `RawSampleBuffer` if they
+    /// were copied in planar channel order, otherwise `None`. The samples of each channel are
+    /// contiguous, and the samples of a channel start `samples_per_channel()` samples after those
+    /// of the previous channel.
+    pub fn as_planar_bytes(&self) -> Option<&[u8]> {
+        if self.is_planar {
+            Some(self.as_bytes())
+        }
+        else {
+            None
+        }
+    }
+
+    /// Gets the number of written samples per channel. If the samples were copied in planar
+    /// channel order, this is the stride between the first samples of consecutive channels.
+    pub fn samples_per_channel(&self) -> usize {
+        self.n_written.checked_div(self.n_channels).unwrap_or(0)
+    }
+
+    /// Copies all audio data from the source `AudioBufferRef` in planar channel order into the
+    /// `RawSampleBuffer`. The two buffers must be equivalent.
+    pub fn copy_planar_ref(&mut self, src: AudioBufferRef)
+    where
+        S: ConvertibleSample,
+    {
+        match src {
+            AudioBufferRef::U8(buf)  => self.copy_planar_typed(&buf),
+            AudioBufferRef::U16(buf) => self.copy_planar_typed(&buf),
+            AudioBufferRef::U24(buf) => self.copy_planar_typed(&buf),
+            AudioBufferRef::U32(buf) => self.copy_planar_typed(&buf),
+            AudioBufferRef::S8(buf)  => self.copy_planar_typed(&buf),
+            AudioBufferRef::S16(buf) => self.copy_planar_typed(&buf),
+            AudioBufferRef::S24(buf) => self.copy_planar_typed(&buf),
+            AudioBufferRef::S32(buf) => self.copy_planar_typed(&buf),
+            AudioBufferRef::F32(buf) => self.copy_planar_typed(&buf),
+            AudioBufferRef::F64(buf) => self.copy_planar_typed(&buf),
+        }
+    }
+
+    /// Copies all audio data from a source `AudioBuffer` that is of a different sample format type
+    /// than that of the `RawSampleBuffer` in planar channel order. The two buffers must be
+    /// equivalent.
+    pub fn copy_planar_typed<F>(&mut self, src: &AudioBuffer<F>)
+    where
+        F: Sample + IntoSample<S>,
+    {
+        match self.byte_order {
+            ByteOrder::Native => {
+                self.copy_planar_with(src, |s| s.into_sample().into_raw_sample())
+            }
+            ByteOrder::LittleEndian => {
+                self.copy_planar_with(src, |s| s.into_sample().into_raw_sample_le())
+            }
+            ByteOrder::BigEndian => {
+                self.copy_planar_with(src, |s| s.into_sample().into_raw_sample_be())
+            }
+        }
+    }
+
+    /// Copies all audio data from the source `AudioBuffer` to the `RawSampleBuffer` in planar order.
+    /// The two buffers must be equivalent.
+    pub fn copy_planar(&mut self, src: &AudioBuffer<S>) {
+        match self.byte_order {
+            ByteOrder::Native => self.copy_planar_with(src, |s| s.into_raw_sample()),
+            ByteOrder::LittleEndian => self.copy_planar_with(src, |s| s.into_raw_sample_le()),
+            ByteOrder::BigEndian => self.copy_planar_with(src, |s| s.into_raw_sample_be()),
+        }
+    }
+
+    /// Copies all audio data from the source `AudioBuffer` to the `RawSampleBuffer` in planar order
+    /// using the provided function to convert each sample into a raw sample.
+    fn copy_planar_with<F, W>(&mut self, src: &AudioBuffer<F>, write: W)
+    where
+        F: Sample,
+        W: Fn(F) -> S::RawType,
+    {
+        let n_channels = src.spec.channels.count();
+        let n_samples = n_channels * src.n_frames;
+
+        // Ensure that the capacity of the sample buffer is greater than or equal to the number
+        // of samples that will be copied from the source buffer.
+        assert!(self.capacity() >= n_samples);
+
+        let dst_buf = &mut self.buf[..n_samples];
+
+        for (ch, dst_ch) in dst_buf.chunks_exact_mut(src.n_frames).enumerate() {
+            let src_ch = src.chan(ch);
+
+            for (&s, d) in src_ch.iter().zip(dst_ch) {
+                *d = write(s);
+            }
+        }
+
+        self.n_written = n_samples;
+        self.n_channels = n_channels;
+        self.is_planar = true;
+    }
+
+    /// Copies all audio data from the source `AudioBufferRef` in interleaved channel order into the
+    /// `RawSampleBuffer`. The two buffers must be equivalent.
+    pub fn copy_interleaved_ref(&mut self, src: AudioBufferRef)
+    where
+        S: ConvertibleSample,
+    {
+        match src {
+            AudioBufferRef::U8(buf)  => self.copy_interleaved_typed(&buf),
+            AudioBufferRef::U16(buf) => self.copy_interleaved_typed(&buf),
+            AudioBufferRef::U24(buf) => self.copy_interleaved_typed(&buf),
+            AudioBufferRef::U32(buf) => self.copy_interleaved_typed(&buf),
+            AudioBufferRef::S8(buf)  => self.copy_interleaved_typed(&buf),
+            AudioBufferRef::S16(buf) => self.copy_interleaved_typed(&buf),
+            AudioBufferRef::S24(buf) => self.copy_interleaved_typed(&buf),
+            AudioBufferRef::S32(buf) => self.copy_interleaved_typed(&buf),
+            AudioBufferRef::F32(buf) => self.copy_interleaved_typed(&buf),
+            AudioBufferRef::F64(buf) => self.copy_interleaved_typed(&buf),
+        }
+    }
+
+    /// Copies all audio data from a source `AudioBuffer` that is of a different sample format type
+    /// than that of the `RawSampleBuffer` in interleaved channel order. The two buffers must be
+    /// equivalent.
+    pub fn copy_interleaved_typed<F>(&mut self, src: &AudioBuffer<F>)
+    where
+        F: Sample + IntoSample<S>,
+    {
+        match self.byte_order {
+            ByteOrder::Native => {
+                self.copy_interleaved_with(src, |s| s.into_sample().into_raw_sample())
+            }
+            ByteOrder::LittleEndian => {
+                self.copy_interleaved_with(src, |s| s.into_sample().into_raw_sample_le())
+            }
+            ByteOrder::BigEndian => {
+                self.copy_interleaved_with(src, |s| s.into_sample().into_raw_sample_be())
+            }
+        }
+    }
+
+    /// Copies all audio data from the source `AudioBuffer` to the `RawSampleBuffer` in interleaved
+    /// channel order. The two buffers must be equivalent.
+    pub fn copy_interleaved(&mut self, src: &AudioBuffer<S>) {
+        match self.byte_order {
+            ByteOrder::Native => self.copy_interleaved_with(src, |s| s.into_raw_sample()),
+            ByteOrder::LittleEndian => self.copy_interleaved_with(src, |s| s.into_raw_sample_le()),
+            ByteOrder::BigEndian => self.copy_interleaved_with(src, |s| s.into_raw_sample_be()),
+        }
+    }
+
+    /// Copies all audio data from the source `AudioBuffer` to the `RawSampleBuffer` in interleaved
+    /// channel order using the provided function to convert each sample into a raw sample.
+    fn copy_interleaved_with<F, W>(&mut self, src: &AudioBuffer<F>, write: W)
+    where
+        F: Sample,
+        W: Fn(F) -> S::RawType,
+    {
+        let n_frames = src.n_frames;
+        let n_channels = src.spec.channels.count();
+        let n_samples = n_frames * n_channels;
+
+        // Ensure that the capacity of the sample buffer is greater than or equal to the number
+        // of samples that will be copied from the source buffer.
+        assert!(self.capacity() >= n_samples);
+
+        // The destination buffer slice.
+        let dst_buf = &mut self.buf[..n_samples];
+
+        // Provide slightly optimized interleave algorithms for Mono and Stereo buffers.
+        match n_channels {
+            // No channels, do nothing.
+            0 => (),
+            // Mono
+            1=> {
+                for (&s, d) in src.chan(0).iter().zip(dst_buf) {
+                    *d = write(s);
+                }
+            }
+            // Stereo
+            2 => {
+                let l_buf = src.chan(0);
+                let r_buf = src.chan(1);
+
+                for ((&l, &r), d) in l_buf.iter().zip(r_buf).zip(dst_buf.chunks_exact_mut(2)) {
+                    d[0] = write(l);
+                    d[1] = write(r);
+                }
+            }
+            // 3+ channels
+            _ => {
+                for ch in 0..n_channels {
+                    let src_ch = src.chan(ch);
+                    let dst_ch_iter = dst_buf[ch..].iter_mut().step_by(n_channels);
+
+                    for (&s, d) in src_ch.iter().zip(dst_ch_iter) {
+                        *d = write(s);
+                    }
+                }
+            }
+        }
+
+        self.n_written = n_samples;
+        self.n_channels = n_channels;
+        self.is_planar = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::Error;
+
+    #[test]
+    fn verify_discrete_channels_interleave() {
+        let channels = Channels::discrete(10);
+
+        assert!(channels.is_discrete());
+        assert_eq!(channels.count(), 10);
+
+        let spec = SignalSpec::new(48_000, channels);
+
+        let mut buf = AudioBuffer::<i32>::new(4, spec);
+
+        buf.render(None, |planes, idx| {
+            for (ch, plane) in planes.planes().iter_mut().enumerate() {
+                plane[idx] = (10 * idx + ch) as i32;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(buf.planes().planes().len(), 10);
+
+        let mut samples = SampleBuffer::<i32>::new(4, spec);
+        samples.copy_interleaved_typed(&buf);
+
+        assert_eq!(samples.len(), 40);
+        assert!(samples.samples().iter().enumerate().all(|(i, &s)| s == i as i32));
+    }
+
+    #[test]
+    fn verify_s16_ref_interleave_is_exact() {
+        let spec = SignalSpec::new_with_layout(44_100, Layout::Stereo);
+
+        let mut buf = AudioBuffer::<i16>::new(3, spec);
+
+        buf.render(None, |planes, idx| {
+            let planes = planes.planes();
+            planes[0][idx] = i16::MIN + idx as i16;
+            planes[1][idx] = i16::MAX - idx as i16;
+            Ok(())
+        })
+        .unwrap();
+
+        let mut samples = SampleBuffer::<i16>::new(3, spec);
+        samples.copy_interleaved_ref(buf.as_audio_buffer_ref());
+
+        assert_eq!(samples.samples(), &[i16::MIN, i16::MAX, i16::MIN + 1, i16::MAX - 1,
+                                        i16::MIN + 2, i16::MAX - 2]);
+
+        let mut same = SampleBuffer::<i16>::new(3, spec);
+        same.copy_interleaved(&buf);
+
+        assert_eq!(same.samples(), samples.samples());
+    }
+
+    #[test]
+    fn verify_from_interleaved() {
+        let spec = SignalSpec::new_with_layout(44_100, Layout::Stereo);
+
+        let buf = AudioBuffer::from_interleaved(&spec, 3, &[1i16, -1, 2, -2, 3, -3]);
+
+        assert_eq!(buf.frames(), 3);
+        assert_eq!(buf.capacity(), 3);
+        assert_eq!(buf.chan(0), &[1, 2, 3]);
+        assert_eq!(buf.chan(1), &[-1, -2, -3]);
+
+        // An empty buffer may be built from no frames.
+        let empty = AudioBuffer::<f32>::from_interleaved(&spec, 0, &[]);
+        assert_eq!(empty.frames(), 0);
+    }
+
+    #[test]
+    fn verify_convert_to() {
+        let spec = SignalSpec::new_with_layout(44_100, Layout::Stereo);
+
+        let buf = AudioBuffer::from_interleaved(&spec, 2, &[0.5f32, -0.5, 1.0, 0.0]);
+        let buf_ref = AudioBufferRef::F32(Cow::Borrowed(&buf));
+        let converted = buf_ref.convert_to::<i16>(DitherType::Identity, 0);
+
+        assert!(converted.spec() == &spec);
+        assert_eq!(converted.chan(0), &[16_384, 32_767]);
+        assert_eq!(converted.chan(1), &[-16_384, 0]);
+
+        let samples = [i32::MAX, i32::MIN, 0x1_0000, -0x1_0000];
+        let buf = AudioBuffer::from_interleaved(&spec, 2, &samples);
+        let buf_ref = AudioBufferRef::S32(Cow::Borrowed(&buf));
+        let converted = buf_ref.convert_to::<i16>(DitherType::Identity, 0);
+
+        assert_eq!(converted.frames(), 2);
+        assert_eq!(converted.chan(0), &[32_767, 1]);
+        assert_eq!(converted.chan(1), &[-32_768, -1]);
+
+        // The dither noise depends on the seed.
+        let samples = (0..256).map(|i| 0x1_0000 * i).collect::<Vec<i32>>();
+        let buf = AudioBuffer::from_interleaved(&spec, 128, &samples);
+        let buf_ref = AudioBufferRef::S32(Cow::Borrowed(&buf));
+
+        let first = buf_ref.convert_to::<i16>(DitherType::Triangular, 1);
+        let again = buf_ref.convert_to::<i16>(DitherType::Triangular, 1);
+        let second = buf_ref.convert_to::<i16>(DitherType::Triangular, 2);
+
+        assert_eq!(first.chan(0), again.chan(0));
+        assert_ne!(first.chan(0), second.chan(0));
+    }
+
+    #[test]
+    fn verify_deinterleave_ingest() {
+        let interleaved: Vec<i16> = (0..24).collect();
+
+        for &layout in &[Layout::Mono, Layout::Stereo, Layout::FivePointOne] {
+            let spec = SignalSpec::new_with_layout(48_000, layout);
+            let n_channels = spec.channels.count();
+
+            // Ingest in two parts to verify frames are appended.
+            let split = n_channels * 2;
+
+            let mut buf = AudioBuffer::<i16>::new(24, spec);
+            buf.copy_from_interleaved(&interleaved[..split]);
+            buf.copy_from_interleaved(&interleaved[split..]);
+
+            assert_eq!(buf.frames(), 24 / n_channels);
+            assert_eq!(buf.chan(n_channels - 1)[0], n_channels as i16 - 1);
+
+            let mut samples = SampleBuffer::<i16>::new(24, spec);
+            samples.copy_interleaved(&buf);
+            assert_eq!(samples.samples(), &interleaved[..]);
+
+            let mut planar = SampleBuffer::<i16>::new(24, spec);
+            planar.copy_planar(&buf);
+
+            let mut ingested = SampleBuffer::<i16>::new(24, spec);
+            ingested.copy_from_slice_interleaved(&interleaved, spec.channels);
+            assert_eq!(ingested.samples(), planar.samples());
+        }
+    }
+
+    #[test]
+    fn verify_remap_channels_round_trip() {
+        let spec = SignalSpec::new_with_layout(48_000, Layout::FivePointOne);
+
+        // Symphonia's 5.1 plane order follows the bit order of the channel mask.
+        let native = [
+            Channels::FRONT_LEFT,
+            Channels::FRONT_RIGHT,
+            Channels::FRONT_CENTRE,
+            Channels::REAR_LEFT,
+            Channels::REAR_RIGHT,
+            Channels::LFE1,
+        ];
+
+        // The WAVE 5.1 plane order.
+        let wave = [
+            Channels::FRONT_LEFT,
+            Channels::FRONT_RIGHT,
+            Channels::FRONT_CENTRE,
+            Channels::LFE1,
+            Channels::REAR_LEFT,
+            Channels::REAR_RIGHT,
+        ];
+
+        let mut buf = AudioBuffer::<i32>::new(4, spec);
+
+        buf.render(None, |planes, idx| {
+            for (ch, plane) in planes.planes().iter_mut().enumerate() {
+                plane[idx] = (100 * ch + idx) as i32;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        let original = buf.clone();
+
+        buf.remap_channels(&native, &wave).unwrap();
+
+        // The LFE plane moves to index 3, and the rear planes move after it.
+        assert_eq!(buf.chan(3), original.chan(5));
+        assert_eq!(buf.chan(4), original.chan(3));
+        assert_eq!(buf.chan(5), original.chan(4));
+
+        buf.remap_channels(&wave, &native).unwrap();
+
+        for ch in 0..6 {
+            assert_eq!(buf.chan(ch), original.chan(ch));
+        }
+
+        // Mismatched channel sets are rejected.
+        let mut bad = wave;
+        bad[3] = Channels::LFE2;
+        assert!(buf.remap_channels(&native, &bad).is_err());
+        assert!(buf.remap_channels(&native, &wave[..5]).is_err());
+    }
+
+    #[test]
+    fn verify_debug_summary_is_bounded() {
+        let spec = SignalSpec::new_with_layout(44_100, Layout::Stereo);
+
+        let mut buf = AudioBuffer::<f32>::new(1_000_000, spec);
+
+        buf.render(None, |planes, idx| {
+            let planes = planes.planes();
+            planes[0][idx] = 0.5;
+            planes[1][idx] = -0.25;
+            Ok(())
+        })
+        .unwrap();
+
+        let summary = format!("{:?}", buf);
+
+        assert!(summary.contains("channels: 2"));
+        assert!(summary.contains("frames: 1000000"));
+        assert!(summary.contains("max: Some(0.5)"));
+        assert!(summary.contains("min: Some(-0.25)"));
+        assert!(summary.len() < 1024);
+
+        // Discrete buffers with many channels only summarize the first few planes.
+        let spec = SignalSpec::new(48_000, Channels::discrete(512));
+        let mut buf = AudioBuffer::<i16>::new(4096, spec);
+        buf.render_reserved(None);
+
+        let summary = format!("{:#?}", buf);
+
+        assert!(summary.contains("channels: 512"));
+        assert!(summary.contains("frames: 4096"));
+        assert!(summary.len() < 8192);
+    }
+
+    #[test]
+    fn verify_apply_gain() {
+        let spec = SignalSpec::new_with_layout(48_000, Layout::Mono);
+
+        // -6 dB is approximately half of full-scale.
+        let mut s16 = AudioBuffer::<i16>::new(1, spec);
+        s16.render(None, |planes, idx| { planes.planes()[0][idx] = i16::MAX; Ok(()) }).unwrap();
+        s16.apply_gain(-6.0);
+        assert!((i32::from(s16.chan(0)[0]) - i32::from(i16::MAX / 2)).abs() < 100);
+
+        let mut s32 = AudioBuffer::<i32>::new(1, spec);
+        s32.render(None, |planes, idx| { planes.planes()[0][idx] = i32::MIN; Ok(()) }).unwrap();
+        s32.apply_gain(-6.0);
+        assert!((i64::from(s32.chan(0)[0]) - i64::from(i32::MIN / 2)).abs() < 10_000_000);
+
+        let mut flt = AudioBuffer::<f32>::new(1, spec);
+        flt.render(None, |planes, idx| { planes.planes()[0][idx] = 1.0; Ok(()) }).unwrap();
+        flt.apply_gain(-6.0);
+        assert!((flt.chan(0)[0] - 0.5).abs() < 0.005);
+
+        // An infinite gain saturates integer samples, and silence remains silent.
+        let mut s16 = AudioBuffer::<i16>::new(3, spec);
+        s16.render(None, |planes, idx| {
+            planes.planes()[0][idx] = [1000, -1000, 0][idx];
+            Ok(())
+        })
+        .unwrap();
+        s16.apply_gain(f32::INFINITY);
+        assert_eq!(s16.chan(0), &[i16::MAX, i16::MIN, 0]);
+
+        let mut s32 = AudioBuffer::<i32>::new(2, spec);
+        s32.render(None, |planes, idx| { planes.planes()[0][idx] = [1, -1][idx]; Ok(()) }).unwrap();
+        s32.apply_gain(f32::INFINITY);
+        assert_eq!(s32.chan(0), &[i32::MAX, i32::MIN]);
+
+        // A buffer without any capacity is left as-is.
+        let mut empty = AudioBuffer::<i16>::new(0, spec);
+        empty.apply_gain(-6.0);
+        assert_eq!(empty.frames(), 0);
+    }
+
+    #[test]
+    fn verify_limit() {
+        let spec = SignalSpec::new_with_layout(48_000, Layout::Mono);
+
+        let sine = |idx: usize| (2.0 * std::f64::consts::PI * idx as f64 / 48.0).sin();
+
+        // A full-scale sine with a gain of +6 dB does not exceed full scale once limited to 0 dB.
+        let mut flt = AudioBuffer::<f32>::new(48, spec);
+        flt.render(None, |planes, idx| { planes.planes()[0][idx] = sine(idx) as f32; Ok(()) })
+            .unwrap();
+
+        flt.apply_gain(6.0);
+        assert!(flt.chan(0).iter().any(|s| s.abs() > 1.9));
+
+        let gained = flt.clone();
+
+        flt.limit(0.0);
+        assert!(flt.chan(0).iter().all(|s| s.abs() <= 1.0));
+        assert_eq!(flt.peak(0), 1.0);
+
+        // Samples within the ceiling are unchanged.
+        for (&limited, &gained) in flt.chan(0).iter().zip(gained.chan(0)) {
+            assert!(gained.abs() > 1.0 || limited == gained);
+        }
+
+        // Integer samples are limited to the ceiling.
+        let mut s16 = AudioBuffer::<i16>::new(48, spec);
+        s16.render(None, |planes, idx| {
+            planes.planes()[0][idx] = (sine(idx) * f64::from(i16::MAX)) as i16;
+            Ok(())
+        })
+        .unwrap();
+
+        s16.limit(-6.0);
+        assert!(s16.chan(0).iter().all(|&s| i32::from(s).abs() <= 16_423));
+        assert!(s16.chan(0).iter().any(|&s| i32::from(s).abs() >= 16_000));
+
+        // There is nothing to limit in a buffer without any capacity.
+        let mut empty = AudioBuffer::<i16>::new(0, spec);
+        empty.limit(-6.0);
+        assert_eq!(empty.frames(), 0);
+    }
+
+    #[test]
+    fn verify_mix_saturates() {
+        let spec = SignalSpec::new_with_layout(48_000, Layout::Stereo);
+
+        // A half-scale square wave.
+        let square = |planes: &mut AudioPlanesMut<'_, i16>, idx| {
+            for plane in planes.planes().iter_mut() {
+                plane[idx] = if idx % 2 == 0 { 16_384 } else { -16_384 };
+            }
+            Ok(())
+        };
+
+        let mut a = AudioBuffer::<i16>::new(8, spec);
+        a.render(None, square).unwrap();
+
+        let mut b = AudioBuffer::<i16>::new(8, spec);
+        b.render(None, square).unwrap();
+
+        a.mix(&b).unwrap();
+        a.mix(&b).unwrap();
+
+        for ch in 0..2 {
+            for (idx, &s) in a.chan(ch).iter().enumerate() {
+                assert_eq!(s, if idx % 2 == 0 { i16::MAX } else { i16::MIN });
+            }
+        }
+
+        // Floating point samples are summed without clamping.
+        let mut a = AudioBuffer::<f32>::new(2, spec);
+        a.render(None, |planes, idx| {
+            for plane in planes.planes().iter_mut() {
+                plane[idx] = 0.75;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        let b = a.clone();
+        a.mix(&b).unwrap();
+        assert_eq!(a.chan(0), &[1.5, 1.5]);
+
+        // Buffers with different frame counts may not be mixed.
+        let mut c = AudioBuffer::<f32>::new(2, spec);
+        c.render_reserved(Some(1));
+        assert!(a.mix(&c).is_err());
+    }
+
+    #[test]
+    fn verify_layout_channel_counts() {
+        let layouts = [
+            (Layout::Mono, 1),
+            (Layout::Stereo, 2),
+            (Layout::TwoPointOne, 3),
+            (Layout::Quad, 4),
+            (Layout::FivePointZero, 5),
+            (Layout::FivePointOne, 6),
+            (Layout::SixPointOne, 7),
+            (Layout::SevenPointOne, 8),
+        ];
+
+        for &(layout, count) in layouts.iter() {
+            assert_eq!(layout.into_channels().count(), count);
+            assert_eq!(layout.channel_count(), count);
+        }
+
+        let channels = Layout::SevenPointOne.into_channels();
+
+        assert!(channels.contains(Channels::SIDE_LEFT | Channels::SIDE_RIGHT));
+        assert!(channels.contains(Channels::REAR_LEFT | Channels::REAR_RIGHT));
+    }
+
+    #[test]
+    fn verify_channels_as_layout() {
+        let layouts = [
+            Layout::Mono,
+            Layout::Stereo,
+            Layout::TwoPointOne,
+            Layout::Quad,
+            Layout::FivePointZero,
+            Layout::FivePointOne,
+            Layout::SixPointOne,
+            Layout::SevenPointOne,
+        ];
+
+        for &layout in layouts.iter() {
+            assert_eq!(layout.into_channels().as_layout(), Some(layout));
+        }
+
+        assert_eq!((Channels::FRONT_LEFT | Channels::FRONT_CENTRE).as_layout(), None);
+        assert_eq!(Layout::Stereo.into_channels().union(Channels::TOP_CENTRE).as_layout(), None);
+        assert_eq!(Channels::discrete(2).as_layout(), None);
+        assert_eq!(Channels::empty().as_layout(), None);
+    }
+
+    #[test]
+    fn verify_write_interleaved_with() {
+        let spec = SignalSpec::new_with_layout(48_000, Layout::Stereo);
+
+        let sine = |frame: usize, ch: usize| {
+            let phase = 2.0 * std::f32::consts::PI * 1000.0 * frame as f32 / 48_000.0;
+            if ch == 0 { phase.sin() } else { -phase.sin() }
+        };
+
+        // Reference: render the sine into an AudioBuffer and interleave it.
+        let mut buf = AudioBuffer::<f32>::new(64, spec);
+
+        buf.render(None, |planes, idx| {
+            let planes = planes.planes();
+            planes[0][idx] = sine(idx, 0);
+            planes[1][idx] = sine(idx, 1);
+            Ok(())
+        })
+        .unwrap();
+
+        let mut reference = SampleBuffer::<f32>::new(64, spec);
+        reference.copy_interleaved(&buf);
+
+        let mut samples = SampleBuffer::<f32>::new(64, spec);
+        samples.write_interleaved_with(64, spec.channels, sine);
+
+        assert_eq!(samples.len(), 128);
+        assert_eq!(samples.samples(), reference.samples());
+
+        // Writing fewer frames replaces the previously written samples.
+        samples.write_interleaved_with(10, spec.channels, sine);
+
+        assert_eq!(samples.samples(), &reference.samples()[..20]);
+    }
+
+    #[test]
+    fn verify_channel_names() {
+        let channels = Layout::FivePointOne.into_channels();
+
+        assert_eq!(channels.to_names(), "FL, FR, FC, RL, RR, LFE1");
+        assert_eq!(channels.to_string(), "FL, FR, FC, RL, RR, LFE1");
+        assert_eq!(format!("{:#b}", channels), "0b1101111");
+
+        assert_eq!(Channels::discrete(3).to_string(), "discrete(3)");
+    }
+
+    #[test]
+    fn verify_split_off_and_append() {
+        let spec = SignalSpec::new_with_layout(48_000, Layout::Stereo);
+
+        // A ramp on the left channel, and an inverted ramp on the right channel.
+        let ramp = (0..10).flat_map(|i| vec![i, -i]).collect::<Vec<i32>>();
+
+        let mut buf = AudioBuffer::<i32>::new(16, spec);
+        buf.copy_from_interleaved(&ramp);
+
+        let original = buf.clone();
+
+        let tail = buf.split_off(6);
+
+        assert_eq!(buf.frames(), 6);
+        assert_eq!(tail.frames(), 4);
+        assert_eq!(tail.capacity(), 10);
+        assert_eq!(buf.chan(0), &[0, 1, 2, 3, 4, 5]);
+        assert_eq!(tail.chan(0), &[6, 7, 8, 9]);
+        assert_eq!(tail.chan(1), &[-6, -7, -8, -9]);
+
+        // Re-appending the split frames recovers the original buffer.
+        buf.append(&tail).unwrap();
+
+        assert!(buf == original);
+
+        // Splitting at the end returns an empty buffer.
+        assert_eq!(buf.split_off(10).frames(), 0);
+
+        // The specifications must match, and there must be enough capacity.
+        let mono = AudioBuffer::<i32>::new(16, SignalSpec::new_with_layout(48_000, Layout::Mono));
+        assert!(buf.append(&mono).is_err());
+
+        assert!(buf.append(&original).is_err());
+        assert_eq!(buf.frames(), 10);
+    }
+
+    #[test]
+    fn verify_try_new_rejects_absurd_durations() {
+        let spec = SignalSpec::new_with_layout(48_000, Layout::FivePointOne);
+
+        // The number of samples overflows.
+        assert!(matches!(AudioBuffer::<f32>::try_new(u64::MAX, spec), Err(Error::LimitError(_))));
+        assert!(SampleBuffer::<f32>::try_new(u64::MAX / 2, spec).is_err());
+        assert!(RawSampleBuffer::<f32>::try_new(u64::MAX / 4, spec).is_err());
+
+        // The number of bytes exceeds the addressable memory.
+        let duration = (usize::MAX / 8) as u64;
+        assert!(AudioBuffer::<f64>::try_new(duration, spec).is_err());
+
+        let buf = AudioBuffer::<f32>::try_new(1024, spec).unwrap();
+        assert_eq!(buf.capacity(), 1024);
+    }
+
+    #[test]
+    fn verify_planar_bytes() {
+        let spec = SignalSpec::new_with_layout(48_000, Layout::Stereo);
+
+        let mut buf = AudioBuffer::<i16>::new(4, spec);
+        buf.copy_from_interleaved(&[1i16, -1, 2, -2, 3, -3]);
+
+        let mut raw = RawSampleBuffer::<i16>::new(4, spec);
+        raw.with_byte_order(ByteOrder::LittleEndian);
+
+        raw.copy_planar(&buf);
+
+        // All samples of channel 0 precede all samples of channel 1.
+        let samples = [1i16, 2, 3, -1, -2, -3];
+        let bytes = samples.iter().flat_map(|s| s.to_le_bytes()).collect::<Vec<u8>>();
+
+        assert_eq!(raw.samples_per_channel(), 3);
+        assert_eq!(raw.as_planar_bytes(), Some(&bytes[..]));
+
+        // Interleaved samples are not planar.
+        raw.copy_interleaved(&buf);
+        assert_eq!(raw.as_planar_bytes(), None);
+
+        let mut samples = SampleBuffer::<i16>::new(4, spec);
+        samples.copy_planar(&buf);
+
+        assert_eq!(samples.samples_per_channel(), 3);
+        assert_eq!(samples.samples(), [1, 2, 3, -1, -2, -3]);
+    }
+
+    #[test]
+    fn verify_channel_index_of() {
+        let channels = Layout::FivePointOne.into_channels();
+
+        assert_eq!(channels.index_of(Channels::FRONT_LEFT), Some(0));
+        assert_eq!(channels.index_of(Channels::FRONT_CENTRE), Some(2));
+        assert_eq!(channels.index_of(Channels::LFE1), Some(5));
+
+        // Absent channels, multiple channels, and discrete channels have no index.
+        assert_eq!(channels.index_of(Channels::REAR_CENTRE), None);
+        assert_eq!(channels.index_of(Channels::FRONT_LEFT | Channels::FRONT_RIGHT), None);
+        assert_eq!(Channels::discrete(6).index_of(Channels::FRONT_LEFT), None);
+    }
+
+    #[test]
+    fn verify_channel_positions() {
+        let channels = Layout::FivePointOne.into_channels();
+        let front = Channels::FRONT_LEFT | Channels::FRONT_RIGHT;
+
+        assert!(channels.has_positions(front));
+        assert!(!channels.has_positions(front | Channels::SIDE_LEFT));
+        assert!(channels.has_any_position(front | Channels::SIDE_LEFT));
+        assert!(!channels.has_any_position(Channels::SIDE_LEFT | Channels::SIDE_RIGHT));
+
+        // The count of 3 discrete channels shares its bits with the front-left and front-right
+        // positions, but discrete channels have no positions.
+        let discrete = Channels::discrete(3);
+
+        assert!(discrete.contains(front));
+        assert!(!discrete.has_positions(front));
+        assert!(!discrete.has_any_position(front));
+
+        // A discrete mask is not a set of positions either.
+        assert!(!channels.has_positions(Channels::discrete(1)));
+        assert!(!channels.has_any_position(Channels::discrete(1)));
+
+        // The index addresses the channel in an audio buffer.
+        let spec = SignalSpec::new(48_000, channels);
+
+        let mut buf = AudioBuffer::<f32>::new(4, spec);
+        buf.silence(None);
+
+        let centre = channels.index_of(Channels::FRONT_CENTRE).unwrap();
+        buf.chan_mut(usize::from(centre)).fill(1.0);
+
+        let interleaved = buf.frames_iter().next().unwrap().collect::<Vec<_>>();
+        assert_eq!(interleaved, [0.0, 0.0, 1.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn verify_frames_iter() {
+        let spec = SignalSpec::new_with_layout(48_000, Layout::FivePointOne);
+
+        let mut buf = AudioBuffer::<i32>::new(8, spec);
+
+        buf.render(Some(5), |planes, idx| {
+            for (ch, plane) in planes.planes().iter_mut().enumerate() {
+                plane[idx] = (100 * ch + idx) as i32;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        let mut samples = SampleBuffer::<i32>::new(8, spec);
+        samples.copy_interleaved(&buf);
+
+        let mut frames = buf.frames_iter();
+
+        assert_eq!(frames.len(), 5);
+
+        let iterated = frames.by_ref().flatten().collect::<Vec<i32>>();
+
+        assert_eq!(iterated, samples.samples());
+
+        // Only written frames are iterated.
+        assert!(frames.next().is_none());
+    }
+
+    #[test]
+    fn verify_raw_bytes_round_trip() {
+        let spec = SignalSpec::new_with_layout(44_100, Layout::Stereo);
+
+        let mut buf = AudioBuffer::<i24>::new(16, spec);
+
+        buf.render(None, |planes, idx| {
+            let planes = planes.planes();
+            planes[0][idx] = i24::from(-4000 * idx as i32);
+            planes[1][idx] = i24::from(1234 * idx as i32);
+            Ok(())
+        })
+        .unwrap();
+
+        let mut expected = SampleBuffer::<i24>::new(16, spec);
+        expected.copy_interleaved_typed(&buf);
+
+        for &byte_order in &[ByteOrder::Native, ByteOrder::LittleEndian, ByteOrder::BigEndian] {
+            let mut raw = RawSampleBuffer::<i24>::new(16, spec);
+            raw.with_byte_order(byte_order);
+            raw.copy_interleaved_typed(&buf);
+
+            let mut ingested = SampleBuffer::<i24>::new(16, spec);
+            ingested.copy_from_bytes(raw.as_bytes(), byte_order).unwrap();
+
+            assert_eq!(ingested.samples(), expected.samples());
+        }
+
+        // Multi-byte samples in the wrong byte order are not the same samples.
+        let mut raw = RawSampleBuffer::<i24>::new(16, spec);
+        raw.with_byte_order(ByteOrder::BigEndian);
+        raw.copy_interleaved_typed(&buf);
+
+        let mut ingested = SampleBuffer::<i24>::new(16, spec);
+        ingested.copy_from_bytes(raw.as_bytes(), ByteOrder::LittleEndian).unwrap();
+
+        assert_ne!(ingested.samples(), expected.samples());
+
+        // Partial samples and excess samples are rejected.
+        assert!(ingested.copy_from_bytes(&raw.as_bytes()[..5], ByteOrder::Native).is_err());
+        assert!(ingested.copy_from_bytes(&[0; 3 * 33], ByteOrder::Native).is_err());
+    }
+
+    #[test]
+    fn verify_copy_interleaved_into() {
+        let spec = SignalSpec::new_with_layout(44_100, Layout::Stereo);
+
+        // Only render some of the frames to verify the planar stride is respected.
+        let mut buf = AudioBuffer::<i16>::new(32, spec);
+
+        buf.render(Some(20), |planes, idx| {
+            let planes = planes.planes();
+            planes[0][idx] = 100 * idx as i16;
+            planes[1][idx] = -100 * idx as i16;
+            Ok(())
+        })
+        .unwrap();
+
+        let mut samples = SampleBuffer::<i16>::new(32, spec);
+        samples.copy_interleaved(&buf);
+
+        let mut dest = [0i16; 64];
+
+        assert_eq!(buf.copy_interleaved_into(&mut dest), 40);
+        assert_eq!(&dest[..40], samples.samples());
+        assert!(dest[40..].iter().all(|&s| s == 0));
+    }
+
+    #[test]
+    fn verify_24bit_buffer_capacity() {
+        let spec = SignalSpec::new_with_layout(96_000, Layout::FivePointOne);
+
+        // The capacity of a sample buffer is measured in samples, regardless of the in-memory or
+        // packed size of the sample format.
+        assert_eq!(SampleBuffer::<i24>::new(100, spec).capacity(), 600);
+        assert_eq!(SampleBuffer::<u24>::new(100, spec).capacity(), 600);
+        assert_eq!(RawSampleBuffer::<i24>::new(100, spec).capacity(), 600);
+        assert_eq!(RawSampleBuffer::<u24>::new(100, spec).capacity(), 600);
+
+        // A full buffer of packed 24-bit samples is 3 bytes per sample.
+        let mut buf = AudioBuffer::<i24>::new(100, spec);
+        buf.render_reserved(None);
+
+        let mut raw = RawSampleBuffer::<i24>::new(100, spec);
+        raw.copy_interleaved_typed(&buf);
+
+        assert_eq!(raw.len(), raw.capacity());
+        assert_eq!(raw.as_bytes().len(), 3 * 600);
+    }
+
+    #[test]
+    fn verify_raw_sample_byte_order() {
+        let spec = SignalSpec::new_with_layout(48_000, Layout::Mono);
+
+        let mut buf = AudioBuffer::<i24>::new(1, spec);
+
+        buf.render(None, |planes, idx| {
+            planes.planes()[0][idx] = i24::from(0x123456);
+            Ok(())
+        })
+        .unwrap();
+
+        let mut raw = RawSampleBuffer::<i24>::new(1, spec);
+
+        assert_eq!(raw.byte_order(), ByteOrder::Native);
+
+        raw.with_byte_order(ByteOrder::LittleEndian).copy_interleaved(&buf);
+        assert_eq!(raw.as_bytes(), &[0x56, 0x34, 0x12]);
+
+        raw.with_byte_order(ByteOrder::BigEndian).copy_planar(&buf);
+        assert_eq!(raw.as_bytes(), &[0x12, 0x34, 0x56]);
+
+        raw.with_byte_order(ByteOrder::Native).copy_interleaved(&buf);
+
+        if cfg!(target_endian = "little") {
+            assert_eq!(raw.as_bytes(), &[0x56, 0x34, 0x12]);
+        }
+        else {
+            assert_eq!(raw.as_bytes(), &[0x12, 0x34, 0x56]);
+        }
+
+        // Converted multi-byte samples are also written in the requested byte order.
+        let mut raw = RawSampleBuffer::<i16>::new(1, spec);
+
+        raw.with_byte_order(ByteOrder::BigEndian).copy_interleaved_typed(&buf);
+        assert_eq!(raw.as_bytes(), &[0x12, 0x34]);
+
+        raw.with_byte_order(ByteOrder::LittleEndian).copy_planar_typed(&buf);
+        assert_eq!(raw.as_bytes(), &[0x34, 0x12]);
+    }
+
+    #[test]
+    fn verify_silence() {
+        let spec = SignalSpec::new_with_layout(48_000, Layout::Stereo);
+
+        let mut buf = AudioBuffer::<u8>::new(8, spec);
+
+        buf.fill(|planes, idx| {
+            for plane in planes.planes() {
+                plane[idx] = 1;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        buf.clear();
+        buf.silence(Some(3));
+
+        assert_eq!(buf.frames(), 3);
+        assert_eq!(buf.chan(0), &[128, 128, 128]);
+        assert_eq!(buf.chan(1), &[128, 128, 128]);
+
+        // Silence the remainder of the buffer.
+        buf.silence(None);
+
+        assert_eq!(buf.frames(), 8);
+        assert!(buf.chan(1).iter().all(|&s| s == 128));
+
+        let mut buf = AudioBuffer::<i16>::new(8, spec);
+
+        buf.fill(|planes, idx| {
+            for plane in planes.planes() {
+                plane[idx] = -1;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        buf.clear();
+        buf.silence(None);
+
+        assert_eq!(buf.frames(), 8);
+        assert!(buf.chan(0).iter().chain(buf.chan(1)).all(|&s| s == 0));
+
+        // A buffer without any capacity can only be silenced for zero frames.
+        let mut buf = AudioBuffer::<i16>::new(0, spec);
+
+        buf.silence(None);
+        buf.silence(Some(0));
+
+        assert_eq!(buf.frames(), 0);
+    }
+
+    #[test]
+    fn verify_default_silence() {
+        // A signal that relies on the provided implementation of silence.
+        struct Wrapped(AudioBuffer<u8>);
+
+        impl Signal<u8> for Wrapped {
+            fn frames(&self) -> usize {
+                self.0.frames()
+            }
+
+            fn clear(&mut self) {
+                self.0.clear()
+            }
+
+            fn chan(&self, channel: usize) -> &[u8] {
+                self.0.chan(channel)
+            }
+
+            fn chan_mut(&mut self, channel: usize) -> &mut [u8] {
+                self.0.chan_mut(channel)
+            }
+
+            fn chan_pair_mut(&mut self, first: usize, second: usize) -> (&mut [u8], &mut [u8]) {
+                self.0.chan_pair_mut(first, second)
+            }
+
+            fn frames_iter(&self) -> AudioFrames<'_, u8> {
+                self.0.frames_iter()
+            }
+
+            fn render_reserved(&mut self, n_frames: Option<usize>) {
+                self.0.render_reserved(n_frames)
+            }
+
+            fn render<'a, F>(&'a mut self, n_frames: Option<usize>, render: F) -> Result<()>
+            where
+                F: FnMut(&mut AudioPlanesMut<'a, u8>, usize) -> Result<()>
+            {
+                self.0.render(n_frames, render)
+            }
+
+            fn transform<F>(&mut self, f: F)
+            where
+                F: Fn(u8) -> u8
+            {
+                self.0.transform(f)
+            }
+        }
+
+        let spec = SignalSpec::new_with_layout(48_000, Layout::Stereo);
+
+        let mut buf = Wrapped(AudioBuffer::<u8>::new(8, spec));
+
+        buf.fill(|planes, idx| {
+            for plane in planes.planes() {
+                plane[idx] = 1;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        buf.clear();
+        buf.render_reserved(Some(2));
+        buf.silence(Some(3));
+
+        assert_eq!(buf.frames(), 5);
+        assert_eq!(buf.chan(0), &[1, 1, 128, 128, 128]);
+        assert_eq!(buf.chan(1), &[1, 1, 128, 128, 128]);
+
+        // Silence the remainder of the buffer.
+        buf.silence(None);
+
+        assert_eq!(buf.frames(), 8);
+        assert!(buf.chan(1)[2..].iter().all(|&s| s == 128));
+
+        // The provided implementation also handles a buffer without any capacity.
+        let mut buf = Wrapped(AudioBuffer::<u8>::new(0, spec));
+
+        buf.silence(None);
+
+        assert_eq!(buf.frames(), 0);
+    }
+
+    #[test]
+    fn verify_peak_and_rms() {
+        use crate::conv::FromSample;
+
+        let spec = SignalSpec::new_with_layout(48_000, Layout::Stereo);
+
+        // A full-scale 1 kHz sine in the left channel, and silence in the right channel.
+        fn sine(idx: usize) -> f64 {
+            (2.0 * std::f64::consts::PI * 1000.0 * idx as f64 / 48_000.0).sin()
+        }
+
+        let mut buf = AudioBuffer::<f32>::new(4800, spec);
+
+        buf.fill(|planes, idx| {
+            planes.planes()[0][idx] = sine(idx) as f32;
+            planes.planes()[1][idx] = 0.0;
+            Ok(())
+        })
+        .unwrap();
+
+        assert!((buf.peak(0) - 1.0).abs() < 1e-6);
+        assert!((buf.rms(0) - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+        assert_eq!(buf.peak(1), 0.0);
+        assert_eq!(buf.rms(1), 0.0);
+
+        let mut buf = AudioBuffer::<i16>::new(4800, spec);
 
-impl RawSample for f32 {
-    type RawType = f32;
+        buf.fill(|planes, idx| {
+            planes.planes()[0][idx] = i16::from_sample(sine(idx));
+            planes.planes()[1][idx] = i16::MIN;
+            Ok(())
+        })
+        .unwrap();
 
-    #[inline(always)]
-    fn into_raw_sample(self) -> Self::RawType {
-        self
-    }
-}
+        assert!(buf.peak(0) >= i16::MAX - 1);
+        assert!((buf.rms(0) - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-3);
 
-impl RawSample for f64 {
-    type RawType = f64;
+        // The magnitude of the most negative sample saturates to the maximum sample value.
+        assert_eq!(buf.peak(1), i16::MAX);
+        assert!((buf.rms(1) - 1.0).abs() < 1e-6);
 
-    #[inline(always)]
-    fn into_raw_sample(self) -> Self::RawType {
-        self
+        // Peaks of unsigned samples are measured from the midpoint.
+        let mut buf = AudioBuffer::<u8>::new(2, spec);
+        buf.silence(None);
+
+        assert_eq!(buf.peak(0), 128);
+        assert_eq!(buf.rms(0), 0.0);
+
+        buf.chan_mut(0)[0] = 0;
+
+        assert_eq!(buf.peak(0), 255);
     }
-}
 
-/// A `RawSampleBuffer`, is a byte-oriented sample buffer. All samples copied to this buffer are
-/// converted into their packed data-type and stored as a stream of bytes. `RawSampleBuffer` is
-/// mean't for safely importing and exporting sample data to and from Symphonia as raw bytes.
-pub struct RawSampleBuffer<S: Sample + RawSample> {
-    buf: Vec<S::RawType>,
-    n_written: usize,
-    // Might take your heart.
-    sample_format: PhantomData<S>,
-}
+    #[test]
+    fn verify_render_frame_indices() {
+        let spec = SignalSpec::new_with_layout(48_000, Layout::Mono);
 
-impl<S: Sample + RawSample> RawSampleBuffer<S> {
-    /// Instantiate a new `RawSampleBuffer` using the specified signal specification and of the given
-    /// duration.
-    pub fn new(duration: Duration, spec: SignalSpec) -> RawSampleBuffer<S> {
-        // The number of channels * duration cannot exceed u64::MAX.
-        assert!(duration <= u64::MAX / spec.channels.count() as u64, "duration too large");
+        let mut buf = AudioBuffer::<i32>::new(8, spec);
 
-        // The total number of samples the buffer will store.
-        let n_samples = duration * spec.channels.count() as u64;
+        let mut indices = Vec::new();
 
-        // Practically speaking, it is not possible to allocate more than usize::MAX bytes of raw
-        // samples. This assertion ensures the potential downcast of n_samples to usize below is
-        // safe.
-        assert!(
-            n_samples <= (usize::MAX / mem::size_of::<S::RawType>()) as u64,
-            "duration too large"
-        );
+        buf.render(Some(3), |planes, idx| {
+            indices.push(idx);
+            planes.planes()[0][idx] = idx as i32;
+            Ok(())
+        })
+        .unwrap();
 
-        // Allocate enough memory for all the samples and fill the buffer with silence.
-        let buf = vec![S::MID.into_raw_sample(); n_samples as usize];
+        // The index restarts at 0 for each render, even though frames were already written.
+        buf.render(Some(2), |planes, idx| {
+            indices.push(idx);
+            planes.planes()[0][idx] = 10 + idx as i32;
+            Ok(())
+        })
+        .unwrap();
 
-        RawSampleBuffer {
-            buf,
-            n_written: 0,
-            sample_format: PhantomData,
-        }
-    }
+        assert_eq!(indices, [0, 1, 2, 0, 1]);
+        assert_eq!(buf.chan(0), &[0, 1, 2, 10, 11]);
 
-    /// Gets the number of written samples.
-    pub fn len(&self) -> usize {
-        self.n_written
-    }
+        // Frames rendered before an error are kept.
+        indices.clear();
 
-    /// Gets the maximum number of samples the `RawSampleBuffer` may store.
-    pub fn capacity(&self) -> usize {
-        self.buf.len()
-    }
+        let result = buf.render(None, |planes, idx| {
+            indices.push(idx);
 
-    /// Gets an immutable slice to the bytes of the sample's written in the `RawSampleBuffer`.
-    pub fn as_bytes(&self) -> &[u8] {
-        // Get a slice to the written raw samples in the buffer, and convert from &[RawType] to
-        // &[u8]. Since &[u8] has the least strict alignment requirements, this should always be
-        // safe and therefore cast_slice should never panic.
-        bytemuck::cast_slice(&self.buf[..self.n_written])
+            if idx == 2 {
+                return decode_error("render error");
+            }
+
+            planes.planes()[0][idx] = 20 + idx as i32;
+            Ok(())
+        });
+
+        assert!(result.is_err());
+        assert_eq!(indices, [0, 1, 2]);
+        assert_eq!(buf.chan(0), &[0, 1, 2, 10, 11, 20, 21]);
     }
 
-    /// Copies all audio data from the source `AudioBufferRef` in planar channel order into the
-    /// `RawSampleBuffer`. The two buffers must be equivalent.
-    pub fn copy_planar_ref(&mut self, src: AudioBufferRef)
-    where
-        S: ConvertibleSample,
-    {
-        match src {
-            AudioBufferRef::U8(buf)  => self.copy_planar_typed(&buf),
-            AudioBufferRef::U16(buf) => self.copy_planar_typed(&buf),
-            AudioBufferRef::U24(buf) => self.copy_planar_typed(&buf),
-            AudioBufferRef::U32(buf) => self.copy_planar_typed(&buf),
-            AudioBufferRef::S8(buf)  => self.copy_planar_typed(&buf),
-            AudioBufferRef::S16(buf) => self.copy_planar_typed(&buf),
-            AudioBufferRef::S24(buf) => self.copy_planar_typed(&buf),
-            AudioBufferRef::S32(buf) => self.copy_planar_typed(&buf),
-            AudioBufferRef::F32(buf) => self.copy_planar_typed(&buf),
-            AudioBufferRef::F64(buf) => self.copy_planar_typed(&buf),
+    #[test]
+    fn verify_normalize() {
+        use crate::conv::FromSample;
+
+        let spec = SignalSpec::new_with_layout(48_000, Layout::Stereo);
+
+        // A -12 dBFS 1 kHz sine in the left channel, and a quieter sine in the right channel.
+        let amplitude = 10f64.powf(-12.0 / 20.0);
+
+        fn sine(idx: usize) -> f64 {
+            (2.0 * std::f64::consts::PI * 1000.0 * idx as f64 / 48_000.0).sin()
         }
+
+        let mut buf = AudioBuffer::<i16>::new(4800, spec);
+
+        buf.fill(|planes, idx| {
+            planes.planes()[0][idx] = i16::from_sample(amplitude * sine(idx));
+            planes.planes()[1][idx] = i16::from_sample(0.5 * amplitude * sine(idx));
+            Ok(())
+        })
+        .unwrap();
+
+        buf.normalize(0.0);
+
+        // The left channel reaches full scale without wrapping, and the balance is preserved.
+        assert!(buf.peak(0) >= i16::MAX - 4);
+        assert!(buf.chan(0).iter().enumerate().all(|(idx, &s)| f64::from(s) * sine(idx) >= 0.0));
+        assert!((buf.rms(1) / buf.rms(0) - 0.5).abs() < 1e-3);
+
+        let mut buf = AudioBuffer::<f32>::new(4800, spec);
+
+        buf.fill(|planes, idx| {
+            planes.planes()[0][idx] = (amplitude * sine(idx)) as f32;
+            planes.planes()[1][idx] = 0.0;
+            Ok(())
+        })
+        .unwrap();
+
+        buf.normalize(0.0);
+
+        assert!((buf.peak(0) - 1.0).abs() < 1e-5);
+
+        // Normalizing silence is a no-op.
+        let mut buf = AudioBuffer::<f32>::new(4, spec);
+        buf.silence(None);
+        buf.normalize(0.0);
+
+        assert!(buf.chan(0).iter().chain(buf.chan(1)).all(|&s| s == 0.0));
     }
 
-    /// Copies all audio data from a source `AudioBuffer` that is of a different sample format type
-    /// than that of the `RawSampleBuffer` in planar channel order. The two buffers must be
-    /// equivalent.
-    pub fn copy_planar_typed<F>(&mut self, src: &AudioBuffer<F>)
-    where
-        F: Sample + IntoSample<S>,
-    {
-        let n_channels = src.spec.channels.count();
-        let n_samples = n_channels * src.n_frames;
+    #[test]
+    fn verify_truncate_and_trim() {
+        let spec = SignalSpec::new_with_layout(48_000, Layout::Stereo);
 
-        // Ensure that the capacity of the sample buffer is greater than or equal to the number
-        // of samples that will be copied from the source buffer.
-        assert!(self.capacity() >= n_samples);
+        let mut buf = AudioBuffer::<i32>::new(8, spec);
 
-        let dst_buf = &mut self.buf[..n_samples];
+        buf.fill(|planes, idx| {
+            planes.planes()[0][idx] = idx as i32;
+            planes.planes()[1][idx] = -(idx as i32);
+            Ok(())
+        })
+        .unwrap();
 
-        for (ch, dst_ch) in dst_buf.chunks_exact_mut(src.n_frames).enumerate() {
-            let src_ch = src.chan(ch);
+        buf.truncate(6);
 
-            for (&s, d) in src_ch.iter().zip(dst_ch) {
-                *d = s.into_sample().into_raw_sample();
-            }
-        }
+        assert_eq!(buf.frames(), 6);
+        assert_eq!(buf.capacity(), 8);
+        assert_eq!(buf.chan(0), &[0, 1, 2, 3, 4, 5]);
 
-        self.n_written = n_samples;
+        // Truncating to more frames than are written has no effect.
+        buf.truncate(7);
+
+        assert_eq!(buf.frames(), 6);
+
+        buf.trim(2, 3);
+
+        assert_eq!(buf.frames(), 3);
+        assert_eq!(buf.capacity(), 8);
+        assert_eq!(buf.chan(0), &[2, 3, 4]);
+        assert_eq!(buf.chan(1), &[-2, -3, -4]);
+
+        buf.trim(0, 0);
+
+        assert_eq!(buf.frames(), 0);
+        assert!(buf.chan(0).is_empty());
     }
 
-    /// Copies all audio data from the source `AudioBuffer` to the `RawSampleBuffer` in planar order.
-    /// The two buffers must be equivalent.
-    pub fn copy_planar(&mut self, src: &AudioBuffer<S>) {
-        let n_channels = src.spec.channels.count();
-        let n_samples = src.n_frames * n_channels;
+    #[test]
+    fn verify_render_multi_channel_ramp() {
+        // Render buffers with planes stored on both the stack and the heap.
+        for &n_channels in [6, AUDIO_PLANES_STORAGE_STACK_LIMIT + 2].iter() {
+            let spec = SignalSpec::new(48_000, Channels::discrete(n_channels));
 
-        // Ensure that the capacity of the sample buffer is greater than or equal to the number
-        // of samples that will be copied from the source buffer.
-        assert!(self.capacity() >= n_samples);
+            let mut buf = AudioBuffer::<i32>::new(64, spec);
 
-        let dst_buf = &mut self.buf[..n_samples];
+            buf.render(Some(40), |planes, idx| {
+                for (ch, plane) in planes.planes().iter_mut().enumerate() {
+                    plane[idx] = (1000 * ch + idx) as i32;
+                }
+                Ok(())
+            })
+            .unwrap();
+
+            // Stop rendering early on an error.
+            let result = buf.render(None, |planes, idx| {
+                if idx == 10 {
+                    return decode_error("render error");
+                }
 
-        for (ch, dst_ch) in dst_buf.chunks_exact_mut(src.n_frames).enumerate() {
-            let src_ch = src.chan(ch);
+                for (ch, plane) in planes.planes().iter_mut().enumerate() {
+                    plane[idx] = (1000 * ch + 40 + idx) as i32;
+                }
+                Ok(())
+            });
 
-            for (&s, d) in src_ch.iter().zip(dst_ch) {
-                *d = s.into_raw_sample();
+            assert!(result.is_err());
+            assert_eq!(buf.frames(), 50);
+
+            for ch in 0..n_channels {
+                let expected = (0..50).map(|i| (1000 * ch + i) as i32).collect::<Vec<i32>>();
+                assert_eq!(buf.chan(ch), &expected[..]);
             }
         }
-
-        self.n_written = n_samples;
     }
 
-    /// Copies all audio data from the source `AudioBufferRef` in interleaved channel order into the
-    /// `RawSampleBuffer`. The two buffers must be equivalent.
-    pub fn copy_interleaved_ref(&mut self, src: AudioBufferRef)
-    where
-        S: ConvertibleSample,
-    {
-        match src {
-            AudioBufferRef::U8(buf)  => self.copy_interleaved_typed(&buf),
-            AudioBufferRef::U16(buf) => self.copy_interleaved_typed(&buf),
-            AudioBufferRef::U24(buf) => self.copy_interleaved_typed(&buf),
-            AudioBufferRef::U32(buf) => self.copy_interleaved_typed(&buf),
-            AudioBufferRef::S8(buf)  => self.copy_interleaved_typed(&buf),
-            AudioBufferRef::S16(buf) => self.copy_interleaved_typed(&buf),
-            AudioBufferRef::S24(buf) => self.copy_interleaved_typed(&buf),
-            AudioBufferRef::S32(buf) => self.copy_interleaved_typed(&buf),
-            AudioBufferRef::F32(buf) => self.copy_interleaved_typed(&buf),
-            AudioBufferRef::F64(buf) => self.copy_interleaved_typed(&buf),
-        }
+    #[test]
+    fn verify_downmix_to_mono() {
+        // Stereo is averaged, and integer samples saturate.
+        let spec = SignalSpec::new_with_layout(44_100, Layout::Stereo);
+
+        let mut stereo = AudioBuffer::<i16>::new(8, spec);
+        stereo.copy_from_interleaved(&[
+            100i16, 300, -400, 0, i16::MAX, i16::MAX, i16::MIN, i16::MIN
+        ]);
+
+        let mono = stereo.downmix_to_mono().unwrap();
+
+        assert_eq!(mono.spec().channels, Channels::FRONT_LEFT);
+        assert_eq!(mono.spec().rate, 44_100);
+        assert_eq!(mono.capacity(), 8);
+        assert_eq!(mono.chan(0), &[200, -200, i16::MAX, i16::MIN]);
+
+        // For 5.1, the centre and surrounds are attenuated by 3 dB, and the LFE is dropped. Planes
+        // are FL, FR, FC, RL, RR, LFE1.
+        let spec = SignalSpec::new_with_layout(48_000, Layout::FivePointOne);
+
+        let mut surround = AudioBuffer::<f32>::new(1, spec);
+        surround.copy_from_interleaved(&[0.2f32, 0.4, 0.5, 0.3, 0.1, 1.0]);
+
+        let mono = surround.downmix_to_mono().unwrap();
+
+        let k = std::f32::consts::FRAC_1_SQRT_2;
+        let left = 0.2 + k * 0.5 + k * 0.3;
+        let right = 0.4 + k * 0.5 + k * 0.1;
+
+        assert_eq!(mono.frames(), 1);
+        assert!((mono.chan(0)[0] - 0.5 * (left + right)).abs() < 1e-6);
+
+        // Discrete channels have no standard downmix.
+        let discrete = AudioBuffer::<f32>::new(1, SignalSpec::new(48_000, Channels::discrete(2)));
+
+        assert!(discrete.downmix_to_mono().is_err());
+
+        // A buffer without any capacity is downmixed to an empty mono buffer.
+        let empty = AudioBuffer::<i16>::new(0, SignalSpec::new_with_layout(44_100, Layout::Stereo));
+
+        let mono = empty.downmix_to_mono().unwrap();
+
+        assert_eq!(mono.capacity(), 0);
+        assert_eq!(mono.frames(), 0);
     }
 
-    /// Copies all audio data from a source `AudioBuffer` that is of a different sample format type
-    /// than that of the `RawSampleBuffer` in interleaved channel order. The two buffers must be
-    /// equivalent.
-    pub fn copy_interleaved_typed<F>(&mut self, src: &AudioBuffer<F>)
-    where
-        F: Sample + IntoSample<S>,
-    {
-        let n_frames = src.n_frames;
-        let n_channels = src.spec.channels.count();
-        let n_samples = n_frames * n_channels;
+    #[test]
+    fn verify_upmix() {
+        // Mono is duplicated into the front left and right channels.
+        let mut mono = AudioBuffer::<i16>::new(4, SignalSpec::new_with_layout(8_000, Layout::Mono));
+        mono.copy_from_interleaved(&[1i16, -2, 3]);
 
-        // Ensure that the capacity of the sample buffer is greater than or equal to the number
-        // of samples that will be copied from the source buffer.
-        assert!(self.capacity() >= n_samples);
+        let stereo = mono.upmix(Layout::Stereo).unwrap();
 
-        // The destination buffer slice.
-        let dst_buf = &mut self.buf[..n_samples];
+        assert_eq!(stereo.spec(), &SignalSpec::new_with_layout(8_000, Layout::Stereo));
+        assert_eq!(stereo.capacity(), 4);
+        assert_eq!(stereo.chan(0), &[1, -2, 3]);
+        assert_eq!(stereo.chan(1), &[1, -2, 3]);
 
-        // Provide slightly optimized interleave algorithms for Mono and Stereo buffers.
-        match n_channels {
-            // No channels, do nothing.
-            0 => (),
-            // Mono
-            1=> {
-                for (&s, d) in src.chan(0).iter().zip(dst_buf) {
-                    *d = s.into_sample().into_raw_sample();
-                }
-            }
-            // Stereo
-            2 => {
-                let l_buf = src.chan(0);
-                let r_buf = src.chan(1);
+        // Stereo is copied into the front channels, and the remaining channels are silent. Planes
+        // are FL, FR, FC, RL, RR, LFE1.
+        let surround = stereo.upmix(Layout::FivePointOne).unwrap();
 
-                for ((&l, &r), d) in l_buf.iter().zip(r_buf).zip(dst_buf.chunks_exact_mut(2)) {
-                    d[0] = l.into_sample().into_raw_sample();
-                    d[1] = r.into_sample().into_raw_sample();
-                }
-            }
-            // 3+ channels
-            _ => {
-                for ch in 0..n_channels {
-                    let src_ch = src.chan(ch);
-                    let dst_ch_iter = dst_buf[ch..].iter_mut().step_by(n_channels);
+        assert_eq!(surround.spec().channels, Layout::FivePointOne.into_channels());
+        assert_eq!(surround.chan(0), &[1, -2, 3]);
+        assert_eq!(surround.chan(1), &[1, -2, 3]);
 
-                    for (&s, d) in src_ch.iter().zip(dst_ch_iter) {
-                        *d = s.into_sample().into_raw_sample();
-                    }
-                }
-            }
+        for ch in 2..6 {
+            assert_eq!(surround.chan(ch), &[0, 0, 0]);
         }
 
-        self.n_written = n_samples;
+        // Upmixing to fewer channels, or from discrete channels, is unsupported.
+        assert!(surround.upmix(Layout::Stereo).is_err());
+        assert!(stereo.upmix(Layout::Mono).is_err());
+
+        let discrete = AudioBuffer::<f32>::new(1, SignalSpec::new(48_000, Channels::discrete(1)));
+
+        assert!(discrete.upmix(Layout::Stereo).is_err());
     }
 
-    /// Copies all audio data from the source `AudioBuffer` to the `RawSampleBuffer` in interleaved
-    /// channel order. The two buffers must be equivalent.
-    pub fn copy_interleaved(&mut self, src: &AudioBuffer<S>) {
-        let n_frames = src.n_frames;
-        let n_channels = src.spec.channels.count();
-        let n_samples = n_frames * n_channels;
+    #[test]
+    fn verify_audio_buffer_eq() {
+        let spec = SignalSpec::new_with_layout(44_100, Layout::Stereo);
 
-        // Ensure that the capacity of the sample buffer is greater than or equal to the number
-        // of samples that will be copied from the source buffer.
-        assert!(self.capacity() >= n_samples);
+        let mut a = AudioBuffer::<i16>::new(4, spec);
+        let mut b = AudioBuffer::<i16>::new(16, spec);
 
-        // The destination buffer slice.
-        let dst_buf = &mut self.buf[..n_samples];
+        // Write different samples beyond the frames that will be compared.
+        a.copy_from_interleaved(&[1i16, 2, 3, 4, 5, 6]);
+        b.copy_from_interleaved(&[1i16, 2, 3, 4, 7, 8]);
 
-        // Provide slightly optimized interleave algorithms for Mono and Stereo buffers.
-        match n_channels {
-            // No channels, do nothing.
-            0 => (),
-            // Mono
-            1=> {
-                for (&s, d) in src.chan(0).iter().zip(dst_buf) {
-                    *d = s.into_raw_sample();
-                }
-            }
-            // Stereo
-            2 => {
-                let l_buf = src.chan(0);
-                let r_buf = src.chan(1);
+        a.truncate(2);
+        b.truncate(2);
 
-                for ((&l, &r), d) in l_buf.iter().zip(r_buf).zip(dst_buf.chunks_exact_mut(2)) {
-                    d[0] = l.into_raw_sample();
-                    d[1] = r.into_raw_sample();
-                }
+        assert_eq!(a, b);
+
+        // A single differing sample is unequal.
+        b.chan_mut(1)[1] = 5;
+        assert_ne!(a, b);
+
+        // So are differing frame counts and signal specs.
+        let mut c = AudioBuffer::<i16>::new(4, spec);
+        c.copy_from_interleaved(&[1i16, 2]);
+        assert_ne!(a, c);
+
+        let mut d = AudioBuffer::<i16>::new(4, SignalSpec::new_with_layout(48_000, Layout::Stereo));
+        d.copy_from_interleaved(&[1i16, 2, 3, 4]);
+        assert_ne!(a, d);
+    }
+
+    #[test]
+    fn verify_try_transform() {
+        let spec = SignalSpec::new_with_layout(44_100, Layout::Stereo);
+
+        let mut buf = AudioBuffer::<i32>::new(8, spec);
+        buf.render(None, |planes, idx| {
+            planes.planes()[0][idx] = idx as i32;
+            planes.planes()[1][idx] = 100 + idx as i32;
+            Ok(())
+        })
+        .unwrap();
+
+        // Fail on the 10th sample, which is the second sample of the right channel.
+        let mut count = 0;
+
+        let result = buf.try_transform(|s| {
+            count += 1;
+            if count == 10 {
+                return decode_error("invalid sample");
             }
-            // 3+ channels
-            _ => {
-                for ch in 0..n_channels {
-                    let src_ch = src.chan(ch);
-                    let dst_ch_iter = dst_buf[ch..].iter_mut().step_by(n_channels);
+            Ok(-s)
+        });
 
-                    for (&s, d) in src_ch.iter().zip(dst_ch_iter) {
-                        *d = s.into_raw_sample();
-                    }
-                }
+        assert!(result.is_err());
+        assert_eq!(count, 10);
+
+        assert_eq!(buf.chan(0), &[0, -1, -2, -3, -4, -5, -6, -7]);
+        assert_eq!(buf.chan(1), &[-100, 101, 102, 103, 104, 105, 106, 107]);
+
+        // An infallible transformation processes every sample.
+        assert!(buf.try_transform(|s| Ok(s.abs())).is_ok());
+
+        assert_eq!(buf.chan(0), &[0, 1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(buf.chan(1), &[100, 101, 102, 103, 104, 105, 106, 107]);
+
+        // A buffer without any capacity has no samples to transform.
+        let mut buf = AudioBuffer::<i32>::new(0, spec);
+
+        assert!(buf.try_transform(|_| decode_error("unexpected sample")).is_ok());
+    }
+
+    #[test]
+    fn verify_transform_leaves_capacity_untouched() {
+        let spec = SignalSpec::new(44_100, Channels::discrete(3));
+
+        let mut buf = AudioBuffer::<u8>::new(8, spec);
+        buf.render(Some(5), |planes, idx| {
+            for (ch, plane) in planes.planes().iter_mut().enumerate() {
+                plane[idx] = (10 * ch + idx) as u8;
             }
-        }
+            Ok(())
+        })
+        .unwrap();
 
-        self.n_written = n_samples;
+        buf.transform(|s| 2 * s);
+
+        for ch in 0..3 {
+            let expected = (0..5).map(|idx| (20 * ch + 2 * idx) as u8).collect::<Vec<_>>();
+            assert_eq!(buf.chan(ch), &expected[..]);
+
+            // The unused capacity of each plane remains silent.
+            let plane = &buf.buf[ch * 8..(ch + 1) * 8];
+            assert!(plane[5..].iter().all(|&s| s == u8::MID));
+        }
     }
-}
\ No newline at end of file
+}
@@ -12,11 +12,16 @@ use std::collections::HashMap;
 use std::default::Default;
 use std::fmt;
 
-use crate::audio::{AudioBufferRef, Channels, Layout};
+use crate::audio::{AsAudioBufferRef, AudioBuffer, AudioBufferRef, Channels, Layout, Signal, SignalSpec};
+use crate::audio::convert_with_dither;
+use crate::conv::IntoSample;
+use crate::conv::dither::DitherType;
+use crate::dsp::downmix::{Downmixer, downmix_channels};
 use crate::errors::{Result, unsupported_error};
 use crate::formats::Packet;
-use crate::sample::SampleFormat;
-use crate::units::TimeBase;
+use crate::sample::{Sample, SampleFormat, i24, u24};
+use crate::units::{Duration, TimeBase};
+use crate::verify::{Md5Verifier, Verifier};
 
 /// A `CodecType` is a unique identifier used to identify a specific codec. `CodecType` is mainly
 /// used for matching a format's stream to a specific `Decoder`. Decoders advertisting support for a
@@ -146,6 +151,12 @@ impl fmt::Display for CodecType {
     }
 }
 
+impl fmt::Debug for CodecType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CodecType({:#06x})", self.0)
+    }
+}
+
 /// Codec parameters stored in a container format's headers and metadata may be passed to a codec
 /// using the `CodecParameters` structure.
 #[derive(Clone)]
@@ -171,6 +182,10 @@ pub struct CodecParameters {
     /// The number of bits per one encoded audio sample.
     pub bits_per_coded_sample: Option<u32>,
 
+    /// The bit rate of the encoded stream in bits per second. For a variable bit rate stream, this
+    /// is the average bit rate.
+    pub bit_rate: Option<u32>,
+
     /// A bitmask of all channels in the stream.
     pub channels: Option<Channels>,
 
@@ -193,6 +208,9 @@ pub struct CodecParameters {
 
     /// Extra data (defined by the codec).
     pub extra_data: Option<Box<[u8]>>,
+
+    /// The MD5 checksum of the decoded audio, if provided by the container or codec.
+    pub md5: Option<[u8; 16]>,
 }
 
 impl CodecParameters {
@@ -205,6 +223,7 @@ impl CodecParameters {
             sample_format: None,
             bits_per_sample: None,
             bits_per_coded_sample: None,
+            bit_rate: None,
             channels: None,
             channel_layout: None,
             leading_padding: None,
@@ -212,6 +231,7 @@ impl CodecParameters {
             max_frames_per_packet: None,
             packet_data_integrity: false,
             extra_data: None,
+            md5: None,
         }
     }
 
@@ -257,6 +277,12 @@ impl CodecParameters {
         self
     }
 
+    /// Provide the bit rate of the encoded stream in bits per second.
+    pub fn with_bit_rate(&mut self, bit_rate: u32) -> &mut Self {
+        self.bit_rate = Some(bit_rate);
+        self
+    }
+
     /// Provide the channel map.
     pub fn with_channels(&mut self, channels: Channels) -> &mut Self {
         self.channels = Some(channels);
@@ -299,18 +325,41 @@ impl CodecParameters {
         self
     }
 
+    /// Provide the MD5 checksum of the decoded audio.
+    pub fn with_md5(&mut self, md5: [u8; 16]) -> &mut Self {
+        self.md5 = Some(md5);
+        self
+    }
+
 }
 
 /// `DecoderOptions` is a common set of options that all decoders use.
 pub struct DecoderOptions {
     /// The decoded audio should be verified if possible during the decode process.
     pub verify: bool,
+    /// The maximum number of channels the decoded audio may have. If the decoded audio has more
+    /// channels, it will be downmixed to the nearest layout within the maximum using the standard
+    /// downmix. If `None`, the decoded audio is never downmixed.
+    pub max_channels: Option<usize>,
+    /// The sample format the decoded audio should be converted to. If `None`, the decoded audio is
+    /// returned in the native sample format of the decoder.
+    pub output_format: Option<SampleFormat>,
+    /// The dither applied when converting the decoded audio to an output sample format with fewer
+    /// effective bits.
+    pub dither: DitherType,
+    /// The leading and trailing padding frames inserted by the encoder, if known, should be
+    /// trimmed from the decoded audio for gapless playback.
+    pub gapless: bool,
 }
 
 impl Default for DecoderOptions {
     fn default() -> Self {
         DecoderOptions {
             verify: false,
+            max_channels: None,
+            output_format: None,
+            dither: DitherType::Identity,
+            gapless: false,
         }
     }
 }
@@ -336,6 +385,60 @@ pub trait Decoder: Send {
     /// of the decoded audio.
     fn decode(&mut self, packet: &Packet) -> Result<AudioBufferRef>;
 
+    /// Decodes a `Packet` of audio data into a caller-provided audio buffer, replacing any existing
+    /// frames in the buffer. The decoded audio is converted to `f32` samples.
+    ///
+    /// The signal specification of the buffer must match that of the decoded audio, and the buffer
+    /// should have a capacity of at least `max_frames_per_packet` frames. Since the buffer is never
+    /// reallocated, a single buffer may be reused to decode an entire stream.
+    fn decode_into(&mut self, packet: &Packet, out: &mut AudioBuffer<f32>) -> Result<()> {
+        let decoded = self.decode(packet)?;
+
+        if decoded.spec() != out.spec() {
+            return unsupported_error("output buffer signal spec does not match the decoded audio");
+        }
+
+        if decoded.frames() > out.capacity() {
+            return unsupported_error("output buffer capacity is too small for the decoded audio");
+        }
+
+        match decoded {
+            AudioBufferRef::U8(buf)  => copy_into(&buf, out),
+            AudioBufferRef::U16(buf) => copy_into(&buf, out),
+            AudioBufferRef::U24(buf) => copy_into(&buf, out),
+            AudioBufferRef::U32(buf) => copy_into(&buf, out),
+            AudioBufferRef::S8(buf)  => copy_into(&buf, out),
+            AudioBufferRef::S16(buf) => copy_into(&buf, out),
+            AudioBufferRef::S24(buf) => copy_into(&buf, out),
+            AudioBufferRef::S32(buf) => copy_into(&buf, out),
+            AudioBufferRef::F32(buf) => copy_into(&buf, out),
+            AudioBufferRef::F64(buf) => copy_into(&buf, out),
+        }
+
+        Ok(())
+    }
+
+    /// Gets the maximum number of frames a single decoded packet may contain, if known. By default,
+    /// this is the maximum frames per packet of the codec parameters.
+    fn max_frames_per_packet(&self) -> Option<u64> {
+        self.codec_params().max_frames_per_packet
+    }
+
+    /// Resets the decoder, discarding all state carried over from previously decoded packets (e.g.,
+    /// overlap buffers or bit reservoirs) without reallocating any buffers. A decoder must be reset
+    /// after a seek since the next packet will not follow the last decoded packet.
+    ///
+    /// Note: For codecs with inter-frame dependencies, the first buffer decoded after a reset lacks
+    /// the history of preceding packets, and may therefore be shorter, or contain less audio, than
+    /// usual.
+    fn reset(&mut self);
+
+    /// Finalizes the decoder after the last packet of the stream was decoded. If the decoded audio
+    /// was verified, returns whether it matched the stream's checksum, otherwise returns `None`.
+    fn finalize(&mut self) -> Result<Option<bool>> {
+        Ok(None)
+    }
+
     /// Closes a decoder.
     fn close(&mut self);
 }
@@ -375,6 +478,18 @@ impl CodecRegistry {
         self.codecs.get(&codec)
     }
 
+    /// Returns `true` if a `Decoder` is registered for the codec.
+    pub fn supports(&self, codec: CodecType) -> bool {
+        self.codecs.contains_key(&codec)
+    }
+
+    /// Gets the `CodecDescriptor`s of all registered codecs ordered by their short name.
+    pub fn list(&self) -> Vec<&CodecDescriptor> {
+        let mut descriptors = self.codecs.values().collect::<Vec<_>>();
+        descriptors.sort_by_key(|descriptor| descriptor.short_name);
+        descriptors
+    }
+
     /// Registers all codecs supported by `Decoder`. If a supported codec was previously registered
     /// by another `Decoder` it will be replaced within the registry.
     pub fn register_all<D: Decoder>(&mut self) {
@@ -397,7 +512,27 @@ impl CodecRegistry {
         -> Result<Box<dyn Decoder>> {
 
         if let Some(descriptor) = self.codecs.get(&params.codec) {
-            Ok((descriptor.inst_func)(params, options)?)
+            let mut decoder = (descriptor.inst_func)(params, options)?;
+
+            // The decoded audio must be verified before it is downmixed.
+            if options.verify {
+                decoder = VerifyDecoder::wrap(decoder)?;
+            }
+
+            if options.gapless {
+                decoder = wrap_gapless_decoder(decoder);
+            }
+
+            if let Some(max_channels) = options.max_channels {
+                decoder = DownmixDecoder::wrap(decoder, max_channels)?;
+            }
+
+            // The decoded audio is converted to the output sample format last since a downmix
+            // changes the sample format.
+            match options.output_format {
+                Some(format) => Ok(wrap_convert_decoder(decoder, format, options.dither)),
+                None => Ok(decoder),
+            }
         }
         else {
             unsupported_error("unsupported codec")
@@ -405,6 +540,502 @@ impl CodecRegistry {
     }
 }
 
+/// A `DownmixDecoder` wraps a `Decoder` and downmixes any decoded audio with more than the maximum
+/// number of channels. Downmixed audio is always returned as 32-bit floating point samples.
+struct DownmixDecoder {
+    inner: Box<dyn Decoder>,
+    params: CodecParameters,
+    max_channels: usize,
+    downmixer: Option<Downmixer>,
+    conv_buf: AudioBuffer<f32>,
+    out_buf: AudioBuffer<f32>,
+}
+
+impl DownmixDecoder {
+    fn wrap(inner: Box<dyn Decoder>, max_channels: usize) -> Result<Box<dyn Decoder>> {
+        let mut params = inner.codec_params().clone();
+
+        // If the channels are known up-front, fail early if they cannot be downmixed, and
+        // advertise the downmixed channels instead.
+        if let Some(channels) = params.channels {
+            match downmix_channels(channels, max_channels) {
+                Some(out_channels) if out_channels == channels => return Ok(inner),
+                Some(out_channels) => {
+                    params.channels = Some(out_channels);
+                    params.channel_layout = None;
+                }
+                None => return unsupported_error("no standard downmix exists for the channels"),
+            }
+        }
+
+        Ok(Box::new(DownmixDecoder {
+            inner,
+            params,
+            max_channels,
+            downmixer: None,
+            conv_buf: AudioBuffer::unused(),
+            out_buf: AudioBuffer::unused(),
+        }))
+    }
+}
+
+/// Converts all written frames of the source buffer into the destination buffer, replacing any
+/// existing frames. The destination buffer must have enough capacity for all frames.
+fn copy_into<S>(src: &AudioBuffer<S>, dest: &mut AudioBuffer<f32>)
+where
+    S: Sample + IntoSample<f32>
+{
+    dest.clear();
+    dest.render_reserved(Some(src.frames()));
+
+    for c in 0..src.spec().channels.count() {
+        for (d, &s) in dest.chan_mut(c).iter_mut().zip(src.chan(c)) {
+            *d = s.into_sample();
+        }
+    }
+}
+
+/// Converts the source buffer into the destination buffer, reallocating the destination buffer if
+/// the signal specification or capacity differ.
+fn convert_into<S>(src: &AudioBuffer<S>, dest: &mut AudioBuffer<f32>)
+where
+    S: Sample + IntoSample<f32>
+{
+    if dest.spec() != src.spec() || dest.capacity() != src.capacity() {
+        *dest = src.make_equivalent();
+    }
+
+    dest.clear();
+    dest.render_reserved(Some(src.frames()));
+
+    src.convert(dest);
+}
+
+impl Decoder for DownmixDecoder {
+    fn try_new(_: &CodecParameters, _: &DecoderOptions) -> Result<Self> {
+        unsupported_error("a downmix decoder must wrap another decoder")
+    }
+
+    fn supported_codecs() -> &'static [CodecDescriptor] {
+        &[]
+    }
+
+    fn codec_params(&self) -> &CodecParameters {
+        &self.params
+    }
+
+    fn decode(&mut self, packet: &Packet) -> Result<AudioBufferRef<'_>> {
+        let decoded = self.inner.decode(packet)?;
+
+        let channels = decoded.spec().channels;
+
+        if channels.count() <= self.max_channels {
+            return Ok(decoded);
+        }
+
+        // Instantiate a new downmixer if the channels of the decoded audio changed.
+        let is_stale = match &self.downmixer {
+            Some(downmixer) => downmixer.in_channels() != channels,
+            None => true,
+        };
+
+        if is_stale {
+            self.downmixer = Some(Downmixer::try_new(channels, self.max_channels)?);
+        }
+
+        match decoded {
+            AudioBufferRef::U8(buf)  => convert_into(&buf, &mut self.conv_buf),
+            AudioBufferRef::U16(buf) => convert_into(&buf, &mut self.conv_buf),
+            AudioBufferRef::U24(buf) => convert_into(&buf, &mut self.conv_buf),
+            AudioBufferRef::U32(buf) => convert_into(&buf, &mut self.conv_buf),
+            AudioBufferRef::S8(buf)  => convert_into(&buf, &mut self.conv_buf),
+            AudioBufferRef::S16(buf) => convert_into(&buf, &mut self.conv_buf),
+            AudioBufferRef::S24(buf) => convert_into(&buf, &mut self.conv_buf),
+            AudioBufferRef::S32(buf) => convert_into(&buf, &mut self.conv_buf),
+            AudioBufferRef::F32(buf) => convert_into(&buf, &mut self.conv_buf),
+            AudioBufferRef::F64(buf) => convert_into(&buf, &mut self.conv_buf),
+        }
+
+        let downmixer = self.downmixer.as_ref().unwrap();
+
+        let out_spec = SignalSpec::new(self.conv_buf.spec().rate, downmixer.out_channels());
+
+        if *self.out_buf.spec() != out_spec || self.out_buf.capacity() < self.conv_buf.frames() {
+            self.out_buf = AudioBuffer::new(self.conv_buf.capacity() as Duration, out_spec);
+        }
+
+        downmixer.process(&self.conv_buf, &mut self.out_buf);
+
+        Ok(self.out_buf.as_audio_buffer_ref())
+    }
+
+    fn max_frames_per_packet(&self) -> Option<u64> {
+        self.inner.max_frames_per_packet()
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset()
+    }
+
+    fn finalize(&mut self) -> Result<Option<bool>> {
+        self.inner.finalize()
+    }
+
+    fn close(&mut self) {
+        self.inner.close()
+    }
+}
+
+/// A `VerifyDecoder` wraps a `Decoder` and verifies all decoded audio against the checksum provided
+/// in the codec parameters.
+struct VerifyDecoder {
+    inner: Box<dyn Decoder>,
+    verifier: Option<Md5Verifier>,
+}
+
+impl VerifyDecoder {
+    fn wrap(inner: Box<dyn Decoder>) -> Result<Box<dyn Decoder>> {
+        let params = inner.codec_params();
+
+        // Audio can only be verified if a checksum, and the bit width it was calculated over, are
+        // known.
+        let verifier = match (params.md5, params.bits_per_sample) {
+            (Some(md5), Some(bits_per_sample)) => Md5Verifier::try_new(md5, bits_per_sample)?,
+            _ => return Ok(inner),
+        };
+
+        Ok(Box::new(VerifyDecoder { inner, verifier: Some(verifier) }))
+    }
+}
+
+impl Decoder for VerifyDecoder {
+    fn try_new(_: &CodecParameters, _: &DecoderOptions) -> Result<Self> {
+        unsupported_error("a verify decoder must wrap another decoder")
+    }
+
+    fn supported_codecs() -> &'static [CodecDescriptor] {
+        &[]
+    }
+
+    fn codec_params(&self) -> &CodecParameters {
+        self.inner.codec_params()
+    }
+
+    fn decode(&mut self, packet: &Packet) -> Result<AudioBufferRef<'_>> {
+        let decoded = self.inner.decode(packet)?;
+
+        if let Some(verifier) = self.verifier.as_mut() {
+            verifier.update(&decoded);
+        }
+
+        Ok(decoded)
+    }
+
+    fn max_frames_per_packet(&self) -> Option<u64> {
+        self.inner.max_frames_per_packet()
+    }
+
+    fn reset(&mut self) {
+        // The checksum is calculated over the entire stream, therefore once the decoder is reset,
+        // such as after a seek, the decoded audio can no longer be verified.
+        self.verifier = None;
+        self.inner.reset()
+    }
+
+    fn finalize(&mut self) -> Result<Option<bool>> {
+        match self.verifier.take() {
+            Some(verifier) => Ok(Some(verifier.finalize()?)),
+            None => self.inner.finalize(),
+        }
+    }
+
+    fn close(&mut self) {
+        self.inner.close()
+    }
+}
+
+/// Gets the sample format of a decoded audio buffer.
+fn sample_format(buf: &AudioBufferRef<'_>) -> SampleFormat {
+    match buf {
+        AudioBufferRef::U8(_)  => SampleFormat::U8,
+        AudioBufferRef::U16(_) => SampleFormat::U16,
+        AudioBufferRef::U24(_) => SampleFormat::U24,
+        AudioBufferRef::U32(_) => SampleFormat::U32,
+        AudioBufferRef::S8(_)  => SampleFormat::S8,
+        AudioBufferRef::S16(_) => SampleFormat::S16,
+        AudioBufferRef::S24(_) => SampleFormat::S24,
+        AudioBufferRef::S32(_) => SampleFormat::S32,
+        AudioBufferRef::F32(_) => SampleFormat::F32,
+        AudioBufferRef::F64(_) => SampleFormat::F64,
+    }
+}
+
+/// A sample format decoded audio may be converted to by a `ConvertDecoder`.
+trait OutputSample: Sample + Send + 'static {
+    /// Converts a decoded audio buffer of any sample format into the destination buffer.
+    fn convert_from(
+        src: &AudioBufferRef,
+        dest: &mut AudioBuffer<Self>,
+        dither: DitherType,
+        seed: u64,
+    );
+
+    /// Gets a decoded audio buffer reference to the buffer.
+    fn as_ref(buf: &AudioBuffer<Self>) -> AudioBufferRef<'_>;
+}
+
+macro_rules! impl_output_sample {
+    ($sample_type:ty) => {
+        impl OutputSample for $sample_type {
+            fn convert_from(
+                src: &AudioBufferRef,
+                dest: &mut AudioBuffer<Self>,
+                dither: DitherType,
+                seed: u64,
+            ) {
+                match src {
+                    AudioBufferRef::U8(buf)  => convert_with_dither(buf, dest, dither, seed),
+                    AudioBufferRef::U16(buf) => convert_with_dither(buf, dest, dither, seed),
+                    AudioBufferRef::U24(buf) => convert_with_dither(buf, dest, dither, seed),
+                    AudioBufferRef::U32(buf) => convert_with_dither(buf, dest, dither, seed),
+                    AudioBufferRef::S8(buf)  => convert_with_dither(buf, dest, dither, seed),
+                    AudioBufferRef::S16(buf) => convert_with_dither(buf, dest, dither, seed),
+                    AudioBufferRef::S24(buf) => convert_with_dither(buf, dest, dither, seed),
+                    AudioBufferRef::S32(buf) => convert_with_dither(buf, dest, dither, seed),
+                    AudioBufferRef::F32(buf) => convert_with_dither(buf, dest, dither, seed),
+                    AudioBufferRef::F64(buf) => convert_with_dither(buf, dest, dither, seed),
+                }
+            }
+
+            fn as_ref(buf: &AudioBuffer<Self>) -> AudioBufferRef<'_> {
+                buf.as_audio_buffer_ref()
+            }
+        }
+    };
+}
+
+impl_output_sample!(u8);
+impl_output_sample!(u16);
+impl_output_sample!(u24);
+impl_output_sample!(u32);
+impl_output_sample!(i8);
+impl_output_sample!(i16);
+impl_output_sample!(i24);
+impl_output_sample!(i32);
+impl_output_sample!(f32);
+impl_output_sample!(f64);
+
+/// A `ConvertDecoder` wraps a `Decoder` and converts all decoded audio to a fixed sample format.
+struct ConvertDecoder<T: OutputSample> {
+    inner: Box<dyn Decoder>,
+    params: CodecParameters,
+    format: SampleFormat,
+    dither: DitherType,
+    /// The number of buffers converted, used to seed the dither of each buffer differently.
+    n_converted: u64,
+    buf: AudioBuffer<T>,
+}
+
+impl<T: OutputSample> ConvertDecoder<T> {
+    fn new(inner: Box<dyn Decoder>, format: SampleFormat, dither: DitherType) -> Self {
+        let mut params = inner.codec_params().clone();
+
+        // Advertise the output sample format instead of the native sample format.
+        params.with_sample_format(format);
+
+        ConvertDecoder { inner, params, format, dither, n_converted: 0, buf: AudioBuffer::unused() }
+    }
+}
+
+/// Wraps a `Decoder` with a `ConvertDecoder` for the given output sample format.
+fn wrap_convert_decoder(
+    inner: Box<dyn Decoder>,
+    format: SampleFormat,
+    dither: DitherType,
+) -> Box<dyn Decoder> {
+    match format {
+        SampleFormat::U8  => Box::new(ConvertDecoder::<u8>::new(inner, format, dither)),
+        SampleFormat::U16 => Box::new(ConvertDecoder::<u16>::new(inner, format, dither)),
+        SampleFormat::U24 => Box::new(ConvertDecoder::<u24>::new(inner, format, dither)),
+        SampleFormat::U32 => Box::new(ConvertDecoder::<u32>::new(inner, format, dither)),
+        SampleFormat::S8  => Box::new(ConvertDecoder::<i8>::new(inner, format, dither)),
+        SampleFormat::S16 => Box::new(ConvertDecoder::<i16>::new(inner, format, dither)),
+        SampleFormat::S24 => Box::new(ConvertDecoder::<i24>::new(inner, format, dither)),
+        SampleFormat::S32 => Box::new(ConvertDecoder::<i32>::new(inner, format, dither)),
+        SampleFormat::F32 => Box::new(ConvertDecoder::<f32>::new(inner, format, dither)),
+        SampleFormat::F64 => Box::new(ConvertDecoder::<f64>::new(inner, format, dither)),
+    }
+}
+
+impl<T: OutputSample> Decoder for ConvertDecoder<T> {
+    fn try_new(_: &CodecParameters, _: &DecoderOptions) -> Result<Self> {
+        unsupported_error("a convert decoder must wrap another decoder")
+    }
+
+    fn supported_codecs() -> &'static [CodecDescriptor] {
+        &[]
+    }
+
+    fn codec_params(&self) -> &CodecParameters {
+        &self.params
+    }
+
+    fn decode(&mut self, packet: &Packet) -> Result<AudioBufferRef<'_>> {
+        let decoded = self.inner.decode(packet)?;
+
+        // Audio already in the output sample format does not need to be converted.
+        if sample_format(&decoded) == self.format {
+            return Ok(decoded);
+        }
+
+        T::convert_from(&decoded, &mut self.buf, self.dither, self.n_converted);
+
+        self.n_converted += 1;
+
+        Ok(T::as_ref(&self.buf))
+    }
+
+    fn max_frames_per_packet(&self) -> Option<u64> {
+        self.inner.max_frames_per_packet()
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset()
+    }
+
+    fn finalize(&mut self) -> Result<Option<bool>> {
+        self.inner.finalize()
+    }
+
+    fn close(&mut self) {
+        self.inner.close()
+    }
+}
+
+/// A `GaplessDecoder` wraps a `Decoder` and trims the leading and trailing padding frames, as
+/// provided in the codec parameters, from the decoded audio.
+///
+/// The position of each packet in the stream is determined from its timestamp. Trimmed audio is
+/// returned in the sample format of the codec parameters, or as 32-bit floating point samples if
+/// the sample format is unknown. All other decoded audio is converted to the same sample format.
+struct GaplessDecoder<T: OutputSample> {
+    inner: Box<dyn Decoder>,
+    params: CodecParameters,
+    format: SampleFormat,
+    /// The number of leading frames to trim.
+    delay: u64,
+    /// The frame position after which all frames are trimmed, if known.
+    end: Option<u64>,
+    buf: AudioBuffer<T>,
+}
+
+impl<T: OutputSample> GaplessDecoder<T> {
+    fn new(inner: Box<dyn Decoder>, format: SampleFormat) -> Self {
+        let mut params = inner.codec_params().clone();
+
+        let delay = u64::from(params.leading_padding.unwrap_or(0));
+
+        // The trailing padding may only be trimmed if the total number of frames is known.
+        let end = params.n_frames.map(|n_frames| {
+            n_frames.saturating_sub(u64::from(params.trailing_padding.unwrap_or(0)))
+        });
+
+        params.with_sample_format(format);
+
+        GaplessDecoder { inner, params, format, delay, end, buf: AudioBuffer::unused() }
+    }
+
+    /// Converts a timestamp into a frame position.
+    fn ts_to_frame(&self, ts: u64) -> u64 {
+        match (self.params.time_base, self.params.sample_rate) {
+            (Some(tb), Some(rate)) => {
+                let frame = u128::from(ts) * u128::from(tb.numer) * u128::from(rate)
+                    / u128::from(tb.denom);
+                frame as u64
+            }
+            _ => ts,
+        }
+    }
+}
+
+/// Wraps a `Decoder` with a `GaplessDecoder` if there is padding to trim.
+fn wrap_gapless_decoder(inner: Box<dyn Decoder>) -> Box<dyn Decoder> {
+    let params = inner.codec_params();
+
+    if params.leading_padding.is_none() && params.trailing_padding.is_none() {
+        return inner;
+    }
+
+    match params.sample_format.unwrap_or(SampleFormat::F32) {
+        SampleFormat::U8  => Box::new(GaplessDecoder::<u8>::new(inner, SampleFormat::U8)),
+        SampleFormat::U16 => Box::new(GaplessDecoder::<u16>::new(inner, SampleFormat::U16)),
+        SampleFormat::U24 => Box::new(GaplessDecoder::<u24>::new(inner, SampleFormat::U24)),
+        SampleFormat::U32 => Box::new(GaplessDecoder::<u32>::new(inner, SampleFormat::U32)),
+        SampleFormat::S8  => Box::new(GaplessDecoder::<i8>::new(inner, SampleFormat::S8)),
+        SampleFormat::S16 => Box::new(GaplessDecoder::<i16>::new(inner, SampleFormat::S16)),
+        SampleFormat::S24 => Box::new(GaplessDecoder::<i24>::new(inner, SampleFormat::S24)),
+        SampleFormat::S32 => Box::new(GaplessDecoder::<i32>::new(inner, SampleFormat::S32)),
+        SampleFormat::F32 => Box::new(GaplessDecoder::<f32>::new(inner, SampleFormat::F32)),
+        SampleFormat::F64 => Box::new(GaplessDecoder::<f64>::new(inner, SampleFormat::F64)),
+    }
+}
+
+impl<T: OutputSample> Decoder for GaplessDecoder<T> {
+    fn try_new(_: &CodecParameters, _: &DecoderOptions) -> Result<Self> {
+        unsupported_error("a gapless decoder must wrap another decoder")
+    }
+
+    fn supported_codecs() -> &'static [CodecDescriptor] {
+        &[]
+    }
+
+    fn codec_params(&self) -> &CodecParameters {
+        &self.params
+    }
+
+    fn decode(&mut self, packet: &Packet) -> Result<AudioBufferRef<'_>> {
+        let start = self.ts_to_frame(packet.pts());
+
+        let decoded = self.inner.decode(packet)?;
+
+        let n_frames = decoded.frames() as u64;
+
+        // The range of decoded frames to keep, relative to the start of the decoded audio.
+        let keep_end = match self.end {
+            Some(end) => end.saturating_sub(start).min(n_frames),
+            None => n_frames,
+        };
+
+        let keep_start = self.delay.saturating_sub(start).min(keep_end);
+
+        if keep_start == 0 && keep_end == n_frames && sample_format(&decoded) == self.format {
+            return Ok(decoded);
+        }
+
+        T::convert_from(&decoded, &mut self.buf, DitherType::Identity, 0);
+
+        self.buf.trim(keep_start as usize, (keep_end - keep_start) as usize);
+
+        Ok(T::as_ref(&self.buf))
+    }
+
+    fn max_frames_per_packet(&self) -> Option<u64> {
+        self.inner.max_frames_per_packet()
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset()
+    }
+
+    fn finalize(&mut self) -> Result<Option<bool>> {
+        self.inner.finalize()
+    }
+
+    fn close(&mut self) {
+        self.inner.close()
+    }
+}
+
 /// Convenience macro for declaring a `CodecDescriptor`.
 #[macro_export]
 macro_rules! support_codec {
@@ -418,4 +1049,212 @@ macro_rules! support_codec {
             }
         }
     };
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::Error;
+
+    /// A decoder that decodes every packet to a buffer of silence with a 5.1 channel layout.
+    struct SurroundDecoder {
+        params: CodecParameters,
+        buf: AudioBuffer<i16>,
+    }
+
+    impl Decoder for SurroundDecoder {
+        fn try_new(params: &CodecParameters, _: &DecoderOptions) -> Result<Self> {
+            let spec = SignalSpec::new_with_layout(48_000, Layout::FivePointOne);
+
+            Ok(SurroundDecoder { params: params.clone(), buf: AudioBuffer::new(256, spec) })
+        }
+
+        fn supported_codecs() -> &'static [CodecDescriptor] {
+            &[support_codec!(CODEC_TYPE_PCM_S16LE, "surround", "5.1 Surround Silence")]
+        }
+
+        fn codec_params(&self) -> &CodecParameters {
+            &self.params
+        }
+
+        fn decode(&mut self, _: &Packet) -> Result<AudioBufferRef<'_>> {
+            self.buf.clear();
+            self.buf.render_reserved(Some(256));
+            Ok(self.buf.as_audio_buffer_ref())
+        }
+
+        fn reset(&mut self) {}
+
+        fn close(&mut self) {}
+    }
+
+    #[test]
+    fn verify_codec_lookup() {
+        let mut registry = CodecRegistry::new();
+        registry.register_all::<SurroundDecoder>();
+
+        let descriptor = registry.get_codec(CODEC_TYPE_PCM_S16LE).unwrap();
+
+        assert_eq!(descriptor.short_name, "surround");
+        assert_eq!(descriptor.long_name, "5.1 Surround Silence");
+        assert!(registry.supports(CODEC_TYPE_PCM_S16LE));
+
+        assert!(registry.get_codec(CODEC_TYPE_FLAC).is_none());
+        assert!(!registry.supports(CODEC_TYPE_FLAC));
+
+        let list = registry.list();
+
+        assert_eq!(list.len(), 1);
+        assert!(list[0].codec == CODEC_TYPE_PCM_S16LE);
+    }
+
+    #[test]
+    fn verify_max_channels_downmixes_to_stereo() {
+        let mut registry = CodecRegistry::new();
+        registry.register_all::<SurroundDecoder>();
+
+        let mut params = CodecParameters::new();
+        params.for_codec(CODEC_TYPE_PCM_S16LE);
+
+        let options = DecoderOptions { max_channels: Some(2), ..Default::default() };
+
+        let mut decoder = registry.make(&params, &options).unwrap();
+
+        let packet = Packet::new_from_slice(0, 0, 0, &[]);
+        let decoded = decoder.decode(&packet).unwrap();
+
+        assert_eq!(decoded.spec().channels, Channels::FRONT_LEFT | Channels::FRONT_RIGHT);
+        assert_eq!(decoded.frames(), 256);
+    }
+
+    #[test]
+    fn verify_max_channels_rejects_discrete_channels() {
+        let mut registry = CodecRegistry::new();
+        registry.register_all::<SurroundDecoder>();
+
+        let mut params = CodecParameters::new();
+        params.for_codec(CODEC_TYPE_PCM_S16LE).with_channels(Channels::discrete(6));
+
+        let options = DecoderOptions { max_channels: Some(2), ..Default::default() };
+
+        match registry.make(&params, &options) {
+            Err(Error::Unsupported(_)) => (),
+            _ => panic!("expected an unsupported error"),
+        }
+    }
+
+    #[test]
+    fn verify_decoded_audio_is_verified_before_downmixing() {
+        let mut registry = CodecRegistry::new();
+        registry.register_all::<SurroundDecoder>();
+
+        // The MD5 checksum of one packet of 16-bit 5.1 silence.
+        let md5 = [
+            0xd2, 0xa7, 0x05, 0x50, 0x48, 0x9d, 0xe3, 0x56,
+            0xa2, 0xcd, 0x6b, 0xfc, 0x40, 0x71, 0x12, 0x04,
+        ];
+
+        let mut params = CodecParameters::new();
+        params.for_codec(CODEC_TYPE_PCM_S16LE).with_bits_per_sample(16).with_md5(md5);
+
+        let options = DecoderOptions { verify: true, max_channels: Some(2), ..Default::default() };
+
+        let packet = Packet::new_from_slice(0, 0, 0, &[]);
+
+        let mut decoder = registry.make(&params, &options).unwrap();
+        decoder.decode(&packet).unwrap();
+
+        assert_eq!(decoder.finalize().unwrap(), Some(true));
+
+        // Decoding an extra packet fails verification.
+        let mut decoder = registry.make(&params, &options).unwrap();
+        decoder.decode(&packet).unwrap();
+        decoder.decode(&packet).unwrap();
+
+        assert_eq!(decoder.finalize().unwrap(), Some(false));
+
+        // Without verification, there is no result.
+        let options = DecoderOptions { verify: false, ..Default::default() };
+
+        let mut decoder = registry.make(&params, &options).unwrap();
+        decoder.decode(&packet).unwrap();
+
+        assert_eq!(decoder.finalize().unwrap(), None);
+    }
+
+    #[test]
+    fn verify_decode_into_reuses_buffer() {
+        let mut registry = CodecRegistry::new();
+        registry.register_all::<SurroundDecoder>();
+
+        let mut params = CodecParameters::new();
+        params.for_codec(CODEC_TYPE_PCM_S16LE).with_max_frames_per_packet(256);
+
+        let mut decoder = registry.make(&params, &Default::default()).unwrap();
+
+        assert_eq!(decoder.max_frames_per_packet(), Some(256));
+
+        let spec = SignalSpec::new_with_layout(48_000, Layout::FivePointOne);
+        let mut out = AudioBuffer::<f32>::new(decoder.max_frames_per_packet().unwrap(), spec);
+
+        // Fill the buffer with non-silence to ensure the decoded audio replaces it.
+        out.render_reserved(None);
+        out.chan_mut(0).iter_mut().for_each(|s| *s = 1.0);
+
+        let samples = out.chan(0).as_ptr();
+
+        let packet = Packet::new_from_slice(0, 0, 0, &[]);
+
+        // The same buffer is reused for every packet.
+        for _ in 0..8 {
+            decoder.decode_into(&packet, &mut out).unwrap();
+
+            assert_eq!(out.frames(), 256);
+            assert_eq!(out.chan(0).as_ptr(), samples);
+            assert!(out.chan(0).iter().all(|&s| s == 0.0));
+        }
+
+        // A buffer that is too small, or has the wrong channels, is rejected.
+        let mut small = AudioBuffer::<f32>::new(128, spec);
+
+        assert!(decoder.decode_into(&packet, &mut small).is_err());
+
+        let stereo = SignalSpec::new_with_layout(48_000, Layout::Stereo);
+        let mut stereo = AudioBuffer::<f32>::new(256, stereo);
+
+        assert!(decoder.decode_into(&packet, &mut stereo).is_err());
+    }
+
+    #[test]
+    fn verify_gapless_trims_padding() {
+        let mut registry = CodecRegistry::new();
+        registry.register_all::<SurroundDecoder>();
+
+        // Four packets of 256 frames, with 300 frames of delay and 100 frames of padding.
+        let mut params = CodecParameters::new();
+        params
+            .for_codec(CODEC_TYPE_PCM_S16LE)
+            .with_sample_format(SampleFormat::S16)
+            .with_n_frames(1024)
+            .with_leading_padding(300)
+            .with_trailing_padding(100);
+
+        let decode_all = |options: &DecoderOptions| {
+            let mut decoder = registry.make(&params, options).unwrap();
+
+            (0..4).map(|i| {
+                let packet = Packet::new_from_slice(0, 256 * i, 256, &[]);
+                let decoded = decoder.decode(&packet).unwrap();
+
+                assert_eq!(sample_format(&decoded), SampleFormat::S16);
+                decoded.frames()
+            })
+            .collect::<Vec<usize>>()
+        };
+
+        let options = DecoderOptions { gapless: true, ..Default::default() };
+
+        assert_eq!(decode_all(&options), vec![0, 212, 256, 156]);
+        assert_eq!(decode_all(&Default::default()), vec![256; 4]);
+    }
+}
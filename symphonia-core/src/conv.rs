@@ -182,8 +182,13 @@ pub mod dither {
 
     impl<F: Sample, T: Sample> Rectangular<F, T> {
         pub fn new() -> Self {
+            Rectangular::with_seed(0xb2c1_01f4_425b_987e)
+        }
+
+        /// Instantiate a `Rectangular` dither with a noise generator seeded by the given seed.
+        pub fn with_seed(seed: u64) -> Self {
             Rectangular {
-                prng: prng::Xoshiro128pp::new(0xb2c1_01f4_425b_987e),
+                prng: prng::Xoshiro128pp::new(seed),
                 from_type: PhantomData,
                 to_type: PhantomData,
             }
@@ -217,8 +222,13 @@ pub mod dither {
 
     impl<F: Sample, T: Sample> Triangular<F, T> {
         pub fn new() -> Self {
+            Triangular::with_seed(0xb2c1_01f4_425b_987e)
+        }
+
+        /// Instantiate a `Triangular` dither with a noise generator seeded by the given seed.
+        pub fn with_seed(seed: u64) -> Self {
             Triangular {
-                prng: prng::Xoshiro128pp::new(0xb2c1_01f4_425b_987e),
+                prng: prng::Xoshiro128pp::new(seed),
                 from_type: PhantomData,
                 to_type: PhantomData,
             }
@@ -244,6 +254,7 @@ pub mod dither {
     }
 
     /// Enumeration of dither algorithms.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
     pub enum DitherType {
         /// No dithering.
         Identity,
@@ -412,6 +423,21 @@ pub mod dither {
 ///
 /// This may be a lossy conversion if converting from a sample type of higher precision to one of
 /// lower precision. No dithering is applied.
+///
+/// All conversions obey the following rules:
+///
+/// * Widening integer conversions (e.g., `i16` to `i24`) are lossless. The sample is shifted into
+///   the most significant bits of the wider type, and converting it back yields the original
+///   sample.
+/// * Narrowing integer conversions (e.g., `i24` to `i16`) discard the least significant bits.
+///   Therefore, the sample is truncated toward negative infinity.
+/// * Conversions between signed and unsigned integers of the same width are lossless, and map the
+///   mid-point of the unsigned sample to 0.
+/// * Integer to floating point conversions map the minimum integer sample to -1.0. Conversions of
+///   integers with at most 24 bits to `f32`, and all integers to `f64`, are lossless. `i32` and
+///   `u32` samples are rounded to the nearest `f32`.
+/// * Floating point to integer conversions clamp the sample to [-1.0, 1.0], and then round to the
+///   nearest integer. A sample of 1.0 saturates to the maximum integer sample.
 pub trait FromSample<F> {
     fn from_sample(val: F) -> Self;
 }
@@ -427,7 +453,7 @@ macro_rules! converter {
     )
 }
 
-// Conversions to u8
+// Conversions to u8. All conversions, except from u8 and i8, are narrowing.
 converter!(u8, u8 , s, s);
 converter!(u8, u16, s, (s >> 8) as u8);
 converter!(u8, u24, s, ((s.into_u32() & 0xff_ffff) >> 16) as u8);
@@ -445,7 +471,7 @@ converter!(u8, f64, s, {
     clamp_u8((s16 + 0x80) as u16)
 });
 
-// Conversions to u16
+// Conversions to u16. Conversions from 8-bit samples are widening, all others are narrowing.
 converter!(u16, u8 , s, u16::from(s) << 8);
 converter!(u16, u16, s, s);
 converter!(u16, u24, s, ((s.into_u32() & 0xff_ffff) >> 8) as u16);
@@ -463,7 +489,7 @@ converter!(u16, f64, s, {
     clamp_u16((s32 + 0x8000) as u32)
 });
 
-// Conversions to u24
+// Conversions to u24. Conversions from 32-bit and floating point samples are narrowing.
 converter!(u24, u8 , s, u24::from(u32::from(s) << 16));
 converter!(u24, u16, s, u24::from(u32::from(s) << 8));
 converter!(u24, u24, s, u24::from(s.into_u32() & 0xff_ffff));
@@ -481,7 +507,7 @@ converter!(u24, f64, s, {
     u24::from(clamp_u24((s32 + 0x80_0000) as u32))
 });
 
-// Conversions to u32
+// Conversions to u32. Conversions from floating point samples are narrowing.
 converter!(u32, u8 , s, u32::from(s) << 24);
 converter!(u32, u16, s, u32::from(s) << 16);
 converter!(u32, u24, s, (s.into_u32() & 0xff_ffff) << 8);
@@ -499,7 +525,7 @@ converter!(u32, f64, s, {
     clamp_u32((s64 + 0x8000_0000) as u64)
 });
 
-// Conversions to i8
+// Conversions to i8. All conversions, except from u8 and i8, are narrowing.
 converter!(i8, u8 , s, s.wrapping_add(0x80) as i8);
 converter!(i8, u16, s, (s.wrapping_add(0x8000) >> 8) as i8);
 converter!(i8, u24, s, (s.into_u32().wrapping_add(0x80_0000) >> 16) as i8);
@@ -511,7 +537,7 @@ converter!(i8, i32, s, (s >> 24) as i8);
 converter!(i8, f32, s, clamp_i8((clamp_f32(s) * 128.0).round() as i16));
 converter!(i8, f64, s, clamp_i8((clamp_f64(s) * 128.0).round() as i16));
 
-// Conversions to i16
+// Conversions to i16. Conversions from 8-bit samples are widening, all others are narrowing.
 converter!(i16, u8 , s, i16::from(s.wrapping_add(0x80)) << 8);
 converter!(i16, u16, s, s.wrapping_add(0x8000) as i16);
 converter!(i16, u24, s, (s.into_u32().wrapping_add(0x80_0000) >> 8) as i16);
@@ -523,7 +549,7 @@ converter!(i16, i32, s, (s >> 16) as i16);
 converter!(i16, f32, s, clamp_i16((clamp_f32(s) * 32_768.0).round() as i32));
 converter!(i16, f64, s, clamp_i16((clamp_f64(s) * 32_768.0).round() as i32));
 
-// Conversions to i24
+// Conversions to i24. Conversions from 32-bit and floating point samples are narrowing.
 converter!(i24, u8 , s, i24::from((i32::from(s) - 0x80) << 16));
 converter!(i24, u16, s, i24::from((i32::from(s) - 0x8000) << 8));
 converter!(i24, u24, s, i24::from((s.into_u32() & 0xff_ffff) as i32 - 0x80_0000));
@@ -535,7 +561,7 @@ converter!(i24, i32, s, i24::from(s >> 8));
 converter!(i24, f32, s, i24::from(clamp_i24((clamp_f32(s) * 8_388_608.0).round() as i32)));
 converter!(i24, f64, s, i24::from(clamp_i24((clamp_f64(s) * 8_388_608.0).round() as i32)));
 
-// Conversions to i32
+// Conversions to i32. Conversions from floating point samples are narrowing.
 converter!(i32, u8 , s, (i32::from(s) - 0x80) << 24);
 converter!(i32, u16, s, (i32::from(s) - 0x8000) << 16);
 converter!(i32, u24, s, ((s.into_u32() & 0xff_ffff) as i32 - 0x80_0000) << 8);
@@ -547,7 +573,7 @@ converter!(i32, i32, s, s);
 converter!(i32, f32, s, clamp_i32((clamp_f32(s) * 2_147_483_648.0).round() as i64));
 converter!(i32, f64, s, clamp_i32((clamp_f64(s) * 2_147_483_648.0).round() as i64));
 
-// Conversions to f32
+// Conversions to f32. Conversions from 32-bit integer and f64 samples round to the nearest f32.
 converter!(f32, u8 , s, f32::from(i8::from_sample(s)) / 128.0);
 converter!(f32, u16, s, f32::from(i16::from_sample(s)) / 32_768.0);
 converter!(f32, u24, s, (i24::from_sample(s).into_i32() as f32) / 8_388_608.0);
@@ -559,7 +585,7 @@ converter!(f32, i32, s, (s as f32) / 2_147_483_648.0);
 converter!(f32, f32, s, s);
 converter!(f32, f64, s, s as f32);
 
-// Conversions to f64
+// Conversions to f64. All conversions are lossless.
 converter!(f64, u8 , s, f64::from(i8::from_sample(s)) / 128.0);
 converter!(f64, u16, s, f64::from(i16::from_sample(s)) / 32_768.0);
 converter!(f64, u24, s, f64::from(i24::from_sample(s).into_i32()) / 8_388_608.0);
@@ -1054,4 +1080,78 @@ mod tests {
         assert_eq!(f32::from_sample(   0f64),  0.0);
         assert_eq!(f32::from_sample(-1.0f64), -1.0);
     }
-}
\ No newline at end of file
+
+    /// Converts a sample to the sample format `T` and back, returning the round-tripped sample.
+    fn round_trip<S, T>(s: S) -> S
+    where
+        S: FromSample<T> + Copy,
+        T: FromSample<S>,
+    {
+        S::from_sample(T::from_sample(s))
+    }
+
+    #[test]
+    fn verify_lossless_round_trips() {
+        // Every u8 sample survives a round-trip through all wider formats.
+        for s in u8::MIN..=u8::MAX {
+            assert_eq!(round_trip::<u8, i16>(s), s);
+            assert_eq!(round_trip::<u8, i24>(s), s);
+            assert_eq!(round_trip::<u8, i32>(s), s);
+            assert_eq!(round_trip::<u8, f32>(s), s);
+        }
+
+        // Every i16 sample survives a round-trip through all wider formats.
+        for s in i16::MIN..=i16::MAX {
+            assert_eq!(round_trip::<i16, i24>(s), s);
+            assert_eq!(round_trip::<i16, i32>(s), s);
+            assert_eq!(round_trip::<i16, f32>(s), s);
+        }
+
+        // A sweep of i24 samples, including both boundaries, survives a round-trip through all
+        // wider formats.
+        for s in (i24::MIN.into_i32()..=i24::MAX.into_i32()).step_by(127).chain(Some(0x7f_ffff)) {
+            let s = i24::from(s);
+
+            assert_eq!(round_trip::<i24, i32>(s), s);
+            assert_eq!(round_trip::<i24, f32>(s), s);
+        }
+
+        // f32 samples exactly representable by the narrower format survive a round-trip.
+        for &s in [-1.0f32, -0.5, 0.0, 0.5, 1.0 - 1.0 / 128.0].iter() {
+            assert_eq!(round_trip::<f32, u8>(s), s);
+            assert_eq!(round_trip::<f32, i16>(s), s);
+            assert_eq!(round_trip::<f32, i24>(s), s);
+            assert_eq!(round_trip::<f32, i32>(s), s);
+        }
+    }
+
+    #[test]
+    fn verify_narrowing_truncates() {
+        // Discarded bits truncate toward negative infinity.
+        assert_eq!(i16::from_sample(0x0001_ffffi32), 1);
+        assert_eq!(i16::from_sample(-1i32), -1);
+        assert_eq!(i16::from_sample(i24::from(0xff)), 0);
+        assert_eq!(i16::from_sample(i24::from(-1)), -1);
+        assert_eq!(i24::from_sample(0xffi32), i24::from(0));
+        assert_eq!(i24::from_sample(-0x100i32 - 1), i24::from(-2));
+        assert_eq!(u8::from_sample(0x7fffi16), 0xff);
+        assert_eq!(u8::from_sample(-0x8000i16 + 0xff), 0x00);
+        assert_eq!(u8::from_sample(i24::from(-1)), 0x7f);
+        assert_eq!(u8::from_sample(-1i32), 0x7f);
+
+        // Floating point samples round to the nearest integer, and saturate at full scale.
+        assert_eq!(i16::from_sample(0.5f32 / 32_768.0), 1);
+        assert_eq!(i16::from_sample(0.49f32 / 32_768.0), 0);
+        assert_eq!(i16::from_sample(-0.49f32 / 32_768.0), 0);
+        assert_eq!(i16::from_sample(2.0f32), i16::MAX);
+        assert_eq!(i16::from_sample(-2.0f32), i16::MIN);
+        assert_eq!(i24::from_sample(1.0f32), i24::MAX);
+        assert_eq!(i32::from_sample(1.0f32), i32::MAX);
+        assert_eq!(u8::from_sample(1.0f32), u8::MAX);
+        assert_eq!(u8::from_sample(-1.0f32), u8::MIN);
+
+        // 32-bit integers round to the nearest f32.
+        assert_eq!(f32::from_sample(i32::MAX), 1.0);
+        assert_eq!(f32::from_sample(i32::MIN), -1.0);
+    }
+}
@@ -0,0 +1,349 @@
+// Symphonia
+// Copyright (c) 2021 The Project Symphonia Developers.
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! The `downmix` module implements a standard downmix of multi-channel audio to a layout with
+//! fewer channels.
+
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use crate::audio::{AudioBuffer, Channels, Signal};
+use crate::errors::{Result, unsupported_error};
+
+/// Gets the left and right coefficients of a channel in the standard (ITU-R BS.775) stereo
+/// downmix. Centre channels are mixed into both the left and right channels, and surround channels
+/// are mixed into their respective side, each attenuated by 3 dB. Low frequency channels are
+/// dropped.
+pub(crate) fn stereo_coefficients(channel: Channels) -> (f32, f32) {
+    const L: Channels = Channels::from_bits_truncate(
+        Channels::REAR_LEFT.bits()
+            | Channels::FRONT_LEFT_CENTRE.bits()
+            | Channels::REAR_LEFT_CENTRE.bits()
+            | Channels::FRONT_LEFT_WIDE.bits()
+            | Channels::FRONT_LEFT_HIGH.bits()
+            | Channels::SIDE_LEFT.bits()
+            | Channels::TOP_FRONT_LEFT.bits()
+            | Channels::TOP_REAR_LEFT.bits()
+    );
+
+    const R: Channels = Channels::from_bits_truncate(
+        Channels::REAR_RIGHT.bits()
+            | Channels::FRONT_RIGHT_CENTRE.bits()
+            | Channels::REAR_RIGHT_CENTRE.bits()
+            | Channels::FRONT_RIGHT_WIDE.bits()
+            | Channels::FRONT_RIGHT_HIGH.bits()
+            | Channels::SIDE_RIGHT.bits()
+            | Channels::TOP_FRONT_RIGHT.bits()
+            | Channels::TOP_REAR_RIGHT.bits()
+    );
+
+    const LFE: Channels = Channels::from_bits_truncate(
+        Channels::LFE1.bits() | Channels::LFE2.bits()
+    );
+
+    if channel == Channels::FRONT_LEFT {
+        (1.0, 0.0)
+    }
+    else if channel == Channels::FRONT_RIGHT {
+        (0.0, 1.0)
+    }
+    else if L.contains(channel) {
+        (FRAC_1_SQRT_2, 0.0)
+    }
+    else if R.contains(channel) {
+        (0.0, FRAC_1_SQRT_2)
+    }
+    else if LFE.contains(channel) {
+        (0.0, 0.0)
+    }
+    else {
+        // All remaining channels are centre channels.
+        (FRAC_1_SQRT_2, FRAC_1_SQRT_2)
+    }
+}
+
+/// Iterates over each channel of a positioned (non-discrete) channel mask in plane order.
+pub(crate) fn positioned_channels(channels: Channels) -> impl Iterator<Item = Channels> {
+    (0..32).map(|bit| Channels::from_bits_truncate(1 << bit))
+           .filter(move |&channel| !channel.is_empty() && channels.has_positions(channel))
+}
+
+/// Gets the surround layouts a signal may be downmixed to, ordered from the most to the fewest
+/// channels. Stereo and mono are not included since any signal may be downmixed to them.
+fn surround_layouts() -> [Channels; 9] {
+    let front = Channels::FRONT_LEFT | Channels::FRONT_RIGHT;
+    let rear = Channels::REAR_LEFT | Channels::REAR_RIGHT;
+    let side = Channels::SIDE_LEFT | Channels::SIDE_RIGHT;
+    let centre = Channels::FRONT_CENTRE;
+    let lfe = Channels::LFE1;
+
+    [
+        // 7.1
+        front | centre | side | rear | lfe,
+        // 5.1, and 5.1 with side channels.
+        front | centre | rear | lfe,
+        front | centre | side | lfe,
+        // 5.0, and 5.0 with side channels.
+        front | centre | rear,
+        front | centre | side,
+        // Quadraphonic, and quadraphonic with side channels.
+        front | rear,
+        front | side,
+        // 3.0, and 2.1.
+        front | centre,
+        front | lfe,
+    ]
+}
+
+/// Gets the channels a signal with the given channels will have after downmixing to, at most,
+/// `max_channels` channels. Signals with more channels than the maximum are downmixed to the
+/// surround layout with the most channels within the maximum whose channels are all present in the
+/// signal. If there is no such layout, signals are downmixed to stereo, or mono if the maximum is
+/// 1. Returns `None` if there is no standard downmix for the channels.
+pub fn downmix_channels(channels: Channels, max_channels: usize) -> Option<Channels> {
+    // Discrete channels do not have positions, and therefore do not have a standard downmix.
+    if channels.is_discrete() || channels.is_empty() || max_channels == 0 {
+        return None;
+    }
+
+    if channels.count() <= max_channels {
+        return Some(channels);
+    }
+
+    let layout = surround_layouts()
+        .iter()
+        .copied()
+        .find(|&layout| layout.count() <= max_channels && channels.has_positions(layout));
+
+    match layout {
+        Some(layout) => Some(layout),
+        None if max_channels >= 2 => Some(Channels::FRONT_LEFT | Channels::FRONT_RIGHT),
+        None => Some(Channels::FRONT_LEFT),
+    }
+}
+
+/// Gets the channels of the downmixed signal a surround channel that is not present in the
+/// downmixed signal is folded into, if any. Side and rear channels are folded into each other, and
+/// a rear centre channel into a rear or side pair.
+fn surround_fold(channel: Channels, out_channels: Channels) -> Option<Channels> {
+    let targets: &[Channels] = match channel {
+        Channels::SIDE_LEFT => &[Channels::REAR_LEFT],
+        Channels::SIDE_RIGHT => &[Channels::REAR_RIGHT],
+        Channels::REAR_LEFT => &[Channels::SIDE_LEFT],
+        Channels::REAR_RIGHT => &[Channels::SIDE_RIGHT],
+        Channels::REAR_CENTRE => &[
+            Channels::from_bits_truncate(Channels::REAR_LEFT.bits() | Channels::REAR_RIGHT.bits()),
+            Channels::from_bits_truncate(Channels::SIDE_LEFT.bits() | Channels::SIDE_RIGHT.bits()),
+        ],
+        _ => &[],
+    };
+
+    targets.iter().copied().find(|&target| out_channels.has_positions(target))
+}
+
+/// A `Downmixer` downmixes planar audio to a layout with fewer channels using the standard downmix
+/// coefficients. Each row of the downmix matrix is normalized such that a full-scale signal in
+/// every source channel will not clip.
+pub struct Downmixer {
+    /// The channels of the source signal.
+    in_channels: Channels,
+    /// The channels of the downmixed signal.
+    out_channels: Channels,
+    /// The downmix matrix. Each row contains the coefficients of all source channels for one
+    /// downmixed channel.
+    matrix: Vec<Vec<f32>>,
+}
+
+impl Downmixer {
+    /// Instantiate a `Downmixer` that downmixes a signal with the given channels to, at most,
+    /// `max_channels` channels. Returns an error if there is no standard downmix for the channels.
+    pub fn try_new(in_channels: Channels, max_channels: usize) -> Result<Downmixer> {
+        let out_channels = match downmix_channels(in_channels, max_channels) {
+            Some(out_channels) => out_channels,
+            None => return unsupported_error("no standard downmix exists for the channels"),
+        };
+
+        let mut matrix = if out_channels.count() == 1 {
+            // The mono downmix is the average of the stereo downmix.
+            let row = positioned_channels(in_channels)
+                .map(stereo_coefficients)
+                .map(|(l, r)| 0.5 * (l + r))
+                .collect();
+
+            vec![row]
+        }
+        else {
+            let outs = positioned_channels(out_channels).collect::<Vec<_>>();
+
+            let mut matrix = vec![vec![0.0; in_channels.count()]; outs.len()];
+
+            // Gets the row of the matrix for an output channel.
+            let row = |channel: Channels| outs.iter().position(|&out| out == channel).unwrap();
+
+            for (i, channel) in positioned_channels(in_channels).enumerate() {
+                if out_channels.has_positions(channel) {
+                    // Channels present in the downmixed signal are copied.
+                    matrix[row(channel)][i] = 1.0;
+                }
+                else if let Some(targets) = surround_fold(channel, out_channels) {
+                    // Surround channels are folded into the remaining surround channels,
+                    // attenuated by 3 dB.
+                    for target in positioned_channels(targets) {
+                        matrix[row(target)][i] = FRAC_1_SQRT_2;
+                    }
+                }
+                else {
+                    // All other channels are mixed into the front left and right channels as in
+                    // the standard stereo downmix.
+                    let (l, r) = stereo_coefficients(channel);
+
+                    matrix[row(Channels::FRONT_LEFT)][i] = l;
+                    matrix[row(Channels::FRONT_RIGHT)][i] = r;
+                }
+            }
+
+            matrix
+        };
+
+        // Normalize each row of the matrix so that the downmixed signal does not clip.
+        for row in matrix.iter_mut() {
+            let sum = row.iter().sum::<f32>();
+
+            if sum > 1.0 {
+                row.iter_mut().for_each(|c| *c /= sum);
+            }
+        }
+
+        Ok(Downmixer { in_channels, out_channels, matrix })
+    }
+
+    /// Gets the channels of the source signal.
+    pub fn in_channels(&self) -> Channels {
+        self.in_channels
+    }
+
+    /// Gets the channels of the downmixed signal.
+    pub fn out_channels(&self) -> Channels {
+        self.out_channels
+    }
+
+    /// Downmixes all frames of the input buffer into the output buffer, replacing any existing
+    /// frames. The channels of the input and output buffers must match the source and downmixed
+    /// channels respectively, and the output buffer must have enough capacity for all frames.
+    pub fn process(&self, input: &AudioBuffer<f32>, output: &mut AudioBuffer<f32>) {
+        assert!(input.spec().channels == self.in_channels, "input channels do not match");
+        assert!(output.spec().channels == self.out_channels, "output channels do not match");
+
+        output.clear();
+        output.render_reserved(Some(input.frames()));
+
+        for (o, row) in self.matrix.iter().enumerate() {
+            let out = output.chan_mut(o);
+
+            out.iter_mut().for_each(|s| *s = 0.0);
+
+            for (i, &coeff) in row.iter().enumerate() {
+                if coeff == 0.0 {
+                    continue;
+                }
+
+                for (d, &s) in out.iter_mut().zip(input.chan(i)) {
+                    *d += coeff * s;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{Layout, SignalSpec};
+
+    #[test]
+    fn verify_downmix_five_point_one_to_stereo() {
+        let spec = SignalSpec::new_with_layout(48_000, Layout::FivePointOne);
+
+        let downmixer = Downmixer::try_new(spec.channels, 2).unwrap();
+
+        assert_eq!(downmixer.out_channels(), Channels::FRONT_LEFT | Channels::FRONT_RIGHT);
+
+        // Planes are FL, FR, FC, RL, RR, LFE1.
+        let mut input = AudioBuffer::<f32>::new(1, spec);
+        input.render(None, |planes, idx| {
+            for (ch, plane) in planes.planes().iter_mut().enumerate() {
+                plane[idx] = [0.5, 0.0, 0.5, 0.5, 0.0, 1.0][ch];
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        let mut output = AudioBuffer::<f32>::new(1, SignalSpec::new(48_000, downmixer.out_channels()));
+        downmixer.process(&input, &mut output);
+
+        let norm = 1.0 + 2.0 * FRAC_1_SQRT_2;
+        let left = (0.5 + FRAC_1_SQRT_2 * 0.5 + FRAC_1_SQRT_2 * 0.5) / norm;
+        let right = (FRAC_1_SQRT_2 * 0.5) / norm;
+
+        assert_eq!(output.frames(), 1);
+        assert!((output.chan(0)[0] - left).abs() < 1e-6);
+        assert!((output.chan(1)[0] - right).abs() < 1e-6);
+    }
+
+    #[test]
+    fn verify_downmix_channels() {
+        let stereo = Channels::FRONT_LEFT | Channels::FRONT_RIGHT;
+
+        assert_eq!(downmix_channels(stereo, 2), Some(stereo));
+        assert_eq!(downmix_channels(stereo, 1), Some(Channels::FRONT_LEFT));
+        assert_eq!(downmix_channels(Channels::discrete(6), 2), None);
+        assert_eq!(downmix_channels(stereo, 0), None);
+
+        // The nearest surround layout within the maximum is selected.
+        let seven_one = SignalSpec::new_with_layout(48_000, Layout::SevenPointOne).channels;
+        let five_one = SignalSpec::new_with_layout(48_000, Layout::FivePointOne).channels;
+        let quad = SignalSpec::new_with_layout(48_000, Layout::Quad).channels;
+
+        assert_eq!(downmix_channels(seven_one, 6), Some(five_one));
+        assert_eq!(downmix_channels(five_one, 5), Some(five_one - Channels::LFE1));
+        assert_eq!(downmix_channels(five_one, 4), Some(quad));
+        assert_eq!(downmix_channels(five_one, 3), Some(stereo | Channels::FRONT_CENTRE));
+        assert_eq!(downmix_channels(five_one, 2), Some(stereo));
+    }
+
+    #[test]
+    fn verify_downmix_seven_point_one_to_five_point_one() {
+        let spec = SignalSpec::new_with_layout(48_000, Layout::SevenPointOne);
+
+        let downmixer = Downmixer::try_new(spec.channels, 6).unwrap();
+
+        let out_spec = SignalSpec::new(48_000, downmixer.out_channels());
+        assert_eq!(out_spec, SignalSpec::new_with_layout(48_000, Layout::FivePointOne));
+
+        // Only the side left channel has a signal.
+        let mut input = AudioBuffer::<f32>::new(1, spec);
+        input.render(None, |planes, idx| {
+            for (channel, plane) in positioned_channels(spec.channels).zip(planes.planes()) {
+                plane[idx] = if channel == Channels::SIDE_LEFT { 1.0 } else { 0.0 };
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        let mut output = AudioBuffer::<f32>::new(1, out_spec);
+        downmixer.process(&input, &mut output);
+
+        // The side left channel is folded into the rear left channel.
+        let rear_left = FRAC_1_SQRT_2 / (1.0 + FRAC_1_SQRT_2);
+
+        let channels = positioned_channels(out_spec.channels);
+
+        for (channel, plane) in channels.zip(output.planes().planes()) {
+            let expected = if channel == Channels::REAR_LEFT { rear_left } else { 0.0 };
+            assert!((plane[0] - expected).abs() < 1e-6);
+        }
+    }
+}
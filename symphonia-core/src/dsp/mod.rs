@@ -8,4 +8,5 @@
 //! The `dsp` module provides efficient implementations of common signal processing algorithms.
 
 pub mod dct;
+pub mod downmix;
 pub mod mdct;
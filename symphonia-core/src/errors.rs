@@ -49,6 +49,8 @@ pub enum Error {
     LimitError(&'static str),
     /// The demuxer or decoder needs to be reset before continuing.
     ResetRequired,
+    /// The end of the stream was reached. This is not a failure, no more packets are available.
+    EndOfStream,
 }
 
 impl fmt::Display for Error {
@@ -70,6 +72,9 @@ impl fmt::Display for Error {
             Error::ResetRequired => {
                 write!(f, "decoder needs to be reset")
             },
+            Error::EndOfStream => {
+                write!(f, "end of stream")
+            },
         }
     }
 }
@@ -83,6 +88,27 @@ impl std::error::Error for Error {
             Error::Unsupported(_) => None,
             Error::LimitError(_) => None,
             Error::ResetRequired => None,
+            Error::EndOfStream => None,
+        }
+    }
+}
+
+impl Error {
+    /// Returns `true` if the error is an IO error indicating that the media source has no more
+    /// data available yet, but may have more later (e.g., a network stream or a pipe).
+    pub fn is_would_block(&self) -> bool {
+        matches!(self, Error::IoError(err) if err.kind() == io::ErrorKind::WouldBlock)
+    }
+
+    /// Converts an unexpected end-of-file IO error into an end-of-stream error, otherwise returns
+    /// the error unchanged. A `FormatReader` should use this where running out of data means that
+    /// there are no more packets, rather than that a packet was truncated.
+    pub fn into_end_of_stream(self) -> Error {
+        match self {
+            Error::IoError(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                Error::EndOfStream
+            }
+            err => err,
         }
     }
 }
@@ -122,5 +148,5 @@ pub fn reset_error<T>() -> Result<T> {
 
 /// Convenience function to create an end-of-stream error.
 pub fn end_of_stream_error<T>() -> Result<T> {
-    Err(Error::IoError(io::Error::new(io::ErrorKind::UnexpectedEof, "end of stream")))
+    Err(Error::EndOfStream)
 }
\ No newline at end of file
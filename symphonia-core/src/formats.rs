@@ -8,11 +8,12 @@
 //! The `format` module provides the traits and support structures necessary to implement media
 //! demuxers.
 
-use crate::codecs::CodecParameters;
-use crate::errors::Result;
+use crate::audio::Channels;
+use crate::codecs::{CodecParameters, CodecRegistry, CodecType};
+use crate::errors::{Error, Result, SeekErrorKind, seek_error, unsupported_error};
 use crate::io::{BufReader, MediaSourceStream};
-use crate::meta::{Metadata, Tag};
-use crate::units::{Time, TimeStamp};
+use crate::meta::{Metadata, MetadataOptions, ReplayGain, Tag, find_producer};
+use crate::units::{Duration, Time, TimeBase, TimeStamp};
 
 pub mod prelude {
     //! The `formats` module prelude.
@@ -24,10 +25,16 @@ pub mod prelude {
         FormatOptions,
         FormatReader,
         Packet,
+        Packets,
+        SeekAccuracy,
+        SeekCaps,
         SeekedTo,
         SeekMode,
+        SeekSupport,
         SeekTo,
         Track,
+        TrackKind,
+        TrackSummary,
     };
 }
 
@@ -61,6 +68,68 @@ pub struct SeekedTo {
     pub actual_ts: TimeStamp,
 }
 
+/// `SeekSupport` describes the directions a `FormatReader` is capable of seeking in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SeekSupport {
+    /// Seeking is not supported.
+    Unsupported,
+    /// Only seeking forward, past the current position, is supported. This is typical of an
+    /// unseekable source, such as a pipe, where seeking is emulated by discarding data.
+    ForwardOnly,
+    /// Seeking forwards and backwards is supported.
+    Full,
+}
+
+/// `SeekAccuracy` describes the position a `FormatReader` seeks to relative to the requested
+/// position.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SeekAccuracy {
+    /// An accurate seek positions the reader at the packet containing the requested position. By
+    /// decoding the packet and discarding frames before the requested position, the seek is sample
+    /// accurate.
+    SampleAccurate,
+    /// A seek positions the reader at a sync point before the requested position. Many packets may
+    /// need to be decoded and discarded to reach the requested position.
+    SyncPoint,
+}
+
+/// `SeekCaps` describes the seeking capabilities of a `FormatReader`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SeekCaps {
+    /// The directions the reader is capable of seeking in.
+    pub support: SeekSupport,
+    /// The accuracy of a seek using the accurate `SeekMode`.
+    pub accuracy: SeekAccuracy,
+    /// The approximate granularity of a seek, in `TimeBase` units of the default track. This is
+    /// the maximum distance between the requested position and the position seeked to. May be
+    /// unknown.
+    pub granularity: Option<Duration>,
+}
+
+impl SeekCaps {
+    /// Instantiate a `SeekCaps` for a `FormatReader` that does not support seeking.
+    pub fn unsupported() -> Self {
+        SeekCaps {
+            support: SeekSupport::Unsupported,
+            accuracy: SeekAccuracy::SyncPoint,
+            granularity: None,
+        }
+    }
+
+    /// Instantiate a `SeekCaps` for a sample accurate `FormatReader` that, depending on if the
+    /// underlying source is seekable, supports full or forward-only seeking.
+    pub fn sample_accurate(is_seekable: bool, granularity: Option<Duration>) -> Self {
+        let support = if is_seekable { SeekSupport::Full } else { SeekSupport::ForwardOnly };
+
+        SeekCaps { support, accuracy: SeekAccuracy::SampleAccurate, granularity }
+    }
+
+    /// Returns `true` if seeking, in any direction, is supported.
+    pub fn is_seekable(&self) -> bool {
+        self.support != SeekSupport::Unsupported
+    }
+}
+
 /// `SeekMode` selects the precision of a seek.
 pub enum SeekMode {
     /// Coarse seek mode is a best-effort attempt to seek to the requested position. The actual
@@ -87,6 +156,12 @@ pub struct FormatOptions {
     /// a good compromise for casual playback of music, podcasts, movies, etc. However, for
     /// highly-interactive applications, this value should be decreased.
     pub seek_index_fill_rate: u16,
+    /// The options used by a `FormatReader` to read metadata embedded in the container, such as
+    /// the size limits of tags and visuals. Default: the default `MetadataOptions`.
+    pub metadata: MetadataOptions,
+    /// The parameters of headerless, raw PCM audio. Raw PCM can only be read if these parameters
+    /// are provided, and the raw PCM format is forced using the `Hint`. Default: `None`.
+    pub raw_pcm: Option<RawPcmOptions>,
 }
 
 impl Default for FormatOptions {
@@ -94,10 +169,24 @@ impl Default for FormatOptions {
         FormatOptions {
             prebuild_seek_index: false,
             seek_index_fill_rate: 20,
+            metadata: Default::default(),
+            raw_pcm: None,
         }
     }
 }
 
+/// `RawPcmOptions` describes headerless, raw PCM audio. Since raw PCM has no header, these
+/// parameters cannot be detected, and must be provided by the user.
+#[derive(Copy, Clone, PartialEq)]
+pub struct RawPcmOptions {
+    /// The sample rate in Hz.
+    pub rate: u32,
+    /// The channels. Samples of each channel are interleaved.
+    pub channels: Channels,
+    /// The PCM codec of the samples (e.g., `CODEC_TYPE_PCM_S16LE`).
+    pub format: CodecType,
+}
+
 /// A `Cue` is a designated point of time within a media stream.
 ///
 /// A `Cue` may be a mapping from either a source track, a chapter, cuesheet, or a timestamp
@@ -127,6 +216,21 @@ pub struct CuePoint {
     pub tags: Vec<Tag>,
 }
 
+/// The kind of media carried by a `Track`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TrackKind {
+    /// An audio track.
+    Audio,
+    /// A video track.
+    Video,
+    /// A subtitle track.
+    Subtitle,
+    /// A text track, such as a chapter track.
+    Text,
+    /// A timed metadata track.
+    Metadata,
+}
+
 /// A `Track` is an independently coded media bitstream. A media format may contain multiple tracks
 /// in one container. Each of those tracks are represented by one `Track`.
 pub struct Track {
@@ -136,6 +240,8 @@ pub struct Track {
     pub codec_params: CodecParameters,
     /// The language of the track. May be unknown.
     pub language: Option<String>,
+    /// The kind of media carried by the track.
+    kind: TrackKind,
 }
 
 impl Track {
@@ -144,10 +250,83 @@ impl Track {
             id,
             codec_params,
             language: None,
+            kind: TrackKind::Audio,
+        }
+    }
+
+    /// Gets the kind of media carried by the track. Default: `TrackKind::Audio`.
+    pub fn kind(&self) -> TrackKind {
+        self.kind
+    }
+
+    /// Provide the kind of media carried by the track.
+    pub fn with_kind(&mut self, kind: TrackKind) -> &mut Self {
+        self.kind = kind;
+        self
+    }
+}
+
+/// A `TrackSummary` is a compact summary of a `Track` suitable for presenting a list of tracks to a
+/// user.
+#[derive(Clone, Debug)]
+pub struct TrackSummary {
+    /// The index of the track in the list of tracks.
+    pub index: usize,
+    /// The unique identifier of the track.
+    pub id: u32,
+    /// The kind of media carried by the track.
+    pub kind: TrackKind,
+    /// The codec type.
+    pub codec: CodecType,
+    /// The short name of the codec. Only known if the codec is registered in the `CodecRegistry`
+    /// used to summarize the track.
+    pub codec_name: Option<&'static str>,
+    /// The sample rate of the audio in Hz. May be unknown.
+    pub sample_rate: Option<u32>,
+    /// The channels of the audio. May be unknown.
+    pub channels: Option<Channels>,
+    /// The length of the track in number of frames. May be unknown.
+    pub n_frames: Option<u64>,
+    /// The duration of the track. Only known if the length and either the `TimeBase` or the sample
+    /// rate of the track are known.
+    pub duration: Option<Time>,
+    /// The language of the track. May be unknown.
+    pub language: Option<String>,
+}
+
+impl TrackSummary {
+    /// Summarizes the track at the given index. The name of the codec is looked up in `codecs`.
+    pub fn new(index: usize, track: &Track, codecs: &CodecRegistry) -> Self {
+        let params = &track.codec_params;
+
+        TrackSummary {
+            index,
+            id: track.id,
+            kind: track.kind,
+            codec: params.codec,
+            codec_name: codecs.get_codec(params.codec).map(|descriptor| descriptor.short_name),
+            sample_rate: params.sample_rate,
+            channels: params.channels,
+            n_frames: params.n_frames,
+            duration: calc_duration(params),
+            language: track.language.clone(),
         }
     }
 }
 
+/// Calculates the duration of a track from its codec parameters. The length of a track is in
+/// `TimeBase` units, however, if the `TimeBase` is unknown, it is assumed to be the reciprocal of
+/// the sample rate since the length of an audio track is generally its number of frames.
+fn calc_duration(params: &CodecParameters) -> Option<Time> {
+    let tb = match (params.time_base, params.sample_rate) {
+        (Some(tb), _) => tb,
+        (None, Some(sample_rate)) if sample_rate > 0 => TimeBase::new(1, sample_rate),
+        _ => return None,
+    };
+
+    params.n_frames.map(|n_frames| tb.calc_time(n_frames))
+}
+
 /// A `FormatReader` is a container demuxer. It provides methods to probe a media container for
 /// information and access the tracks encapsulated in the container.
 ///
@@ -189,6 +368,43 @@ pub trait FormatReader: Send {
     /// optional performance enhancement, therefore, a coarse seek may sometimes be an accurate seek.
     fn seek(&mut self, mode: SeekMode, to: SeekTo) -> Result<SeekedTo>;
 
+    /// Seek to a fraction, between 0.0 and 1.0 inclusive, of the total duration of the default
+    /// track. Returns an out-of-range seek error if the fraction is outside that range, or an
+    /// unseekable seek error if the total duration is unknown.
+    fn seek_fraction(&mut self, mode: SeekMode, fraction: f64) -> Result<SeekedTo> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return seek_error(SeekErrorKind::OutOfRange);
+        }
+
+        let total = match self.total_duration() {
+            Some(total) => total,
+            None => return seek_error(SeekErrorKind::Unseekable),
+        };
+
+        let time = Time::from(f64::from(total) * fraction);
+
+        self.seek(mode, SeekTo::Time { time, track_id: None })
+    }
+
+    /// Seek to the first packet starting at, or after, the given byte offset in the media source.
+    ///
+    /// A byte seek is a coarse seek intended for formats without an accurate seek index. Since the
+    /// timestamp of the packet seeked to may not be known, the timestamps of subsequent packets may
+    /// be estimated. By default, byte seeking is unsupported.
+    fn seek_byte(&mut self, _offset: u64) -> Result<()> {
+        unsupported_error("byte seeking is not supported")
+    }
+
+    /// Gets the seeking capabilities of the `FormatReader`. By default, a `FormatReader` is assumed
+    /// to support seeking in both directions to a sync point of unknown granularity.
+    fn seek_caps(&self) -> SeekCaps {
+        SeekCaps {
+            support: SeekSupport::Full,
+            accuracy: SeekAccuracy::SyncPoint,
+            granularity: None,
+        }
+    }
+
     /// Gets a list of tracks in the container.
     fn tracks(&self) -> &[Track];
 
@@ -199,13 +415,114 @@ pub trait FormatReader: Send {
         self.tracks().first()
     }
 
+    /// Gets the track at the given index in the list of tracks. Returns `None` if the index is out
+    /// of range.
+    fn track(&self, index: usize) -> Option<&Track> {
+        self.tracks().get(index)
+    }
+
+    /// Gets all tracks with the given codec type, in the order they appear in the list of tracks.
+    fn tracks_with_codec(&self, codec: CodecType) -> Vec<&Track> {
+        self.tracks().iter().filter(|track| track.codec_params.codec == codec).collect()
+    }
+
+    /// Gets the total duration of the default track, if known. By default, the duration is
+    /// calculated from the length and `TimeBase`, or sample rate, of the default track. A
+    /// `FormatReader` may override this if the duration can be determined by other means, such as
+    /// from format-level headers or the length of the media source.
+    fn total_duration(&self) -> Option<Time> {
+        calc_duration(&self.default_track()?.codec_params)
+    }
+
+    /// Gets a summary of every track in the container. The names of the codecs are looked up in
+    /// `codecs`.
+    fn track_summaries(&self, codecs: &CodecRegistry) -> Vec<TrackSummary> {
+        self.tracks()
+            .iter()
+            .enumerate()
+            .map(|(index, track)| TrackSummary::new(index, track, codecs))
+            .collect()
+    }
+
+    /// Gets a best-guess identification of the tool that produced the container (e.g., "iTunes",
+    /// "FFmpeg", or "Nero"). By default, the encoder tags of the current metadata revision are
+    /// used. A `FormatReader` may override this to also consider container-specific hints.
+    fn producer_hint(&mut self) -> Option<String> {
+        find_producer(self.metadata().current()?.tags())
+    }
+
+    /// Gets the ReplayGain values from the ReplayGain tags of the current metadata revision, if
+    /// any.
+    fn replay_gain(&mut self) -> Option<ReplayGain> {
+        ReplayGain::from_tags(self.metadata().current()?.tags())
+    }
+
+    /// Get the next packet from the container without waiting for the media source. Returns
+    /// `Ok(None)` if the media source signalled, with a `WouldBlock` IO error, that the rest of
+    /// the next packet is not available yet. The partially read packet is not consumed, so this
+    /// function may be called again once more data is available. The end of the stream is still
+    /// an end-of-stream error. If a partially read packet cannot be given back, for example if the
+    /// packet is larger than the buffer of the stream, an unsupported error is returned instead.
+    ///
+    /// By default, this is unsupported since a `FormatReader` must be able to give back a
+    /// partially read packet.
+    fn try_next_packet(&mut self) -> Result<Option<Packet>> {
+        unsupported_error("non-blocking packet reads are not supported")
+    }
+
     /// Get the next packet from the container.
+    ///
+    /// Once all packets have been read, `Error::EndOfStream` is returned.
     fn next_packet(&mut self) -> Result<Packet>;
 
-    /// Destroys the `FormatReader` and returns the underlying stream
+    /// Destroys the `FormatReader` and returns the underlying stream. The original `MediaSource`
+    /// may then be recovered using `MediaSourceStream::into_inner`.
     fn into_inner(self: Box<Self>) -> MediaSourceStream;
 }
 
+impl dyn FormatReader {
+    /// Gets an iterator over the remaining packets in the container. See [`Packets`] for details.
+    pub fn packets(&mut self) -> Packets<'_> {
+        Packets::new(self)
+    }
+}
+
+/// `Packets` is an iterator over the packets of a `FormatReader`.
+///
+/// Packets are read until the end of the stream is reached, at which point the iterator returns
+/// `None`. All other errors are returned as `Some(Err(..))`, and the iterator may continue to be
+/// used afterwards if the error is recoverable.
+pub struct Packets<'a> {
+    reader: &'a mut dyn FormatReader,
+    is_done: bool,
+}
+
+impl<'a> Packets<'a> {
+    /// Instantiate a `Packets` iterator over the remaining packets of a `FormatReader`.
+    pub fn new(reader: &'a mut dyn FormatReader) -> Self {
+        Packets { reader, is_done: false }
+    }
+}
+
+impl<'a> Iterator for Packets<'a> {
+    type Item = Result<Packet>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_done {
+            return None;
+        }
+
+        match self.reader.next_packet() {
+            Ok(packet) => Some(Ok(packet)),
+            Err(Error::EndOfStream) => {
+                self.is_done = true;
+                None
+            }
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
 /// A `Packet` contains a discrete amount of encoded data for a single codec bitstream. The exact
 /// amount of data is bounded, but not defined, and is dependant on the container and/or the
 /// encapsulated codec.
@@ -242,6 +559,16 @@ impl Packet {
         self.dur
     }
 
+    /// Get the presentation time of the packet given the `TimeBase` of its track.
+    pub fn pts_time(&self, time_base: TimeBase) -> Time {
+        time_base.calc_time(self.pts)
+    }
+
+    /// Get the duration of the packet as a `Time` given the `TimeBase` of its track.
+    pub fn duration_time(&self, time_base: TimeBase) -> Time {
+        time_base.calc_time(self.dur)
+    }
+
     /// Get the packet buffer as an immutable slice.
     pub fn buf(&self) -> &[u8] {
         &self.data
@@ -420,4 +747,169 @@ pub mod util {
         }
     }
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::codecs::{CodecDescriptor, CODEC_TYPE_FLAC, CODEC_TYPE_NULL, CODEC_TYPE_OPUS};
+    use crate::errors::{Error, unsupported_error};
+    use crate::meta::MetadataLog;
+    use crate::units::TimeBase;
+
+    struct TwoTrackReader {
+        tracks: Vec<Track>,
+        metadata: MetadataLog,
+    }
+
+    impl FormatReader for TwoTrackReader {
+        fn try_new(_: MediaSourceStream, _: &FormatOptions) -> Result<Self> {
+            let mut flac = CodecParameters::new();
+            flac.for_codec(CODEC_TYPE_FLAC)
+                .with_sample_rate(44_100)
+                .with_time_base(TimeBase::new(1, 44_100))
+                .with_n_frames(44_100 * 90)
+                .with_channels(Channels::FRONT_LEFT | Channels::FRONT_RIGHT);
+
+            let mut opus = CodecParameters::new();
+            opus.for_codec(CODEC_TYPE_OPUS)
+                .with_sample_rate(48_000)
+                .with_channels(Channels::FRONT_LEFT);
+
+            let mut commentary = Track::new(7, opus);
+            commentary.language = Some("eng".to_string());
+
+            Ok(TwoTrackReader {
+                tracks: vec![Track::new(1, flac), commentary],
+                metadata: Default::default(),
+            })
+        }
+
+        fn cues(&self) -> &[Cue] {
+            &[]
+        }
+
+        fn metadata(&mut self) -> Metadata<'_> {
+            self.metadata.metadata()
+        }
+
+        fn seek(&mut self, _: SeekMode, _: SeekTo) -> Result<SeekedTo> {
+            unsupported_error("seeking is not supported")
+        }
+
+        fn tracks(&self) -> &[Track] {
+            &self.tracks
+        }
+
+        fn next_packet(&mut self) -> Result<Packet> {
+            Err(Error::EndOfStream)
+        }
+
+        fn into_inner(self: Box<Self>) -> MediaSourceStream {
+            MediaSourceStream::new(Box::new(Cursor::new(Vec::new())), Default::default())
+        }
+    }
+
+    #[test]
+    fn verify_track_summaries() {
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(Vec::new())), Default::default());
+        let reader = TwoTrackReader::try_new(mss, &Default::default()).unwrap();
+
+        // Only the FLAC codec is registered.
+        let mut codecs = CodecRegistry::new();
+
+        codecs.register(&CodecDescriptor {
+            codec: CODEC_TYPE_FLAC,
+            short_name: "flac",
+            long_name: "Free Lossless Audio Codec",
+            inst_func: |_, _| unsupported_error("decoding is not supported"),
+        });
+
+        let summaries = reader.track_summaries(&codecs);
+
+        assert_eq!(summaries.len(), 2);
+
+        assert_eq!(summaries[0].index, 0);
+        assert_eq!(summaries[0].id, 1);
+        assert_eq!(summaries[0].kind, TrackKind::Audio);
+        assert!(summaries[0].codec == CODEC_TYPE_FLAC);
+        assert_eq!(summaries[0].codec_name, Some("flac"));
+        assert_eq!(summaries[0].sample_rate, Some(44_100));
+        assert_eq!(summaries[0].channels, Some(Channels::FRONT_LEFT | Channels::FRONT_RIGHT));
+        assert_eq!(summaries[0].n_frames, Some(44_100 * 90));
+        assert_eq!(summaries[0].duration, Some(Time::new(90, 0.0)));
+        assert_eq!(summaries[0].language, None);
+
+        assert_eq!(summaries[1].index, 1);
+        assert_eq!(summaries[1].id, 7);
+        assert_eq!(summaries[1].kind, TrackKind::Audio);
+        assert!(summaries[1].codec == CODEC_TYPE_OPUS);
+        assert_eq!(summaries[1].codec_name, None);
+        assert_eq!(summaries[1].sample_rate, Some(48_000));
+        assert_eq!(summaries[1].channels, Some(Channels::FRONT_LEFT));
+        assert_eq!(summaries[1].n_frames, None);
+        assert_eq!(summaries[1].duration, None);
+        assert_eq!(summaries[1].language.as_deref(), Some("eng"));
+    }
+
+    #[test]
+    fn verify_packet_time() {
+        let packet = Packet::new_from_slice(1, 66_150, 1_152, &[]);
+        let time_base = TimeBase::new(1, 44_100);
+
+        assert_eq!(packet.pts_time(time_base), Time::new(1, 0.5));
+        assert_eq!(f64::from(packet.pts_time(time_base)), 1.5);
+        assert_eq!(packet.duration_time(TimeBase::new(1, 1_152)), Time::new(1, 0.0));
+    }
+
+    #[test]
+    fn verify_track_selection() {
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(Vec::new())), Default::default());
+        let reader = TwoTrackReader::try_new(mss, &Default::default()).unwrap();
+
+        assert_eq!(reader.track(0).map(|track| track.id), Some(1));
+        assert_eq!(reader.track(1).map(|track| track.id), Some(7));
+        assert!(reader.track(2).is_none());
+
+        let opus = reader.tracks_with_codec(CODEC_TYPE_OPUS);
+
+        assert_eq!(opus.len(), 1);
+        assert_eq!(opus[0].id, 7);
+
+        assert!(reader.tracks_with_codec(CODEC_TYPE_NULL).is_empty());
+    }
+
+    #[test]
+    fn verify_packets_end_at_end_of_stream() {
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(Vec::new())), Default::default());
+        let mut reader: Box<dyn FormatReader> =
+            Box::new(TwoTrackReader::try_new(mss, &Default::default()).unwrap());
+
+        let mut packets = reader.packets();
+
+        assert!(packets.next().is_none());
+        assert!(packets.next().is_none());
+    }
+
+    #[test]
+    fn verify_total_duration() {
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(Vec::new())), Default::default());
+        let mut reader = TwoTrackReader::try_new(mss, &Default::default()).unwrap();
+
+        // The duration of the default track is 90 seconds.
+        assert_eq!(reader.total_duration(), Some(Time::new(90, 0.0)));
+
+        // Without a timebase, the length is assumed to be in frames at the sample rate.
+        reader.tracks[0].codec_params.time_base = None;
+        reader.tracks[0].codec_params.n_frames = Some(44_100 * 3 + 22_050);
+
+        assert_eq!(reader.total_duration(), Some(Time::new(3, 0.5)));
+
+        // The length is unknown.
+        reader.tracks[0].codec_params.n_frames = None;
+
+        assert_eq!(reader.total_duration(), None);
+    }
+}
@@ -88,6 +88,26 @@ impl MediaSourceStream {
         }
     }
 
+    /// Unwraps the `MediaSourceStream`, returning a `MediaSource` positioned at the current
+    /// position of the stream.
+    ///
+    /// The bytes buffered, but not yet read, must be given back to the underlying `MediaSource`.
+    /// If it is seekable, it is seeked backwards by that amount and returned. Otherwise, or if the
+    /// seek fails, the `MediaSourceStream` itself is returned so that no bytes are lost.
+    pub fn into_inner(mut self) -> Box<dyn MediaSource> {
+        let unread_len = self.unread_buffer_len() as i64;
+
+        if unread_len == 0 {
+            return self.inner;
+        }
+
+        if self.inner.is_seekable() && self.inner.seek(io::SeekFrom::Current(-unread_len)).is_ok() {
+            return self.inner;
+        }
+
+        Box::new(self)
+    }
+
     /// Get the number of bytes buffered but not yet read.
     ///
     /// Note: this is the maximum number of bytes that can be seeked forwards within the buffer.
@@ -111,6 +131,14 @@ impl MediaSourceStream {
         cmp::min(self.ring.len(), self.rel_pos as usize) - unread_len
     }
 
+    /// Gets the number of bytes that are guaranteed to remain buffered after being read.
+    ///
+    /// Note: this is the maximum number of bytes that can always be seeked backwards within the
+    /// buffer.
+    pub fn max_read_buffer_len(&self) -> usize {
+        self.ring.len() - Self::MAX_BLOCK_LEN
+    }
+
     /// Seek backwards with in the buffered data. This is deprecated, use `seek_buffered_rel()`
     /// instead.
     pub fn rewind(&mut self, len: usize) {
@@ -460,6 +488,7 @@ impl ReadBytes for MediaSourceStream {
 mod tests {
     use std::io::{Read, Cursor};
     use super::{MediaSourceStream, ReadBytes};
+    use crate::io::ReadOnlySource;
 
     /// Generate a random vector of bytes of the specified length using a PRNG.
     fn generate_random_bytes(len: usize) -> Box<[u8]> {
@@ -599,4 +628,30 @@ mod tests {
         assert_eq!(mss.read_u32().unwrap(), 2378776723);
         assert_eq!(mss.read_u64().unwrap(), 5170196279331153683);
     }
+
+    #[test]
+    fn verify_mss_into_inner() {
+        let data = generate_random_bytes(96 * 1024);
+
+        // A seekable source is seeked back to the position of the stream.
+        let source = Cursor::new(data.clone());
+        let mut mss = MediaSourceStream::new(Box::new(source), Default::default());
+        mss.ignore_bytes(1234).unwrap();
+
+        let mut rest = Vec::new();
+        mss.into_inner().read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, &data[1234..]);
+
+        // The stream of a non-seekable source keeps its buffered bytes.
+        let source = ReadOnlySource::new(Cursor::new(data.clone()));
+        let mut mss = MediaSourceStream::new(Box::new(source), Default::default());
+        mss.ignore_bytes(1234).unwrap();
+
+        let mut inner = mss.into_inner();
+        assert!(!inner.is_seekable());
+
+        let mut rest = Vec::new();
+        inner.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, &data[1234..]);
+    }
 }
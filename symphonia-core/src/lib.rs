@@ -16,6 +16,8 @@ pub mod formats;
 pub mod io;
 pub mod meta;
 pub mod probe;
+pub mod resample;
 pub mod sample;
 pub mod units;
-pub mod util;
\ No newline at end of file
+pub mod util;
+pub mod verify;
\ No newline at end of file
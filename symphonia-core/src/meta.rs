@@ -11,8 +11,11 @@ use std::borrow::Cow;
 use std::collections::VecDeque;
 use std::convert::From;
 use std::fmt;
+use std::collections::HashMap;
 use std::num::NonZeroU32;
 
+use lazy_static::lazy_static;
+
 use crate::errors::Result;
 use crate::io::MediaSourceStream;
 
@@ -23,7 +26,7 @@ use crate::io::MediaSourceStream;
 ///
 /// All limits can be defaulted to a reasonable value specific to the situation. These defaults will
 /// generally not break any normal streams.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub enum Limit {
     /// Do not impose any limit.
     None,
@@ -52,7 +55,7 @@ impl Default for Limit {
 }
 
 /// `MetadataOptions` is a common set of options that all metadata readers use.
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone, Debug)]
 pub struct MetadataOptions {
     /// The maximum size limit in bytes that a tag may occupy in memory once decoded. Tags exceeding
     /// this limit will be skipped by the demuxer. Take note that tags in-memory are stored as UTF-8
@@ -61,6 +64,24 @@ pub struct MetadataOptions {
 
     /// The maximum size limit in bytes that a visual (picture) may occupy.
     pub limit_visual_bytes: Limit,
+
+    /// If `false`, visuals (pictures), such as embedded cover art, are skipped instead of read.
+    /// Default: `true`.
+    pub read_visuals: bool,
+
+    /// If `false`, tags are skipped instead of read. Default: `true`.
+    pub read_tags: bool,
+}
+
+impl Default for MetadataOptions {
+    fn default() -> Self {
+        MetadataOptions {
+            limit_metadata_bytes: Default::default(),
+            limit_visual_bytes: Default::default(),
+            read_visuals: true,
+            read_tags: true,
+        }
+    }
 }
 
 /// `StandardVisualKey` is an enumeration providing standardized keys for common visual dispositions.
@@ -95,7 +116,7 @@ pub enum StandardVisualKey {
 /// `StandardTagKey` is an enumeration providing standardized keys for common tag types.
 /// A tag reader may assign a `StandardTagKey` to a `Tag` if the tag's key is generally
 /// accepted to map to a specific usage.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum StandardTagKey {
     AcoustidFingerprint,
     AcoustidId,
@@ -108,6 +129,7 @@ pub enum StandardTagKey {
     Compilation,
     Composer,
     Conductor,
+    ContentAdvisory,
     ContentGroup,
     Copyright,
     Date,
@@ -121,6 +143,7 @@ pub enum StandardTagKey {
     EncodingDate,
     Engineer,
     Ensemble,
+    GaplessPlayback,
     Genre,
     IdentAsin,
     IdentBarcode,
@@ -186,6 +209,7 @@ pub enum StandardTagKey {
     SortArtist,
     SortComposer,
     SortTrackTitle,
+    SoundCheckGain,
     TaggingDate,
     TrackNumber,
     TrackSubtitle,
@@ -210,11 +234,185 @@ pub enum StandardTagKey {
     Writer,
 }
 
+/// Implements the human-readable labels of the standard tag keys.
+macro_rules! impl_standard_tag_key_labels {
+    ($($key:ident => $label:expr,)*) => {
+        impl StandardTagKey {
+            /// All standard tag keys.
+            const ALL: &'static [StandardTagKey] = &[$(StandardTagKey::$key,)*];
+
+            /// Gets a human-readable label for the standard tag key (e.g., "Album Artist").
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(StandardTagKey::$key => $label,)*
+                }
+            }
+        }
+    };
+}
+
+impl_standard_tag_key_labels! {
+    AcoustidFingerprint         => "AcoustID Fingerprint",
+    AcoustidId                  => "AcoustID ID",
+    Album                       => "Album",
+    AlbumArtist                 => "Album Artist",
+    Arranger                    => "Arranger",
+    Artist                      => "Artist",
+    Bpm                         => "BPM",
+    Comment                     => "Comment",
+    Compilation                 => "Compilation",
+    Composer                    => "Composer",
+    Conductor                   => "Conductor",
+    ContentAdvisory             => "Content Advisory",
+    ContentGroup                => "Content Group",
+    Copyright                   => "Copyright",
+    Date                        => "Date",
+    Description                 => "Description",
+    DiscNumber                  => "Disc Number",
+    DiscSubtitle                => "Disc Subtitle",
+    DiscTotal                   => "Disc Total",
+    EncodedBy                   => "Encoded By",
+    Encoder                     => "Encoder",
+    EncoderSettings             => "Encoder Settings",
+    EncodingDate                => "Encoding Date",
+    Engineer                    => "Engineer",
+    Ensemble                    => "Ensemble",
+    GaplessPlayback             => "Gapless Playback",
+    Genre                       => "Genre",
+    IdentAsin                   => "ASIN",
+    IdentBarcode                => "Barcode",
+    IdentCatalogNumber          => "Catalog Number",
+    IdentEanUpn                 => "EAN/UPN",
+    IdentIsrc                   => "ISRC",
+    IdentPn                     => "Part Number",
+    IdentPodcast                => "Podcast ID",
+    IdentUpc                    => "UPC",
+    Label                       => "Label",
+    Language                    => "Language",
+    License                     => "License",
+    Lyricist                    => "Lyricist",
+    Lyrics                      => "Lyrics",
+    MediaFormat                 => "Media Format",
+    MixDj                       => "Mix DJ",
+    MixEngineer                 => "Mix Engineer",
+    Mood                        => "Mood",
+    MovementName                => "Movement Name",
+    MovementNumber              => "Movement Number",
+    MusicBrainzAlbumArtistId    => "MusicBrainz Album Artist ID",
+    MusicBrainzAlbumId          => "MusicBrainz Album ID",
+    MusicBrainzArtistId         => "MusicBrainz Artist ID",
+    MusicBrainzDiscId           => "MusicBrainz Disc ID",
+    MusicBrainzGenreId          => "MusicBrainz Genre ID",
+    MusicBrainzLabelId          => "MusicBrainz Label ID",
+    MusicBrainzOriginalAlbumId  => "MusicBrainz Original Album ID",
+    MusicBrainzOriginalArtistId => "MusicBrainz Original Artist ID",
+    MusicBrainzRecordingId      => "MusicBrainz Recording ID",
+    MusicBrainzReleaseGroupId   => "MusicBrainz Release Group ID",
+    MusicBrainzReleaseStatus    => "MusicBrainz Release Status",
+    MusicBrainzReleaseTrackId   => "MusicBrainz Release Track ID",
+    MusicBrainzReleaseType      => "MusicBrainz Release Type",
+    MusicBrainzTrackId          => "MusicBrainz Track ID",
+    MusicBrainzWorkId           => "MusicBrainz Work ID",
+    Opus                        => "Opus",
+    OriginalAlbum               => "Original Album",
+    OriginalArtist              => "Original Artist",
+    OriginalDate                => "Original Date",
+    OriginalFile                => "Original File",
+    OriginalWriter              => "Original Writer",
+    Owner                       => "Owner",
+    Part                        => "Part",
+    PartTotal                   => "Part Total",
+    Performer                   => "Performer",
+    Podcast                     => "Podcast",
+    PodcastCategory             => "Podcast Category",
+    PodcastDescription          => "Podcast Description",
+    PodcastKeywords             => "Podcast Keywords",
+    Producer                    => "Producer",
+    PurchaseDate                => "Purchase Date",
+    Rating                      => "Rating",
+    ReleaseCountry              => "Release Country",
+    ReleaseDate                 => "Release Date",
+    Remixer                     => "Remixer",
+    ReplayGainAlbumGain         => "ReplayGain Album Gain",
+    ReplayGainAlbumPeak         => "ReplayGain Album Peak",
+    ReplayGainTrackGain         => "ReplayGain Track Gain",
+    ReplayGainTrackPeak         => "ReplayGain Track Peak",
+    Script                      => "Script",
+    SortAlbum                   => "Sort Album",
+    SortAlbumArtist             => "Sort Album Artist",
+    SortArtist                  => "Sort Artist",
+    SortComposer                => "Sort Composer",
+    SortTrackTitle              => "Sort Track Title",
+    SoundCheckGain              => "Sound Check Gain",
+    TaggingDate                 => "Tagging Date",
+    TrackNumber                 => "Track Number",
+    TrackSubtitle               => "Track Subtitle",
+    TrackTitle                  => "Track Title",
+    TrackTotal                  => "Track Total",
+    TvEpisode                   => "TV Episode",
+    TvEpisodeTitle              => "TV Episode Title",
+    TvNetwork                   => "TV Network",
+    TvSeason                    => "TV Season",
+    TvShowTitle                 => "TV Show Title",
+    Url                         => "URL",
+    UrlArtist                   => "Artist URL",
+    UrlCopyright                => "Copyright URL",
+    UrlInternetRadio            => "Internet Radio URL",
+    UrlLabel                    => "Label URL",
+    UrlOfficial                 => "Official URL",
+    UrlPayment                  => "Payment URL",
+    UrlPodcast                  => "Podcast URL",
+    UrlPurchase                 => "Purchase URL",
+    UrlSource                   => "Source URL",
+    Version                     => "Version",
+    Writer                      => "Writer",
+}
+
+/// Common names of standard tag keys that are neither their label, nor their name.
+const STANDARD_TAG_KEY_ALIASES: &[(&str, StandardTagKey)] = &[
+    ("title", StandardTagKey::TrackTitle),
+    ("track", StandardTagKey::TrackNumber),
+    ("disc", StandardTagKey::DiscNumber),
+];
+
+/// Normalizes a standard tag key label, or name, for comparison by ignoring case, spaces, and
+/// punctuation.
+fn normalize_tag_key(key: &str) -> String {
+    key.chars().filter(char::is_ascii_alphanumeric).map(|c| c.to_ascii_lowercase()).collect()
+}
+
+impl std::str::FromStr for StandardTagKey {
+    type Err = ();
+
+    /// Finds the standard tag key with the given label, name, or common name (e.g., "title"). The
+    /// comparison ignores case, spaces, and punctuation, so "Album Artist", "album-artist", and
+    /// "AlbumArtist" all match.
+    fn from_str(key: &str) -> std::result::Result<Self, Self::Err> {
+        let key = normalize_tag_key(key);
+
+        let alias = STANDARD_TAG_KEY_ALIASES.iter().find(|(alias, _)| *alias == key);
+
+        if let Some((_, std_key)) = alias {
+            return Ok(*std_key);
+        }
+
+        StandardTagKey::ALL
+            .iter()
+            .find(|std_key| {
+                normalize_tag_key(std_key.as_str()) == key
+                    || normalize_tag_key(&format!("{:?}", std_key)) == key
+            })
+            .copied()
+            .ok_or(())
+    }
+}
+
 /// A `Tag` value.
 ///
 /// Note: The data types in this enumeration are a generalization. Depending on the particular tag
 /// format, the actual data type a specific tag may have a lesser width or encoding than the data
 /// type in this enumeration.
+#[derive(Clone)]
 pub enum Value {
     /// A binary buffer.
     Binary(Box<[u8]>),
@@ -289,6 +487,7 @@ impl fmt::Display for Value {
 }
 
 /// A `Tag` encapsulates a key-value pair of metadata.
+#[derive(Clone)]
 pub struct Tag {
     /// If the `Tag`'s key string is commonly associated with a typical type, meaning, or purpose,
     /// then if recognized a `StandardTagKey` will be assigned to this `Tag`.
@@ -339,6 +538,241 @@ impl fmt::Display for Tag {
     }
 }
 
+lazy_static! {
+    static ref VORBIS_COMMENT_MAP: HashMap<&'static str, StandardTagKey> = {
+        let mut m = HashMap::new();
+        m.insert("album artist"                , StandardTagKey::AlbumArtist);
+        m.insert("album"                       , StandardTagKey::Album);
+        m.insert("albumartist"                 , StandardTagKey::AlbumArtist);
+        m.insert("albumartistsort"             , StandardTagKey::SortAlbumArtist);
+        m.insert("albumsort"                   , StandardTagKey::SortAlbum);
+        m.insert("arranger"                    , StandardTagKey::Arranger);
+        m.insert("artist"                      , StandardTagKey::Artist);
+        m.insert("artistsort"                  , StandardTagKey::SortArtist);
+        // TODO: Is Author a synonym for Writer?
+        m.insert("author"                      , StandardTagKey::Writer);
+        m.insert("barcode"                     , StandardTagKey::IdentBarcode);
+        m.insert("bpm"                         , StandardTagKey::Bpm);
+        m.insert("catalog #"                   , StandardTagKey::IdentCatalogNumber);
+        m.insert("catalog"                     , StandardTagKey::IdentCatalogNumber);
+        m.insert("catalognumber"               , StandardTagKey::IdentCatalogNumber);
+        m.insert("catalogue #"                 , StandardTagKey::IdentCatalogNumber);
+        m.insert("comment"                     , StandardTagKey::Comment);
+        m.insert("compileation"                , StandardTagKey::Compilation);
+        m.insert("composer"                    , StandardTagKey::Composer);
+        m.insert("conductor"                   , StandardTagKey::Conductor);
+        m.insert("copyright"                   , StandardTagKey::Copyright);
+        m.insert("date"                        , StandardTagKey::Date);
+        m.insert("description"                 , StandardTagKey::Description);
+        m.insert("disc"                        , StandardTagKey::DiscNumber);
+        m.insert("discnumber"                  , StandardTagKey::DiscNumber);
+        m.insert("discsubtitle"                , StandardTagKey::DiscSubtitle);
+        m.insert("disctotal"                   , StandardTagKey::DiscTotal);
+        m.insert("disk"                        , StandardTagKey::DiscNumber);
+        m.insert("disknumber"                  , StandardTagKey::DiscNumber);
+        m.insert("disksubtitle"                , StandardTagKey::DiscSubtitle);
+        m.insert("disktotal"                   , StandardTagKey::DiscTotal);
+        m.insert("djmixer"                     , StandardTagKey::MixDj);
+        m.insert("ean/upn"                     , StandardTagKey::IdentEanUpn);
+        m.insert("encoded-by"                  , StandardTagKey::EncodedBy);
+        m.insert("encoder settings"            , StandardTagKey::EncoderSettings);
+        m.insert("encoder"                     , StandardTagKey::Encoder);
+        m.insert("encoding"                    , StandardTagKey::EncoderSettings);
+        m.insert("engineer"                    , StandardTagKey::Engineer);
+        m.insert("ensemble"                    , StandardTagKey::Ensemble);
+        m.insert("genre"                       , StandardTagKey::Genre);
+        m.insert("isrc"                        , StandardTagKey::IdentIsrc);
+        m.insert("language"                    , StandardTagKey::Language);
+        m.insert("label"                       , StandardTagKey::Label);
+        m.insert("license"                     , StandardTagKey::License);
+        m.insert("lyricist"                    , StandardTagKey::Lyricist);
+        m.insert("lyrics"                      , StandardTagKey::Lyrics);
+        m.insert("media"                       , StandardTagKey::MediaFormat);
+        m.insert("mixer"                       , StandardTagKey::MixEngineer);
+        m.insert("mood"                        , StandardTagKey::Mood);
+        m.insert("musicbrainz_albumartistid"   , StandardTagKey::MusicBrainzAlbumArtistId);
+        m.insert("musicbrainz_albumid"         , StandardTagKey::MusicBrainzAlbumId);
+        m.insert("musicbrainz_albumstatus"     , StandardTagKey::MusicBrainzReleaseStatus);
+        m.insert("musicbrainz_albumtype"       , StandardTagKey::MusicBrainzReleaseType);
+        m.insert("musicbrainz_artistid"        , StandardTagKey::MusicBrainzArtistId);
+        m.insert("musicbrainz_discid"          , StandardTagKey::MusicBrainzDiscId);
+        m.insert("musicbrainz_originalalbumid" , StandardTagKey::MusicBrainzOriginalAlbumId);
+        m.insert("musicbrainz_originalartistid", StandardTagKey::MusicBrainzOriginalArtistId);
+        m.insert("musicbrainz_recordingid"     , StandardTagKey::MusicBrainzRecordingId);
+        m.insert("musicbrainz_releasegroupid"  , StandardTagKey::MusicBrainzReleaseGroupId);
+        m.insert("musicbrainz_releasetrackid"  , StandardTagKey::MusicBrainzReleaseTrackId);
+        m.insert("musicbrainz_trackid"         , StandardTagKey::MusicBrainzTrackId);
+        m.insert("musicbrainz_workid"          , StandardTagKey::MusicBrainzWorkId);
+        m.insert("opus"                        , StandardTagKey::Opus);
+        m.insert("organization"                , StandardTagKey::Label);
+        m.insert("originaldate"                , StandardTagKey::OriginalDate);
+        m.insert("part"                        , StandardTagKey::Part);
+        m.insert("performer"                   , StandardTagKey::Performer);
+        m.insert("producer"                    , StandardTagKey::Producer);
+        m.insert("productnumber"               , StandardTagKey::IdentPn);
+        // TODO: Is Publisher a synonym for Label?
+        m.insert("publisher"                   , StandardTagKey::Label);
+        m.insert("rating"                      , StandardTagKey::Rating);
+        m.insert("releasecountry"              , StandardTagKey::ReleaseCountry);
+        m.insert("releasestatus"               , StandardTagKey::MusicBrainzReleaseStatus);
+        m.insert("releasetype"                 , StandardTagKey::MusicBrainzReleaseType);
+        m.insert("remixer"                     , StandardTagKey::Remixer);
+        m.insert("replaygain_album_gain"       , StandardTagKey::ReplayGainAlbumGain);
+        m.insert("replaygain_album_peak"       , StandardTagKey::ReplayGainAlbumPeak);
+        m.insert("replaygain_track_gain"       , StandardTagKey::ReplayGainTrackGain);
+        m.insert("replaygain_track_peak"       , StandardTagKey::ReplayGainTrackPeak);
+        m.insert("script"                      , StandardTagKey::Script);
+        m.insert("subtitle"                    , StandardTagKey::TrackSubtitle);
+        m.insert("title"                       , StandardTagKey::TrackTitle);
+        m.insert("titlesort"                   , StandardTagKey::SortTrackTitle);
+        m.insert("totaldiscs"                  , StandardTagKey::DiscTotal);
+        m.insert("totaltracks"                 , StandardTagKey::TrackTotal);
+        m.insert("tracknumber"                 , StandardTagKey::TrackNumber);
+        m.insert("tracktotal"                  , StandardTagKey::TrackTotal);
+        m.insert("upc"                         , StandardTagKey::IdentUpc);
+        m.insert("version"                     , StandardTagKey::Version);
+        m.insert("writer"                      , StandardTagKey::Writer);
+        m.insert("year"                        , StandardTagKey::Date);
+        m
+    };
+}
+
+/// Maps a Vorbis Comment field name to a `StandardTagKey`, if one exists. Field names are
+/// case-insensitive. Vorbis Comments are used by FLAC and OGG, and by many other formats for
+/// free-form tags.
+pub fn map_vorbis_comment(key: &str) -> Option<StandardTagKey> {
+    VORBIS_COMMENT_MAP.get(key.to_ascii_lowercase().as_str()).copied()
+}
+
+/// Gets a best-guess name of the tool that produced a file from the value of an encoder tag.
+/// Well-known encoders and muxers are normalized to a common name, otherwise the trimmed tag value
+/// is returned. Returns `None` if the value is empty.
+pub fn producer_name(encoder: &str) -> Option<String> {
+    // Pairs of a lowercase pattern found in the tag value, and the normalized tool name.
+    const PRODUCERS: &[(&str, &str)] = &[
+        ("lavf", "FFmpeg"),
+        ("lavc", "FFmpeg"),
+        ("ffmpeg", "FFmpeg"),
+        ("itunes", "iTunes"),
+        ("qaac", "qaac"),
+        ("fdk", "FDK AAC"),
+        ("faac", "libfaac"),
+        ("nero", "Nero"),
+        ("lame", "LAME"),
+        ("handbrake", "HandBrake"),
+        ("gpac", "GPAC"),
+        ("libvorbis", "libvorbis"),
+        ("libopus", "libopus"),
+        ("libflac", "libFLAC"),
+    ];
+
+    let encoder = encoder.trim();
+
+    if encoder.is_empty() {
+        return None;
+    }
+
+    let lower = encoder.to_lowercase();
+
+    match PRODUCERS.iter().find(|(pattern, _)| lower.contains(pattern)) {
+        Some((_, name)) => Some(name.to_string()),
+        None => Some(encoder.to_string()),
+    }
+}
+
+/// Gets a best-guess name of the tool that produced a file from the encoder tags in a list of tags.
+/// The `Encoder` tag is preferred over the `EncodedBy` tag.
+pub fn find_producer(tags: &[Tag]) -> Option<String> {
+    let producer = |tag: &Tag| match tag.value {
+        Value::String(ref value) => producer_name(value),
+        _ => None,
+    };
+
+    let encoder = tags.iter().filter(|tag| matches!(tag.std_key, Some(StandardTagKey::Encoder)));
+    let encoded_by =
+        tags.iter().filter(|tag| matches!(tag.std_key, Some(StandardTagKey::EncodedBy)));
+
+    encoder.chain(encoded_by).find_map(producer)
+}
+
+/// `ReplayGain` contains the ReplayGain loudness normalization values of a track and its album.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ReplayGain {
+    /// The gain, in decibels, to apply to the track.
+    pub track_gain_db: Option<f32>,
+    /// The peak sample amplitude of the track, where 1.0 is full scale.
+    pub track_peak: Option<f32>,
+    /// The gain, in decibels, to apply to the album.
+    pub album_gain_db: Option<f32>,
+    /// The peak sample amplitude of the album, where 1.0 is full scale.
+    pub album_peak: Option<f32>,
+}
+
+impl ReplayGain {
+    /// Gets the ReplayGain values from the ReplayGain tags in a list of tags. Returns `None` if
+    /// there are no valid ReplayGain tags.
+    pub fn from_tags(tags: &[Tag]) -> Option<ReplayGain> {
+        let mut replay_gain = ReplayGain::default();
+
+        for tag in tags {
+            let value = match &tag.value {
+                Value::String(value) => value,
+                _ => continue,
+            };
+
+            match tag.std_key {
+                Some(StandardTagKey::ReplayGainTrackGain) => {
+                    replay_gain.track_gain_db = parse_replay_gain(value);
+                }
+                Some(StandardTagKey::ReplayGainTrackPeak) => {
+                    replay_gain.track_peak = parse_replay_gain_peak(value);
+                }
+                Some(StandardTagKey::ReplayGainAlbumGain) => {
+                    replay_gain.album_gain_db = parse_replay_gain(value);
+                }
+                Some(StandardTagKey::ReplayGainAlbumPeak) => {
+                    replay_gain.album_peak = parse_replay_gain_peak(value);
+                }
+                _ => (),
+            }
+        }
+
+        if replay_gain == ReplayGain::default() {
+            None
+        }
+        else {
+            Some(replay_gain)
+        }
+    }
+}
+
+/// Parses a ReplayGain decimal number. Both a period and a comma are accepted as the decimal
+/// separator since some taggers write the number using the separator of the user's locale.
+fn parse_replay_gain_number(value: &str) -> Option<f32> {
+    let value = value.trim().replace(',', ".");
+    value.parse::<f32>().ok().filter(|value| value.is_finite())
+}
+
+/// Parses a ReplayGain gain value (e.g., "-6.48 dB") and returns the gain in decibels.
+pub fn parse_replay_gain(value: &str) -> Option<f32> {
+    let value = value.trim();
+
+    // The unit suffix is optional.
+    let value = match value.len().checked_sub(2) {
+        Some(i) if value.is_char_boundary(i) && value[i..].eq_ignore_ascii_case("db") => {
+            &value[..i]
+        }
+        _ => value,
+    };
+
+    parse_replay_gain_number(value)
+}
+
+/// Parses a ReplayGain peak value (e.g., "0.988525") and returns the peak amplitude.
+pub fn parse_replay_gain_peak(value: &str) -> Option<f32> {
+    parse_replay_gain_number(value).filter(|&peak| peak >= 0.0)
+}
+
 /// A 2 dimensional (width and height) size type.
 #[derive(Copy, Clone)]
 pub struct Size {
@@ -512,4 +946,106 @@ pub trait MetadataReader: Send {
 
     /// Read all metadata and return it if successful.
     fn read_all(&mut self, reader: &mut MediaSourceStream) -> Result<MetadataRevision>;
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_standard_tag_key_labels() {
+        assert_eq!(StandardTagKey::AlbumArtist.as_str(), "Album Artist");
+        assert_eq!(StandardTagKey::IdentIsrc.as_str(), "ISRC");
+        assert_eq!(StandardTagKey::MusicBrainzTrackId.as_str(), "MusicBrainz Track ID");
+
+        assert!(matches!("Album Artist".parse(), Ok(StandardTagKey::AlbumArtist)));
+        assert!(matches!("album-artist".parse(), Ok(StandardTagKey::AlbumArtist)));
+        assert!(matches!("ISRC".parse(), Ok(StandardTagKey::IdentIsrc)));
+        assert!(matches!("IdentIsrc".parse(), Ok(StandardTagKey::IdentIsrc)));
+        assert!(matches!("replaygain_track_gain".parse(), Ok(StandardTagKey::ReplayGainTrackGain)));
+        assert!(matches!("Title".parse(), Ok(StandardTagKey::TrackTitle)));
+        assert!("Not A Key".parse::<StandardTagKey>().is_err());
+
+        // Every label, and name, maps back to its own key.
+        for std_key in StandardTagKey::ALL {
+            let name = format!("{:?}", std_key);
+
+            let from_label = std_key.as_str().parse::<StandardTagKey>().unwrap();
+            assert_eq!(format!("{:?}", from_label), name);
+
+            let from_name = name.parse::<StandardTagKey>().unwrap();
+            assert_eq!(format!("{:?}", from_name), name);
+        }
+    }
+
+    #[test]
+    fn verify_parse_replay_gain() {
+        assert_eq!(parse_replay_gain("-6.48 dB"), Some(-6.48));
+        assert_eq!(parse_replay_gain("+2.5 dB"), Some(2.5));
+        assert_eq!(parse_replay_gain(" -0.01dB "), Some(-0.01));
+        assert_eq!(parse_replay_gain("3.20 DB"), Some(3.2));
+        assert_eq!(parse_replay_gain("-7.1"), Some(-7.1));
+
+        // The decimal separator of the user's locale may have been used.
+        assert_eq!(parse_replay_gain("-6,48 dB"), Some(-6.48));
+
+        assert_eq!(parse_replay_gain(""), None);
+        assert_eq!(parse_replay_gain("dB"), None);
+        assert_eq!(parse_replay_gain("loud dB"), None);
+        assert_eq!(parse_replay_gain("inf dB"), None);
+
+        assert_eq!(parse_replay_gain_peak("0.988525"), Some(0.988525));
+        assert_eq!(parse_replay_gain_peak("1,000000"), Some(1.0));
+        assert_eq!(parse_replay_gain_peak("-1.0"), None);
+    }
+
+    #[test]
+    fn verify_replay_gain_from_tags() {
+        let tag = |std_key, key: &str, value: &str| Tag::new(Some(std_key), key, value.into());
+
+        let tags = [
+            tag(StandardTagKey::ReplayGainTrackGain, "replaygain_track_gain", "-6.48 dB"),
+            tag(StandardTagKey::ReplayGainTrackPeak, "replaygain_track_peak", "0.988525"),
+            tag(
+                StandardTagKey::ReplayGainAlbumGain,
+                "com.apple.iTunes:replaygain_album_gain",
+                "-7.02 dB",
+            ),
+            tag(StandardTagKey::Artist, "artist", "-1.00 dB"),
+        ];
+
+        let replay_gain = ReplayGain::from_tags(&tags).unwrap();
+
+        assert_eq!(replay_gain.track_gain_db, Some(-6.48));
+        assert_eq!(replay_gain.track_peak, Some(0.988525));
+        assert_eq!(replay_gain.album_gain_db, Some(-7.02));
+        assert_eq!(replay_gain.album_peak, None);
+
+        assert_eq!(ReplayGain::from_tags(&tags[3..]), None);
+    }
+
+    #[test]
+    fn verify_map_vorbis_comment() {
+        use StandardTagKey::*;
+
+        let map = map_vorbis_comment;
+
+        assert!(matches!(map("TITLE"), Some(TrackTitle)));
+        assert!(matches!(map("Artist"), Some(Artist)));
+        assert!(matches!(map("album"), Some(Album)));
+        assert!(matches!(map("ALBUMARTIST"), Some(AlbumArtist)));
+        assert!(matches!(map("DATE"), Some(Date)));
+        assert!(matches!(map("YEAR"), Some(Date)));
+        assert!(matches!(map("TRACKNUMBER"), Some(TrackNumber)));
+        assert!(matches!(map("TOTALTRACKS"), Some(TrackTotal)));
+        assert!(matches!(map("DISCNUMBER"), Some(DiscNumber)));
+        assert!(matches!(map("REPLAYGAIN_TRACK_GAIN"), Some(ReplayGainTrackGain)));
+        assert!(matches!(map("REPLAYGAIN_ALBUM_PEAK"), Some(ReplayGainAlbumPeak)));
+        assert!(matches!(map("MUSICBRAINZ_TRACKID"), Some(MusicBrainzTrackId)));
+        assert!(matches!(map("MUSICBRAINZ_ALBUMARTISTID"), Some(MusicBrainzAlbumArtistId)));
+        assert!(matches!(map("VERSION"), Some(Version)));
+
+        assert!(map("X-CUSTOM").is_none());
+        assert!(map("").is_none());
+    }
+}
@@ -103,9 +103,11 @@ pub struct Descriptor {
     pub extensions: &'static [&'static str],
     /// A list of case-insensitive MIME types that are generally used by the format.
     pub mime_types: &'static [&'static str],
-    /// A byte-string start-of-stream marker that will be searched for within the stream.
+    /// A byte-string start-of-stream marker that will be searched for within the stream. A format
+    /// without any markers is never detected, and must be forced using a `Hint`.
     pub markers: &'static [&'static [u8]],
-    /// A function to score a context buffer.
+    /// A function to score a context buffer starting with one of the markers. A score of 0
+    /// indicates the format is not supported.
     pub score: fn(&[u8]) -> u8,
     /// An instantiation function.
     pub inst: Instantiate,
@@ -118,7 +120,9 @@ pub trait QueryDescriptor {
     fn query() -> &'static [Descriptor];
 
     /// Using the provided context buffer, score calculate and returns a value between 0 and 255
-    /// indicating the confidence of the reader in decoding or parsing the source stream.
+    /// indicating the confidence of the reader in decoding or parsing the source stream. If more
+    /// than one registered reader matches a stream marker, the reader with the highest score is
+    /// chosen. A score of 0 indicates the source stream is not supported by the reader.
     fn score(context: &[u8]) -> u8;
 }
 
@@ -129,9 +133,13 @@ pub trait QueryDescriptor {
 /// from a file path, HTTP header, email  attachment metadata, etc. `Hint`s are optional, and won't
 /// lead the probe astray if they're wrong, but they may provide an informed initial guess and
 /// optimize the guessing process siginificantly especially as more formats are registered.
+///
+/// Unlike other hints, a forced format is always obeyed. The probe will only instantiate the
+/// `FormatReader` of the forced format, and fails if that reader does not support the stream.
 pub struct Hint {
     extension: Option<String>,
     mime_type: Option<String>,
+    format: Option<String>,
 }
 
 impl Hint {
@@ -140,6 +148,7 @@ impl Hint {
         Hint {
             extension: None,
             mime_type: None,
+            format: None,
         }
     }
 
@@ -150,10 +159,22 @@ impl Hint {
     }
 
     /// Add a MIME/Media-type `Hint`.
-    pub fn mime_type(&mut self, mime_type: &str) -> &mut Self {
+    pub fn with_mime(&mut self, mime_type: &str) -> &mut Self {
         self.mime_type = Some(mime_type.to_owned());
         self
     }
+
+    /// Add a MIME/Media-type `Hint`. Equivalent to `with_mime`.
+    pub fn mime_type(&mut self, mime_type: &str) -> &mut Self {
+        self.with_mime(mime_type)
+    }
+
+    /// Force the format with the given case-insensitive short name (e.g., "flac") to be used,
+    /// regardless of the stream's extension or the scores of other formats.
+    pub fn force_format(&mut self, short_name: &str) -> &mut Self {
+        self.format = Some(short_name.to_owned());
+        self
+    }
 }
 
 /// Metadata that came from the `metadata` field of [`ProbeResult`].
@@ -231,8 +252,47 @@ impl Probe {
         self.registered.push(*descriptor);
     }
 
+    /// Finds the registered `Descriptor` with a marker at the start of the context buffer that
+    /// scores the context the highest. If multiple descriptors have the same score, the first
+    /// registered is returned. If a format is forced, the descriptors of all other formats are
+    /// ignored.
+    fn best_match(&self, context: &[u8], forced: Option<&str>) -> Option<&Descriptor> {
+        let mut best: Option<(&Descriptor, u8)> = None;
+
+        for registered in &self.registered {
+            let is_match = registered.markers.iter().any(|marker| context.starts_with(marker));
+
+            if !is_match {
+                continue;
+            }
+
+            if let (Some(forced), Instantiate::Format(_)) = (forced, registered.inst) {
+                if !registered.short_name.eq_ignore_ascii_case(forced) {
+                    continue;
+                }
+            }
+
+            let score = (registered.score)(context);
+
+            info!("{} scored {} for the stream marker.", registered.short_name, score);
+
+            // A score of 0 indicates the descriptor does not support the stream.
+            if score > best.map_or(0, |(_, best_score)| best_score) {
+                best = Some((registered, score));
+            }
+        }
+
+        best.map(|(registered, _)| registered)
+    }
+
     /// Searches the provided `MediaSourceStream` for metadata or a container format.
     pub fn next(&self, mss: &mut MediaSourceStream) -> Result<Instantiate> {
+        self.search(mss, None)
+    }
+
+    /// Searches the provided `MediaSourceStream` for metadata, or a container format. If a format
+    /// is forced, only that container format is searched for.
+    fn search(&self, mss: &mut MediaSourceStream, forced: Option<&str>) -> Result<Instantiate> {
         let mut win = 0u16;
 
         let init_pos = mss.pos();
@@ -269,33 +329,25 @@ impl Probe {
                     count,
                 );
 
-                // Search for registered markers in the 16-byte window.
-                for registered in &self.registered {
-                    for marker in registered.markers {
-                        let len = marker.len();
+                // Search for registered markers in the 16-byte window. If a match is found, return
+                // the instantiate of the highest scoring match.
+                if let Some(registered) = self.best_match(&context, forced) {
+                    // Re-align the stream to the start of the marker.
+                    mss.rewind(16);
 
-                        // If a match is found, return the instantiate.
-                        if context[0..len] == **marker {
-                            // Re-align the stream to the start of the marker.
-                            mss.rewind(16);
+                    info!(
+                        "found the stream marker for {} @ {}+{} bytes.",
+                        registered.short_name,
+                        init_pos,
+                        count,
+                    );
 
-                            // TODO: Implement scoring.
-
-                            info!(
-                                "found the stream marker {:x?} @ {}+{} bytes.",
-                                &context[0..len],
-                                init_pos,
-                                count,
-                            );
-
-                            return Ok(registered.inst)
-                        }
-                    }
+                    return Ok(registered.inst)
                 }
 
-                // If no registered markers were matched, then the bloom filter returned a false
-                // positive. Re-align the stream to the end of the 2-byte window and continue the
-                // search.
+                // If no registered markers were matched, or all matches scored 0, then the bloom
+                // filter returned a false positive. Re-align the stream to the end of the 2-byte
+                // window and continue the search.
                 mss.rewind(16 - 2);
             }
         }
@@ -303,25 +355,52 @@ impl Probe {
         // Could not find any marker within the probe limit.
         error!("reached probe limit of {} bytes.", Probe::PROBE_SEARCH_LIMIT);
 
-        unsupported_error("no suitable reader found")
+        match forced {
+            Some(_) => unsupported_error("the stream marker of the forced format was not found"),
+            None => unsupported_error("no suitable reader found"),
+        }
     }
 
     /// Searches the provided `MediaSourceStream` for a container format. Any metadata that is read
     /// during the search will be queued and attached to the `FormatReader` instance once a
     /// container format is found.
+    ///
+    /// If the hint forces a format, only the `FormatReader` of that format is instantiated. An
+    /// error is returned if the forced format is not registered, or does not support the stream.
     pub fn format(
         &self,
-        _hint: &Hint,
+        hint: &Hint,
         mut mss: MediaSourceStream,
         format_opts: &FormatOptions,
         metadata_opts: &MetadataOptions,
     ) -> Result<ProbeResult> {
 
+        let forced = hint.format.as_deref();
+
+        if let Some(forced) = forced {
+            let registered = self.registered.iter().find(|registered| {
+                matches!(registered.inst, Instantiate::Format(_))
+                    && registered.short_name.eq_ignore_ascii_case(forced)
+            });
+
+            match registered {
+                // A format without a stream marker (e.g., raw PCM) cannot be searched for. It is
+                // instantiated at the current position of the stream instead.
+                Some(Descriptor { markers: &[], inst: Instantiate::Format(fmt), .. }) => {
+                    let format = fmt(mss, format_opts)?;
+
+                    return Ok(ProbeResult { format, metadata: ProbedMetadata { metadata: None } });
+                }
+                Some(_) => (),
+                None => return unsupported_error("the forced format is not registered"),
+            }
+        }
+
         let mut metadata: MetadataLog = Default::default();
 
         // Loop over all elements in the stream until a container format is found.
         loop {
-            match self.next(&mut mss)? {
+            match self.search(&mut mss, forced)? {
                 // If a container format is found, return an instance to it's reader.
                 Instantiate::Format(fmt) => {
                     let format = fmt(mss, format_opts)?;
@@ -385,3 +464,74 @@ macro_rules! support_metadata {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descriptor(short_name: &'static str, score: fn(&[u8]) -> u8) -> Descriptor {
+        Descriptor {
+            short_name,
+            long_name: short_name,
+            extensions: &[],
+            mime_types: &[],
+            markers: &[ b"SYNC" ],
+            score,
+            inst: Instantiate::Metadata(|_| unreachable!()),
+        }
+    }
+
+    #[test]
+    fn verify_highest_score_wins() {
+        let mut probe = Probe::default();
+
+        probe.register(&descriptor("low", |_| 64));
+        probe.register(&descriptor("high", |context| if context[4] == 1 { 200 } else { 0 }));
+        probe.register(&descriptor("tied", |context| if context[4] == 1 { 200 } else { 0 }));
+
+        let context = *b"SYNC\x01abcdefghijk";
+
+        // Both higher scoring descriptors accept the context, the first registered wins.
+        assert_eq!(probe.best_match(&context, None).unwrap().short_name, "high");
+
+        let context = *b"SYNC\x00abcdefghijk";
+
+        // Descriptors scoring 0 reject the context, leaving only the low scoring descriptor.
+        assert_eq!(probe.best_match(&context, None).unwrap().short_name, "low");
+
+        // No descriptor has the marker.
+        assert!(probe.best_match(b"CNYS\x01abcdefghijk", None).is_none());
+    }
+
+    #[test]
+    fn verify_forced_format() {
+        let format = |short_name, score| Descriptor {
+            inst: Instantiate::Format(|_, _| unreachable!()),
+            ..descriptor(short_name, score)
+        };
+
+        let mut probe = Probe::default();
+
+        probe.register(&format("low", |_| 64));
+        probe.register(&format("high", |_| 200));
+        probe.register(&format("none", |_| 0));
+
+        let context = *b"SYNC\x01abcdefghijk";
+
+        // A forced format is chosen regardless of the score of other formats.
+        assert_eq!(probe.best_match(&context, Some("LOW")).unwrap().short_name, "low");
+
+        // A forced format that rejects the stream is not replaced by another format.
+        assert!(probe.best_match(&context, Some("none")).is_none());
+
+        // A forced format that is not registered is an error.
+        let mut hint = Hint::new();
+        hint.force_format("missing");
+
+        let source = Box::new(std::io::Cursor::new(context));
+        let mss = MediaSourceStream::new(source, Default::default());
+
+        let result = probe.format(&hint, mss, &Default::default(), &Default::default());
+        assert!(result.is_err());
+    }
+}
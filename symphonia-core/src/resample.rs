@@ -0,0 +1,309 @@
+// Symphonia
+// Copyright (c) 2021 The Project Symphonia Developers.
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! The `resample` module provides sample rate converters for planar audio.
+
+use std::f64::consts::PI;
+
+use crate::audio::{AudioBuffer, Channels, Signal};
+use crate::errors::{Result, unsupported_error};
+
+/// A `Resampler` converts audio from one sample rate to another.
+///
+/// A resampler is a stream processor. Input frames that cannot yet be resampled, either because
+/// more input is required to interpolate them, or because the output buffer is full, are retained
+/// and consumed by the next call to `process`.
+pub trait Resampler {
+    /// Resamples the input buffer into the output buffer, replacing any existing frames in the
+    /// output buffer. Returns the number of frames written to the output buffer.
+    fn process(&mut self, input: &AudioBuffer<f32>, output: &mut AudioBuffer<f32>) -> Result<usize>;
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// The stream state shared by all resamplers.
+///
+/// The current output position is tracked as an exact fraction of the input sample period to
+/// prevent drift when the ratio between the sample rates is not an integer.
+struct ResamplerState {
+    /// The channels of the audio.
+    channels: Channels,
+    /// The input sample rate, reduced by the greatest common divisor of both sample rates.
+    in_rate: u64,
+    /// The output sample rate, reduced by the greatest common divisor of both sample rates.
+    out_rate: u64,
+    /// The number of input frames on either side of the output position used to interpolate an
+    /// output frame.
+    half_width: usize,
+    /// Per-channel input samples that have not yet been fully consumed.
+    pending: Vec<Vec<f32>>,
+    /// The index of the first pending frame used to interpolate the next output frame.
+    pos: usize,
+    /// The fractional part of the output position, in units of 1 / `out_rate` input frames.
+    frac: u64,
+}
+
+impl ResamplerState {
+    fn try_new(in_rate: u32, out_rate: u32, channels: Channels, half_width: usize) -> Result<Self> {
+        if in_rate == 0 || out_rate == 0 {
+            return unsupported_error("resampler sample rates must be non-zero");
+        }
+
+        let div = gcd(u64::from(in_rate), u64::from(out_rate));
+
+        // Prime the pending samples with silence such that the first output frame is aligned with
+        // the first input frame.
+        let pending = vec![vec![0.0; half_width - 1]; channels.count()];
+
+        Ok(ResamplerState {
+            channels,
+            in_rate: u64::from(in_rate) / div,
+            out_rate: u64::from(out_rate) / div,
+            half_width,
+            pending,
+            pos: 0,
+            frac: 0,
+        })
+    }
+
+    /// Resamples the input into the output. For each output frame, `kernel` is called with the
+    /// fractional output position, and must fill the provided slice with the interpolation
+    /// coefficients for each of the `2 * half_width` input frames surrounding that position.
+    fn process<K>(
+        &mut self,
+        input: &AudioBuffer<f32>,
+        output: &mut AudioBuffer<f32>,
+        mut kernel: K,
+    ) -> Result<usize>
+    where
+        K: FnMut(f64, &mut [f32]),
+    {
+        if input.spec().channels != self.channels || output.spec().channels != self.channels {
+            return unsupported_error("resampler channels do not match");
+        }
+
+        let width = 2 * self.half_width;
+
+        // Append the new input to the pending input.
+        for (c, pending) in self.pending.iter_mut().enumerate() {
+            pending.extend_from_slice(input.chan(c));
+        }
+
+        let n_pending = self.pending.first().map(|pending| pending.len()).unwrap_or(0);
+
+        // Calculate the number of output frames that can be interpolated from the pending input.
+        let n_frames = if n_pending >= self.pos + width {
+            let last = (n_pending - width - self.pos) as u64;
+            let n_avail = ((last + 1) * self.out_rate - self.frac).div_ceil(self.in_rate);
+            (n_avail as usize).min(output.capacity())
+        }
+        else {
+            0
+        };
+
+        output.clear();
+        output.render_reserved(Some(n_frames));
+
+        let mut coeffs = vec![0.0; width];
+
+        for i in 0..n_frames {
+            kernel(self.frac as f64 / self.out_rate as f64, &mut coeffs);
+
+            for (c, pending) in self.pending.iter().enumerate() {
+                let window = &pending[self.pos..self.pos + width];
+
+                output.chan_mut(c)[i] = window.iter().zip(&coeffs).map(|(&s, &k)| s * k).sum();
+            }
+
+            // Advance the output position by one output sample period.
+            self.frac += self.in_rate;
+            self.pos += (self.frac / self.out_rate) as usize;
+            self.frac %= self.out_rate;
+        }
+
+        // Drop all fully consumed input frames.
+        let n_consumed = self.pos.min(n_pending);
+
+        for pending in self.pending.iter_mut() {
+            pending.drain(..n_consumed);
+        }
+
+        self.pos -= n_consumed;
+
+        Ok(n_frames)
+    }
+}
+
+/// A `LinearResampler` resamples audio using linear interpolation between adjacent input frames.
+///
+/// Linear interpolation is very fast, but does not band-limit the signal. It is suitable when
+/// performance is more important than quality.
+pub struct LinearResampler {
+    state: ResamplerState,
+}
+
+impl LinearResampler {
+    /// Instantiate a `LinearResampler` that converts audio with the given channels from the input
+    /// sample rate to the output sample rate.
+    pub fn try_new(in_rate: u32, out_rate: u32, channels: Channels) -> Result<Self> {
+        Ok(LinearResampler { state: ResamplerState::try_new(in_rate, out_rate, channels, 1)? })
+    }
+}
+
+impl Resampler for LinearResampler {
+    fn process(&mut self, input: &AudioBuffer<f32>, output: &mut AudioBuffer<f32>) -> Result<usize> {
+        self.state.process(input, output, |t, coeffs| {
+            coeffs[0] = (1.0 - t) as f32;
+            coeffs[1] = t as f32;
+        })
+    }
+}
+
+/// The number of input frames on either side of the output position used by the
+/// `SincResampler`.
+const SINC_HALF_WIDTH: usize = 16;
+
+/// A `SincResampler` resamples audio using a windowed-sinc interpolation filter.
+///
+/// The sinc filter is windowed by a Blackman window and, when downsampling, its cutoff is lowered
+/// to the output Nyquist frequency to prevent aliasing.
+pub struct SincResampler {
+    state: ResamplerState,
+    cutoff: f64,
+}
+
+impl SincResampler {
+    /// Instantiate a `SincResampler` that converts audio with the given channels from the input
+    /// sample rate to the output sample rate.
+    pub fn try_new(in_rate: u32, out_rate: u32, channels: Channels) -> Result<Self> {
+        let state = ResamplerState::try_new(in_rate, out_rate, channels, SINC_HALF_WIDTH)?;
+
+        // Leave a small transition band below the Nyquist frequency.
+        let cutoff = 0.95 * (f64::from(out_rate) / f64::from(in_rate)).min(1.0);
+
+        Ok(SincResampler { state, cutoff })
+    }
+}
+
+impl Resampler for SincResampler {
+    fn process(&mut self, input: &AudioBuffer<f32>, output: &mut AudioBuffer<f32>) -> Result<usize> {
+        let cutoff = self.cutoff;
+        let half_width = SINC_HALF_WIDTH as f64;
+
+        self.state.process(input, output, |t, coeffs| {
+            for (j, coeff) in coeffs.iter_mut().enumerate() {
+                // The distance, in input frames, of this tap from the output position.
+                let x = j as f64 - (half_width - 1.0) - t;
+
+                let sinc = if x == 0.0 { 1.0 } else { (PI * cutoff * x).sin() / (PI * cutoff * x) };
+
+                let window = 0.42
+                    + 0.5 * (PI * x / half_width).cos()
+                    + 0.08 * (2.0 * PI * x / half_width).cos();
+
+                *coeff = (cutoff * sinc * window) as f32;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{Layout, SignalSpec};
+
+    /// Resamples a 1 kHz sine from 44.1 kHz to 48 kHz in unevenly sized chunks, and returns the
+    /// resampled audio.
+    fn resample_sine<R: Resampler>(resampler: &mut R) -> Vec<f32> {
+        let in_spec = SignalSpec::new_with_layout(44_100, Layout::Mono);
+        let out_spec = SignalSpec::new_with_layout(48_000, Layout::Mono);
+
+        let mut input = AudioBuffer::<f32>::new(1024, in_spec);
+        let mut output = AudioBuffer::<f32>::new(1200, out_spec);
+
+        let mut resampled = Vec::new();
+        let mut n = 0;
+
+        for &len in [1024, 7, 333, 1000, 1, 512].iter().cycle().take(60) {
+            input.clear();
+            input.render(Some(len), |planes, idx| {
+                let t = (n + idx) as f64 / 44_100.0;
+                planes.planes()[0][idx] = (2.0 * PI * 1000.0 * t).sin() as f32;
+                Ok(())
+            })
+            .unwrap();
+
+            n += len;
+
+            let n_frames = resampler.process(&input, &mut output).unwrap();
+
+            assert_eq!(n_frames, output.frames());
+            resampled.extend_from_slice(output.chan(0));
+        }
+
+        resampled
+    }
+
+    /// Estimates the frequency of a sine sampled at 48 kHz from its rising zero crossings.
+    fn estimate_frequency(samples: &[f32]) -> f64 {
+        let mut first = None;
+        let mut last = 0.0;
+        let mut count = 0;
+
+        for (i, w) in samples.windows(2).enumerate() {
+            if w[0] < 0.0 && w[1] >= 0.0 {
+                let crossing = i as f64 + f64::from(w[0] / (w[0] - w[1]));
+
+                if first.is_none() {
+                    first = Some(crossing);
+                }
+
+                last = crossing;
+                count += 1;
+            }
+        }
+
+        48_000.0 * f64::from(count - 1) / (last - first.unwrap())
+    }
+
+    #[test]
+    fn verify_linear_resampler_preserves_frequency() {
+        let mut resampler = LinearResampler::try_new(44_100, 48_000, Channels::FRONT_LEFT).unwrap();
+
+        let resampled = resample_sine(&mut resampler);
+
+        let n_input: usize = [1024, 7, 333, 1000, 1, 512].iter().sum::<usize>() * 10;
+        let expected = n_input * 48_000 / 44_100;
+
+        assert!((resampled.len() as i64 - expected as i64).abs() <= 2);
+        assert!((estimate_frequency(&resampled) - 1000.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn verify_sinc_resampler_preserves_frequency() {
+        let mut resampler = SincResampler::try_new(44_100, 48_000, Channels::FRONT_LEFT).unwrap();
+
+        let resampled = resample_sine(&mut resampler);
+
+        // Skip the filter's startup transient.
+        let resampled = &resampled[64..];
+
+        assert!((estimate_frequency(resampled) - 1000.0).abs() < 1.0);
+
+        // The peak amplitude of the resampled sine should be preserved.
+        let peak = resampled.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+
+        assert!((peak - 1.0).abs() < 0.01);
+    }
+}
@@ -12,7 +12,7 @@ use std::fmt;
 use crate::util::clamp::{clamp_i24, clamp_u24};
 
 /// SampleFormat describes the data encoding for an audio sample.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum SampleFormat {
     /// Unsigned 8-bit integer.
     U8,
@@ -162,6 +162,31 @@ impl i24 {
             [ b[1], b[2], b[3] ]
         }
     }
+
+    #[inline]
+    pub fn to_le_bytes(self) -> [u8; 3] {
+        let b = self.0.to_le_bytes();
+        [ b[0], b[1], b[2] ]
+    }
+
+    #[inline]
+    pub fn to_be_bytes(self) -> [u8; 3] {
+        let b = self.0.to_be_bytes();
+        [ b[1], b[2], b[3] ]
+    }
+
+    #[inline]
+    pub fn from_ne_bytes(b: [u8; 3]) -> Self {
+        // Place the bytes in the most-significant bytes, and sign-extend with an arithmetic shift.
+        let b = if cfg!(target_endian = "little") {
+            [ 0, b[0], b[1], b[2] ]
+        }
+        else {
+            [ b[0], b[1], b[2], 0 ]
+        };
+
+        i24(i32::from_ne_bytes(b) >> 8)
+    }
 }
 
 impl fmt::Display for i24 {
@@ -288,6 +313,30 @@ impl u24 {
             [ b[1], b[2], b[3] ]
         }
     }
+
+    #[inline]
+    pub fn to_le_bytes(self) -> [u8; 3] {
+        let b = self.0.to_le_bytes();
+        [ b[0], b[1], b[2] ]
+    }
+
+    #[inline]
+    pub fn to_be_bytes(self) -> [u8; 3] {
+        let b = self.0.to_be_bytes();
+        [ b[1], b[2], b[3] ]
+    }
+
+    #[inline]
+    pub fn from_ne_bytes(b: [u8; 3]) -> Self {
+        let b = if cfg!(target_endian = "little") {
+            [ b[0], b[1], b[2], 0 ]
+        }
+        else {
+            [ 0, b[0], b[1], b[2] ]
+        };
+
+        u24(u32::from_ne_bytes(b))
+    }
 }
 
 impl fmt::Display for u24 {
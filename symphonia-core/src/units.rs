@@ -108,6 +108,12 @@ impl From<f64> for Time {
     }
 }
 
+impl From<Time> for f64 {
+    fn from(time: Time) -> Self {
+        time.seconds as f64 + time.frac
+    }
+}
+
 #[derive(Debug, Default, Copy, Clone, PartialEq)]
 /// A `TimeBase` is the conversion factor between a `TimeStamp` and real-world seconds.
 pub struct TimeBase {
@@ -235,6 +241,8 @@ mod tests {
         assert_eq!(tb1.calc_timestamp(Time::new(14_073_748_835_532, 0.803125)), 0x10_0000_0000_0001);
         assert_eq!(tb1.calc_timestamp(Time::new(57_646_075_230_342_348, 0.796875)), u64::MAX);
 
+        // Verify conversion to seconds
+        assert_eq!(f64::from(tb1.calc_time(12_345)), 38.578125);
     }
 
 }
\ No newline at end of file
@@ -0,0 +1,159 @@
+// Symphonia
+// Copyright (c) 2021 The Project Symphonia Developers.
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! The `verify` module provides verifiers that check decoded audio against a checksum of the
+//! original audio stored by the container or codec.
+
+use crate::audio::{AudioBuffer, AudioBufferRef, Signal};
+use crate::checksum::Md5;
+use crate::conv::IntoSample;
+use crate::errors::{Result, unsupported_error};
+use crate::io::Monitor;
+use crate::sample::Sample;
+
+/// A `Verifier` accumulates a checksum of decoded audio, and compares it against an expected
+/// checksum once all the audio has been decoded.
+pub trait Verifier {
+    /// Updates the checksum with all written frames of a decoded audio buffer.
+    fn update(&mut self, buf: &AudioBufferRef);
+
+    /// Finalizes the checksum, and returns `true` if it matches the expected checksum.
+    fn finalize(self) -> Result<bool>
+    where
+        Self: Sized;
+}
+
+/// `Md5Verifier` verifies decoded audio against the MD5 checksum of the original audio, as stored
+/// by FLAC.
+///
+/// The checksum is calculated over interleaved, little-endian, signed samples of the original bit
+/// width rounded up to the nearest byte. Decoded samples are first converted to signed 32-bit
+/// samples, and then shifted down to the original bit width.
+pub struct Md5Verifier {
+    state: Md5,
+    expected: [u8; 16],
+    bits_per_sample: u32,
+    buf: Vec<u8>,
+}
+
+impl Md5Verifier {
+    /// Instantiate a `Md5Verifier` for audio with the given number of bits per sample.
+    pub fn try_new(expected: [u8; 16], bits_per_sample: u32) -> Result<Self> {
+        if bits_per_sample == 0 || bits_per_sample > 32 {
+            return unsupported_error("verifier bits per sample must be between 1 and 32");
+        }
+
+        Ok(Md5Verifier { state: Default::default(), expected, bits_per_sample, buf: Vec::new() })
+    }
+
+    /// Gets the MD5 checksum of the audio verified so far.
+    pub fn md5(&self) -> [u8; 16] {
+        self.state.md5()
+    }
+
+    fn update_typed<S>(&mut self, buf: &AudioBuffer<S>)
+    where
+        S: Sample + IntoSample<i32>
+    {
+        let bytes_per_sample = self.bits_per_sample.div_ceil(8) as usize;
+        let shift = 32 - self.bits_per_sample;
+
+        let n_channels = buf.spec().channels.count();
+        let len = n_channels * buf.frames() * bytes_per_sample;
+
+        if self.buf.len() < len {
+            self.buf.resize(len, 0);
+        }
+
+        let stride = n_channels * bytes_per_sample;
+
+        for ch in 0..n_channels {
+            let dst = self.buf[ch * bytes_per_sample..len].chunks_mut(stride);
+
+            for (out, &s) in dst.zip(buf.chan(ch)) {
+                let s: i32 = s.into_sample();
+                let bytes = (s >> shift).to_le_bytes();
+
+                out[..bytes_per_sample].copy_from_slice(&bytes[..bytes_per_sample]);
+            }
+        }
+
+        self.state.process_buf_bytes(&self.buf[..len]);
+    }
+}
+
+impl Verifier for Md5Verifier {
+    fn update(&mut self, buf: &AudioBufferRef) {
+        match buf {
+            AudioBufferRef::U8(buf)  => self.update_typed(buf),
+            AudioBufferRef::U16(buf) => self.update_typed(buf),
+            AudioBufferRef::U24(buf) => self.update_typed(buf),
+            AudioBufferRef::U32(buf) => self.update_typed(buf),
+            AudioBufferRef::S8(buf)  => self.update_typed(buf),
+            AudioBufferRef::S16(buf) => self.update_typed(buf),
+            AudioBufferRef::S24(buf) => self.update_typed(buf),
+            AudioBufferRef::S32(buf) => self.update_typed(buf),
+            AudioBufferRef::F32(buf) => self.update_typed(buf),
+            AudioBufferRef::F64(buf) => self.update_typed(buf),
+        }
+    }
+
+    fn finalize(self) -> Result<bool> {
+        Ok(self.md5() == self.expected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{AsAudioBufferRef, Layout, SignalSpec};
+
+    /// The MD5 checksum of the interleaved 16-bit little-endian samples of `SAMPLES`.
+    const SAMPLES_MD5: [u8; 16] = [
+        0x7c, 0x2c, 0xa5, 0xea, 0x7b, 0x1c, 0x1b, 0x4e,
+        0xf3, 0xd0, 0x87, 0xd2, 0x23, 0x04, 0x7d, 0xf6,
+    ];
+
+    const SAMPLES: [i16; 8] = [1, -1, 2, -2, 300, -300, i16::MAX, i16::MIN];
+
+    #[test]
+    fn verify_md5_verifier() {
+        let spec = SignalSpec::new_with_layout(44_100, Layout::Stereo);
+
+        // Verify the samples in two buffers to ensure the checksum spans buffers.
+        let mut buf = AudioBuffer::<i16>::new(4, spec);
+
+        let mut verifier = Md5Verifier::try_new(SAMPLES_MD5, 16).unwrap();
+
+        for samples in SAMPLES.chunks(4) {
+            buf.clear();
+            buf.copy_from_interleaved(samples);
+            verifier.update(&buf.as_audio_buffer_ref());
+        }
+
+        assert!(verifier.finalize().unwrap());
+
+        // Samples decoded to a wider format, as FLAC does, verify against the same checksum.
+        let mut buf = AudioBuffer::<i32>::new(4, spec);
+        buf.copy_from_interleaved(&SAMPLES);
+
+        let mut verifier = Md5Verifier::try_new(SAMPLES_MD5, 16).unwrap();
+        verifier.update(&buf.as_audio_buffer_ref());
+
+        assert!(verifier.finalize().unwrap());
+
+        // A single differing sample fails verification.
+        buf.chan_mut(1)[3] = 0;
+
+        let mut verifier = Md5Verifier::try_new(SAMPLES_MD5, 16).unwrap();
+        verifier.update(&buf.as_audio_buffer_ref());
+
+        assert!(!verifier.finalize().unwrap());
+
+        assert!(Md5Verifier::try_new(SAMPLES_MD5, 0).is_err());
+    }
+}
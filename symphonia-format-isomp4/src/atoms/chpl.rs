@@ -0,0 +1,62 @@
+// Symphonia
+// Copyright (c) 2021 The Project Symphonia Developers.
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use symphonia_core::errors::{Result, decode_error};
+use symphonia_core::io::ReadBytes;
+
+use crate::atoms::AtomHeader;
+
+/// A chapter in a Nero chapter list.
+#[derive(Debug)]
+pub struct NeroChapter {
+    /// The start time of the chapter in units of 100 nanoseconds.
+    pub start: u64,
+    /// The title of the chapter.
+    pub title: String,
+}
+
+/// Nero chapter list atom.
+#[derive(Debug)]
+pub struct ChplAtom {
+    /// The chapters.
+    pub chapters: Vec<NeroChapter>,
+}
+
+impl ChplAtom {
+    /// The number of chapter list time units per second.
+    pub const TIMESCALE: u32 = 10_000_000;
+
+    /// Reads the Nero chapter list atom. Only the chapters are retained, the atom header is not.
+    pub fn read<B: ReadBytes>(reader: &mut B) -> Result<Self> {
+        let (version, _) = AtomHeader::read_extra(reader)?;
+
+        match version {
+            0 => (),
+            // Version 1 has an additional reserved field.
+            1 => reader.ignore_bytes(4)?,
+            _ => return decode_error("invalid chpl version"),
+        }
+
+        let n_chapters = reader.read_u8()?;
+
+        let mut chapters = Vec::with_capacity(usize::from(n_chapters));
+
+        for _ in 0..n_chapters {
+            let start = reader.read_be_u64()?;
+
+            let len = reader.read_u8()?;
+            let title = reader.read_boxed_slice_exact(usize::from(len))?;
+
+            chapters.push(NeroChapter {
+                start,
+                title: String::from_utf8_lossy(&title).into_owned(),
+            });
+        }
+
+        Ok(ChplAtom { chapters })
+    }
+}
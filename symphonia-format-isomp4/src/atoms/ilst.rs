@@ -9,11 +9,13 @@ use symphonia_core::errors::{Result, decode_error};
 use symphonia_core::io::{ReadBytes, BufReader};
 use symphonia_core::util::bits;
 use symphonia_core::meta::{MetadataRevision, MetadataBuilder, StandardTagKey, StandardVisualKey, Tag};
-use symphonia_core::meta::{Value, Visual};
+use symphonia_core::meta::{Size, Value, Visual};
 use symphonia_metadata::{id3v1, itunes};
 
 use crate::atoms::{Atom, AtomHeader, AtomIterator, AtomType};
 
+use std::num::NonZeroU32;
+
 use encoding_rs::{UTF_16BE, SHIFT_JIS};
 use log::warn;
 
@@ -280,7 +282,7 @@ fn add_var_unsigned_int_tag<B: ReadBytes>(
 
     let tag = iter.read_atom::<MetaTagAtom>()?;
 
-    if let Some(value_atom) = tag.values.first() {
+    for value_atom in tag.values.iter() {
         if let Some(value) = parse_var_unsigned_int(&value_atom.data) {
             builder.add_tag(Tag::new(Some(std_key), "", value));
         }
@@ -300,7 +302,7 @@ fn add_var_signed_int_tag<B: ReadBytes>(
 
     let tag = iter.read_atom::<MetaTagAtom>()?;
 
-    if let Some(value_atom) = tag.values.first() {
+    for value_atom in tag.values.iter() {
         if let Some(value) = parse_var_signed_int(&value_atom.data) {
             builder.add_tag(Tag::new(Some(std_key), "", value));
         }
@@ -320,8 +322,7 @@ fn add_boolean_tag<B: ReadBytes>(
 
     let tag = iter.read_atom::<MetaTagAtom>()?;
 
-    // There should only be 1 value.
-    if let Some(value) = tag.values.first() {
+    for value in tag.values.iter() {
         // Boolean tags are just "flags", only add a tag if the boolean is true (1).
         if let Some(bool_value) = value.data.first() {
             if *bool_value == 1 {
@@ -358,6 +359,108 @@ fn add_m_of_n_tag<B: ReadBytes>(
     Ok(())
 }
 
+/// Reads the dimensions and bits per pixel from the IHDR chunk of a PNG image.
+fn read_png_info(data: &[u8]) -> Option<(Size, Option<NonZeroU32>)> {
+    const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+    if !data.starts_with(PNG_SIGNATURE) {
+        return None;
+    }
+
+    let mut reader = BufReader::new(&data[PNG_SIGNATURE.len()..]);
+
+    // The IHDR chunk is always the first chunk.
+    let _len = reader.read_be_u32().ok()?;
+
+    if reader.read_quad_bytes().ok()? != *b"IHDR" {
+        return None;
+    }
+
+    let width = reader.read_be_u32().ok()?;
+    let height = reader.read_be_u32().ok()?;
+    let bit_depth = u32::from(reader.read_u8().ok()?);
+
+    // The number of samples per pixel for each colour type.
+    let n_samples = match reader.read_u8().ok()? {
+        0 | 3 => 1,
+        2 => 3,
+        4 => 2,
+        6 => 4,
+        _ => return None,
+    };
+
+    Some((Size { width, height }, NonZeroU32::new(bit_depth * n_samples)))
+}
+
+/// Reads the dimensions and bits per pixel from the first start-of-frame segment of a JPEG image.
+fn read_jpeg_info(data: &[u8]) -> Option<(Size, Option<NonZeroU32>)> {
+    if !data.starts_with(&[0xff, 0xd8]) {
+        return None;
+    }
+
+    let mut reader = BufReader::new(&data[2..]);
+
+    loop {
+        if reader.read_u8().ok()? != 0xff {
+            return None;
+        }
+
+        let marker = reader.read_u8().ok()?;
+
+        match marker {
+            // Fill bytes, and markers without a segment.
+            0xff | 0x01 | 0xd0..=0xd7 => continue,
+            // The start-of-frame markers, except for DHT (0xc4), JPG (0xc8), and DAC (0xcc).
+            0xc0..=0xcf if marker != 0xc4 && marker != 0xc8 && marker != 0xcc => {
+                let _len = reader.read_be_u16().ok()?;
+                let precision = u32::from(reader.read_u8().ok()?);
+                let height = u32::from(reader.read_be_u16().ok()?);
+                let width = u32::from(reader.read_be_u16().ok()?);
+                let n_components = u32::from(reader.read_u8().ok()?);
+
+                return Some((Size { width, height }, NonZeroU32::new(precision * n_components)));
+            }
+            // The start-of-scan and end-of-image markers end the headers.
+            0xda | 0xd9 => return None,
+            _ => {
+                let len = reader.read_be_u16().ok()?;
+                reader.ignore_bytes(u64::from(len.checked_sub(2)?)).ok()?;
+            }
+        }
+    }
+}
+
+/// Reads the dimensions and bits per pixel from the DIB header of a BMP image.
+fn read_bmp_info(data: &[u8]) -> Option<(Size, Option<NonZeroU32>)> {
+    if !data.starts_with(b"BM") || data.len() < 14 {
+        return None;
+    }
+
+    // The DIB header follows the 14 byte file header.
+    let mut reader = BufReader::new(&data[14..]);
+
+    let (width, height) = match reader.read_u32().ok()? {
+        // The OS/2 BITMAPCOREHEADER has 16-bit dimensions.
+        12 => (u32::from(reader.read_u16().ok()?), u32::from(reader.read_u16().ok()?)),
+        // All other headers have signed 32-bit dimensions. A negative height indicates the rows
+        // are stored top-down.
+        _ => (reader.read_u32().ok()?, (reader.read_u32().ok()? as i32).unsigned_abs()),
+    };
+
+    let _planes = reader.read_u16().ok()?;
+    let bits_per_pixel = u32::from(reader.read_u16().ok()?);
+
+    Some((Size { width, height }, NonZeroU32::new(bits_per_pixel)))
+}
+
+/// Reads the dimensions and bits per pixel from the header of a PNG, JPEG, or BMP image.
+fn read_image_info(data: &[u8]) -> Option<(Size, Option<NonZeroU32>)> {
+    read_png_info(data)
+        .or_else(|| read_jpeg_info(data))
+        .or_else(|| read_bmp_info(data))
+        .filter(|(size, _)| size.width > 0 && size.height > 0)
+}
+
 fn add_visual_tag<B: ReadBytes>(
     iter: &mut AtomIterator<B>,
     builder: &mut MetadataBuilder,
@@ -374,10 +477,15 @@ fn add_visual_tag<B: ReadBytes>(
             _ => "",
         };
 
+        let (dimensions, bits_per_pixel) = match read_image_info(&value.data) {
+            Some((dimensions, bits_per_pixel)) => (Some(dimensions), bits_per_pixel),
+            None => (None, None),
+        };
+
         builder.add_visual(Visual {
             media_type: media_type.into(),
-            dimensions: None,
-            bits_per_pixel: None,
+            dimensions,
+            bits_per_pixel,
             color_mode: None,
             usage: Some(StandardVisualKey::FrontCover),
             tags: Default::default(),
@@ -389,9 +497,27 @@ fn add_visual_tag<B: ReadBytes>(
 }
 
 fn add_advisory_tag<B: ReadBytes>(
-    _iter: &mut AtomIterator<B>,
-    _builder: &mut MetadataBuilder,
+    iter: &mut AtomIterator<B>,
+    builder: &mut MetadataBuilder,
 ) -> Result<()> {
+    let tag = iter.read_atom::<MetaTagAtom>()?;
+
+    for value in tag.values.iter() {
+        let advisory = match value.data.first() {
+            Some(0) => Some("None"),
+            // Some older files use 4 to indicate explicit content.
+            Some(1) | Some(4) => Some("Explicit"),
+            Some(2) => Some("Clean"),
+            _ => None,
+        };
+
+        if let Some(advisory) = advisory {
+            builder.add_tag(
+                Tag::new(Some(StandardTagKey::ContentAdvisory), "", Value::from(advisory))
+            );
+        }
+    }
+
     Ok(())
 }
 
@@ -401,8 +527,7 @@ fn add_media_type_tag<B: ReadBytes>(
 ) -> Result<()> {
     let tag = iter.read_atom::<MetaTagAtom>()?;
 
-    // There should only be 1 value.
-    if let Some(value) = tag.values.first() {
+    for value in tag.values.iter() {
         if let Some(media_type_value) = value.data.get(0) {
             let media_type = match media_type_value {
                 0  => "Movie",
@@ -436,8 +561,7 @@ fn add_id3v1_genre_tag<B: ReadBytes>(
 
     let tag = iter.read_atom::<MetaTagAtom>()?;
 
-    // There should only be 1 value.
-    if let Some(value) = tag.values.first() {
+    for value in tag.values.iter() {
         // The ID3v1 genre is stored as a unsigned 16-bit big-endian integer.
         let index = BufReader::new(&value.data).read_be_u16()?;
 
@@ -445,7 +569,7 @@ fn add_id3v1_genre_tag<B: ReadBytes>(
         if index > 0 {
             if let Some(genre) = id3v1::util::genre_name((index - 1) as u8) {
                 builder.add_tag(
-                    Tag::new(Some(StandardTagKey::Genre), "", Value::from(*genre))
+                    Tag::new(Some(StandardTagKey::Genre), "", Value::from(genre))
                 );
             }
         }
@@ -468,10 +592,25 @@ fn add_freeform_tag<B: ReadBytes>(
             // Gets the fully qualified tag name.
             let full_name = tag.full_name();
 
+            // The gain in decibels of the iTunes Sound Check normalization tag, if valid.
+            let sound_check_gain = match &value {
+                Value::String(norm) if full_name == itunes::ITUNES_NORM_TAG => {
+                    itunes::parse_itunes_norm(norm)
+                }
+                _ => None,
+            };
+
             // Try to map iTunes freeform tags to standard tag keys.
             let std_key = itunes::std_key_from_tag(&full_name);
 
             builder.add_tag(Tag::new(std_key, &full_name, value));
+
+            // The Sound Check gain is added after the original tag.
+            if let Some(gain) = sound_check_gain {
+                let std_key = Some(StandardTagKey::SoundCheckGain);
+
+                builder.add_tag(Tag::new(std_key, &full_name, Value::from(gain)));
+            }
         }
         else {
             warn!("unsupported data type {:?} for free-form tag", value_atom.data_type);
@@ -482,6 +621,10 @@ fn add_freeform_tag<B: ReadBytes>(
 
 }
 
+/// The default maximum size of the payload of a data atom, in bytes. This is large enough for any
+/// reasonable cover art.
+const DEFAULT_DATA_LIMIT: usize = 20 * 1024 * 1024;
+
 /// Metadata tag data atom.
 pub struct MetaTagDataAtom {
     /// Atom header.
@@ -498,17 +641,17 @@ impl Atom for MetaTagDataAtom {
     }
 
     fn read<B: ReadBytes>(reader: &mut B, header: AtomHeader) -> Result<Self> {
-        let (version, flags) = AtomHeader::read_extra(reader)?;
-
-        // For the mov brand, this a data type indicator and must always be 0 (well-known type). It
-        // specifies the table in which the next 24-bit integer specifying the actual data type
-        // indexes. For iso/mp4, this is a version, and there is only one version, 0. Therefore,
-        // flags are interpreted as the actual data type index.
-        if version != 0 {
-            return decode_error("invalid data atom version");
-        }
-
-        let data_type = DataType::from(flags);
+        let (type_set, flags) = AtomHeader::read_extra(reader)?;
+
+        // For the mov brand, this is a type set indicator that specifies the table in which the
+        // next 24-bit integer, the actual data type, indexes. For iso/mp4, this is a version, and
+        // there is only one version, 0. Both the mp4 version 0 and the mov type set 0 use the table
+        // of well-known data types. No other type sets are defined, therefore their data types are
+        // unknown and the value will be skipped.
+        let data_type = match type_set {
+            0 => DataType::from(flags),
+            _ => DataType::Unknown((u32::from(type_set) << 24) | flags),
+        };
 
         // For the mov brand, the next four bytes are country and languages code. However, for
         // iso/mp4 these codes should be ignored.
@@ -516,10 +659,27 @@ impl Atom for MetaTagDataAtom {
         let _language = reader.read_be_u16()?;
 
         // The data payload is the remainder of the atom.
-        // TODO: Apply a limit.
-        let data = reader.read_boxed_slice_exact(
-            (header.data_len - AtomHeader::EXTRA_DATA_SIZE - 4) as usize
-        )?;
+        let data_len = match header.data_len.checked_sub(AtomHeader::EXTRA_DATA_SIZE + 4) {
+            Some(data_len) => data_len,
+            None => return decode_error("invalid data atom length"),
+        };
+
+        // Do not trust the data length of the atom. Limit it to prevent a malformed atom from
+        // causing an excessively large allocation.
+        let limit = match data_type {
+            DataType::Bmp | DataType::Jpeg | DataType::Png => {
+                header.metadata_opts.limit_visual_bytes
+            }
+            _ => header.metadata_opts.limit_metadata_bytes,
+        };
+
+        if let Some(limit) = limit.limit_or_default(DEFAULT_DATA_LIMIT) {
+            if data_len > limit as u64 {
+                return decode_error("data atom exceeds the size limit");
+            }
+        }
+
+        let data = reader.read_boxed_slice_exact(data_len as usize)?;
 
         Ok(MetaTagDataAtom {
             header,
@@ -545,9 +705,12 @@ impl Atom for MetaTagNamespaceAtom {
     fn read<B: ReadBytes>(reader: &mut B, header: AtomHeader) -> Result<Self> {
         let (_, _) = AtomHeader::read_extra(reader)?;
 
-        let buf = reader.read_boxed_slice_exact(
-            (header.data_len - AtomHeader::EXTRA_DATA_SIZE) as usize
-        )?;
+        let len = match header.data_len.checked_sub(AtomHeader::EXTRA_DATA_SIZE) {
+            Some(len) => len,
+            None => return decode_error("invalid tag name atom length"),
+        };
+
+        let buf = reader.read_boxed_slice_exact(len as usize)?;
 
         // Do a lossy conversion because metadata should not prevent the demuxer from working.
         let value = String::from_utf8_lossy(&buf).to_string();
@@ -643,11 +806,24 @@ impl Atom for IlstAtom {
     }
 
     fn read<B: ReadBytes>(reader: &mut B, header: AtomHeader) -> Result<Self> {
+        let metadata_opts = header.metadata_opts;
+
         let mut iter = AtomIterator::new(reader, header);
 
         let mut mb = MetadataBuilder::new();
 
         while let Some(header) = iter.next()? {
+            // Skip the cover art if visuals are not to be read, and all other tags if tags are not
+            // to be read.
+            let is_read = match header.atype {
+                AtomType::CoverTag => metadata_opts.read_visuals,
+                _ => metadata_opts.read_tags,
+            };
+
+            if !is_read {
+                continue;
+            }
+
             // Ignore standard atoms, check if other is a metadata atom.
             match &header.atype {
                 AtomType::AdvisoryTag => {
@@ -705,8 +881,7 @@ impl Atom for IlstAtom {
                     add_generic_tag(&mut iter, &mut mb, Some(StandardTagKey::Encoder))?
                 }
                 AtomType::GaplessPlaybackTag => {
-                    // TODO: Need standard tag key for gapless playback.
-                    // add_boolean_tag(&mut iter, &mut mb, )?
+                    add_boolean_tag(&mut iter, &mut mb, StandardTagKey::GaplessPlayback)?
                 }
                 AtomType::GenreTag => {
                     add_id3v1_genre_tag(&mut iter, &mut mb)?
@@ -749,7 +924,7 @@ impl Atom for IlstAtom {
                     add_generic_tag(&mut iter, &mut mb, Some(StandardTagKey::SortAlbum))?
                 }
                 AtomType::SortArtistTag => {
-                    add_generic_tag(&mut iter, &mut mb, Some(StandardTagKey::Artist))?
+                    add_generic_tag(&mut iter, &mut mb, Some(StandardTagKey::SortArtist))?
                 }
                 AtomType::SortComposerTag => {
                     add_generic_tag(&mut iter, &mut mb, Some(StandardTagKey::SortComposer))?
@@ -799,4 +974,60 @@ impl Atom for IlstAtom {
             metadata: mb.metadata(),
         })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_read_png_info() {
+        // A 3x2 8-bit RGBA PNG signature and IHDR chunk.
+        let png = [
+            0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n',
+            0, 0, 0, 13, b'I', b'H', b'D', b'R',
+            0, 0, 0, 3, 0, 0, 0, 2, 8, 6, 0, 0, 0,
+        ];
+
+        let (size, bits_per_pixel) = read_image_info(&png).unwrap();
+
+        assert_eq!((size.width, size.height), (3, 2));
+        assert_eq!(bits_per_pixel, NonZeroU32::new(32));
+
+        // A truncated header cannot be read.
+        assert!(read_image_info(&png[..20]).is_none());
+    }
+
+    #[test]
+    fn verify_read_jpeg_info() {
+        // A JPEG with an APP0 segment followed by a 640x480 8-bit YCbCr progressive SOF2 segment.
+        let jpeg = [
+            0xff, 0xd8,
+            0xff, 0xe0, 0, 6, b'J', b'F', b'I', b'F',
+            0xff, 0xc2, 0, 17, 8, 0x01, 0xe0, 0x02, 0x80, 3,
+            1, 0x22, 0, 2, 0x11, 1, 3, 0x11, 1,
+        ];
+
+        let (size, bits_per_pixel) = read_image_info(&jpeg).unwrap();
+
+        assert_eq!((size.width, size.height), (640, 480));
+        assert_eq!(bits_per_pixel, NonZeroU32::new(24));
+
+        // A JPEG without a start-of-frame segment before the start-of-scan.
+        assert!(read_image_info(&[0xff, 0xd8, 0xff, 0xda, 0, 2]).is_none());
+    }
+
+    #[test]
+    fn verify_read_bmp_info() {
+        // A top-down 4x3 24-bit BMP with a BITMAPINFOHEADER.
+        let mut bmp = vec![b'B', b'M', 0, 0, 0, 0, 0, 0, 0, 0, 54, 0, 0, 0, 40, 0, 0, 0];
+        bmp.extend_from_slice(&4i32.to_le_bytes());
+        bmp.extend_from_slice(&(-3i32).to_le_bytes());
+        bmp.extend_from_slice(&[1, 0, 24, 0]);
+
+        let (size, bits_per_pixel) = read_image_info(&bmp).unwrap();
+
+        assert_eq!((size.width, size.height), (4, 3));
+        assert_eq!(bits_per_pixel, NonZeroU32::new(24));
+    }
+}
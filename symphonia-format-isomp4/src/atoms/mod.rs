@@ -7,7 +7,9 @@
 
 use symphonia_core::errors::{Result, decode_error};
 use symphonia_core::io::ReadBytes;
+use symphonia_core::meta::MetadataOptions;
 
+pub(crate) mod chpl;
 pub(crate) mod co64;
 pub(crate) mod ctts;
 pub(crate) mod edts;
@@ -40,10 +42,12 @@ pub(crate) mod tfhd;
 pub(crate) mod tkhd;
 pub(crate) mod traf;
 pub(crate) mod trak;
+pub(crate) mod tref;
 pub(crate) mod trex;
 pub(crate) mod trun;
 pub(crate) mod udta;
 
+pub use chpl::ChplAtom;
 pub use co64::Co64Atom;
 pub use ctts::CttsAtom;
 pub use edts::EdtsAtom;
@@ -76,6 +80,7 @@ pub use tfhd::TfhdAtom;
 pub use tkhd::TkhdAtom;
 pub use traf::TrafAtom;
 pub use trak::TrakAtom;
+pub use tref::TrefAtom;
 pub use trex::TrexAtom;
 pub use trun::TrunAtom;
 pub use udta::UdtaAtom;
@@ -89,6 +94,7 @@ pub enum AtomType {
     ArtistLowerTag,
     ArtistTag,
     CategoryTag,
+    ChapterTrackReference,
     ChunkOffset,
     ChunkOffset64,
     CommentTag,
@@ -134,6 +140,7 @@ pub enum AtomType {
     MovieFragmentHeader,
     MovieHeader,
     Mp4a,
+    NeroChapterList,
     OwnerTag,
     PodcastTag,
     PurchaseDateTag,
@@ -160,6 +167,7 @@ pub enum AtomType {
     TrackFragmentRun,
     TrackHeader,
     TrackNumberTag,
+    TrackReference,
     TrackTitleTag,
     TvEpisodeNameTag,
     TvEpisodeNumberTag,
@@ -175,6 +183,8 @@ pub enum AtomType {
 impl From<[u8; 4]> for AtomType {
     fn from(val: [u8; 4]) -> Self {
         match &val {
+            b"chap" => AtomType::ChapterTrackReference,
+            b"chpl" => AtomType::NeroChapterList,
             b"co64" => AtomType::ChunkOffset64,
             b"ctts" => AtomType::CompositionTimeToSample,
             b"data" => AtomType::MetaTagData,
@@ -213,6 +223,7 @@ impl From<[u8; 4]> for AtomType {
             b"tkhd" => AtomType::TrackHeader,
             b"traf" => AtomType::TrackFragment,
             b"trak" => AtomType::Track,
+            b"tref" => AtomType::TrackReference,
             b"trex" => AtomType::TrackExtends,
             b"trun" => AtomType::TrackFragmentRun,
             b"udta" => AtomType::UserData,
@@ -276,6 +287,8 @@ pub struct AtomHeader {
     pub atom_len: u64,
     /// The size of the payload data.
     pub data_len: u64,
+    /// The metadata options inherited from the iterator that read the header.
+    pub metadata_opts: MetadataOptions,
 }
 
 impl AtomHeader {
@@ -313,7 +326,7 @@ impl AtomHeader {
             }
         };
 
-        Ok(AtomHeader { atype, atom_len, data_len })
+        Ok(AtomHeader { atype, atom_len, data_len, metadata_opts: Default::default() })
     }
 
     #[allow(dead_code)]
@@ -346,6 +359,7 @@ pub struct AtomIterator<B: ReadBytes> {
     cur_atom: Option<AtomHeader>,
     base_pos: u64,
     next_atom_pos: u64,
+    metadata_opts: MetadataOptions,
 }
 
 impl<B: ReadBytes> AtomIterator<B> {
@@ -359,25 +373,38 @@ impl<B: ReadBytes> AtomIterator<B> {
             cur_atom: None,
             base_pos,
             next_atom_pos: base_pos,
+            metadata_opts: Default::default(),
         }
     }
 
     pub fn new(reader: B, container: AtomHeader) -> Self {
         let base_pos = reader.pos();
 
+        // Atoms within the container inherit the metadata options of the container.
         AtomIterator {
             reader,
             len: Some(container.data_len),
             cur_atom: None,
             base_pos,
             next_atom_pos: base_pos,
+            metadata_opts: container.metadata_opts,
         }
     }
 
+    /// Sets the metadata options used to read the metadata atoms found by the iterator.
+    pub fn with_metadata_options(mut self, metadata_opts: MetadataOptions) -> Self {
+        self.metadata_opts = metadata_opts;
+        self
+    }
+
     pub fn into_inner(self) -> B {
         self.reader
     }
 
+    pub fn inner(&self) -> &B {
+        &self.reader
+    }
+
     pub fn inner_mut(&mut self) -> &mut B {
         &mut self.reader
     }
@@ -404,7 +431,9 @@ impl<B: ReadBytes> AtomIterator<B> {
         }
 
         // Read the next atom header.
-        let atom = AtomHeader::read(&mut self.reader)?;
+        let mut atom = AtomHeader::read(&mut self.reader)?;
+
+        atom.metadata_opts = self.metadata_opts;
 
         // Calculate the start position for the next atom (the exclusive end of the current atom).
         self.next_atom_pos += match atom.atom_len {
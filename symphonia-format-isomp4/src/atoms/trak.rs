@@ -8,7 +8,7 @@
 use symphonia_core::errors::{Result, decode_error};
 use symphonia_core::io::ReadBytes;
 
-use crate::atoms::{Atom, AtomHeader, AtomIterator, AtomType, EdtsAtom, MdiaAtom, TkhdAtom};
+use crate::atoms::{Atom, AtomHeader, AtomIterator, AtomType, EdtsAtom, MdiaAtom, TkhdAtom, TrefAtom};
 
 /// Track atom.
 #[derive(Debug)]
@@ -19,6 +19,8 @@ pub struct TrakAtom {
     pub tkhd: TkhdAtom,
     /// Optional, edit list atom.
     pub edts: Option<EdtsAtom>,
+    /// Optional, track reference atom.
+    pub tref: Option<TrefAtom>,
     /// Media atom.
     pub mdia: MdiaAtom,
 }
@@ -33,6 +35,7 @@ impl Atom for TrakAtom {
 
         let mut tkhd = None;
         let mut edts = None;
+        let mut tref = None;
         let mut mdia = None;
 
         while let Some(header) = iter.next()? {
@@ -43,6 +46,9 @@ impl Atom for TrakAtom {
                 AtomType::Edit => {
                     edts = Some(iter.read_atom::<EdtsAtom>()?);
                 }
+                AtomType::TrackReference => {
+                    tref = Some(TrefAtom::read(iter.inner_mut(), header)?);
+                }
                 AtomType::Media => {
                     mdia = Some(iter.read_atom::<MdiaAtom>()?);
                 }
@@ -62,6 +68,7 @@ impl Atom for TrakAtom {
             header,
             tkhd: tkhd.unwrap(),
             edts,
+            tref,
             mdia: mdia.unwrap(),
         })        
     }
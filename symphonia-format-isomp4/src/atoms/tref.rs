@@ -0,0 +1,39 @@
+// Symphonia
+// Copyright (c) 2020 The Project Symphonia Developers.
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use symphonia_core::errors::Result;
+use symphonia_core::io::ReadBytes;
+
+use crate::atoms::{AtomHeader, AtomIterator, AtomType};
+
+/// Track reference atom.
+#[derive(Debug)]
+pub struct TrefAtom {
+    /// The track identifiers of the chapter tracks referenced by the track.
+    pub chap: Vec<u32>,
+}
+
+impl TrefAtom {
+    /// Reads the track reference atom. Only the references are retained, the atom header is not.
+    pub fn read<B: ReadBytes>(reader: &mut B, header: AtomHeader) -> Result<Self> {
+        let mut iter = AtomIterator::new(reader, header);
+
+        let mut chap = Vec::new();
+
+        while let Some(header) = iter.next()? {
+            // The chapter track reference atom is a list of track identifiers. Other reference
+            // types are ignored.
+            if let AtomType::ChapterTrackReference = header.atype {
+                for _ in 0..header.data_len / 4 {
+                    chap.push(iter.inner_mut().read_be_u32()?);
+                }
+            }
+        }
+
+        Ok(TrefAtom { chap })
+    }
+}
@@ -9,7 +9,7 @@ use symphonia_core::errors::Result;
 use symphonia_core::io::ReadBytes;
 use symphonia_core::meta::MetadataLog;
 
-use crate::atoms::{Atom, AtomHeader, AtomIterator, AtomType, MetaAtom};
+use crate::atoms::{Atom, AtomHeader, AtomIterator, AtomType, ChplAtom, MetaAtom};
 
 /// User data atom.
 #[derive(Debug)]
@@ -18,6 +18,8 @@ pub struct UdtaAtom {
     header: AtomHeader,
     /// Metadata atom.
     pub meta: Option<MetaAtom>,
+    /// Nero chapter list atom.
+    pub chpl: Option<ChplAtom>,
 }
 
 impl UdtaAtom {
@@ -38,12 +40,16 @@ impl Atom for UdtaAtom {
         let mut iter = AtomIterator::new(reader, header);
         
         let mut meta = None;
+        let mut chpl = None;
 
         while let Some(header) = iter.next()? {
             match header.atype {
                 AtomType::Meta => {
                     meta = Some(iter.read_atom::<MetaAtom>()?);
                 }
+                AtomType::NeroChapterList => {
+                    chpl = Some(ChplAtom::read(iter.inner_mut())?);
+                }
                 _ => ()
             }
         }
@@ -51,6 +57,7 @@ impl Atom for UdtaAtom {
         Ok(UdtaAtom {
             header,
             meta,
+            chpl,
         })
     }
 }
\ No newline at end of file
@@ -9,19 +9,24 @@
 use symphonia_core::{errors::end_of_stream_error, support_format};
 
 use symphonia_core::codecs::{CodecParameters, CODEC_TYPE_AAC};
-use symphonia_core::errors::{Result, SeekErrorKind, decode_error, seek_error, unsupported_error};
+use symphonia_core::errors::{Error, Result, SeekErrorKind};
+use symphonia_core::errors::{decode_error, seek_error, unsupported_error};
 use symphonia_core::formats::prelude::*;
+use symphonia_core::formats::CuePoint;
 use symphonia_core::io::{ReadBytes, MediaSource, MediaSourceStream};
-use symphonia_core::meta::{Metadata, MetadataLog};
+use symphonia_core::meta::{Metadata, MetadataLog, StandardTagKey, Tag, Value, find_producer};
 use symphonia_core::probe::{Descriptor, Instantiate, QueryDescriptor};
 use symphonia_core::units::Time;
+use symphonia_metadata::itunes;
 
 use std::io::{Seek, SeekFrom};
 use std::sync::Arc;
 
 use crate::atoms::{AtomIterator, AtomType};
-use crate::atoms::{FtypAtom, MoovAtom, MoofAtom, SidxAtom, TrakAtom, MetaAtom, MvexAtom};
+use crate::atoms::{ChplAtom, FtypAtom, MoovAtom, MoofAtom, SidxAtom, TrakAtom, MetaAtom, MvexAtom};
+use crate::atoms::hdlr::TrackType;
 use crate::atoms::stsd::SampleDescription;
+use crate::fourcc::FourCc;
 use crate::stream::*;
 
 use log::{debug, info, trace, warn};
@@ -98,6 +103,173 @@ struct SampleDataInfo {
     len: u32,
 }
 
+/// Information regarding a QuickTime-style chapter track.
+struct ChapterTrackInfo {
+    /// The track number of the text track containing the chapters.
+    track_num: u32,
+    /// The number of samples (chapters) in the chapter track.
+    n_samples: u32,
+    /// The timescale of the chapter track.
+    timescale: u32,
+    /// The timescale of the track that references the chapter track.
+    ref_timescale: u32,
+}
+
+/// Gets the encoder delay, padding, and total number of frames from the iTunes gapless playback
+/// information tag, if present.
+fn find_gapless_info(metadata: &mut MetadataLog) -> Option<(u32, u32, u64)> {
+    let metadata = metadata.metadata();
+
+    metadata.current()?.tags().iter().filter(|tag| tag.key == itunes::ITUNES_SMPB_TAG).find_map(
+        |tag| match &tag.value {
+            Value::String(smpb) => itunes::parse_itunes_smpb(smpb),
+            _ => None,
+        }
+    )
+}
+
+/// Gets the language of a track. An undetermined language is considered unknown.
+fn track_language(trak: &TrakAtom) -> Option<String> {
+    match trak.mdia.mdhd.language.as_str() {
+        "" | "und" => None,
+        language => Some(language.to_string()),
+    }
+}
+
+/// Finds the first text track referenced as a chapter track by a `chap` track reference.
+fn find_chapter_track(moov: &MoovAtom) -> Option<ChapterTrackInfo> {
+    for trak in moov.traks.iter() {
+        let chap = match &trak.tref {
+            Some(tref) => &tref.chap,
+            _ => continue,
+        };
+
+        for &id in chap.iter() {
+            let found = moov.traks.iter()
+                                  .enumerate()
+                                  .find(|(_, chap_trak)| chap_trak.tkhd.id == id);
+
+            match found {
+                Some((track_num, chap_trak)) if chap_trak.mdia.hdlr.track_type == TrackType::Text => {
+                    return Some(ChapterTrackInfo {
+                        track_num: track_num as u32,
+                        n_samples: chap_trak.mdia.minf.stbl.stsz.sample_count,
+                        timescale: chap_trak.mdia.mdhd.timescale,
+                        ref_timescale: trak.mdia.mdhd.timescale,
+                    });
+                }
+                Some(_) => warn!("chapter track with id={} is not a text track", id),
+                None => warn!("missing chapter track with id={}", id),
+            }
+        }
+    }
+
+    None
+}
+
+/// Reads the title from a QuickTime text sample. A text sample starts with a 16-bit length
+/// followed by the text. The text is UTF-8 encoded unless it starts with a UTF-16 byte order mark.
+fn read_text_sample(buf: &[u8]) -> Result<String> {
+    if buf.len() < 2 {
+        return decode_error("text sample is too short");
+    }
+
+    let len = usize::from(u16::from_be_bytes([buf[0], buf[1]]));
+
+    let text = buf.get(2..2 + len).ok_or(Error::DecodeError("text sample length is invalid"))?;
+
+    if text.starts_with(&[0xfe, 0xff]) || text.starts_with(&[0xff, 0xfe]) {
+        // Decoding will detect the byte order using the byte order mark.
+        let (title, _, _) = encoding_rs::UTF_16BE.decode(text);
+        Ok(title.into_owned())
+    }
+    else {
+        Ok(String::from_utf8_lossy(text).into_owned())
+    }
+}
+
+/// Reads the samples of a chapter track and creates a `Cue` for each chapter. The start timestamp
+/// of each `Cue` is in the timebase of the track referencing the chapter track.
+fn read_chapter_cues(
+    mss: &mut MediaSourceStream,
+    seg: &dyn StreamSegment,
+    info: &ChapterTrackInfo,
+) -> Result<Vec<Cue>> {
+    if info.timescale == 0 {
+        return decode_error("chapter track timescale is 0");
+    }
+
+    let mut cues = Vec::new();
+
+    for sample_num in 0..info.n_samples {
+        let timing = match seg.sample_timing(info.track_num, sample_num)? {
+            Some(timing) => timing,
+            _ => break,
+        };
+
+        let data_desc = seg.sample_data(info.track_num, sample_num, true)?;
+
+        mss.seek(SeekFrom::Start(data_desc.base_pos + data_desc.offset.unwrap_or(0)))?;
+
+        let title = read_text_sample(&mss.read_boxed_slice_exact(data_desc.size as usize)?)?;
+
+        // Convert the chapter timestamp to the timebase of the referencing track.
+        let start_ts = u128::from(timing.ts) * u128::from(info.ref_timescale)
+                        / u128::from(info.timescale);
+
+        cues.push(Cue {
+            index: sample_num + 1,
+            start_ts: start_ts as u64,
+            tags: vec![ Tag::new(Some(StandardTagKey::TrackTitle), "TITLE", Value::from(title)) ],
+            points: Vec::new(),
+        });
+    }
+
+    Ok(cues)
+}
+
+/// Creates a `Cue` for each chapter of a Nero chapter list. The start timestamp of each `Cue` is
+/// in the given timescale.
+fn nero_chapter_cues(chpl: &ChplAtom, timescale: u32) -> Vec<Cue> {
+    chpl.chapters.iter()
+        .enumerate()
+        .map(|(i, chapter)| {
+            // Convert the chapter start time to the timescale.
+            let start_ts = u128::from(chapter.start) * u128::from(timescale)
+                            / u128::from(ChplAtom::TIMESCALE);
+
+            let title = Value::from(chapter.title.as_str());
+
+            Cue {
+                index: i as u32 + 1,
+                start_ts: start_ts as u64,
+                tags: vec![ Tag::new(Some(StandardTagKey::TrackTitle), "TITLE", title) ],
+                points: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+/// Nests the chapters of a Nero chapter list within the chapters of a chapter track. Each Nero
+/// chapter becomes a `CuePoint` of the last chapter starting before it. A Nero chapter starting
+/// together with a chapter, or before the first chapter, duplicates or precedes the chapter track,
+/// and is dropped.
+fn nest_nero_chapters(cues: &mut [Cue], nero_cues: Vec<Cue>) {
+    for nero_cue in nero_cues {
+        let parent = cues.iter_mut().rev().find(|cue| cue.start_ts <= nero_cue.start_ts);
+
+        match parent {
+            Some(parent) if parent.start_ts < nero_cue.start_ts => {
+                parent.points.push(CuePoint {
+                    start_offset_ts: nero_cue.start_ts - parent.start_ts,
+                    tags: nero_cue.tags,
+                });
+            }
+            _ => (),
+        }
+    }
+}
+
 /// ISO Base Media File Format (MP4, M4A, MOV, etc.) demultiplexer.
 ///
 /// `IsoMp4Reader` implements a demuxer for the ISO Base Media File Format.
@@ -112,6 +284,10 @@ pub struct IsoMp4Reader {
     track_states: Vec<TrackState>,
     /// Optional, movie extends atom used for fragmented streams.
     mvex: Option<Arc<MvexAtom>>,
+    /// The major brand of the file type.
+    major_brand: FourCc,
+    /// The compatible brands of the file type.
+    compatible_brands: Vec<FourCc>,
 }
 
 impl IsoMp4Reader {
@@ -347,7 +523,7 @@ impl QueryDescriptor for IsoMp4Reader {
 
 impl FormatReader for IsoMp4Reader {
 
-    fn try_new(mut mss: MediaSourceStream, _options: &FormatOptions) -> Result<Self> {
+    fn try_new(mut mss: MediaSourceStream, options: &FormatOptions) -> Result<Self> {
 
         // To get to beginning of the atom.
         mss.seek_buffered_rel(-4);
@@ -373,7 +549,8 @@ impl FormatReader for IsoMp4Reader {
         let mut metadata = MetadataLog::default();
 
         // Parse all atoms if the stream is seekable, otherwise parse all atoms up-to the mdat atom.
-        let mut iter = AtomIterator::new_root(mss, total_len);
+        let mut iter =
+            AtomIterator::new_root(mss, total_len).with_metadata_options(options.metadata);
 
         while let Some(header) = iter.next()? {
             // Top-level atoms.
@@ -436,9 +613,10 @@ impl FormatReader for IsoMp4Reader {
             }
         }
 
-        if ftyp.is_none() {
-            return unsupported_error("missing ftyp atom");
-        }
+        let ftyp = match ftyp {
+            Some(ftyp) => ftyp,
+            None => return unsupported_error("missing ftyp atom"),
+        };
 
         if moov.is_none() {
             return unsupported_error("missing moov atom");
@@ -450,7 +628,7 @@ impl FormatReader for IsoMp4Reader {
             let mut mss = iter.into_inner();
             mss.seek(SeekFrom::Start(0))?;
 
-            iter = AtomIterator::new_root(mss, total_len);
+            iter = AtomIterator::new_root(mss, total_len).with_metadata_options(options.metadata);
         }
 
         let mut moov = moov.unwrap();
@@ -467,16 +645,59 @@ impl FormatReader for IsoMp4Reader {
 
         moov.take_metadata(&mut metadata);
 
+        // Find the chapter track, if any, before the moov atom is consumed.
+        let chapter_track = find_chapter_track(&moov);
+
+        // The timescale of the chapter track cues, if any.
+        let chapter_timescale = chapter_track.as_ref().map(|info| info.ref_timescale);
+
+        // Take the Nero chapter list, if any, and the timescale of the first audio track it applies
+        // to, or the movie timescale if there are no audio tracks.
+        let nero_chapters = moov.udta.as_mut().and_then(|udta| udta.chpl.take());
+
+        let nero_timescale = moov.traks.iter()
+            .find(|trak| trak.mdia.hdlr.track_type == TrackType::Sound)
+            .map(|trak| trak.mdia.mdhd.timescale)
+            .unwrap_or(moov.mvhd.timescale);
+
+        let gapless_info = find_gapless_info(&mut metadata);
+
         // Instantiate a TrackState for each track in the stream.
         let track_states = moov.traks.iter()
-                               .enumerate()
-                               .map(|(t, trak)| TrackState::new(t as u32, trak))
-                               .collect::<Vec<TrackState>>();
+            .enumerate()
+            .map(|(t, trak)| {
+                let mut state = TrackState::new(t as u32, trak);
+
+                // The gapless playback information describes the audio track(s).
+                if let Some((delay, padding, n_frames)) = gapless_info {
+                    if trak.mdia.hdlr.track_type == TrackType::Sound {
+                        state.codec_params
+                            .with_leading_padding(delay)
+                            .with_trailing_padding(padding)
+                            .with_n_frames(n_frames);
+                    }
+                }
+
+                state
+            })
+            .collect::<Vec<TrackState>>();
 
         // Instantiate a Tracks for all tracks above.
         let tracks = track_states.iter()
-                                  .map(|track| Track::new(track.track_num, track.codec_params()))
-                                  .collect();
+            .zip(moov.traks.iter())
+            .map(|(state, trak)| {
+                let mut track = Track::new(state.track_num, state.codec_params());
+                track.language = track_language(trak);
+                track.with_kind(match trak.mdia.hdlr.track_type {
+                    TrackType::Sound => TrackKind::Audio,
+                    TrackType::Video => TrackKind::Video,
+                    TrackType::Subtitle => TrackKind::Subtitle,
+                    TrackType::Text => TrackKind::Text,
+                    TrackType::Metadata => TrackKind::Metadata,
+                });
+                track
+            })
+            .collect();
 
         // A Movie Extends (mvex) atom is required to support segmented streams. If the mvex atom is
         // present, wrap it in an Arc so it can be shared amongst all segments.
@@ -484,14 +705,57 @@ impl FormatReader for IsoMp4Reader {
 
         let segs: Vec<Box<dyn StreamSegment>> = vec![ Box::new(MoovSegment::new(moov)) ];
 
+        // The chapters of a chapter track are stored as samples in the media data. Since they are
+        // needed up-front, they may only be read if the stream is seekable.
+        let cues = match chapter_track {
+            Some(info) if is_seekable => {
+                let mss = iter.inner_mut();
+
+                let cues = match read_chapter_cues(mss, segs[0].as_ref(), &info) {
+                    Ok(cues) => cues,
+                    Err(err) => {
+                        warn!("failed to read chapter track: {}", err);
+                        Default::default()
+                    }
+                };
+
+                // Return to the beginning of the stream.
+                mss.seek(SeekFrom::Start(0))?;
+
+                cues
+            }
+            Some(_) => {
+                info!("ignoring chapter track in an unseekable stream.");
+                Default::default()
+            }
+            _ => Default::default(),
+        };
+
+        // A chapter track is preferred, but otherwise use the Nero chapter list. If both are
+        // present, the Nero chapter list may subdivide the chapters of the chapter track.
+        let cues = match nero_chapters {
+            Some(chpl) if cues.is_empty() => nero_chapter_cues(&chpl, nero_timescale),
+            Some(chpl) => {
+                let mut cues = cues;
+
+                let timescale = chapter_timescale.unwrap_or(nero_timescale);
+                nest_nero_chapters(&mut cues, nero_chapter_cues(&chpl, timescale));
+
+                cues
+            }
+            _ => cues,
+        };
+
         Ok(IsoMp4Reader {
             iter,
             tracks,
-            cues: Default::default(),
+            cues,
             metadata,
             track_states,
             segs,
             mvex,
+            major_brand: ftyp.major,
+            compatible_brands: ftyp.compatible,
         })
     }
 
@@ -546,6 +810,36 @@ impl FormatReader for IsoMp4Reader {
         self.metadata.metadata()
     }
 
+    fn producer_hint(&mut self) -> Option<String> {
+        // Prefer the encoder tags since they identify the tool precisely.
+        let producer = self.metadata.metadata().current().and_then(|rev| find_producer(rev.tags()));
+
+        if producer.is_some() {
+            return producer;
+        }
+
+        // Otherwise, some tools may be identified by the brands they write.
+        let is_major = |brand: &[u8; 4]| self.major_brand == FourCc::new(*brand);
+        let is_compatible = |brand: &[u8; 4]| self.compatible_brands.contains(&FourCc::new(*brand));
+
+        if is_compatible(b"NDAS") || is_compatible(b"NDSC") || is_compatible(b"NDXC") {
+            Some("Nero".to_string())
+        }
+        else if is_major(b"qt  ") {
+            Some("QuickTime".to_string())
+        }
+        else if is_major(b"M4A ") || is_major(b"M4B ") {
+            Some("iTunes".to_string())
+        }
+        else if is_major(b"isom") && is_compatible(b"iso2") {
+            // The FFmpeg MP4 muxer writes the isom major brand with the iso2 compatible brand.
+            Some("FFmpeg".to_string())
+        }
+        else {
+            None
+        }
+    }
+
     fn cues(&self) -> &[Cue] {
         &self.cues
     }
@@ -554,6 +848,17 @@ impl FormatReader for IsoMp4Reader {
         &self.tracks
     }
 
+    fn seek_caps(&self) -> SeekCaps {
+        // Seeks are to the sample (packet) containing the desired timestamp.
+        match self.default_track() {
+            Some(track) => {
+                let granularity = track.codec_params.max_frames_per_packet;
+                SeekCaps::sample_accurate(self.iter.inner().is_seekable(), granularity)
+            }
+            None => SeekCaps::unsupported(),
+        }
+    }
+
     fn seek(&mut self, _mode: SeekMode, to: SeekTo) -> Result<SeekedTo> {
 
         if self.tracks.is_empty() {
@@ -603,4 +908,696 @@ impl FormatReader for IsoMp4Reader {
     fn into_inner(self: Box<Self>) -> MediaSourceStream {
         self.iter.into_inner()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use symphonia_core::formats::{FormatReader, SeekAccuracy, SeekMode, SeekSupport, SeekTo};
+    use symphonia_core::formats::TrackKind;
+    use symphonia_core::units::TimeBase;
+    use symphonia_core::io::{MediaSourceStream, ReadBytes, ReadOnlySource};
+    use symphonia_core::errors::{Error, Result};
+    use symphonia_core::formats::FormatOptions;
+    use symphonia_core::meta::{Limit, StandardTagKey, Tag, Value};
+
+    use super::IsoMp4Reader;
+
+    fn atom(atype: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&(8 + payload.len() as u32).to_be_bytes());
+        buf.extend_from_slice(atype);
+        buf.extend_from_slice(payload);
+        buf
+    }
+
+    fn full_atom(atype: &[u8; 4], fields: &[u32]) -> Vec<u8> {
+        let payload = fields.iter().flat_map(|f| f.to_be_bytes().to_vec()).collect::<Vec<u8>>();
+        atom(atype, &payload)
+    }
+
+    /// Builds a mvhd atom with a timescale of 1000.
+    fn mvhd() -> Vec<u8> {
+        full_atom(b"mvhd", &[0, 0, 0, 1000, 0, 0x0001_0000, 0x0100_0000])
+    }
+
+    /// Opens a reader, emulating the probe which consumes the atom size before the ftyp marker.
+    fn open_mp4(file: Vec<u8>, options: &FormatOptions) -> Result<IsoMp4Reader> {
+        let mut mss = MediaSourceStream::new(Box::new(Cursor::new(file)), Default::default());
+        mss.read_be_u32().unwrap();
+
+        IsoMp4Reader::try_new(mss, options)
+    }
+
+    /// Reads the tags of a file with the given file type and metadata item list.
+    fn read_tags(ftyp: &[u8], ilst: &[u8]) -> Vec<Tag> {
+        let meta = atom(b"meta", &[&[0, 0, 0, 0][..], ilst].concat());
+
+        let file = [atom(b"ftyp", ftyp), meta, atom(b"moov", &mvhd())].concat();
+
+        let mut reader = open_mp4(file, &Default::default()).unwrap();
+
+        let metadata = reader.metadata();
+        metadata.current().unwrap().tags().to_vec()
+    }
+
+    /// Reads the tags of an M4A file with the given metadata item list.
+    fn read_ilst_tags(ilst: &[u8]) -> Vec<Tag> {
+        read_tags(b"M4A \0\0\0\0M4A mp42", ilst)
+    }
+
+    /// Builds a trak atom with a single chunk of samples at `offset`.
+    #[allow(clippy::too_many_arguments)]
+    fn trak(
+        id: u32,
+        handler: &[u8; 4],
+        timescale: u32,
+        language: &[u8; 3],
+        tref: &[u8],
+        durations: &[u32],
+        sizes: &[u32],
+        offset: u32,
+    ) -> Vec<u8> {
+        let n_samples = sizes.len() as u32;
+
+        // Version, ctime, mtime, id, reserved, duration, 2x reserved, layer + alternate group,
+        // and volume + reserved.
+        let tkhd = full_atom(b"tkhd", &[0, 0, 0, id, 0, 0, 0, 0, 0, 0]);
+        // Version, ctime, mtime, timescale, duration, and a packed ISO 639-2 language + quality.
+        let code = language.iter().fold(0, |code, &c| (code << 5) | u32::from(c - 0x60));
+        let mdhd = full_atom(b"mdhd", &[0, 0, 0, timescale, 0, code << 16]);
+
+        let mut hdlr_payload = vec![0; 24];
+        hdlr_payload[8..12].copy_from_slice(handler);
+        let hdlr = atom(b"hdlr", &hdlr_payload);
+
+        // A sample description with a single unsupported entry.
+        let stsd = atom(b"stsd", &[&[0, 0, 0, 0, 0, 0, 0, 1][..], &atom(b"xxxx", &[])].concat());
+
+        let mut stts_fields = vec![0, durations.len() as u32];
+        for &dur in durations {
+            stts_fields.extend_from_slice(&[1, dur]);
+        }
+
+        let mut stsz_fields = vec![0, 0, n_samples];
+        stsz_fields.extend_from_slice(sizes);
+
+        let stbl = atom(b"stbl", &[
+            stsd,
+            full_atom(b"stts", &stts_fields),
+            full_atom(b"stsc", &[0, 1, 1, n_samples, 1]),
+            full_atom(b"stsz", &stsz_fields),
+            full_atom(b"stco", &[0, 1, offset]),
+        ].concat());
+
+        let mdia = atom(b"mdia", &[mdhd, hdlr, atom(b"minf", &stbl)].concat());
+
+        atom(b"trak", &[tkhd, tref.to_vec(), mdia].concat())
+    }
+
+    /// Builds a version 1 Nero chapter list atom with start times in units of 100 ns.
+    fn chpl(chapters: &[(&str, u64)]) -> Vec<u8> {
+        let mut chpl = vec![1, 0, 0, 0, 0, 0, 0, 0, chapters.len() as u8];
+
+        for &(title, start) in chapters {
+            chpl.extend_from_slice(&start.to_be_bytes());
+            chpl.push(title.len() as u8);
+            chpl.extend_from_slice(title.as_bytes());
+        }
+
+        atom(b"chpl", &chpl)
+    }
+
+    /// Builds an MP4 with an audio track that references a text track containing chapters, and the
+    /// given user data atoms.
+    fn make_chaptered_mp4(chapters: &[(&str, u32)], udta: &[u8]) -> Vec<u8> {
+        let ftyp = atom(b"ftyp", b"M4A \0\0\0\0M4A mp42");
+
+        let mut text = Vec::new();
+        let mut sizes = Vec::new();
+
+        for (title, _) in chapters {
+            text.extend_from_slice(&(title.len() as u16).to_be_bytes());
+            text.extend_from_slice(title.as_bytes());
+            sizes.push(2 + title.len() as u32);
+        }
+
+        let durations = chapters.iter().map(|&(_, dur)| dur).collect::<Vec<u32>>();
+
+        let audio = [0u8; 4];
+
+        let make_moov = |offset: u32| {
+            let tref = atom(b"tref", &full_atom(b"chap", &[2]));
+
+            let udta = if udta.is_empty() { Vec::new() } else { atom(b"udta", udta) };
+
+            atom(b"moov", &[
+                mvhd(),
+                trak(1, b"soun", 44_100, b"und", &tref, &[1024], &[4], offset),
+                trak(2, b"text", 1000, b"und", &[], &durations, &sizes, offset + 4),
+                udta,
+            ].concat())
+        };
+
+        // The media data follows the ftyp and moov atoms, and an 8 byte mdat header.
+        let offset = (ftyp.len() + make_moov(0).len() + 8) as u32;
+
+        [ftyp, make_moov(offset), atom(b"mdat", &[&audio[..], &text].concat())].concat()
+    }
+
+    #[test]
+    fn verify_chap_track_reference_cues() {
+        let chapters = [("Intro", 5_000), ("Chapter 2", 2_500), ("Outro", 1_000)];
+
+        let reader = open_mp4(make_chaptered_mp4(&chapters, &[]), &Default::default()).unwrap();
+
+        let cues = reader.cues();
+
+        assert_eq!(cues.len(), 3);
+
+        let expected = [("Intro", 0), ("Chapter 2", 220_500), ("Outro", 330_750)];
+
+        for (cue, &(title, ts)) in cues.iter().zip(expected.iter()) {
+            assert_eq!(cue.start_ts, ts);
+            assert_eq!(cue.tags.len(), 1);
+            // Without a Nero chapter list, the chapters are not subdivided.
+            assert!(cue.points.is_empty());
+
+            match &cue.tags[0].value {
+                Value::String(value) => assert_eq!(value, title),
+                _ => panic!("chapter title is not a string"),
+            }
+        }
+    }
+
+    #[test]
+    fn verify_nested_nero_chapter_cues() {
+        let chapters = [("Intro", 5_000), ("Chapter 2", 2_500), ("Outro", 1_000)];
+
+        // The Nero chapter list repeats the chapters, and subdivides the first and last chapters.
+        let chpl = chpl(&[
+            ("Intro", 0),
+            ("Intro Part 2", 25_000_000),
+            ("Chapter 2", 50_000_000),
+            ("Outro", 75_000_000),
+            ("Credits", 80_000_000),
+            ("Hidden Track", 82_500_000),
+        ]);
+
+        let reader = open_mp4(make_chaptered_mp4(&chapters, &chpl), &Default::default()).unwrap();
+
+        let cues = reader.cues();
+
+        assert_eq!(cues.len(), 3);
+        assert_eq!(cues.iter().map(|cue| cue.start_ts).collect::<Vec<_>>(), [0, 220_500, 330_750]);
+
+        let points = cues.iter()
+            .map(|cue| {
+                cue.points.iter()
+                    .map(|point| match &point.tags[0].value {
+                        Value::String(title) => (point.start_offset_ts, title.as_str()),
+                        _ => panic!("chapter title is not a string"),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // The offsets are relative to the start of the parent chapter.
+        assert_eq!(points[0], [(110_250, "Intro Part 2")]);
+        assert!(points[1].is_empty());
+        assert_eq!(points[2], [(22_050, "Credits"), (33_075, "Hidden Track")]);
+    }
+
+    #[test]
+    fn verify_producer_hint() {
+        let ftyp = atom(b"ftyp", b"M4A \0\0\0\0M4A mp42");
+
+        // A data atom with the UTF-8 data type, followed by the country and language codes.
+        let data = atom(b"data", &[&[0, 0, 0, 1, 0, 0, 0, 0][..], b"Lavf58.76.100"].concat());
+
+        let ilst = atom(b"ilst", &atom(b"\xa9too", &data));
+        let meta = atom(b"meta", &[&[0, 0, 0, 0][..], &ilst].concat());
+
+        let moov = atom(b"moov", &mvhd());
+
+        let file = [ftyp.clone(), meta, moov.clone()].concat();
+
+        let mut reader = open_mp4(file, &Default::default()).unwrap();
+
+        // The encoder tag takes precedence over the M4A brand.
+        assert_eq!(reader.producer_hint().as_deref(), Some("FFmpeg"));
+
+        // Without an encoder tag, the brand is used.
+        let file = [ftyp, moov].concat();
+
+        let mut reader = open_mp4(file, &Default::default()).unwrap();
+
+        assert_eq!(reader.producer_hint().as_deref(), Some("iTunes"));
+    }
+
+    #[test]
+    fn verify_multiple_audio_tracks() {
+        let ftyp = atom(b"ftyp", b"M4A \0\0\0\0M4A mp42");
+
+        // Each track has two 4 byte samples, each sample filled with a track specific value.
+        let mdat = [[1u8; 8], [2u8; 8]].concat();
+
+        let make_moov = |offset: u32| {
+            atom(b"moov", &[
+                mvhd(),
+                trak(1, b"soun", 44_100, b"eng", &[], &[1024, 1024], &[4, 4], offset),
+                trak(2, b"soun", 48_000, b"fra", &[], &[1024, 1024], &[4, 4], offset + 8),
+            ].concat())
+        };
+
+        let offset = (ftyp.len() + make_moov(0).len() + 8) as u32;
+
+        let file = [ftyp, make_moov(offset), atom(b"mdat", &mdat)].concat();
+
+        let mut reader = open_mp4(file, &Default::default()).unwrap();
+
+        // Both tracks are exposed in track order, each with their own language and time base.
+        let tracks = reader.tracks();
+
+        assert_eq!(tracks.len(), 2);
+        assert_eq!(tracks[0].id, 0);
+        assert_eq!(tracks[0].language.as_deref(), Some("eng"));
+        assert_eq!(tracks[0].codec_params.time_base, Some(TimeBase::new(1, 44_100)));
+        assert_eq!(tracks[1].id, 1);
+        assert_eq!(tracks[1].language.as_deref(), Some("fra"));
+        assert_eq!(tracks[1].codec_params.time_base, Some(TimeBase::new(1, 48_000)));
+
+        // Every packet belongs to exactly one track, and carries only that track's data.
+        let mut n_packets = [0; 2];
+
+        while let Ok(packet) = reader.next_packet() {
+            let track = packet.track_id() as usize;
+
+            assert_eq!(packet.buf(), &[track as u8 + 1; 4]);
+            n_packets[track] += 1;
+        }
+
+        assert_eq!(n_packets, [2, 2]);
+
+        // Each track may be seeked independently.
+        let seeked = reader.seek(SeekMode::Accurate, SeekTo::TimeStamp { ts: 1024, track_id: 1 })
+                           .unwrap();
+
+        assert_eq!(seeked.track_id, 1);
+        assert_eq!(seeked.actual_ts, 1024);
+
+        // Other tracks are seeked to the same time, so skip their packets.
+        let packet = loop {
+            let packet = reader.next_packet().unwrap();
+
+            if packet.track_id() == 1 {
+                break packet;
+            }
+        };
+
+        assert_eq!(packet.pts(), 1024);
+        assert_eq!(packet.buf(), &[2; 4]);
+    }
+
+    #[test]
+    fn verify_seek_caps() {
+        let file = make_chaptered_mp4(&[("Intro", 5_000)], &[]);
+
+        let reader = open_mp4(file.clone(), &Default::default()).unwrap();
+        let caps = reader.seek_caps();
+
+        assert_eq!(caps.support, SeekSupport::Full);
+        assert_eq!(caps.accuracy, SeekAccuracy::SampleAccurate);
+
+        // An unseekable source may only be seeked forward by discarding data.
+        let source = Box::new(ReadOnlySource::new(Cursor::new(file)));
+
+        let mut mss = MediaSourceStream::new(source, Default::default());
+        mss.read_be_u32().unwrap();
+
+        let reader = IsoMp4Reader::try_new(mss, &Default::default()).unwrap();
+
+        assert_eq!(reader.seek_caps().support, SeekSupport::ForwardOnly);
+    }
+
+    #[test]
+    fn verify_gapless_info() {
+        let ftyp = atom(b"ftyp", b"M4A \0\0\0\0M4A mp42");
+
+        let smpb = b" 00000000 00000840 000001C0 0000000000046E00";
+
+        // A free-form tag with the iTunes namespace, the gapless tag name, and a UTF-8 value.
+        let tag = atom(b"----", &[
+            atom(b"mean", &[&[0, 0, 0, 0][..], b"com.apple.iTunes"].concat()),
+            atom(b"name", &[&[0, 0, 0, 0][..], b"iTunSMPB"].concat()),
+            atom(b"data", &[&[0, 0, 0, 1, 0, 0, 0, 0][..], smpb].concat()),
+        ].concat());
+
+        let meta = atom(b"meta", &[&[0, 0, 0, 0][..], &atom(b"ilst", &tag)].concat());
+
+        let moov = atom(b"moov", &[
+            mvhd(),
+            trak(1, b"soun", 44_100, b"und", &[], &[1024], &[4], 0),
+            trak(2, b"text", 1000, b"und", &[], &[1000], &[4], 0),
+        ].concat());
+
+        let file = [ftyp, meta, moov].concat();
+
+        let reader = open_mp4(file, &Default::default()).unwrap();
+
+        let tracks = reader.tracks();
+
+        assert_eq!(tracks[0].kind(), TrackKind::Audio);
+        assert_eq!(tracks[1].kind(), TrackKind::Text);
+
+        // Only the audio track has gapless playback information.
+        assert_eq!(tracks[0].codec_params.leading_padding, Some(2112));
+        assert_eq!(tracks[0].codec_params.trailing_padding, Some(448));
+        assert_eq!(tracks[0].codec_params.n_frames, Some(2112 + 290_304 + 448));
+
+        assert_eq!(tracks[1].codec_params.leading_padding, None);
+        assert_eq!(tracks[1].codec_params.trailing_padding, None);
+    }
+
+    #[test]
+    fn verify_musicbrainz_tags() {
+        // Free-form MusicBrainz tags as written by Picard, and by taggers that use the Vorbis
+        // Comment field names.
+        let tag = |name: &[u8], value: &[u8]| {
+            atom(b"----", &[
+                atom(b"mean", &[&[0, 0, 0, 0][..], b"com.apple.iTunes"].concat()),
+                atom(b"name", &[&[0, 0, 0, 0][..], name].concat()),
+                atom(b"data", &[&[0, 0, 0, 1, 0, 0, 0, 0][..], value].concat()),
+            ].concat())
+        };
+
+        let ilst = atom(b"ilst", &[
+            tag(b"MusicBrainz Track Id", b"e4f1a1b2"),
+            tag(b"MUSICBRAINZ_ALBUMID", b"c3d4e5f6"),
+        ].concat());
+
+        let tags = read_ilst_tags(&ilst);
+
+        assert_eq!(tags.len(), 2);
+        assert!(matches!(tags[0].std_key, Some(StandardTagKey::MusicBrainzTrackId)));
+        assert!(matches!(tags[1].std_key, Some(StandardTagKey::MusicBrainzAlbumId)));
+    }
+
+    #[test]
+    fn verify_sound_check_tag() {
+        let norm = " 000003E8 000007D0 00000000 00000000 00000000 00000000 00007FFF 00007FFF \
+                    00000000 00000000";
+
+        let ilst = atom(b"ilst", &atom(b"----", &[
+            atom(b"mean", &[&[0, 0, 0, 0][..], b"com.apple.iTunes"].concat()),
+            atom(b"name", &[&[0, 0, 0, 0][..], b"iTunNORM"].concat()),
+            atom(b"data", &[&[0, 0, 0, 1, 0, 0, 0, 0][..], norm.as_bytes()].concat()),
+        ].concat()));
+
+        let tags = read_ilst_tags(&ilst);
+
+        // The original value is kept, and is followed by the gain of the louder channel.
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags[0].key, "com.apple.iTunes:iTunNORM");
+        assert_eq!(tags[0].std_key, None);
+        assert!(matches!(&tags[0].value, Value::String(value) if value == norm));
+
+        assert_eq!(tags[1].key, "com.apple.iTunes:iTunNORM");
+        assert_eq!(tags[1].std_key, Some(StandardTagKey::SoundCheckGain));
+
+        match tags[1].value {
+            Value::Float(gain) => assert!((gain + 3.0103).abs() < 1e-4),
+            _ => panic!("sound check gain is not a float"),
+        }
+    }
+
+    #[test]
+    fn verify_string_tags() {
+        // A data atom with the given data type, followed by the country and language codes.
+        let data = |data_type: u32, value: &[u8]| {
+            atom(b"data", &[&data_type.to_be_bytes()[..], &[0, 0, 0, 0], value].concat())
+        };
+
+        let title = "Caf\u{e9} \u{266b}".encode_utf16().flat_map(|c| c.to_be_bytes().to_vec());
+
+        let ilst = atom(b"ilst", &[
+            // A valid UTF-8 artist.
+            atom(b"\xa9ART", &data(1, "Caf\u{e9}".as_bytes())),
+            // An invalid UTF-8 album.
+            atom(b"\xa9alb", &data(1, b"Caf\xe9")),
+            // A UTF-16 title.
+            atom(b"\xa9nam", &data(2, &title.collect::<Vec<u8>>())),
+            // A comment with an unknown data type.
+            atom(b"\xa9cmt", &data(99, b"?")),
+        ].concat());
+
+        let tags = read_ilst_tags(&ilst);
+
+        let values = tags.iter()
+            .map(|tag| match &tag.value {
+                Value::String(value) => (tag.std_key, value.as_str()),
+                _ => panic!("tag value is not a string"),
+            })
+            .collect::<Vec<_>>();
+
+        // Invalid UTF-8 is replaced, and the tag with the unknown data type is skipped.
+        assert_eq!(values.len(), 3);
+        assert!(matches!(values[0], (Some(StandardTagKey::Artist), "Caf\u{e9}")));
+        assert!(matches!(values[1], (Some(StandardTagKey::Album), "Caf\u{fffd}")));
+        assert!(matches!(values[2], (Some(StandardTagKey::TrackTitle), "Caf\u{e9} \u{266b}")));
+    }
+
+    #[test]
+    fn verify_sort_tags() {
+        let data = |value: &str| {
+            atom(b"data", &[&[0, 0, 0, 1, 0, 0, 0, 0][..], value.as_bytes()].concat())
+        };
+
+        let ilst = atom(b"ilst", &[
+            atom(b"\xa9ART", &data("The Artist")),
+            atom(b"soar", &data("Artist, The")),
+            atom(b"soaa", &data("Album Artist, The")),
+            atom(b"sonm", &data("Title, The")),
+        ].concat());
+
+        let tags = read_ilst_tags(&ilst);
+
+        // Each sort tag has its own key, distinct from the tag it sorts.
+        assert_eq!(tags.len(), 4);
+        assert!(matches!(tags[0].std_key, Some(StandardTagKey::Artist)));
+        assert!(matches!(tags[1].std_key, Some(StandardTagKey::SortArtist)));
+        assert!(matches!(tags[2].std_key, Some(StandardTagKey::SortAlbumArtist)));
+        assert!(matches!(tags[3].std_key, Some(StandardTagKey::SortTrackTitle)));
+    }
+
+    #[test]
+    fn verify_multi_value_tags() {
+        let data = |value: &str| {
+            atom(b"data", &[&[0, 0, 0, 1, 0, 0, 0, 0][..], value.as_bytes()].concat())
+        };
+
+        // Some taggers store multiple values as multiple data atoms within one metadata atom.
+        let ilst = atom(b"ilst", &[
+            atom(b"\xa9gen", &[data("Rock"), data("Blues")].concat()),
+            atom(b"tmpo", &[
+                atom(b"data", &[0, 0, 0, 21, 0, 0, 0, 0, 0, 120]),
+                atom(b"data", &[0, 0, 0, 21, 0, 0, 0, 0, 0, 90]),
+            ].concat()),
+        ].concat());
+
+        let tags = read_ilst_tags(&ilst);
+
+        assert_eq!(tags.len(), 4);
+
+        assert!(matches!(tags[0].std_key, Some(StandardTagKey::Genre)));
+        assert!(matches!(&tags[0].value, Value::String(value) if value == "Rock"));
+        assert!(matches!(tags[1].std_key, Some(StandardTagKey::Genre)));
+        assert!(matches!(&tags[1].value, Value::String(value) if value == "Blues"));
+
+        assert!(matches!(tags[2].std_key, Some(StandardTagKey::Bpm)));
+        assert!(matches!(tags[2].value, Value::SignedInt(120)));
+        assert!(matches!(tags[3].std_key, Some(StandardTagKey::Bpm)));
+        assert!(matches!(tags[3].value, Value::SignedInt(90)));
+    }
+
+    #[test]
+    fn verify_gapless_playback_tag() {
+        // A set gapless playback flag emits a tag, a cleared flag does not.
+        let ilst = atom(b"ilst", &[
+            atom(b"pgap", &atom(b"data", &[0, 0, 0, 21, 0, 0, 0, 0, 1])),
+            atom(b"pgap", &atom(b"data", &[0, 0, 0, 21, 0, 0, 0, 0, 0])),
+        ].concat());
+
+        let tags = read_ilst_tags(&ilst);
+
+        assert_eq!(tags.len(), 1);
+        assert!(matches!(tags[0].std_key, Some(StandardTagKey::GaplessPlayback)));
+        assert!(matches!(tags[0].value, Value::Flag));
+    }
+
+    #[test]
+    fn verify_tv_show_tags() {
+        let data = |value: &str| {
+            atom(b"data", &[&[0, 0, 0, 1, 0, 0, 0, 0][..], value.as_bytes()].concat())
+        };
+
+        // The season and episode numbers are big-endian integers.
+        let ilst = atom(b"ilst", &[
+            atom(b"tvsh", &data("The Show")),
+            atom(b"tven", &data("S02E07")),
+            atom(b"tvnn", &data("The Network")),
+            atom(b"tvsn", &atom(b"data", &[0, 0, 0, 21, 0, 0, 0, 0, 0, 0, 0, 2])),
+            atom(b"tves", &atom(b"data", &[0, 0, 0, 21, 0, 0, 0, 0, 0, 0, 0, 7])),
+        ].concat());
+
+        let tags = read_tags(b"M4V \0\0\0\0M4V mp42", &ilst);
+
+        assert_eq!(tags.len(), 5);
+
+        assert!(matches!(tags[0].std_key, Some(StandardTagKey::TvShowTitle)));
+        assert!(matches!(&tags[0].value, Value::String(value) if value == "The Show"));
+        assert!(matches!(tags[1].std_key, Some(StandardTagKey::TvEpisodeTitle)));
+        assert!(matches!(&tags[1].value, Value::String(value) if value == "S02E07"));
+        assert!(matches!(tags[2].std_key, Some(StandardTagKey::TvNetwork)));
+        assert!(matches!(&tags[2].value, Value::String(value) if value == "The Network"));
+        assert!(matches!(tags[3].std_key, Some(StandardTagKey::TvSeason)));
+        assert!(matches!(tags[3].value, Value::UnsignedInt(2)));
+        assert!(matches!(tags[4].std_key, Some(StandardTagKey::TvEpisode)));
+        assert!(matches!(tags[4].value, Value::UnsignedInt(7)));
+    }
+
+    #[test]
+    fn verify_advisory_tag() {
+        // An advisory with the unsigned 8-bit integer data type, and an advisory without a value.
+        let ilst = atom(b"ilst", &[
+            atom(b"rtng", &atom(b"data", &[0, 0, 0, 21, 0, 0, 0, 0, 1])),
+            atom(b"rtng", &atom(b"data", &[0, 0, 0, 21, 0, 0, 0, 0])),
+        ].concat());
+
+        let tags = read_ilst_tags(&ilst);
+
+        assert_eq!(tags.len(), 1);
+        assert!(matches!(tags[0].std_key, Some(StandardTagKey::ContentAdvisory)));
+        assert!(matches!(&tags[0].value, Value::String(value) if value == "Explicit"));
+    }
+
+    #[test]
+    fn verify_data_atom_limit() {
+        let ftyp = atom(b"ftyp", b"M4A \0\0\0\0M4A mp42");
+        let make_file = |data: &[u8]| {
+            let ilst = atom(b"ilst", &atom(b"\xa9nam", data));
+            let meta = atom(b"meta", &[&[0, 0, 0, 0][..], &ilst].concat());
+
+            [ftyp.clone(), meta, atom(b"moov", &mvhd())].concat()
+        };
+
+        let try_read = |file: Vec<u8>, options: &FormatOptions| {
+            open_mp4(file, options).map(|_| ())
+        };
+
+        // A data atom that claims to be almost 4 GiB, but is only 12 bytes long.
+        let huge = [&0xffff_fff0u32.to_be_bytes()[..], b"data", &[0, 0, 0, 1, 0, 0, 0, 0]].concat();
+
+        match try_read(make_file(&huge), &Default::default()) {
+            Err(Error::DecodeError(_)) => (),
+            _ => panic!("expected a decode error"),
+        }
+
+        // The limit is configurable.
+        let title = atom(b"data", &[&[0, 0, 0, 1, 0, 0, 0, 0][..], b"A Title"].concat());
+
+        let mut options = FormatOptions::default();
+        options.metadata.limit_metadata_bytes = Limit::Maximum(4);
+
+        assert!(try_read(make_file(&title), &options).is_err());
+        assert!(try_read(make_file(&title), &Default::default()).is_ok());
+    }
+
+    #[test]
+    fn verify_mov_data_type_sets() {
+        // Data atoms with a type set indicator, a data type, and then the country and language
+        // codes.
+        let ilst = atom(b"ilst", &[
+            // A UTF-8 title from the well-known type set.
+            atom(b"\xa9nam", &atom(b"data", &[&[0, 0, 0, 1, 0, 0, 0, 0][..], b"Title"].concat())),
+            // An artist from an undefined type set.
+            atom(b"\xa9ART", &atom(b"data", &[&[1, 0, 0, 1, 0, 0, 0, 0][..], b"Artist"].concat())),
+        ].concat());
+
+        let tags = read_tags(b"qt  \0\0\0\0qt  ", &ilst);
+
+        // The value with the unknown type set is skipped instead of failing the reader.
+        assert_eq!(tags.len(), 1);
+        assert!(matches!(tags[0].std_key, Some(StandardTagKey::TrackTitle)));
+        assert!(matches!(&tags[0].value, Value::String(value) if value == "Title"));
+    }
+
+    #[test]
+    fn verify_nero_chapter_cues() {
+        let ftyp = atom(b"ftyp", b"M4B \0\0\0\0M4B mp42");
+
+        let chpl = chpl(&[("Intro", 0), ("Chapter 2", 50_000_000), ("Outro", 75_000_000)]);
+
+        let moov = atom(b"moov", &[
+            mvhd(),
+            trak(1, b"soun", 44_100, b"und", &[], &[1024], &[4], 0),
+            atom(b"udta", &chpl),
+        ].concat());
+
+        let file = [ftyp, moov].concat();
+
+        let reader = open_mp4(file, &Default::default()).unwrap();
+
+        let cues = reader.cues();
+
+        assert_eq!(cues.len(), 3);
+
+        // The start times are in the timescale of the audio track.
+        let expected = [("Intro", 0), ("Chapter 2", 220_500), ("Outro", 330_750)];
+
+        for (i, (cue, &(title, ts))) in cues.iter().zip(expected.iter()).enumerate() {
+            assert_eq!(cue.index, i as u32 + 1);
+            assert_eq!(cue.start_ts, ts);
+            assert!(matches!(&cue.tags[0].value, Value::String(value) if value == title));
+            assert!(cue.points.is_empty());
+        }
+    }
+
+    #[test]
+    fn verify_skip_visuals_and_tags() {
+        let ftyp = atom(b"ftyp", b"M4A \0\0\0\0M4A mp42");
+
+        // A title, and a JPEG cover.
+        let ilst = atom(b"ilst", &[
+            atom(b"\xa9nam", &atom(b"data", &[&[0, 0, 0, 1, 0, 0, 0, 0][..], b"Title"].concat())),
+            atom(b"covr", &atom(b"data", &[0, 0, 0, 13, 0, 0, 0, 0, 0xff, 0xd8, 0xff, 0xd9])),
+        ].concat());
+
+        let meta = atom(b"meta", &[&[0, 0, 0, 0][..], &ilst].concat());
+
+        let file = [ftyp, meta, atom(b"moov", &mvhd())].concat();
+
+        // Returns the number of tags and visuals read with the given options.
+        let read = |options: &FormatOptions| {
+            let mut reader = open_mp4(file.clone(), options).unwrap();
+
+            let metadata = reader.metadata();
+            let rev = metadata.current().unwrap();
+
+            (rev.tags().len(), rev.visuals().len())
+        };
+
+        assert_eq!(read(&Default::default()), (1, 1));
+
+        let mut options = FormatOptions::default();
+        options.metadata.read_visuals = false;
+
+        assert_eq!(read(&options), (1, 0));
+
+        let mut options = FormatOptions::default();
+        options.metadata.read_tags = false;
+
+        assert_eq!(read(&options), (0, 1));
+    }
+}
@@ -8,7 +8,7 @@
 use std::collections::BTreeMap;
 
 use symphonia_core::support_format;
-use symphonia_core::errors::{Result, unsupported_error};
+use symphonia_core::errors::{Error, Result, unsupported_error};
 use symphonia_core::formats::prelude::*;
 use symphonia_core::io::MediaSourceStream;
 use symphonia_core::meta::{Metadata, MetadataLog};
@@ -125,7 +125,10 @@ impl FormatReader for OggReader {
         // Loop until a bitstream packet is read from the physical stream.
         loop {
             // Get the next packet, and consume it immediately.
-            let ogg_packet = self.physical_stream.next_packet(&mut self.reader)?;
+            // If there are no more pages, then there are no more packets.
+            let ogg_packet = self.physical_stream
+                                 .next_packet(&mut self.reader)
+                                 .map_err(Error::into_end_of_stream)?;
             self.physical_stream.consume_packet();
 
             // If the packet belongs to a logical stream with a mapper, process it.
@@ -167,6 +170,10 @@ impl FormatReader for OggReader {
         &self.tracks
     }
 
+    fn seek_caps(&self) -> SeekCaps {
+        SeekCaps::unsupported()
+    }
+
     fn seek(&mut self, _mode: SeekMode, _to: SeekTo) -> Result<SeekedTo> {
         unsupported_error("ogg seeking unsupported")
     }
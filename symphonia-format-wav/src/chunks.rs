@@ -152,26 +152,28 @@ impl<P: ParseChunk> ChunkParser<P> {
     }
 }
 
-fn map_wave_channels(channel_mask: u32) -> Channels {
-    const SPEAKER_FRONT_LEFT: u32            = 0x1;
-    const SPEAKER_FRONT_RIGHT: u32           = 0x2;
-    const SPEAKER_FRONT_CENTER: u32          = 0x4;
-    const SPEAKER_LOW_FREQUENCY: u32         = 0x8;
-    const SPEAKER_BACK_LEFT: u32             = 0x10;
-    const SPEAKER_BACK_RIGHT: u32            = 0x20;
-    const SPEAKER_FRONT_LEFT_OF_CENTER: u32  = 0x40;
-    const SPEAKER_FRONT_RIGHT_OF_CENTER: u32 = 0x80;
-    const SPEAKER_BACK_CENTER: u32           = 0x100;
-    const SPEAKER_SIDE_LEFT: u32             = 0x200;
-    const SPEAKER_SIDE_RIGHT: u32            = 0x400;
-    const SPEAKER_TOP_CENTER: u32            = 0x800;
-    const SPEAKER_TOP_FRONT_LEFT: u32        = 0x1000;
-    const SPEAKER_TOP_FRONT_CENTER: u32      = 0x2000;
-    const SPEAKER_TOP_FRONT_RIGHT: u32       = 0x4000;
-    const SPEAKER_TOP_BACK_LEFT: u32         = 0x8000;
-    const SPEAKER_TOP_BACK_CENTER: u32       = 0x10000;
-    const SPEAKER_TOP_BACK_RIGHT: u32        = 0x20000;
+// Speaker position bits of the WAVE_FORMAT_EXTENSIBLE channel mask (dwChannelMask). These
+// definitions can be found in ksmedia.h of the Microsoft Windows Platform SDK.
+const SPEAKER_FRONT_LEFT: u32            = 0x1;
+const SPEAKER_FRONT_RIGHT: u32           = 0x2;
+const SPEAKER_FRONT_CENTER: u32          = 0x4;
+const SPEAKER_LOW_FREQUENCY: u32         = 0x8;
+const SPEAKER_BACK_LEFT: u32             = 0x10;
+const SPEAKER_BACK_RIGHT: u32            = 0x20;
+const SPEAKER_FRONT_LEFT_OF_CENTER: u32  = 0x40;
+const SPEAKER_FRONT_RIGHT_OF_CENTER: u32 = 0x80;
+const SPEAKER_BACK_CENTER: u32           = 0x100;
+const SPEAKER_SIDE_LEFT: u32             = 0x200;
+const SPEAKER_SIDE_RIGHT: u32            = 0x400;
+const SPEAKER_TOP_CENTER: u32            = 0x800;
+const SPEAKER_TOP_FRONT_LEFT: u32        = 0x1000;
+const SPEAKER_TOP_FRONT_CENTER: u32      = 0x2000;
+const SPEAKER_TOP_FRONT_RIGHT: u32       = 0x4000;
+const SPEAKER_TOP_BACK_LEFT: u32         = 0x8000;
+const SPEAKER_TOP_BACK_CENTER: u32       = 0x10000;
+const SPEAKER_TOP_BACK_RIGHT: u32        = 0x20000;
 
+fn map_wave_channels(channel_mask: u32) -> Channels {
     let mut channels = Channels::empty();
 
     if channel_mask & SPEAKER_FRONT_LEFT            != 0 { channels |= Channels::FRONT_LEFT;         }
@@ -196,6 +198,66 @@ fn map_wave_channels(channel_mask: u32) -> Channels {
     channels
 }
 
+/// Maps a `Channels` bit mask to the equivalent WAVE_FORMAT_EXTENSIBLE channel mask
+/// (dwChannelMask). Discrete channels do not have speaker positions and map to a channel mask of 0.
+/// If any channel does not have an equivalent speaker position, `None` is returned.
+pub fn wave_channel_mask(channels: Channels) -> Option<u32> {
+    const SPEAKER_MAP: [(Channels, u32); 18] = [
+        (Channels::FRONT_LEFT,         SPEAKER_FRONT_LEFT           ),
+        (Channels::FRONT_RIGHT,        SPEAKER_FRONT_RIGHT          ),
+        (Channels::FRONT_CENTRE,       SPEAKER_FRONT_CENTER         ),
+        (Channels::LFE1,               SPEAKER_LOW_FREQUENCY        ),
+        (Channels::REAR_LEFT,          SPEAKER_BACK_LEFT            ),
+        (Channels::REAR_RIGHT,         SPEAKER_BACK_RIGHT           ),
+        (Channels::FRONT_LEFT_CENTRE,  SPEAKER_FRONT_LEFT_OF_CENTER ),
+        (Channels::FRONT_RIGHT_CENTRE, SPEAKER_FRONT_RIGHT_OF_CENTER),
+        (Channels::REAR_CENTRE,        SPEAKER_BACK_CENTER          ),
+        (Channels::SIDE_LEFT,          SPEAKER_SIDE_LEFT            ),
+        (Channels::SIDE_RIGHT,         SPEAKER_SIDE_RIGHT           ),
+        (Channels::TOP_CENTRE,         SPEAKER_TOP_CENTER           ),
+        (Channels::TOP_FRONT_LEFT,     SPEAKER_TOP_FRONT_LEFT       ),
+        (Channels::TOP_FRONT_CENTRE,   SPEAKER_TOP_FRONT_CENTER     ),
+        (Channels::TOP_FRONT_RIGHT,    SPEAKER_TOP_FRONT_RIGHT      ),
+        (Channels::TOP_REAR_LEFT,      SPEAKER_TOP_BACK_LEFT        ),
+        (Channels::TOP_REAR_CENTRE,    SPEAKER_TOP_BACK_CENTER      ),
+        (Channels::TOP_REAR_RIGHT,     SPEAKER_TOP_BACK_RIGHT       ),
+    ];
+
+    if channels.is_discrete() {
+        return Some(0);
+    }
+
+    let mut mask = 0;
+    let mut mapped = Channels::empty();
+
+    for &(channel, speaker) in SPEAKER_MAP.iter() {
+        if channels.has_positions(channel) {
+            mask |= speaker;
+            mapped |= channel;
+        }
+    }
+
+    // All channels must have a speaker position.
+    if mapped == channels {
+        Some(mask)
+    }
+    else {
+        None
+    }
+}
+
+/// Returns `true` if a WAVE stream with the given channels and codec must be written using the
+/// WAVE_FORMAT_EXTENSIBLE format. This is the case for streams with more than 2 channels, or
+/// discrete channels, since only the extensible format can describe their speaker positions. It is
+/// also the case for codecs other than 8-bit or 16-bit integer PCM.
+pub fn is_wave_format_extensible_required(channels: Channels, codec: CodecType) -> bool {
+    if channels.is_discrete() || channels.count() > 2 {
+        return true;
+    }
+
+    !matches!(codec, CODEC_TYPE_PCM_U8 | CODEC_TYPE_PCM_S16LE)
+}
+
 pub enum WaveFormatData {
     Pcm(WaveFormatPcm),
     IeeeFloat(WaveFormatIeeeFloat),
@@ -365,6 +427,7 @@ impl WaveFormatChunk {
     fn read_ext_fmt<B: ReadBytes>(
         reader: &mut B,
         bits_per_coded_sample: u16,
+        n_channels: u16,
         len: u32,
     ) -> Result<WaveFormatData> {
         // WaveFormat for the extensible format must be extended to 40 bytes in length.
@@ -469,7 +532,12 @@ impl WaveFormatChunk {
             _ => return unsupported_error("unsupported fmt_ext sub-type"),
         };
 
-        let channels = map_wave_channels(channel_mask);
+        // If the channel mask does not assign a position to every channel, then the channels are
+        // passed through as discrete channels in index order.
+        let channels = match map_wave_channels(channel_mask) {
+            channels if channels.count() == usize::from(n_channels) => channels,
+            _ => Channels::discrete(usize::from(n_channels)),
+        };
 
         Ok(WaveFormatData::Extensible(WaveFormatExtensible {
             bits_per_sample,
@@ -564,7 +632,7 @@ impl ParseChunk for WaveFormatChunk {
             }
             // The Extensible Wave Format
             WAVE_FORMAT_EXTENSIBLE => {
-                Self::read_ext_fmt(reader, bits_per_sample, len)
+                Self::read_ext_fmt(reader, bits_per_sample, n_channels, len)
             }
             // The Alaw Wave Format.
             WAVE_FORMAT_ALAW => {
@@ -749,4 +817,75 @@ impl ParseChunkTag for RiffInfoListChunks {
         // TODO: Actually validate that the chunk is an info chunk.
         parser!(RiffInfoListChunks::Info, InfoChunk, tag, len)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use symphonia_core::audio::Channels;
+    use symphonia_core::codecs::{CODEC_TYPE_PCM_S16LE, CODEC_TYPE_PCM_S24LE, CODEC_TYPE_PCM_F32LE};
+    use symphonia_core::io::BufReader;
+
+    use super::{ParseChunk, WaveFormatChunk, WaveFormatData};
+    use super::{wave_channel_mask, is_wave_format_extensible_required};
+
+    const SURROUND_5P1: Channels = Channels::from_bits_truncate(
+        Channels::FRONT_LEFT.bits()
+            | Channels::FRONT_RIGHT.bits()
+            | Channels::FRONT_CENTRE.bits()
+            | Channels::LFE1.bits()
+            | Channels::REAR_LEFT.bits()
+            | Channels::REAR_RIGHT.bits()
+    );
+
+    /// Builds an extensible 16-bit PCM fmt chunk body with the given channel mask.
+    fn ext_fmt_chunk(n_channels: u16, channel_mask: u32) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&0xfffe_u16.to_le_bytes());
+        buf.extend_from_slice(&n_channels.to_le_bytes());
+        buf.extend_from_slice(&48000_u32.to_le_bytes());
+        buf.extend_from_slice(&(48000 * 2 * u32::from(n_channels)).to_le_bytes());
+        buf.extend_from_slice(&(2 * n_channels).to_le_bytes());
+        buf.extend_from_slice(&16_u16.to_le_bytes());
+        buf.extend_from_slice(&22_u16.to_le_bytes());
+        buf.extend_from_slice(&16_u16.to_le_bytes());
+        buf.extend_from_slice(&channel_mask.to_le_bytes());
+        buf.extend_from_slice(&[
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00,
+            0x80, 0x00, 0x00, 0xaa, 0x00, 0x38, 0x9b, 0x71,
+        ]);
+        buf
+    }
+
+    #[test]
+    fn verify_wave_channel_mask() {
+        assert_eq!(wave_channel_mask(Channels::FRONT_LEFT | Channels::FRONT_RIGHT), Some(0x3));
+        assert_eq!(wave_channel_mask(SURROUND_5P1), Some(0x3f));
+        assert_eq!(wave_channel_mask(Channels::discrete(4)), Some(0));
+        assert_eq!(wave_channel_mask(Channels::FRONT_LEFT | Channels::LFE2), None);
+    }
+
+    #[test]
+    fn verify_wave_channel_mask_round_trip() {
+        let mask = wave_channel_mask(SURROUND_5P1).unwrap();
+        let buf = ext_fmt_chunk(6, mask);
+
+        let mut reader = BufReader::new(&buf);
+        let fmt = WaveFormatChunk::parse(&mut reader, *b"fmt ", buf.len() as u32).unwrap();
+
+        match fmt.format_data {
+            WaveFormatData::Extensible(ext) => assert_eq!(ext.channels, SURROUND_5P1),
+            _ => panic!("expected extensible format data"),
+        }
+    }
+
+    #[test]
+    fn verify_extensible_required() {
+        let stereo = Channels::FRONT_LEFT | Channels::FRONT_RIGHT;
+
+        assert!(!is_wave_format_extensible_required(stereo, CODEC_TYPE_PCM_S16LE));
+        assert!(is_wave_format_extensible_required(stereo, CODEC_TYPE_PCM_S24LE));
+        assert!(is_wave_format_extensible_required(stereo, CODEC_TYPE_PCM_F32LE));
+        assert!(is_wave_format_extensible_required(SURROUND_5P1, CODEC_TYPE_PCM_S16LE));
+        assert!(is_wave_format_extensible_required(Channels::discrete(2), CODEC_TYPE_PCM_S16LE));
+    }
+}
@@ -11,12 +11,16 @@
 use std::io::{Seek, SeekFrom};
 
 use symphonia_core::support_format;
-use symphonia_core::codecs::CodecParameters;
-use symphonia_core::errors::{Result, seek_error, unsupported_error, SeekErrorKind};
+use symphonia_core::codecs::{CodecParameters, CodecType};
+use symphonia_core::codecs::{CODEC_TYPE_PCM_U8, CODEC_TYPE_PCM_S16LE, CODEC_TYPE_PCM_S24LE};
+use symphonia_core::codecs::{CODEC_TYPE_PCM_S32LE, CODEC_TYPE_PCM_F32LE, CODEC_TYPE_PCM_F64LE};
+use symphonia_core::codecs::{CODEC_TYPE_PCM_ALAW, CODEC_TYPE_PCM_MULAW};
+use symphonia_core::errors::{Error, Result, seek_error, unsupported_error, SeekErrorKind};
 use symphonia_core::formats::prelude::*;
 use symphonia_core::io::*;
 use symphonia_core::meta::{Metadata, MetadataRevision, MetadataBuilder, MetadataLog};
 use symphonia_core::probe::{Descriptor, Instantiate, QueryDescriptor};
+use symphonia_core::sample::SampleFormat;
 
 use log::{debug, error};
 
@@ -24,6 +28,8 @@ mod chunks;
 
 use chunks::*;
 
+pub use chunks::{wave_channel_mask, is_wave_format_extensible_required};
+
 /// WAVE is actually a RIFF stream, with a "RIFF" ASCII stream marker.
 const WAVE_STREAM_MARKER: [u8; 4] = *b"RIFF";
 
@@ -162,7 +168,8 @@ impl FormatReader for WavReader {
 
         // Read up-to WAVE_MAX_FRAMES_PER_PACKET number of frames per packet.
         let packet_len = WAVE_MAX_FRAMES_PER_PACKET * u64::from(self.frame_len);
-        let packet_buf = self.reader.read_boxed_slice(packet_len as usize)?;
+        let packet_buf = self.reader.read_boxed_slice(packet_len as usize)
+                                    .map_err(|err| Error::from(err).into_end_of_stream())?;
 
         // The packet duration is the length of the packet in bytes divided by the length per frame.
         let dur = packet_buf.len() as u64 / u64::from(self.frame_len);
@@ -170,6 +177,29 @@ impl FormatReader for WavReader {
         Ok(Packet::new_from_boxed_slice(0, pts, dur, packet_buf))
     }
 
+    fn try_next_packet(&mut self) -> Result<Option<Packet>> {
+        // The bytes of an incomplete packet are given back so it can be read again once the rest of
+        // it is available. This is only possible if the whole packet remains buffered.
+        let packet_len = WAVE_MAX_FRAMES_PER_PACKET * u64::from(self.frame_len);
+
+        if packet_len > self.reader.max_read_buffer_len() as u64 {
+            return unsupported_error("wav: packets are larger than the stream buffer");
+        }
+
+        let pos = self.reader.pos();
+
+        match self.next_packet() {
+            Ok(packet) => Ok(Some(packet)),
+            Err(err) if err.is_would_block() => {
+                if self.reader.seek_buffered(pos) != pos {
+                    return unsupported_error("wav: incomplete packet is no longer buffered");
+                }
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
     fn metadata(&mut self) -> Metadata<'_> {
         self.metadata.metadata()
     }
@@ -182,6 +212,16 @@ impl FormatReader for WavReader {
         &self.tracks
     }
 
+    fn seek_caps(&self) -> SeekCaps {
+        // Seeks are to packet boundaries, see the comment in seek.
+        if self.tracks.is_empty() || self.frame_len == 0 {
+            SeekCaps::unsupported()
+        }
+        else {
+            SeekCaps::sample_accurate(self.reader.is_seekable(), Some(WAVE_MAX_FRAMES_PER_PACKET))
+        }
+    }
+
     fn seek(&mut self, _mode: SeekMode, to: SeekTo) -> Result<SeekedTo> {
 
         if self.tracks.is_empty() || self.frame_len == 0 {
@@ -282,7 +322,8 @@ fn append_format_params(codec_params: &mut CodecParameters, format: &WaveFormatC
 
     codec_params
         .with_max_frames_per_packet(WAVE_MAX_FRAMES_PER_PACKET)
-        .with_sample_rate(format.sample_rate);
+        .with_sample_rate(format.sample_rate)
+        .with_bit_rate(format.avg_bytes_per_sec.saturating_mul(8));
 
     match format.format_data {
         WaveFormatData::Pcm(ref pcm) => {
@@ -315,6 +356,26 @@ fn append_format_params(codec_params: &mut CodecParameters, format: &WaveFormatC
                 .with_channels(mulaw.channels);
         }
     }
+
+    // The sample format distinguishes between integer and floating point PCM of the same width.
+    if let Some(sample_format) = codec_sample_format(codec_params.codec) {
+        codec_params.with_sample_format(sample_format);
+    }
+}
+
+/// Gets the sample format of the decoded audio for a WAVE codec.
+fn codec_sample_format(codec: CodecType) -> Option<SampleFormat> {
+    match codec {
+        CODEC_TYPE_PCM_U8    => Some(SampleFormat::U8),
+        CODEC_TYPE_PCM_S16LE => Some(SampleFormat::S16),
+        CODEC_TYPE_PCM_S24LE => Some(SampleFormat::S24),
+        CODEC_TYPE_PCM_S32LE => Some(SampleFormat::S32),
+        CODEC_TYPE_PCM_F32LE => Some(SampleFormat::F32),
+        CODEC_TYPE_PCM_F64LE => Some(SampleFormat::F64),
+        // A-law and Mu-law expand to 16-bit linear PCM.
+        CODEC_TYPE_PCM_ALAW | CODEC_TYPE_PCM_MULAW => Some(SampleFormat::S16),
+        _ => None,
+    }
 }
 
 fn append_fact_params(codec_params: &mut CodecParameters, fact: &FactChunk) {
@@ -326,4 +387,41 @@ fn append_data_params(codec_params: &mut CodecParameters, data: &DataChunk, fram
         let n_frames = data.len / u32::from(frame_len);
         codec_params.with_n_frames(u64::from(n_frames));
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use symphonia_core::formats::{FormatOptions, FormatReader};
+    use symphonia_core::io::MediaSourceStream;
+
+    use super::WavReader;
+
+    #[test]
+    fn verify_bit_rate() {
+        // A 16-bit stereo PCM stream at 44.1 kHz with 100 frames of silence.
+        let mut buf = Vec::new();
+        buf.extend_from_slice(b"RIFF");
+        buf.extend_from_slice(&(4 + 24 + 8 + 400_u32).to_le_bytes());
+        buf.extend_from_slice(b"WAVEfmt ");
+        buf.extend_from_slice(&16_u32.to_le_bytes());
+        buf.extend_from_slice(&1_u16.to_le_bytes());
+        buf.extend_from_slice(&2_u16.to_le_bytes());
+        buf.extend_from_slice(&44_100_u32.to_le_bytes());
+        buf.extend_from_slice(&(44_100 * 4_u32).to_le_bytes());
+        buf.extend_from_slice(&4_u16.to_le_bytes());
+        buf.extend_from_slice(&16_u16.to_le_bytes());
+        buf.extend_from_slice(b"data");
+        buf.extend_from_slice(&400_u32.to_le_bytes());
+        buf.extend_from_slice(&[0; 400]);
+
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(buf)), Default::default());
+        let reader = WavReader::try_new(mss, &FormatOptions::default()).unwrap();
+
+        // The bit rate is the average bytes per second of the format chunk.
+        let params = &reader.tracks()[0].codec_params;
+        assert_eq!(params.bit_rate, Some(1_411_200));
+        assert_eq!(params.n_frames, Some(100));
+    }
+}
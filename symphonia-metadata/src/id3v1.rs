@@ -266,14 +266,9 @@ pub fn read_id3v1<B: ReadBytes>(reader: &mut B, metadata: &mut MetadataBuilder)
         );
     }
 
-    let genre_idx = buf[124] as usize;
-
-    // Convert the genre index to an actual genre name using the GENRES lookup table. Genre #133 is
-    // an offensive term and is excluded from Symphonia.
-    if genre_idx < GENRES.len() && genre_idx != 133 {
-        metadata.add_tag(
-            Tag::new(Some(StandardTagKey::Genre), "GENRE", Value::from(GENRES[genre_idx]))
-        );
+    // Convert the genre index to an actual genre name.
+    if let Some(genre) = util::genre_name(buf[124]) {
+        metadata.add_tag(Tag::new(Some(StandardTagKey::Genre), "GENRE", Value::from(genre)));
     }
 
     Ok(())
@@ -286,8 +281,32 @@ fn decode_iso8859_text(data: &[u8]) -> String {
 pub mod util {
     use super::GENRES;
 
-    /// Try to get the genre name for the ID3v1 genre index.
-    pub fn genre_name(index: u8) -> Option<&'static &'static str> {
-        GENRES.get(usize::from(index))
+    /// Try to get the genre name for the ID3v1 genre index. The genre list includes the Winamp
+    /// extended genres, and is shared by all metadata formats that reference genres by their ID3v1
+    /// index.
+    pub fn genre_name(index: u8) -> Option<&'static str> {
+        // Genre #133 is an offensive term and is excluded from Symphonia.
+        if index == 133 {
+            return None;
+        }
+
+        GENRES.get(usize::from(index)).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::util::genre_name;
+
+    #[test]
+    fn verify_genre_names() {
+        assert_eq!(genre_name(0), Some("Blues"));
+        assert_eq!(genre_name(9), Some("Metal"));
+        assert_eq!(genre_name(17), Some("Rock"));
+        assert_eq!(genre_name(80), Some("Folk"));
+        assert_eq!(genre_name(191), Some("Psybient"));
+        assert_eq!(genre_name(133), None);
+        assert_eq!(genre_name(192), None);
+        assert_eq!(genre_name(255), None);
     }
 }
\ No newline at end of file
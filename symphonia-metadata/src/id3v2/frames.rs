@@ -17,6 +17,8 @@ use encoding_rs::UTF_16BE;
 use lazy_static::lazy_static;
 use log::warn;
 
+use crate::id3v1::util::genre_name;
+
 use super::unsync::{decode_unsynchronisation, read_syncsafe_leq32};
 
 // The following is a list of all standardized ID3v2.x frames for all ID3v2 major versions and their
@@ -269,7 +271,7 @@ lazy_static! {
             m.insert(b"TALB", (read_text_frame, Some(StandardTagKey::Album)));
             m.insert(b"TBPM", (read_text_frame, Some(StandardTagKey::Bpm)));
             m.insert(b"TCOM", (read_text_frame, Some(StandardTagKey::Composer)));
-            m.insert(b"TCON", (read_text_frame, Some(StandardTagKey::Genre)));
+            m.insert(b"TCON", (read_tcon_frame, Some(StandardTagKey::Genre)));
             m.insert(b"TCOP", (read_text_frame, Some(StandardTagKey::Copyright)));
             m.insert(b"TDAT", (read_text_frame, Some(StandardTagKey::Date)));
             m.insert(b"TDEN", (read_text_frame, Some(StandardTagKey::EncodingDate)));
@@ -644,6 +646,86 @@ fn read_text_frame(
     Ok(FrameResult::MultipleTags(tags))
 }
 
+/// Reads a `TCON` (content type) text frame. Genres may be referenced by their ID3v1 genre index,
+/// either as a plain number, or enclosed in parenthesis and optionally followed by a refinement. A
+/// value referencing multiple genres yields one `Tag` per genre.
+fn read_tcon_frame(
+    reader: &mut BufReader<'_>,
+    std_key: Option<StandardTagKey>,
+    id: &str,
+) -> Result<FrameResult> {
+    let tags = match read_text_frame(reader, std_key, id)? {
+        FrameResult::MultipleTags(tags) => tags,
+        result => return Ok(result),
+    };
+
+    let mut resolved = Vec::with_capacity(tags.len());
+
+    for tag in tags {
+        let genres = match tag.value {
+            Value::String(ref text) => resolve_genres(text),
+            _ => None,
+        };
+
+        match genres {
+            Some(genres) => {
+                for genre in genres {
+                    resolved.push(Tag::new(tag.std_key, &tag.key, Value::from(genre)));
+                }
+            }
+            None => resolved.push(tag),
+        }
+    }
+
+    Ok(FrameResult::MultipleTags(resolved))
+}
+
+/// Resolves the ID3v1 genre references in a `TCON` frame value to a list of genre names. Returns
+/// `None` if the value does not reference any genres, or references an unknown genre.
+fn resolve_genres(text: &str) -> Option<Vec<String>> {
+    let text = text.trim();
+
+    // A plain ID3v1 genre index (ID3v2.4).
+    if let Ok(index) = text.parse::<u8>() {
+        return genre_name(index).map(|genre| vec![genre.to_string()]);
+    }
+
+    if !text.starts_with('(') {
+        return None;
+    }
+
+    // A list of parenthesized ID3v1 genre indicies, or the special remix or cover references
+    // (ID3v2.3 and older).
+    let mut genres = Vec::new();
+    let mut rest = text;
+
+    while rest.starts_with('(') && !rest.starts_with("((") {
+        let end = rest.find(')')?;
+
+        let genre = match &rest[1..end] {
+            "RX" => "Remix",
+            "CR" => "Cover",
+            index => genre_name(index.parse().ok()?)?,
+        };
+
+        genres.push(genre.to_string());
+
+        rest = &rest[end + 1..];
+    }
+
+    // The references may be followed by a refinement. A refinement starting with a parenthesis is
+    // escaped by doubling it.
+    let refinement = if rest.starts_with("((") { &rest[1..] } else { rest }.trim();
+
+    // A refinement takes precedence over the reference it refines.
+    if !refinement.is_empty() {
+        genres.pop();
+        genres.push(refinement.to_string());
+    }
+
+    Some(genres)
+}
+
 /// Reads a `TXXX` (user defined) text frame.
 fn read_txxx_frame(
     reader: &mut BufReader<'_>,
@@ -938,4 +1020,78 @@ fn decode_text(encoding: Encoding, data: &[u8]) -> Cow<'_, str> {
             UTF_16BE.decode(&data[..end]).0
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use symphonia_core::io::BufReader;
+    use symphonia_core::meta::{StandardTagKey, Value};
+
+    use super::{read_tcon_frame, resolve_genres, FrameResult};
+
+    #[test]
+    fn verify_resolve_genres() {
+        // Plain and parenthesized genre indicies.
+        assert_eq!(resolve_genres("17"), Some(vec!["Rock".to_string()]));
+        assert_eq!(resolve_genres("(17)"), Some(vec!["Rock".to_string()]));
+
+        // Multiple genre references.
+        assert_eq!(
+            resolve_genres("(51)(39)"),
+            Some(vec!["Techno-Industrial".to_string(), "Noise".to_string()])
+        );
+
+        // A refinement replaces the last genre reference.
+        assert_eq!(resolve_genres("(4)Eurodisco"), Some(vec!["Eurodisco".to_string()]));
+        assert_eq!(
+            resolve_genres("(17)(4)Eurodisco"),
+            Some(vec!["Rock".to_string(), "Eurodisco".to_string()])
+        );
+
+        // The remix and cover references.
+        assert_eq!(resolve_genres("(RX)"), Some(vec!["Remix".to_string()]));
+        assert_eq!(resolve_genres("(CR)(17)"), Some(vec!["Cover".to_string(), "Rock".to_string()]));
+
+        // An escaped parenthesis begins a refinement.
+        assert_eq!(
+            resolve_genres("((I can figure out any genre)"),
+            Some(vec!["(I can figure out any genre)".to_string()])
+        );
+        assert_eq!(resolve_genres("(17)((Live)"), Some(vec!["(Live)".to_string()]));
+
+        // Plain text, unknown genres, and malformed references are not resolved.
+        assert_eq!(resolve_genres("Rock"), None);
+        assert_eq!(resolve_genres("(133)"), None);
+        assert_eq!(resolve_genres("(255)"), None);
+        assert_eq!(resolve_genres("(17"), None);
+        assert_eq!(resolve_genres("(XX)"), None);
+    }
+
+    #[test]
+    fn verify_read_tcon_frame() {
+        // An ISO-8859-1 encoded frame with a list of genre references, and a plain genre name.
+        let frame = b"\x00(51)(39)\x00Synthwave";
+
+        let mut reader = BufReader::new(frame);
+
+        let tags = match read_tcon_frame(&mut reader, Some(StandardTagKey::Genre), "TCON") {
+            Ok(FrameResult::MultipleTags(tags)) => tags,
+            _ => panic!("expected multiple tags"),
+        };
+
+        let values = tags
+            .iter()
+            .map(|tag| {
+                assert_eq!(tag.std_key, Some(StandardTagKey::Genre));
+                assert_eq!(tag.key, "TCON");
+
+                match tag.value {
+                    Value::String(ref genre) => genre.as_str(),
+                    _ => panic!("genre is not a string"),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(values, ["Techno-Industrial", "Noise", "Synthwave"]);
+    }
+}
@@ -7,7 +7,7 @@
 
 //! iTunes metadata support.
 
-use symphonia_core::meta::StandardTagKey;
+use symphonia_core::meta::{StandardTagKey, map_vorbis_comment};
 
 use std::collections::HashMap;
 
@@ -39,6 +39,9 @@ lazy_static! {
             m.insert("com.apple.iTunes:MusicBrainz Album Status", StandardTagKey::MusicBrainzReleaseStatus);
             m.insert("com.apple.iTunes:MusicBrainz Album Type", StandardTagKey::MusicBrainzReleaseType);
             m.insert("com.apple.iTunes:MusicBrainz Artist Id", StandardTagKey::MusicBrainzArtistId);
+            m.insert("com.apple.iTunes:MusicBrainz Disc Id", StandardTagKey::MusicBrainzDiscId);
+            m.insert("com.apple.iTunes:MusicBrainz Original Album Id", StandardTagKey::MusicBrainzOriginalAlbumId);
+            m.insert("com.apple.iTunes:MusicBrainz Original Artist Id", StandardTagKey::MusicBrainzOriginalArtistId);
             m.insert("com.apple.iTunes:MusicBrainz Release Group Id", StandardTagKey::MusicBrainzReleaseGroupId);
             m.insert("com.apple.iTunes:MusicBrainz Release Track Id", StandardTagKey::MusicBrainzReleaseTrackId);
             m.insert("com.apple.iTunes:MusicBrainz Track Id", StandardTagKey::MusicBrainzTrackId);
@@ -46,16 +49,146 @@ lazy_static! {
             m.insert("com.apple.iTunes:originaldate", StandardTagKey::OriginalDate);
             m.insert("com.apple.iTunes:PRODUCER", StandardTagKey::Producer);
             m.insert("com.apple.iTunes:REMIXER", StandardTagKey::Remixer);
+            m.insert("com.apple.iTunes:replaygain_album_gain", StandardTagKey::ReplayGainAlbumGain);
+            m.insert("com.apple.iTunes:replaygain_album_peak", StandardTagKey::ReplayGainAlbumPeak);
+            m.insert("com.apple.iTunes:replaygain_track_gain", StandardTagKey::ReplayGainTrackGain);
+            m.insert("com.apple.iTunes:replaygain_track_peak", StandardTagKey::ReplayGainTrackPeak);
             m.insert("com.apple.iTunes:SCRIPT", StandardTagKey::Script);
             m.insert("com.apple.iTunes:SUBTITLE", StandardTagKey::TrackSubtitle);
             m
         };
 }
 
-/// Try to map the iTunes `tag` name to a `StandardTagKey`.
+/// The fully qualified name of the iTunes Sound Check normalization tag.
+pub const ITUNES_NORM_TAG: &str = "com.apple.iTunes:iTunNORM";
+
+/// Parses an iTunes Sound Check normalization (iTunNORM) value and returns the normalization gain in
+/// decibels.
+///
+/// The value is a list of ten space-separated 8-digit hexadecimal numbers. The first pair are the
+/// left and right channel loudness relative to a reference of 1000. The louder of the two channels
+/// determines the gain.
+pub fn parse_itunes_norm(value: &str) -> Option<f64> {
+    let mut values = value.split_whitespace().map(|v| u32::from_str_radix(v, 16));
+
+    let left = values.next()?.ok()?;
+    let right = values.next()?.ok()?;
+
+    let loudness = left.max(right);
+
+    if loudness == 0 {
+        return None;
+    }
+
+    Some(-10.0 * (f64::from(loudness) / 1000.0).log10())
+}
+
+/// The fully qualified name of the iTunes gapless playback information tag.
+pub const ITUNES_SMPB_TAG: &str = "com.apple.iTunes:iTunSMPB";
+
+/// Parses an iTunes gapless playback information (iTunSMPB) value and returns the number of
+/// leading (delay) and trailing (padding) frames added by the encoder, and the total number of
+/// encoded frames including the delay and padding.
+///
+/// The value is a list of space-separated hexadecimal numbers. The second and third are the delay
+/// and padding, and the fourth is the number of frames of the original audio.
+pub fn parse_itunes_smpb(value: &str) -> Option<(u32, u32, u64)> {
+    let mut values = value.split_whitespace().skip(1);
+
+    let delay = u32::from_str_radix(values.next()?, 16).ok()?;
+    let padding = u32::from_str_radix(values.next()?, 16).ok()?;
+    let n_frames = u64::from_str_radix(values.next()?, 16).ok()?;
+
+    Some((delay, padding, u64::from(delay) + n_frames + u64::from(padding)))
+}
+
+/// Try to map the iTunes `tag` name to a `StandardTagKey`. Tag names are matched
+/// case-insensitively. Some taggers name iTunes tags using Vorbis Comment field names instead
+/// (e.g., `MUSICBRAINZ_TRACKID` instead of `MusicBrainz Track Id`), these are mapped as well.
 pub fn std_key_from_tag(tag: &str) -> Option<StandardTagKey> {
-    match ITUNES_TAG_MAP.get(tag) {
-        Some(key) => Some(*key),
-        _         => None,
+    if let Some(key) = ITUNES_TAG_MAP.get(tag) {
+        return Some(*key);
     }
-}
\ No newline at end of file
+
+    let key = ITUNES_TAG_MAP.iter().find(|(name, _)| name.eq_ignore_ascii_case(tag));
+
+    match key {
+        Some((_, key)) => Some(*key),
+        _              => tag.strip_prefix("com.apple.iTunes:").and_then(map_vorbis_comment),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use symphonia_core::meta::{StandardTagKey, map_vorbis_comment};
+
+    use super::{parse_itunes_norm, parse_itunes_smpb, std_key_from_tag};
+
+    #[test]
+    fn verify_parse_itunes_norm() {
+        let norm = " 00000A1D 00000A13 00003E9A 00003EA2 00024CA8 00024CA8 00007FFF 00007FFF \
+                     00024CA8 00024CA8";
+
+        // The louder channel is 0xa1d (2589), therefore the gain is -10 * log10(2.589) dB.
+        let gain = parse_itunes_norm(norm).unwrap();
+
+        assert!((gain - -4.1313).abs() < 1e-4);
+
+        // The derived linear gain factor.
+        assert!((10f64.powf(gain / 20.0) - 0.6215).abs() < 1e-4);
+
+        assert_eq!(parse_itunes_norm(""), None);
+        assert_eq!(parse_itunes_norm("00000000 00000000"), None);
+        assert_eq!(parse_itunes_norm("00000A1D ZZZZZZZZ"), None);
+    }
+
+    #[test]
+    fn verify_parse_itunes_smpb() {
+        let smpb = " 00000000 00000840 000001C0 0000000000046E00 00000000 00000000 00000000 \
+                     00000000 00000000 00000000 00000000 00000000";
+
+        // A delay of 2112 frames, padding of 448 frames, and 290304 frames of original audio.
+        assert_eq!(parse_itunes_smpb(smpb), Some((2112, 448, 292_864)));
+
+        assert_eq!(parse_itunes_smpb(""), None);
+        assert_eq!(parse_itunes_smpb("00000000 00000840"), None);
+        assert_eq!(parse_itunes_smpb("00000000 ZZZZZZZZ 000001C0 0000000000046E00"), None);
+    }
+
+    #[test]
+    fn verify_replay_gain_tags() {
+        assert!(matches!(
+            std_key_from_tag("com.apple.iTunes:replaygain_track_gain"),
+            Some(StandardTagKey::ReplayGainTrackGain)
+        ));
+        assert!(matches!(
+            std_key_from_tag("com.apple.iTunes:replaygain_album_peak"),
+            Some(StandardTagKey::ReplayGainAlbumPeak)
+        ));
+    }
+
+    #[test]
+    fn verify_musicbrainz_tags() {
+        // Each spelling of the MusicBrainz track ID maps to the same key as the Vorbis Comment.
+        for name in &[
+            "com.apple.iTunes:MusicBrainz Track Id",
+            "com.apple.iTunes:MusicBrainz Track ID",
+            "com.apple.iTunes:MUSICBRAINZ_TRACKID",
+        ] {
+            assert!(matches!(std_key_from_tag(name), Some(StandardTagKey::MusicBrainzTrackId)));
+        }
+
+        assert!(matches!(
+            map_vorbis_comment("MUSICBRAINZ_TRACKID"),
+            Some(StandardTagKey::MusicBrainzTrackId)
+        ));
+
+        assert!(matches!(
+            std_key_from_tag("com.apple.iTunes:MusicBrainz Original Album Id"),
+            Some(StandardTagKey::MusicBrainzOriginalAlbumId)
+        ));
+
+        assert!(std_key_from_tag("com.apple.iTunes:Unknown").is_none());
+        assert!(std_key_from_tag("org.example:MUSICBRAINZ_TRACKID").is_none());
+    }
+}
@@ -7,107 +7,9 @@
 
 //! A Vorbic COMMENT metadata reader for FLAC or OGG formats.
 
-use std::collections::HashMap;
-use lazy_static::lazy_static;
 use symphonia_core::errors::Result;
 use symphonia_core::io::ReadBytes;
-use symphonia_core::meta::{MetadataBuilder, StandardTagKey, Tag, Value};
-
-lazy_static! {
-    static ref VORBIS_COMMENT_MAP: HashMap<&'static str, StandardTagKey> = {
-        let mut m = HashMap::new();
-        m.insert("album artist"                , StandardTagKey::AlbumArtist);
-        m.insert("album"                       , StandardTagKey::Album);
-        m.insert("albumartist"                 , StandardTagKey::AlbumArtist);
-        m.insert("albumartistsort"             , StandardTagKey::SortAlbumArtist);
-        m.insert("albumsort"                   , StandardTagKey::SortAlbum);
-        m.insert("arranger"                    , StandardTagKey::Arranger);
-        m.insert("artist"                      , StandardTagKey::Artist);
-        m.insert("artistsort"                  , StandardTagKey::SortArtist);
-        // TODO: Is Author a synonym for Writer?
-        m.insert("author"                      , StandardTagKey::Writer);
-        m.insert("barcode"                     , StandardTagKey::IdentBarcode);
-        m.insert("bpm"                         , StandardTagKey::Bpm);
-        m.insert("catalog #"                   , StandardTagKey::IdentCatalogNumber);
-        m.insert("catalog"                     , StandardTagKey::IdentCatalogNumber);
-        m.insert("catalognumber"               , StandardTagKey::IdentCatalogNumber);
-        m.insert("catalogue #"                 , StandardTagKey::IdentCatalogNumber);
-        m.insert("comment"                     , StandardTagKey::Comment);
-        m.insert("compileation"                , StandardTagKey::Compilation);
-        m.insert("composer"                    , StandardTagKey::Composer);
-        m.insert("conductor"                   , StandardTagKey::Conductor);
-        m.insert("copyright"                   , StandardTagKey::Copyright);
-        m.insert("date"                        , StandardTagKey::Date);
-        m.insert("description"                 , StandardTagKey::Description);
-        m.insert("disc"                        , StandardTagKey::DiscNumber);
-        m.insert("discnumber"                  , StandardTagKey::DiscNumber);
-        m.insert("discsubtitle"                , StandardTagKey::DiscSubtitle);
-        m.insert("disctotal"                   , StandardTagKey::DiscTotal);
-        m.insert("disk"                        , StandardTagKey::DiscNumber);
-        m.insert("disknumber"                  , StandardTagKey::DiscNumber);
-        m.insert("disksubtitle"                , StandardTagKey::DiscSubtitle);
-        m.insert("disktotal"                   , StandardTagKey::DiscTotal);
-        m.insert("djmixer"                     , StandardTagKey::MixDj);
-        m.insert("ean/upn"                     , StandardTagKey::IdentEanUpn);
-        m.insert("encoded-by"                  , StandardTagKey::EncodedBy);
-        m.insert("encoder settings"            , StandardTagKey::EncoderSettings);
-        m.insert("encoder"                     , StandardTagKey::Encoder);
-        m.insert("encoding"                    , StandardTagKey::EncoderSettings);
-        m.insert("engineer"                    , StandardTagKey::Engineer);
-        m.insert("ensemble"                    , StandardTagKey::Ensemble);
-        m.insert("genre"                       , StandardTagKey::Genre);
-        m.insert("isrc"                        , StandardTagKey::IdentIsrc);
-        m.insert("language"                    , StandardTagKey::Language);
-        m.insert("label"                       , StandardTagKey::Label);
-        m.insert("license"                     , StandardTagKey::License);
-        m.insert("lyricist"                    , StandardTagKey::Lyricist);
-        m.insert("lyrics"                      , StandardTagKey::Lyrics);
-        m.insert("media"                       , StandardTagKey::MediaFormat);
-        m.insert("mixer"                       , StandardTagKey::MixEngineer);
-        m.insert("mood"                        , StandardTagKey::Mood);
-        m.insert("musicbrainz_albumartistid"   , StandardTagKey::MusicBrainzAlbumArtistId);
-        m.insert("musicbrainz_albumid"         , StandardTagKey::MusicBrainzAlbumId);
-        m.insert("musicbrainz_artistid"        , StandardTagKey::MusicBrainzArtistId);
-        m.insert("musicbrainz_discid"          , StandardTagKey::MusicBrainzDiscId);
-        m.insert("musicbrainz_originalalbumid" , StandardTagKey::MusicBrainzOriginalAlbumId);
-        m.insert("musicbrainz_originalartistid", StandardTagKey::MusicBrainzOriginalArtistId);
-        m.insert("musicbrainz_recordingid"     , StandardTagKey::MusicBrainzRecordingId);
-        m.insert("musicbrainz_releasegroupid"  , StandardTagKey::MusicBrainzReleaseGroupId);
-        m.insert("musicbrainz_releasetrackid"  , StandardTagKey::MusicBrainzReleaseTrackId);
-        m.insert("musicbrainz_trackid"         , StandardTagKey::MusicBrainzTrackId);
-        m.insert("musicbrainz_workid"          , StandardTagKey::MusicBrainzWorkId);
-        m.insert("opus"                        , StandardTagKey::Opus);
-        m.insert("organization"                , StandardTagKey::Label);
-        m.insert("originaldate"                , StandardTagKey::OriginalDate);
-        m.insert("part"                        , StandardTagKey::Part);
-        m.insert("performer"                   , StandardTagKey::Performer);
-        m.insert("producer"                    , StandardTagKey::Producer);
-        m.insert("productnumber"               , StandardTagKey::IdentPn);
-        // TODO: Is Publisher a synonym for Label?
-        m.insert("publisher"                   , StandardTagKey::Label);
-        m.insert("rating"                      , StandardTagKey::Rating);
-        m.insert("releasecountry"              , StandardTagKey::ReleaseCountry);
-        m.insert("remixer"                     , StandardTagKey::Remixer);
-        m.insert("replaygain_album_gain"       , StandardTagKey::ReplayGainAlbumGain);
-        m.insert("replaygain_album_peak"       , StandardTagKey::ReplayGainAlbumPeak);
-        m.insert("replaygain_track_gain"       , StandardTagKey::ReplayGainTrackGain);
-        m.insert("replaygain_track_peak"       , StandardTagKey::ReplayGainTrackPeak);
-        m.insert("script"                      , StandardTagKey::Script);
-        m.insert("subtitle"                    , StandardTagKey::TrackSubtitle);
-        m.insert("title"                       , StandardTagKey::TrackTitle);
-        m.insert("titlesort"                   , StandardTagKey::SortTrackTitle);
-        m.insert("totaldiscs"                  , StandardTagKey::DiscTotal);
-        m.insert("totaltracks"                 , StandardTagKey::TrackTotal);
-        m.insert("tracknumber"                 , StandardTagKey::TrackNumber);
-        m.insert("tracktotal"                  , StandardTagKey::TrackTotal);
-        m.insert("upc"                         , StandardTagKey::IdentUpc);
-        m.insert("version"                     , StandardTagKey::Remixer);
-        m.insert("version"                     , StandardTagKey::Version);
-        m.insert("writer"                      , StandardTagKey::Writer);
-        m.insert("year"                        , StandardTagKey::Date);
-        m
-    };
-}
+use symphonia_core::meta::{MetadataBuilder, Tag, Value, map_vorbis_comment};
 
 /// Parse the given Vorbis Comment string into a `Tag`.
 fn parse(tag: &str) -> Tag {
@@ -121,10 +23,7 @@ fn parse(tag: &str) -> Tag {
     let field: Vec<&str> = tag.splitn(2, '=').collect();
 
     // Attempt to assign a standardized tag key.
-    let std_tag = match VORBIS_COMMENT_MAP.get(field[0].to_lowercase().as_str()) {
-        Some(&tag) => Some(tag),
-        None => None,
-    };
+    let std_tag = map_vorbis_comment(field[0]);
 
     // The value field was empty so only the key field exists. Create an empty tag for the given
     // key field.
@@ -164,3 +63,43 @@ pub fn read_comment_no_framing<B : ReadBytes>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use symphonia_core::io::BufReader;
+    use symphonia_core::meta::{MetadataBuilder, StandardTagKey, Value};
+
+    use super::read_comment_no_framing;
+
+    #[test]
+    fn verify_read_comment() {
+        let comments = ["TITLE=Song", "MusicBrainz_TrackId=e4f1a1b2", "EMPTY", "X-CUSTOM=a=b"];
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&6u32.to_le_bytes());
+        buf.extend_from_slice(b"vendor");
+        buf.extend_from_slice(&(comments.len() as u32).to_le_bytes());
+
+        for comment in &comments {
+            buf.extend_from_slice(&(comment.len() as u32).to_le_bytes());
+            buf.extend_from_slice(comment.as_bytes());
+        }
+
+        let mut builder = MetadataBuilder::new();
+        read_comment_no_framing(&mut BufReader::new(&buf), &mut builder).unwrap();
+
+        let metadata = builder.metadata();
+        let tags = metadata.tags();
+
+        assert_eq!(tags.len(), 4);
+
+        assert!(matches!(tags[0].std_key, Some(StandardTagKey::TrackTitle)));
+        assert!(matches!(tags[1].std_key, Some(StandardTagKey::MusicBrainzTrackId)));
+        assert_eq!(tags[1].key, "MusicBrainz_TrackId");
+
+        // A comment without a value has an empty value, and only the first = separates the value.
+        assert!(matches!(&tags[2].value, Value::String(value) if value.is_empty()));
+        assert!(tags[3].std_key.is_none());
+        assert!(matches!(&tags[3].value, Value::String(value) if value == "a=b"));
+    }
+}
@@ -0,0 +1,180 @@
+// Symphonia
+// Copyright (c) 2019 The Project Symphonia Developers.
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Interactive keyboard controls for playback.
+//!
+//! The terminal is switched to a non-canonical mode without echo using `stty`, and key presses
+//! are read from standard input on a separate thread. The original terminal settings are restored
+//! when the `Controls` are dropped.
+
+use std::io::{IsTerminal, Read};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use log::warn;
+
+/// The number of seconds seeked by the left and right arrow keys.
+const SEEK_STEP: f64 = 5.0;
+
+/// A playback control.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Control {
+    /// Pause playback, or resume it if paused.
+    TogglePause,
+    /// Seek relative to the current position by the given number of seconds.
+    Seek(f64),
+    /// Stop playback.
+    Quit,
+}
+
+/// Parses the bytes of key presses into controls.
+#[derive(Default)]
+struct KeyParser {
+    /// The number of bytes of an escape sequence read so far.
+    escape: usize,
+}
+
+impl KeyParser {
+    /// Parses the next byte. Returns a control once a whole key press bound to a control has been
+    /// parsed.
+    fn parse(&mut self, byte: u8) -> Option<Control> {
+        // The arrow keys are sent as the escape sequences ESC [ C (right), and ESC [ D (left).
+        match (self.escape, byte) {
+            (0, 0x1b) | (1, b'[') => {
+                self.escape += 1;
+                None
+            }
+            (2, b'C') => {
+                self.escape = 0;
+                Some(Control::Seek(SEEK_STEP))
+            }
+            (2, b'D') => {
+                self.escape = 0;
+                Some(Control::Seek(-SEEK_STEP))
+            }
+            (0, b' ') => Some(Control::TogglePause),
+            // Since signals are disabled, Ctrl-C is also read as a key press.
+            (0, b'q') | (0, b'Q') | (0, 0x03) => Some(Control::Quit),
+            _ => {
+                self.escape = 0;
+                None
+            }
+        }
+    }
+}
+
+/// Runs `stty` with the given arguments on the terminal attached to standard input, and returns
+/// its output if successful.
+fn stty(args: &[&str]) -> Option<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    if output.status.success() {
+        String::from_utf8(output.stdout).ok()
+    }
+    else {
+        None
+    }
+}
+
+/// Interactive keyboard controls.
+pub struct Controls {
+    rx: Receiver<Control>,
+    /// The terminal settings to restore.
+    saved: String,
+}
+
+impl Controls {
+    /// Instantiate the keyboard controls. Returns `None` if standard input is not a terminal, or
+    /// the terminal mode could not be changed.
+    pub fn try_new() -> Option<Controls> {
+        if !std::io::stdin().is_terminal() {
+            return None;
+        }
+
+        let saved = match stty(&["-g"]) {
+            Some(saved) => saved.trim().to_string(),
+            None => {
+                warn!("failed to get the terminal settings, keyboard controls are disabled");
+                return None;
+            }
+        };
+
+        // Read each key press as it is typed, without echoing it or interpreting Ctrl-C as a
+        // signal. Signals are disabled so the terminal is always restored when quitting.
+        stty(&["-icanon", "-echo", "-isig", "min", "1"])?;
+
+        let (tx, rx) = mpsc::channel();
+
+        // The thread is blocked on reading standard input until the player exits.
+        thread::spawn(move || {
+            let mut parser = KeyParser::default();
+
+            for byte in std::io::stdin().lock().bytes() {
+                let control = match byte {
+                    Ok(byte) => parser.parse(byte),
+                    Err(_) => break,
+                };
+
+                if let Some(control) = control {
+                    if tx.send(control).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Some(Controls { rx, saved })
+    }
+
+    /// Gets the next control, if any, without blocking.
+    pub fn poll(&self) -> Option<Control> {
+        self.rx.try_recv().ok()
+    }
+
+    /// Waits for the next control. Returns `None` if no more controls can be received.
+    pub fn wait(&self) -> Option<Control> {
+        self.rx.recv().ok()
+    }
+}
+
+impl Drop for Controls {
+    fn drop(&mut self) {
+        if stty(&[&self.saved]).is_none() {
+            warn!("failed to restore the terminal settings");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Control, KeyParser};
+
+    #[test]
+    fn verify_key_parser() {
+        let mut parser = KeyParser::default();
+
+        let controls = b" x\x1b[C\x1b[D\x1b[Aq\x1bq"
+            .iter()
+            .filter_map(|&byte| parser.parse(byte))
+            .collect::<Vec<_>>();
+
+        // The up arrow key, and unbound keys are ignored. An unfinished escape sequence is
+        // discarded.
+        assert_eq!(controls, [
+            Control::TogglePause,
+            Control::Seek(5.0),
+            Control::Seek(-5.0),
+            Control::Quit,
+        ]);
+    }
+}
@@ -0,0 +1,186 @@
+// Symphonia
+// Copyright (c) 2019 The Project Symphonia Developers.
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Machine-readable JSON output of a probed input.
+//!
+//! All fields are always present. Fields whose value is unknown are `null`.
+
+use std::fmt::Write;
+
+use symphonia::core::formats::{Cue, Track};
+use symphonia::core::meta::{MetadataRevision, Tag, Value, Visual};
+use symphonia::core::probe::ProbeResult;
+
+/// Encodes a string as a JSON string.
+fn string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+
+    out.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+/// Encodes an optional number as a JSON number, or `null`.
+fn number<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "null".to_string(), |value| value.to_string())
+}
+
+/// Encodes an optional string as a JSON string, or `null`.
+fn opt_string(value: Option<&str>) -> String {
+    value.map_or_else(|| "null".to_string(), string)
+}
+
+/// Encodes a list of encoded values as a JSON array.
+fn array<I: IntoIterator<Item = String>>(items: I) -> String {
+    format!("[{}]", items.into_iter().collect::<Vec<_>>().join(","))
+}
+
+/// Encodes a list of names and encoded values as a JSON object.
+fn object(fields: &[(&str, String)]) -> String {
+    let fields = fields.iter().map(|(name, value)| format!("{}:{}", string(name), value));
+    format!("{{{}}}", fields.collect::<Vec<_>>().join(","))
+}
+
+fn value(value: &Value) -> String {
+    match value {
+        Value::Boolean(boolean) => boolean.to_string(),
+        // The presence of a flag implies it is set.
+        Value::Flag => "true".to_string(),
+        // JSON does not support infinite or NaN numbers.
+        Value::Float(float) if float.is_finite() => float.to_string(),
+        Value::Float(_) => "null".to_string(),
+        Value::SignedInt(int) => int.to_string(),
+        Value::UnsignedInt(uint) => uint.to_string(),
+        // Binary values are encoded as their hexadecimal string representation.
+        Value::Binary(_) | Value::String(_) => string(&value.to_string()),
+    }
+}
+
+fn tag(tag: &Tag) -> String {
+    object(&[
+        ("key", string(&tag.key)),
+        ("std_key", opt_string(tag.std_key.map(|std_key| format!("{:?}", std_key)).as_deref())),
+        ("value", value(&tag.value)),
+    ])
+}
+
+fn tags(tags: &[Tag]) -> String {
+    array(tags.iter().map(tag))
+}
+
+fn track(track: &Track) -> String {
+    let params = &track.codec_params;
+
+    let codec = symphonia::default::get_codecs().get_codec(params.codec);
+    let channel_map = params.channels.map(|channels| channels.to_string());
+
+    object(&[
+        ("id", track.id.to_string()),
+        ("codec", opt_string(codec.map(|codec| codec.short_name))),
+        ("codec_name", opt_string(codec.map(|codec| codec.long_name))),
+        ("sample_rate", number(params.sample_rate)),
+        ("channels", number(params.channels.map(|channels| channels.count()))),
+        ("channel_map", opt_string(channel_map.as_deref())),
+        ("sample_format", opt_string(params.sample_format.map(|f| format!("{:?}", f)).as_deref())),
+        ("bits_per_sample", number(params.bits_per_sample)),
+        ("frames", number(params.n_frames)),
+        ("bit_rate", number(params.bit_rate)),
+        ("language", opt_string(track.language.as_deref())),
+    ])
+}
+
+fn visual(visual: &Visual) -> String {
+    let dimensions = visual.dimensions;
+
+    object(&[
+        ("media_type", string(&visual.media_type)),
+        ("usage", opt_string(visual.usage.map(|usage| format!("{:?}", usage)).as_deref())),
+        ("width", number(dimensions.map(|dimensions| dimensions.width))),
+        ("height", number(dimensions.map(|dimensions| dimensions.height))),
+        ("bits_per_pixel", number(visual.bits_per_pixel)),
+        ("size", visual.data.len().to_string()),
+        ("tags", tags(&visual.tags)),
+    ])
+}
+
+fn cue(cue: &Cue) -> String {
+    let points = cue.points.iter().map(|point| {
+        object(&[
+            ("start_offset_ts", point.start_offset_ts.to_string()),
+            ("tags", tags(&point.tags)),
+        ])
+    });
+
+    object(&[
+        ("index", cue.index.to_string()),
+        ("start_ts", cue.start_ts.to_string()),
+        ("tags", tags(&cue.tags)),
+        ("points", array(points)),
+    ])
+}
+
+/// Encodes the path, tracks, metadata, and cues of a probed input as a JSON object.
+pub fn format_probe(path: &str, probed: &mut ProbeResult) -> String {
+    let duration = probed.format.total_duration().map(f64::from);
+
+    let tracks = array(probed.format.tracks().iter().map(track));
+    let cues = array(probed.format.cues().iter().map(cue));
+
+    let metadata = |rev: Option<&MetadataRevision>| match rev {
+        Some(rev) => (tags(rev.tags()), array(rev.visuals().iter().map(visual))),
+        None => (array(None), array(None)),
+    };
+
+    // Prefer metadata that's provided in the container format, over other tags found during the
+    // probe operation.
+    let (tags, visuals) = match probed.format.metadata().current() {
+        Some(rev) => metadata(Some(rev)),
+        None => metadata(probed.metadata.get().as_ref().and_then(|m| m.current())),
+    };
+
+    object(&[
+        ("path", string(path)),
+        ("duration", number(duration)),
+        ("tracks", tracks),
+        ("tags", tags),
+        ("visuals", visuals),
+        ("cues", cues),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use symphonia::core::meta::{StandardTagKey, Tag, Value};
+
+    use super::{string, tag};
+
+    #[test]
+    fn verify_json_encoding() {
+        assert_eq!(string("a \"b\"\\\n\u{1}"), r#""a \"b\"\\\n\u0001""#);
+
+        let title = Tag::new(Some(StandardTagKey::TrackTitle), "INAM", Value::from("Title"));
+        assert_eq!(tag(&title), r#"{"key":"INAM","std_key":"TrackTitle","value":"Title"}"#);
+
+        let unknown = Tag::new(None, "X", Value::Float(f64::NAN));
+        assert_eq!(tag(&unknown), r#"{"key":"X","std_key":null,"value":null}"#);
+    }
+}
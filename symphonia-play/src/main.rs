@@ -13,156 +13,819 @@
 // fields with default values.
 #![allow(clippy::needless_update)]
 
+use std::borrow::Cow;
+use std::fmt::Write;
 use std::fs::File;
-use std::path::Path;
-
-use symphonia;
-use symphonia::core::errors::{Result, Error};
-use symphonia::core::codecs::DecoderOptions;
-use symphonia::core::formats::{Cue, FormatReader, FormatOptions, SeekMode, SeekTo, Track};
-use symphonia::core::meta::{ColorMode, MetadataOptions, Tag, Value, Visual};
+use std::path::{Path, PathBuf};
+
+use symphonia::core::audio::{AudioBuffer, AudioBufferRef, Channels, Signal, SignalSpec};
+use symphonia::core::errors::{Result, Error, SeekErrorKind, seek_error, unsupported_error};
+use symphonia::core::codecs::{CodecParameters, CodecType, Decoder, DecoderOptions};
+use symphonia::core::conv::IntoSample;
+use symphonia::core::formats::{Cue, FormatReader, FormatOptions, RawPcmOptions, SeekMode, SeekTo};
+use symphonia::core::formats::{Track, TrackSummary};
+use symphonia::core::meta::{ColorMode, MetadataOptions, ReplayGain, StandardTagKey, Tag, Value};
+use symphonia::core::meta::Visual;
 use symphonia::core::io::{MediaSourceStream, MediaSource, ReadOnlySource};
 use symphonia::core::probe::{Hint, ProbeResult};
-use symphonia::core::units::{Duration, Time};
+use symphonia::core::sample::Sample;
+use symphonia::core::units::{Duration, Time, TimeBase, TimeStamp};
 
-use clap::{Arg, App};
+use clap::{Arg, App, ArgMatches};
+use controls::{Control, Controls};
 use log::{error, info, warn};
-use pretty_env_logger;
 
+mod controls;
+mod json;
 mod output;
 
+/// Builds the command line interface of the player.
+fn app() -> App<'static, 'static> {
+    App::new("Symphonia Play")
+        .version("1.0")
+        .author("Philip Deljanov <philip.deljanov@gmail.com>")
+        .about("Play audio with Symphonia")
+        .arg(Arg::with_name("seek")
+            .long("seek")
+            .short("-s")
+            .value_name("TIME")
+            .help("Seek to a time in seconds (SS.sss), or minutes and seconds \
+                   (MM:SS.sss), or to a percentage of the duration if suffixed \
+                   with %, or to a byte offset if prefixed with @")
+            .validator(|value| SeekPosition::parse(&value).map(|_| ()))
+            .conflicts_with_all(
+                &[
+                    "verify",
+                    "decode-only",
+                    "verify-only",
+                    "probe-only"
+                ]
+            ))
+        .arg(Arg::with_name("loop")
+            .long("loop")
+            .value_name("COUNT")
+            .min_values(0)
+            .require_equals(true)
+            .help("Play the track the given number of times, or forever if no \
+                   count is given")
+            .validator(|value| match value.parse::<u32>() {
+                Ok(count) if count > 0 => Ok(()),
+                _ => Err(format!("invalid loop count '{}'", value)),
+            })
+            .conflicts_with_all(&[ "decode-only", "verify-only", "probe-only" ]))
+        .arg(Arg::with_name("track")
+            .long("track")
+            .short("-t")
+            .value_name("NUMBER")
+            .help("Decode or play the track with the given number, as listed when \
+                   probing, instead of the default track")
+            .validator(|value| match value.parse::<usize>() {
+                Ok(track_num) if track_num > 0 => Ok(()),
+                _ => Err(format!("invalid track number '{}'", value)),
+            })
+            .conflicts_with_all(&[ "probe-only" ]))
+        .arg(Arg::with_name("preview")
+            .long("preview")
+            .value_name("SECONDS")
+            .help("Only decode or play the first given number of seconds")
+            .validator(|value| match value.parse::<f64>() {
+                Ok(secs) if secs >= 0.0 && secs.is_finite() => Ok(()),
+                _ => Err(format!("invalid preview duration '{}'", value)),
+            })
+            .conflicts_with_all(&[ "seek", "probe-only" ]))
+        .arg(Arg::with_name("max-channels")
+            .long("max-channels")
+            .value_name("COUNT")
+            .help("Downmix audio with more than the given number of channels")
+            .validator(|value| match value.parse::<usize>() {
+                Ok(max_channels) if max_channels > 0 => Ok(()),
+                _ => Err(format!("invalid maximum channels '{}'", value)),
+            })
+            .conflicts_with_all(&[ "probe-only" ]))
+        .arg(Arg::with_name("gapless")
+            .long("gapless")
+            .help("Trim the encoder delay and padding, if known, for gapless \
+                   playback")
+            .conflicts_with_all(&[ "probe-only" ]))
+        .arg(Arg::with_name("output-chunk")
+            .long("output-chunk")
+            .value_name("FRAMES")
+            .help("Write decoded audio to the audio device in chunks of the \
+                   given number of frames")
+            .validator(|value| match value.parse::<usize>() {
+                Ok(len) if len > 0 => Ok(()),
+                _ => Err(format!("invalid chunk length '{}'", value)),
+            })
+            .conflicts_with_all(&[ "decode-only", "verify-only", "probe-only" ]))
+        .arg(Arg::with_name("list-devices")
+            .long("list-devices")
+            .help("List the audio output devices, and exit"))
+        .arg(Arg::with_name("device")
+            .long("device")
+            .short("-d")
+            .value_name("DEVICE")
+            .help("Play audio on the audio output device with the given number, as \
+                   listed by --list-devices, or name")
+            .conflicts_with_all(
+                &[ "decode-only", "verify-only", "probe-only", "output" ]
+            ))
+        .arg(Arg::with_name("output")
+            .long("output")
+            .short("-o")
+            .value_name("FILE")
+            .help("Write decoded audio to a WAVE file instead of the audio device")
+            .conflicts_with_all(&[ "decode-only", "verify-only", "probe-only" ]))
+        .arg(Arg::with_name("output-bits")
+            .long("output-bits")
+            .value_name("BITS")
+            .possible_values(&[ "16", "24", "32" ])
+            .help("The bits per sample of the WAVE file, 16 by default")
+            .requires("output"))
+        .arg(Arg::with_name("volume")
+            .long("volume")
+            .value_name("VOLUME")
+            .help("Scale the output by a linear volume between 0.0 and 2.0. A \
+                   volume above 1.0 may clip")
+            .validator(|value| parse_volume(&value).map(|_| ()))
+            .conflicts_with_all(&[ "decode-only", "verify-only", "probe-only" ]))
+        .arg(Arg::with_name("replaygain")
+            .long("replaygain")
+            .value_name("MODE")
+            .possible_values(&[ "track", "album", "off" ])
+            .help("Apply the track or album ReplayGain during playback, if \
+                   available")
+            .conflicts_with_all(&[ "decode-only", "verify-only", "probe-only" ]))
+        .arg(Arg::with_name("sound-check")
+            .long("sound-check")
+            .help("Apply Sound Check normalization during playback, if available")
+            .conflicts_with_all(&[ "decode-only", "verify-only", "probe-only" ]))
+        .arg(Arg::with_name("format-hint")
+            .long("format-hint")
+            .value_name("NAME")
+            .help("Read the input as the format with the given short name (e.g., \
+                   flac, wave, isomp4), regardless of the file extension"))
+        .arg(Arg::with_name("raw")
+            .long("raw")
+            .value_name("FORMAT,RATE,CHANNELS")
+            .help("Read the input as headerless PCM audio with the given sample \
+                   format (e.g., s16le, f32be, mulaw), sample rate, and number of \
+                   channels")
+            .validator(|value| parse_raw_pcm(&value).map(|_| ()))
+            .conflicts_with("format-hint"))
+        .arg(Arg::with_name("no-cover")
+            .long("no-cover")
+            .help("Do not read embedded cover art"))
+        .arg(Arg::with_name("show-subcues")
+            .long("show-subcues")
+            .help("Print the offsets and tags of all sub-cues")
+            .conflicts_with_all(&[ "decode-only", "verify-only" ]))
+        .arg(Arg::with_name("decode-only")
+            .long("decode-only")
+            .help("Decode, but do not play the audio")
+            .conflicts_with_all(&[ "probe-only", "verify-only", "verify" ]))
+        .arg(Arg::with_name("probe-only")
+            .long("probe-only")
+            .help("Only probe the input for metadata")
+            .conflicts_with_all(&[ "decode-only", "verify-only" ]))
+        .arg(Arg::with_name("format")
+            .long("format")
+            .value_name("FORMAT")
+            .possible_values(&[ "pretty", "json" ])
+            .help("The format of the probe-only output")
+            .requires("probe-only"))
+        .arg(Arg::with_name("get-tag")
+            .long("get-tag")
+            .value_name("KEY")
+            .help("Only print the values of the tag with a standard tag key, or \
+                   key, matching KEY, ignoring case. Fails if there is no such \
+                   tag")
+            .requires("probe-only")
+            .conflicts_with_all(&[ "format" ]))
+        .arg(Arg::with_name("verify-only")
+            .long("verify-only")
+            .help("Verify the decoded audio is valid, but do not play the audio")
+            .conflicts_with_all(&[ "verify" ]))
+        .arg(Arg::with_name("verify")
+            .long("verify")
+            .short("-V")
+            .help("Verify the decoded audio is valid during playback"))
+        .arg(Arg::with_name("verbose")
+            .short("v")
+            .multiple(true)
+            .help("Sets the level of verbosity, and displays the playback \
+                   progress"))
+        .arg(Arg::with_name("crossfade")
+            .long("crossfade")
+            .value_name("SECONDS")
+            .help("Crossfade each input into the next over the given number of \
+                   seconds")
+            .validator(|value| match value.parse::<f64>() {
+                Ok(secs) if secs >= 0.0 && secs.is_finite() => Ok(()),
+                _ => Err(format!("invalid crossfade duration '{}'", value)),
+            })
+            .conflicts_with_all(&[ "decode-only", "verify-only", "probe-only" ]))
+        .arg(Arg::with_name("playlist")
+            .long("playlist")
+            .value_name("FILE")
+            .help("Play the files listed in the M3U playlist after the inputs"))
+        .arg(Arg::with_name("INPUT")
+            .help("The input file paths, played in order, or specify - to use \
+                   standard input")
+            .required_unless_one(&[ "list-devices", "playlist" ])
+            .multiple(true)
+            .index(1))
+}
+
 fn main() {
-    pretty_env_logger::init();
-
-    let matches = App::new("Symphonia Play")
-                        .version("1.0")
-                        .author("Philip Deljanov <philip.deljanov@gmail.com>")
-                        .about("Play audio with Symphonia")
-                        .arg(Arg::with_name("seek")
-                            .long("seek")
-                            .short("-s")
-                            .value_name("TIME")
-                            .help("Seek to the given time in seconds")
-                            .conflicts_with_all(
-                                &[
-                                    "verify",
-                                    "decode-only",
-                                    "verify-only",
-                                    "probe-only"
-                                ]
-                            ))
-                        .arg(Arg::with_name("decode-only")
-                            .long("decode-only")
-                            .help("Decode, but do not play the audio")
-                            .conflicts_with_all(&[ "probe-only", "verify-only", "verify" ]))
-                        .arg(Arg::with_name("probe-only")
-                            .long("probe-only")
-                            .help("Only probe the input for metadata")
-                            .conflicts_with_all(&[ "decode-only", "verify-only" ]))
-                        .arg(Arg::with_name("verify-only")
-                            .long("verify-only")
-                            .help("Verify the decoded audio is valid, but do not play the audio")
-                            .conflicts_with_all(&[ "verify" ]))
-                        .arg(Arg::with_name("verify")
-                            .long("verify")
-                            .short("-V")
-                            .help("Verify the decoded audio is valid during playback"))
-                       .arg(Arg::with_name("verbose")
-                            .short("v")
-                            .multiple(true)
-                            .help("Sets the level of verbosity"))
-                        .arg(Arg::with_name("INPUT")
-                            .help("The input file path, or specify - to use standard input")
-                            .required(true)
-                            .index(1))
-                        .get_matches();
-
-    let path_str = matches.value_of("INPUT").unwrap();
+    let matches = app().get_matches();
 
-    // Create a hint to help the format registry guess what format reader is appropriate.
-    let mut hint = Hint::new();
+    // Unless overridden by RUST_LOG, informational messages are logged when verbose.
+    let mut logger = pretty_env_logger::formatted_builder();
 
-    // If the path string is '-' then read from standard input.
-    let source = if path_str == "-" {
-        Box::new(ReadOnlySource::new(std::io::stdin())) as Box<dyn MediaSource>
+    match std::env::var("RUST_LOG") {
+        Ok(filters) => logger.parse_filters(&filters),
+        Err(_) if matches.is_present("verbose") => logger.filter_level(log::LevelFilter::Info),
+        Err(_) => logger.filter_level(log::LevelFilter::Error),
+    };
+
+    logger.init();
+
+    if matches.is_present("list-devices") {
+        match output::list_devices() {
+            Ok(devices) => print_devices(&devices),
+            Err(err) => {
+                error!("failed to list audio output devices: {:?}", err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // Check the selected audio output device exists before decoding starts. The reason for the
+    // failure is logged by the check.
+    if let Some(device) = matches.value_of("device") {
+        if output::check_device(device).is_err() {
+            std::process::exit(1);
+        }
+    }
+
+    let mut paths = match matches.values_of("INPUT") {
+        Some(paths) => paths.map(String::from).collect::<Vec<_>>(),
+        None => Vec::new(),
+    };
+
+    // If present, play the files in the playlist after the inputs.
+    if let Some(playlist) = matches.value_of("playlist") {
+        match std::fs::read(playlist) {
+            Ok(data) => {
+                let dir = Path::new(playlist).parent().unwrap_or_else(|| Path::new(""));
+                paths.extend(parse_m3u(&String::from_utf8_lossy(&data), dir));
+            }
+            Err(err) => {
+                error!("failed to read playlist {}: {}", playlist, err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // An input is required unless listing devices.
+    if paths.is_empty() {
+        error!("no input file was provided");
+        std::process::exit(1);
+    }
+
+    // The inputs are played one after another through the same audio output, which is opened once
+    // the first audio is decoded.
+    let mut audio_output = None;
+
+    // When playing, and possible, use the keyboard controls for all the inputs. Standard input is
+    // used for the keyboard controls, unless it is an input. The terminal is restored when the
+    // controls are dropped.
+    let is_playing = !["decode-only", "verify-only", "probe-only"]
+        .iter()
+        .any(|mode| matches.is_present(mode));
+
+    let controls = if is_playing && paths.iter().all(|path| path != "-") {
+        controls::Controls::try_new()
     }
     else {
-        // Othwerise, get a Path from the path string.
-        let path = Path::new(path_str);
+        None
+    };
 
-        // Provide the file extension as a hint.
-        if let Some(extension) = path.extension() {
-            if let Some(extension_str) = extension.to_str() {
-                hint.with_extension(extension_str);
+    let mut result = Ok(());
+
+    for path_str in paths.iter() {
+        match run(path_str, &matches, &mut audio_output, controls.as_ref()) {
+            Ok(PlayEnd::Finished) => (),
+            Ok(PlayEnd::Quit) => break,
+            Err(err) => {
+                result = Err(err);
+                break;
             }
         }
+    }
+
+    // Write out any audio remaining in the audio output.
+    if let Some(sink) = audio_output.as_mut() {
+        sink.output.flush()
+    }
+
+    // Restore the terminal before exiting.
+    drop(controls);
+
+    if let Err(err) = result {
+        error!("{}", err);
+        std::process::exit(1);
+    }
+}
+
+/// Opens the input at the given path, and then probes, decodes, verifies, or plays it as selected
+/// by the arguments. Audio is played through the given audio output, which is opened if it is not
+/// already open. If present, playback is controlled by the given keyboard controls.
+fn run(
+    path_str: &str,
+    matches: &ArgMatches<'_>,
+    audio_output: &mut Option<Sink>,
+    controls: Option<&Controls>,
+) -> std::result::Result<PlayEnd, String> {
+    // Create a hint to help the format registry guess what format reader is appropriate.
+    let mut hint = Hint::new();
 
-        Box::new(File::open(path).unwrap())
+    let source = match open_input(path_str, &mut hint) {
+        Ok(source) => source,
+        Err(err) => return Err(format!("failed to open {}: {}", path_str, err)),
     };
 
+    // If present, parse the raw PCM argument. Raw PCM has no header, so the format must be forced.
+    let raw_pcm = matches.value_of("raw").map(|value| parse_raw_pcm(value).unwrap());
+
+    // If present, force the input to be read as the given format.
+    let format_hint = match raw_pcm {
+        Some(_) => Some("raw"),
+        None => matches.value_of("format-hint"),
+    };
+
+    if let Some(format_hint) = format_hint {
+        hint.force_format(format_hint);
+    }
+
     // Create the media source stream using the boxed media source from above.
     let mss = MediaSourceStream::new(source, Default::default());
 
-    // Use the default options for metadata and format readers.
-    let format_opts: FormatOptions = Default::default();
-    let metadata_opts: MetadataOptions = Default::default();
+    // Use the default options for metadata and format readers, except skip embedded cover art if
+    // requested.
+    let metadata_opts = MetadataOptions {
+        read_visuals: !matches.is_present("no-cover"),
+        ..Default::default()
+    };
+
+    let format_opts = FormatOptions { metadata: metadata_opts, raw_pcm, ..Default::default() };
+
+    // If present, parse the preview argument. It was validated when the arguments were parsed.
+    let preview_time = matches.value_of("preview").and_then(|p| p.parse::<f64>().ok());
+
+    let show_subcues = matches.is_present("show-subcues");
+
+    // If present, parse the track argument. It was validated when the arguments were parsed.
+    let track_num = matches.value_of("track").and_then(|p| p.parse::<usize>().ok());
+
+    let verbose = matches.is_present("verbose");
+
+    // If present, parse the maximum channels argument. It was validated when the arguments were
+    // parsed.
+    let max_channels = matches.value_of("max-channels").and_then(|p| p.parse::<usize>().ok());
 
     // Probe the media source stream for metadata and get the format reader.
     match symphonia::default::get_probe().format(&hint, mss, &format_opts, &metadata_opts) {
         Ok(mut probed) => {
+            // Validate the selected track before decoding.
+            if let Some(track_num) = track_num {
+                if select_track(probed.format.as_ref(), Some(track_num)).is_none() {
+                    return Err(format!(
+                        "track {} does not exist. the input has {} track(s).",
+                        track_num,
+                        probed.format.tracks().len()
+                    ));
+                }
+            }
+
             let result = if matches.is_present("verify-only") {
                 // Verify-only mode decodes and verifies the audio, but does not play it.
-                decode_only(probed.format, &DecoderOptions { verify: true, ..Default::default() })
+                let options = DecoderOptions { verify: true, max_channels, ..Default::default() };
+                decode_only(probed.format, track_num, &options, preview_time)
+                    .map(|_| PlayEnd::Finished)
             }
             else if matches.is_present("decode-only") {
                 // Decode-only mode decodes the audio, but does not play or verify it.
-                decode_only(probed.format, &DecoderOptions { verify: false, ..Default::default() })
+                let options = DecoderOptions {
+                    verify: false,
+                    max_channels,
+                    gapless: matches.is_present("gapless"),
+                    ..Default::default()
+                };
+                decode_only(probed.format, track_num, &options, preview_time)
+                    .map(|_| PlayEnd::Finished)
+            }
+            else if let Some(key) = matches.value_of("get-tag") {
+                // Only print the values of the tag, one per line.
+                let values = find_tag_values(&mut probed, key);
+
+                if values.is_empty() {
+                    return Err(format!("tag not found: {}", key));
+                }
+
+                for value in values {
+                    println!("{}", value);
+                }
+                Ok(PlayEnd::Finished)
             }
             else if matches.is_present("probe-only") {
                 // Probe-only mode only prints information about the format, tracks, metadata, etc.
-                pretty_print_format(path_str, &mut probed);
-                Ok(())
+                match matches.value_of("format") {
+                    Some("json") => println!("{}", json::format_probe(path_str, &mut probed)),
+                    _ => pretty_print_format(path_str, &mut probed, show_subcues),
+                }
+                Ok(PlayEnd::Finished)
             }
             else {
                 // Playback mode.
-                pretty_print_format(path_str, &mut probed);
+                pretty_print_format(path_str, &mut probed, show_subcues);
 
-                // If present, parse the seek argument.
-                let seek_time = matches.value_of("seek").map(|p| p.parse::<f64>().unwrap_or(0.0));
+                // If present, parse the seek argument. It was validated when the arguments were
+                // parsed.
+                let seek_pos = matches.value_of("seek").and_then(|p| SeekPosition::parse(p).ok());
+
+                // If present, parse the loop argument. Loop forever if no count is provided. It was
+                // validated when the arguments were parsed.
+                let loop_count = if matches.is_present("loop") {
+                    matches.value_of("loop").and_then(|p| p.parse::<u32>().ok())
+                }
+                else {
+                    Some(1)
+                };
 
                 // Set the decoder options.
                 let options = DecoderOptions {
                     verify: matches.is_present("verify"),
+                    max_channels,
+                    gapless: matches.is_present("gapless"),
                     ..Default::default()
                 };
 
+                // If Sound Check is enabled, get the normalization gain.
+                let sound_check_db = if matches.is_present("sound-check") {
+                    find_sound_check_gain(&mut probed)
+                }
+                else {
+                    None
+                };
+
+                // If present, parse the volume argument. It was validated when the arguments were
+                // parsed.
+                let volume_db = matches.value_of("volume")
+                                       .and_then(|p| parse_volume(p).ok())
+                                       .and_then(volume_gain_db);
+
+                // If ReplayGain is enabled, get the ReplayGain for the mode.
+                let replay_gain_db = match matches.value_of("replaygain") {
+                    Some("track") => find_replay_gain_db(&mut probed, ReplayGainMode::Track),
+                    Some("album") => find_replay_gain_db(&mut probed, ReplayGainMode::Album),
+                    _ => None,
+                };
+
+                // The total gain applied to the output.
+                let gains = [sound_check_db, volume_db, replay_gain_db];
+
+                let gain_db = if gains.iter().any(Option::is_some) {
+                    Some(gains.iter().flatten().sum())
+                }
+                else {
+                    None
+                };
+
+                let output_opts = OutputOptions {
+                    // If present, parse the output chunk length argument. It was validated when the
+                    // arguments were parsed.
+                    chunk_len: matches.value_of("output-chunk")
+                                      .and_then(|p| p.parse::<usize>().ok()),
+                    wav_path: matches.value_of("output").map(PathBuf::from),
+                    wav_bits: matches.value_of("output-bits")
+                                     .and_then(|p| p.parse::<u16>().ok())
+                                     .unwrap_or(16),
+                    device: matches.value_of("device").map(String::from),
+                    crossfade: matches.value_of("crossfade").and_then(|p| p.parse::<f64>().ok()),
+                };
+
+                let play_opts = PlayOptions {
+                    seek_pos,
+                    preview_time,
+                    gain_db,
+                    loop_count,
+                    show_progress: verbose,
+                };
+
                 // Play it!
-                play(probed.format, seek_time, &options)
+                play(
+                    probed.format,
+                    track_num,
+                    &play_opts,
+                    &output_opts,
+                    &options,
+                    audio_output,
+                    controls,
+                )
             };
 
-            if let Err(err) = result {
-                error!("error: {}", err);
-            }
+            result.map_err(|err| format!("error: {}", err))
         }
         Err(err) => {
-            // The input was not supported by any format reader.
-            error!("file not supported. reason? {}", err);
+            match format_hint {
+                // The input was not supported by the forced format reader.
+                Some(format_hint) => {
+                    Err(format!("file could not be read as {}: {}", format_hint, err))
+                }
+                // The input was not supported by any format reader.
+                None => Err(format!("file not supported. reason? {}", err)),
+            }
         }
     }
 }
 
-fn decode_only(mut reader: Box<dyn FormatReader>, decode_options: &DecoderOptions) -> Result<()> {
-    // Get the default track.
-    // TODO: Allow track selection.
-    let track = reader.default_track().unwrap();
+/// Parses raw PCM parameters given as a sample format, sample rate, and number of channels
+/// separated by commas (e.g., s16le,44100,2).
+fn parse_raw_pcm(value: &str) -> std::result::Result<RawPcmOptions, String> {
+    use symphonia::core::codecs::*;
+
+    let fields = value.split(',').map(str::trim).collect::<Vec<_>>();
+
+    let (format, rate, channels) = match fields[..] {
+        [format, rate, channels] => (format, rate, channels),
+        _ => return Err("expected a sample format, sample rate, and channels".to_string()),
+    };
+
+    let format = match format.to_ascii_lowercase().as_str() {
+        "u8"    => CODEC_TYPE_PCM_U8,
+        "s8"    => CODEC_TYPE_PCM_S8,
+        "u16le" => CODEC_TYPE_PCM_U16LE,
+        "u16be" => CODEC_TYPE_PCM_U16BE,
+        "s16le" => CODEC_TYPE_PCM_S16LE,
+        "s16be" => CODEC_TYPE_PCM_S16BE,
+        "u24le" => CODEC_TYPE_PCM_U24LE,
+        "u24be" => CODEC_TYPE_PCM_U24BE,
+        "s24le" => CODEC_TYPE_PCM_S24LE,
+        "s24be" => CODEC_TYPE_PCM_S24BE,
+        "u32le" => CODEC_TYPE_PCM_U32LE,
+        "u32be" => CODEC_TYPE_PCM_U32BE,
+        "s32le" => CODEC_TYPE_PCM_S32LE,
+        "s32be" => CODEC_TYPE_PCM_S32BE,
+        "f32le" => CODEC_TYPE_PCM_F32LE,
+        "f32be" => CODEC_TYPE_PCM_F32BE,
+        "f64le" => CODEC_TYPE_PCM_F64LE,
+        "f64be" => CODEC_TYPE_PCM_F64BE,
+        "alaw"  => CODEC_TYPE_PCM_ALAW,
+        "mulaw" => CODEC_TYPE_PCM_MULAW,
+        _ => return Err(format!("unknown sample format: {}", format)),
+    };
+
+    let rate = match rate.parse::<u32>() {
+        Ok(rate) if rate > 0 => rate,
+        _ => return Err(format!("invalid sample rate: {}", rate)),
+    };
+
+    // Mono and stereo use the standard channel positions, any more channels are discrete.
+    let channels = match channels.parse::<usize>() {
+        Ok(1) => Channels::FRONT_LEFT,
+        Ok(2) => Channels::FRONT_LEFT | Channels::FRONT_RIGHT,
+        Ok(n) if n > 2 && n <= Channels::MAX_DISCRETE => Channels::discrete(n),
+        _ => return Err(format!("invalid number of channels: {}", channels)),
+    };
+
+    Ok(RawPcmOptions { rate, channels, format })
+}
+
+/// Gets the paths of the files listed in an M3U playlist. Relative paths are relative to the
+/// directory of the playlist.
+fn parse_m3u(playlist: &str, dir: &Path) -> Vec<String> {
+    playlist
+        .lines()
+        .map(|line| line.trim_start_matches('\u{feff}').trim())
+        // Comments, and the directives of extended M3U playlists, start with #.
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| dir.join(line).to_string_lossy().into_owned())
+        .collect()
+}
+
+/// Opens the input at the given path, or standard input if the path is `-`. The file extension,
+/// if any, is added to the hint.
+fn open_input(path_str: &str, hint: &mut Hint) -> Result<Box<dyn MediaSource>> {
+    // If the path string is '-' then read from standard input.
+    if path_str == "-" {
+        return Ok(Box::new(ReadOnlySource::new(std::io::stdin())));
+    }
+
+    // Othwerise, get a Path from the path string.
+    let path = Path::new(path_str);
+
+    // Provide the file extension as a hint.
+    if let Some(extension) = path.extension() {
+        if let Some(extension_str) = extension.to_str() {
+            hint.with_extension(extension_str);
+        }
+    }
+
+    Ok(Box::new(File::open(path)?))
+}
+
+/// A preview optionally limits decoding to a fixed duration from the start of a track.
+struct Preview {
+    time: Option<f64>,
+    remaining: Option<u64>,
+}
+
+impl Preview {
+    fn new(time: Option<f64>) -> Self {
+        Preview { time, remaining: None }
+    }
+
+    /// Consumes up-to `n_frames` frames of a decoded buffer with the given sample rate from the
+    /// preview, and returns the number of frames that should be kept.
+    fn take(&mut self, n_frames: usize, sample_rate: u32) -> usize {
+        let time = match self.time {
+            Some(time) => time,
+            None => return n_frames,
+        };
+
+        // The preview duration is converted to frames using the sample rate of the first decoded
+        // buffer.
+        let remaining = self.remaining.get_or_insert_with(|| {
+            (time.max(0.0) * f64::from(sample_rate)).round() as u64
+        });
+
+        let n_kept = (*remaining).min(n_frames as u64);
+        *remaining -= n_kept;
+
+        n_kept as usize
+    }
+
+    /// Returns `true` if the preview duration has been reached.
+    fn is_done(&self) -> bool {
+        self.remaining == Some(0)
+    }
+}
+
+/// Displays the playback progress of a track on a status line that is updated in place.
+struct Progress {
+    /// The timebase of the track.
+    time_base: Option<TimeBase>,
+    /// The total duration of the track.
+    total: Option<Time>,
+    /// If `false`, the progress is not displayed.
+    enabled: bool,
+    /// The elapsed time, in whole seconds, last displayed.
+    shown: Option<u64>,
+    /// The length of the last displayed status line.
+    len: usize,
+}
+
+impl Progress {
+    fn new(track: &Track, total_duration: Option<Time>, enabled: bool) -> Self {
+        let time_base = track_time_base(track);
+
+        let total = total_duration.filter(|&total| f64::from(total) > 0.0);
+
+        Progress { time_base, total, enabled, shown: None, len: 0 }
+    }
+
+    /// Gets the percentage of the track played once playback reaches the given timestamp.
+    fn percent(&self, ts: TimeStamp) -> Option<u32> {
+        let played = f64::from(self.time_base?.calc_time(ts));
+
+        Some((100.0 * played / f64::from(self.total?)).min(100.0) as u32)
+    }
+
+    /// Gets the status line once playback reaches the given timestamp. The total duration and
+    /// percentage played are omitted if the total duration is unknown.
+    fn status(&self, ts: TimeStamp) -> Option<String> {
+        let elapsed = self.time_base?.calc_time(ts);
+
+        let mut status = format_clock(elapsed.seconds);
+
+        if let (Some(total), Some(percent)) = (self.total, self.percent(ts)) {
+            let _ = write!(status, " / {} ({:>3}%)", format_clock(total.seconds), percent);
+        }
+
+        let _ = write!(status, " [ts: {}]", ts);
+
+        Some(status)
+    }
+
+    /// Updates the progress, redisplaying the status line once every second of playback.
+    fn update(&mut self, ts: TimeStamp) {
+        if !self.enabled {
+            return;
+        }
+
+        let elapsed = match self.time_base {
+            Some(time_base) => time_base.calc_time(ts).seconds,
+            None => return,
+        };
+
+        if self.shown != Some(elapsed) {
+            if let Some(status) = self.status(ts) {
+                // Pad the status line to overwrite a longer previous status line.
+                eprint!("\r{:<len$}", status, len = self.len);
+
+                self.len = status.len();
+                self.shown = Some(elapsed);
+            }
+        }
+    }
+
+    /// Ends the status line, if any progress was displayed.
+    fn finish(&self) {
+        if self.shown.is_some() {
+            eprintln!();
+        }
+    }
+}
+
+/// Gets the timebase of a track. If the track has no timebase, assume timestamps are in frames.
+fn track_time_base(track: &Track) -> Option<TimeBase> {
+    let params = &track.codec_params;
+
+    params.time_base.or_else(|| params.sample_rate.map(|rate| TimeBase::new(1, rate)))
+}
+
+/// Converts a duration in the units of the time base, or in frames if there is no time base, to a
+/// number of frames at the given sample rate.
+fn ts_to_frames(ts: u64, time_base: Option<TimeBase>, rate: u32) -> u64 {
+    match time_base {
+        Some(time_base) => {
+            let time = time_base.calc_time(ts);
+            time.seconds * u64::from(rate) + (time.frac * f64::from(rate)).round() as u64
+        }
+        None => ts,
+    }
+}
+
+/// Formats a number of seconds as hours, minutes, and seconds (e.g., 1:02:03).
+fn format_clock(secs: u64) -> String {
+    format!("{}:{:0>2}:{:0>2}", secs / 3600, (secs % 3600) / 60, secs % 60)
+}
+
+/// Formats a `Time` as hours, minutes, and seconds (e.g., 1:02:03.456).
+fn format_time(time: Time) -> String {
+    let millis = (time.frac * 1000.0).round() as u64;
+
+    // Carry the fractional part if it rounds up to a whole second.
+    let (secs, millis) = (time.seconds + millis / 1000, millis % 1000);
+
+    format!("{}:{:0>2}:{:0>2}.{:0>3}", secs / 3600, (secs % 3600) / 60, secs % 60, millis)
+}
+
+/// Selects the track with the given number, counting from 1 as tracks are printed, or the default
+/// track if no number is provided. Returns `None` if the track does not exist.
+fn select_track(reader: &dyn FormatReader, track_num: Option<usize>) -> Option<&Track> {
+    match track_num {
+        Some(track_num) => reader.track(track_num.checked_sub(1)?),
+        None => reader.default_track(),
+    }
+}
+
+/// Decodes the selected track, or the default track if no track is selected. If a preview time is
+/// provided, decoding stops once that much audio has been decoded, and the number of frames
+/// decoded is returned.
+fn decode_only(
+    mut reader: Box<dyn FormatReader>,
+    track_num: Option<usize>,
+    decode_options: &DecoderOptions,
+    preview_time: Option<f64>,
+) -> Result<u64> {
+    // Get the selected track.
+    let track = select_track(reader.as_ref(), track_num).unwrap();
     let track_id = track.id;
 
     // Create a decoder for the track.
-    let mut decoder = symphonia::default::get_codecs().make(&track.codec_params, &decode_options)?;
+    let mut decoder = make_decoder(&track.codec_params, decode_options)?;
+
+    let mut preview = Preview::new(preview_time);
+    let mut n_frames = 0;
+
+    let mut packets = reader.packets();
 
     // Decode all packets, ignoring all decode errors.
     let result = loop {
-        let packet = reader.next_packet()?;
+        // Stop decoding once the preview duration is reached.
+        if preview.is_done() {
+            break Ok(n_frames);
+        }
+
+        let packet = match packets.next() {
+            Some(Ok(packet)) => packet,
+            Some(Err(err)) => break Err(err),
+            // The end of the stream is a normal termination.
+            None => break Ok(n_frames),
+        };
 
         // If the packet does not belong to the selected track, skip over it.
         if packet.track_id() != track_id {
@@ -171,61 +834,631 @@ fn decode_only(mut reader: Box<dyn FormatReader>, decode_options: &DecoderOption
 
         // Decode the packet into audio samples.
         match decoder.decode(&packet) {
+            Ok(decoded) => {
+                n_frames += preview.take(decoded.frames(), decoded.spec().rate) as u64;
+            }
             Err(Error::DecodeError(err)) => warn!("decode error: {}", err),
             Err(err) => break Err(err),
-            _ => continue,
         }
     };
 
+    // If the whole stream was decoded, print the result of the verification, if any.
+    if decode_options.verify && !preview.is_done() {
+        print_verification(decoder.as_mut());
+    }
+
     // Close the decoder.
     decoder.close();
 
     result
 }
 
-fn play(mut reader: Box<dyn FormatReader>, seek_time: Option<f64>, decode_options: &DecoderOptions) -> Result<()> {
-    // The audio output device.
-    let mut audio_output = None;
+/// Gets a descriptive name for a codec, if it is registered, otherwise the codec type number.
+fn codec_name(codec: CodecType) -> String {
+    match symphonia::default::get_codecs().get_codec(codec) {
+        Some(descriptor) => format!("{} ({})", descriptor.long_name, descriptor.short_name),
+        None => format!("Unknown (#{})", codec),
+    }
+}
 
-    // Get the default track.
-    // TODO: Allow track selection.
-    let track = reader.default_track().unwrap();
-    let track_id = track.id;
+/// Instantiates a decoder for the codec parameters, printing the name of the codec if it is not
+/// supported.
+fn make_decoder(params: &CodecParameters, options: &DecoderOptions) -> Result<Box<dyn Decoder>> {
+    let codecs = symphonia::default::get_codecs();
 
-    // Create a decoder for the track.
-    let mut decoder = symphonia::default::get_codecs().make(&track.codec_params, &decode_options)?;
+    if !codecs.supports(params.codec) {
+        error!("unsupported codec: {}", codec_name(params.codec));
+    }
 
-    // If there is a seek time, seek the reader to the time specified and get the timestamp of the
-    // seeked position. All packets with a timestamp < the seeked position will not be played.
-    //
-    // Note: This is a half-baked approach to seeking! After seeking the reader, packets should be
-    // decoded and *samples* discarded up-to the exact *sample* indicated by required_ts. The current
-    // approach will discard excess samples if seeking to a sample within a packet.
-    let seek_ts = if let Some(time) = seek_time {
-        let seek_to = SeekTo::Time { time: Time::from(time), track_id: None };
-
-        // Attempt the seek. If the seek fails, ignore the error and return a seek timestamp of 0 so
-        // that no samples are trimmed.
-        match reader.seek(SeekMode::Accurate, seek_to) {
-            Ok(seeked_to) => {
-                seeked_to.required_ts
+    codecs.make(params, options)
+}
+
+/// Prints the result of verifying the decoded audio against the checksum of the stream.
+fn print_verification(decoder: &mut dyn Decoder) {
+    match decoder.finalize() {
+        Ok(Some(true)) => println!("verification: passed"),
+        Ok(Some(false)) => println!("verification: failed"),
+        Ok(None) => println!("verification: unsupported"),
+        Err(err) => warn!("verification error: {}", err),
+    }
+}
+
+/// Gets the Sound Check normalization gain in decibels, preferring metadata that's provided in the
+/// container format.
+fn find_sound_check_gain(probed: &mut ProbeResult) -> Option<f32> {
+    fn find_gain(tags: &[Tag]) -> Option<f32> {
+        tags.iter().find_map(|tag| match (tag.std_key, &tag.value) {
+            (Some(StandardTagKey::SoundCheckGain), Value::Float(gain)) => Some(*gain as f32),
+            _ => None,
+        })
+    }
+
+    if let Some(gain) = probed.format.metadata().current().and_then(|rev| find_gain(rev.tags())) {
+        return Some(gain);
+    }
+
+    probed.metadata.get().as_ref().and_then(|m| m.current()).and_then(|rev| find_gain(rev.tags()))
+}
+
+/// Gets the values of the tags matching the key, preferring metadata that's provided in the
+/// container format. A tag matches if its standard tag key is the standard tag key named by the
+/// key, or if its key is the key, ignoring case.
+fn find_tag_values(probed: &mut ProbeResult, key: &str) -> Vec<String> {
+    fn find_values(tags: &[Tag], key: &str) -> Vec<String> {
+        let std_key = key.parse::<StandardTagKey>().ok();
+
+        tags.iter()
+            .filter(|tag| {
+                (std_key.is_some() && tag.std_key == std_key) || tag.key.eq_ignore_ascii_case(key)
+            })
+            .map(|tag| tag.value.to_string())
+            .collect()
+    }
+
+    let values = match probed.format.metadata().current() {
+        Some(rev) => find_values(rev.tags(), key),
+        None => Vec::new(),
+    };
+
+    if !values.is_empty() {
+        return values;
+    }
+
+    match probed.metadata.get().as_ref().and_then(|m| m.current()) {
+        Some(rev) => find_values(rev.tags(), key),
+        None => Vec::new(),
+    }
+}
+
+/// The ReplayGain to apply.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum ReplayGainMode {
+    /// Normalize each track to the same loudness.
+    Track,
+    /// Normalize each album to the same loudness, preserving the loudness of the tracks relative to
+    /// each other.
+    Album,
+}
+
+/// Gets the gain in decibels for the ReplayGain mode, falling back to the gain of the other mode if
+/// the gain for the mode is not available. The gain is reduced, if necessary, so that the peak does
+/// not clip. Returns the gain, and the mode of the gain.
+fn replay_gain_db(replay_gain: &ReplayGain, mode: ReplayGainMode) -> Option<(f32, ReplayGainMode)> {
+    let track = replay_gain.track_gain_db.map(|gain| (gain, replay_gain.track_peak));
+    let album = replay_gain.album_gain_db.map(|gain| (gain, replay_gain.album_peak));
+
+    let ((gain_db, peak), mode) = match mode {
+        ReplayGainMode::Track => {
+            track.map(|track| (track, ReplayGainMode::Track))
+                 .or_else(|| album.map(|album| (album, ReplayGainMode::Album)))
+        }
+        ReplayGainMode::Album => {
+            album.map(|album| (album, ReplayGainMode::Album))
+                 .or_else(|| track.map(|track| (track, ReplayGainMode::Track)))
+        }
+    }?;
+
+    // The gain that would amplify the peak to exactly full scale.
+    let max_gain_db = peak.filter(|&peak| peak > 0.0).map(|peak| -20.0 * peak.log10());
+
+    Some((max_gain_db.map_or(gain_db, |max_gain_db| gain_db.min(max_gain_db)), mode))
+}
+
+/// Gets the ReplayGain in decibels for the ReplayGain mode, preferring metadata that's provided in
+/// the container format.
+fn find_replay_gain_db(probed: &mut ProbeResult, mode: ReplayGainMode) -> Option<f32> {
+    let replay_gain = probed.format.replay_gain().or_else(|| {
+        probed.metadata.get().as_ref().and_then(|m| m.current()).and_then(|rev| {
+            ReplayGain::from_tags(rev.tags())
+        })
+    });
+
+    let found = replay_gain.and_then(|replay_gain| replay_gain_db(&replay_gain, mode));
+
+    match found {
+        Some((gain_db, found_mode)) if found_mode != mode => {
+            info!("{:?} ReplayGain is not available, using {:?} ReplayGain of {:.2} dB",
+                  mode, found_mode, gain_db)
+        }
+        Some((gain_db, _)) => info!("{:?} ReplayGain: {:.2} dB", mode, gain_db),
+        None => info!("ReplayGain is not available"),
+    }
+
+    found.map(|(gain_db, _)| gain_db)
+}
+
+/// The maximum volume. A volume greater than 1.0 amplifies the audio, and may clip.
+const MAX_VOLUME: f32 = 2.0;
+
+/// Parses a linear volume, clamping it to between 0.0 and `MAX_VOLUME`.
+fn parse_volume(value: &str) -> std::result::Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(volume) if !volume.is_nan() => Ok(volume.clamp(0.0, MAX_VOLUME)),
+        _ => Err(format!("invalid volume '{}'", value)),
+    }
+}
+
+/// Converts a linear volume to a gain in decibels. Returns `None` for unity volume since no gain
+/// needs to be applied.
+fn volume_gain_db(volume: f32) -> Option<f32> {
+    if volume == 1.0 {
+        None
+    }
+    else {
+        // A volume of 0.0 is an infinite attenuation, which mutes the audio.
+        Some(20.0 * volume.log10())
+    }
+}
+
+/// Applies a gain in decibels to the decoded audio buffer. Rather than copying each decoded
+/// buffer, the decoded audio is converted into the scratch buffer owned by the caller, and the gain
+/// is applied there. The scratch buffer is only reallocated if it cannot hold the decoded audio.
+fn apply_gain<'a>(
+    decoded: &AudioBufferRef<'_>,
+    gain_db: f32,
+    scratch: &'a mut AudioBuffer<f64>,
+) -> AudioBufferRef<'a> {
+    fn copy<S: Sample + IntoSample<f64>>(src: &AudioBuffer<S>, dst: &mut AudioBuffer<f64>) {
+        if dst.spec() != src.spec() || dst.capacity() < src.frames() {
+            *dst = AudioBuffer::new(src.capacity() as Duration, *src.spec());
+        }
+
+        dst.clear();
+        dst.render_reserved(Some(src.frames()));
+
+        for ch in 0..src.spec().channels.count() {
+            for (d, &s) in dst.chan_mut(ch).iter_mut().zip(src.chan(ch)) {
+                *d = s.into_sample();
             }
-            Err(err) => {
-                // Don't give-up on a seek error.
-                warn!("seek error: {}", err);
-                0
+        }
+    }
+
+    match decoded {
+        AudioBufferRef::U8(buf)  => copy(buf, scratch),
+        AudioBufferRef::U16(buf) => copy(buf, scratch),
+        AudioBufferRef::U24(buf) => copy(buf, scratch),
+        AudioBufferRef::U32(buf) => copy(buf, scratch),
+        AudioBufferRef::S8(buf)  => copy(buf, scratch),
+        AudioBufferRef::S16(buf) => copy(buf, scratch),
+        AudioBufferRef::S24(buf) => copy(buf, scratch),
+        AudioBufferRef::S32(buf) => copy(buf, scratch),
+        AudioBufferRef::F32(buf) => copy(buf, scratch),
+        AudioBufferRef::F64(buf) => copy(buf, scratch),
+    }
+
+    scratch.apply_gain(gain_db);
+
+    AudioBufferRef::F64(Cow::Borrowed(scratch))
+}
+
+/// A position to seek to before playback.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum SeekPosition {
+    /// A time in seconds.
+    Time(f64),
+    /// A fraction of the total duration between 0.0 and 1.0.
+    Fraction(f64),
+    /// A byte offset in the media source.
+    Byte(u64),
+}
+
+impl SeekPosition {
+    /// Parses a seek position. A byte offset is prefixed with `@`, a percentage of the total
+    /// duration is suffixed with `%`, otherwise the position is a time in seconds (`SS.sss`), or
+    /// minutes and seconds (`MM:SS.sss`).
+    fn parse(value: &str) -> std::result::Result<SeekPosition, String> {
+        if let Some(offset) = value.strip_prefix('@') {
+            return match offset.parse() {
+                Ok(offset) => Ok(SeekPosition::Byte(offset)),
+                Err(_) => Err(format!("invalid byte offset '{}'", offset)),
+            };
+        }
+
+        if let Some(percent) = value.strip_suffix('%') {
+            return match percent.parse::<f64>() {
+                Ok(percent) if (0.0..=100.0).contains(&percent) => {
+                    Ok(SeekPosition::Fraction(percent / 100.0))
+                }
+                _ => Err(format!("invalid percentage '{}', expected 0 to 100", percent)),
+            };
+        }
+
+        let invalid = || format!("invalid time '{}', expected SS.sss or MM:SS.sss", value);
+
+        let (mins, secs) = match value.split_once(':') {
+            Some((mins, secs)) => (mins.parse::<u32>().map_err(|_| invalid())?, secs),
+            None => (0, value),
+        };
+
+        let secs = secs.parse::<f64>().map_err(|_| invalid())?;
+
+        // The seconds must be less than a minute if minutes are provided.
+        if !secs.is_finite() || secs < 0.0 || (value.contains(':') && secs >= 60.0) {
+            return Err(invalid());
+        }
+
+        Ok(SeekPosition::Time(60.0 * f64::from(mins) + secs))
+    }
+}
+
+/// Discards the first `n_frames` frames of the decoded audio buffer.
+fn trim_start(decoded: AudioBufferRef<'_>, n_frames: usize) -> AudioBufferRef<'_> {
+    fn trim<S: Sample>(buf: &mut AudioBuffer<S>, n_frames: usize) {
+        let len = buf.frames() - n_frames;
+        buf.trim(n_frames, len);
+    }
+
+    match decoded {
+        AudioBufferRef::U8(mut buf) => { trim(buf.to_mut(), n_frames); AudioBufferRef::U8(buf) }
+        AudioBufferRef::U16(mut buf) => { trim(buf.to_mut(), n_frames); AudioBufferRef::U16(buf) }
+        AudioBufferRef::U24(mut buf) => { trim(buf.to_mut(), n_frames); AudioBufferRef::U24(buf) }
+        AudioBufferRef::U32(mut buf) => { trim(buf.to_mut(), n_frames); AudioBufferRef::U32(buf) }
+        AudioBufferRef::S8(mut buf) => { trim(buf.to_mut(), n_frames); AudioBufferRef::S8(buf) }
+        AudioBufferRef::S16(mut buf) => { trim(buf.to_mut(), n_frames); AudioBufferRef::S16(buf) }
+        AudioBufferRef::S24(mut buf) => { trim(buf.to_mut(), n_frames); AudioBufferRef::S24(buf) }
+        AudioBufferRef::S32(mut buf) => { trim(buf.to_mut(), n_frames); AudioBufferRef::S32(buf) }
+        AudioBufferRef::F32(mut buf) => { trim(buf.to_mut(), n_frames); AudioBufferRef::F32(buf) }
+        AudioBufferRef::F64(mut buf) => { trim(buf.to_mut(), n_frames); AudioBufferRef::F64(buf) }
+    }
+}
+
+/// Options for the output of decoded audio during playback.
+#[derive(Default)]
+struct OutputOptions {
+    /// If set, write decoded audio to the output in chunks of the given number of frames.
+    chunk_len: Option<usize>,
+    /// If set, write decoded audio to a WAVE file at the given path instead of the audio device.
+    wav_path: Option<PathBuf>,
+    /// The bits per sample of the WAVE file.
+    wav_bits: u16,
+    /// If set, play audio on the audio output device with the given number or name instead of
+    /// the default device.
+    device: Option<String>,
+    /// If set, crossfade each track into the next over the given number of seconds.
+    crossfade: Option<f64>,
+}
+
+/// An open audio output, and the decoded audio it can play.
+struct Sink {
+    output: Box<dyn output::AudioOutput>,
+    /// The signal specification of the decoded audio the audio output accepts, or `None` if the
+    /// audio output converts decoded audio with any signal specification.
+    spec: Option<SignalSpec>,
+    /// The maximum duration of the decoded audio buffers the audio output accepts, or `None` if
+    /// there is no maximum.
+    duration: Option<Duration>,
+}
+
+impl Sink {
+    /// Returns `true` if the audio output can play decoded audio with the given signal
+    /// specification, and buffers of, at most, `duration` frames.
+    fn accepts(&self, spec: SignalSpec, duration: Duration) -> bool {
+        let spec_accepted = match self.spec {
+            Some(accepted) => accepted == spec,
+            None => true,
+        };
+
+        let duration_accepted = match self.duration {
+            Some(accepted) => duration <= accepted,
+            None => true,
+        };
+
+        spec_accepted && duration_accepted
+    }
+}
+
+/// Opens the audio output for decoded audio with the given signal specification, and buffers
+/// of, at most, `duration` frames.
+fn open_output(
+    spec: SignalSpec,
+    duration: Duration,
+    output_opts: &OutputOptions,
+) -> output::Result<Sink> {
+    // If chunking, the audio output must accept a whole chunk in one write.
+    let duration = match output_opts.chunk_len {
+        Some(chunk_len) => duration.max(chunk_len as Duration),
+        None => duration,
+    };
+
+    let inner: Box<dyn output::AudioOutput> = match &output_opts.wav_path {
+        Some(path) => {
+            let file = File::create(path).map_err(|err| {
+                error!("failed to create {}: {}", path.display(), err);
+                output::AudioOutputError::OpenStream
+            })?;
+
+            let writer = std::io::BufWriter::new(file);
+
+            Box::new(output::WavOutput::try_new(writer, spec, duration, output_opts.wav_bits)?)
+        }
+        None => output::try_open(spec, duration, output_opts.device.as_deref())?,
+    };
+
+    // A WAVE file, and a chunked output, accept buffers of any duration.
+    let max_duration = match (&output_opts.wav_path, output_opts.chunk_len) {
+        (None, None) => Some(duration),
+        _ => None,
+    };
+
+    let output: Box<dyn output::AudioOutput> = match output_opts.chunk_len {
+        Some(chunk_len) => Box::new(output::ChunkedOutput::new(inner, spec, chunk_len)),
+        None => inner,
+    };
+
+    // The crossfade length is converted to frames using the sample rate of the audio output.
+    let crossfade_len = output_opts.crossfade.map(|secs| {
+        (secs * f64::from(spec.rate)).round() as usize
+    });
+
+    Ok(match crossfade_len {
+        Some(len) if len > 0 => {
+            // A crossfade converts all tracks to the signal specification of the audio output.
+            let output = Box::new(output::CrossfadeOutput::new(output, spec, duration, len));
+
+            Sink { output, spec: None, duration: None }
+        }
+        _ => Sink { output, spec: Some(spec), duration: max_duration },
+    })
+}
+
+/// Seeks the selected track of the reader to the seek position, and resets the decoder. A
+/// percentage is of the given total duration of the selected track. Returns the timestamp of the
+/// first frame to play.
+fn seek(
+    reader: &mut dyn FormatReader,
+    decoder: &mut dyn Decoder,
+    track_id: u32,
+    total_duration: Option<Time>,
+    seek_pos: SeekPosition,
+) -> TimeStamp {
+    let seeked = match seek_pos {
+        SeekPosition::Time(time) => {
+            let seek_to = SeekTo::Time { time: Time::from(time), track_id: Some(track_id) };
+            reader.seek(SeekMode::Accurate, seek_to)
+        }
+        SeekPosition::Fraction(fraction) => match total_duration {
+            Some(total) => {
+                let time = Time::from(f64::from(total) * fraction);
+                reader.seek(SeekMode::Accurate, SeekTo::Time { time, track_id: Some(track_id) })
+            }
+            None => seek_error(SeekErrorKind::Unseekable),
+        },
+        SeekPosition::Byte(offset) => {
+            // A byte seek lands on a packet boundary, therefore no samples need to be trimmed.
+            match reader.seek_byte(offset) {
+                Ok(_) => decoder.reset(),
+                Err(err) => warn!("seek error: {}", err),
             }
+            return 0;
+        }
+    };
+
+    // If the seek fails, ignore the error and return a seek timestamp of 0 so that no samples are
+    // trimmed.
+    match seeked {
+        Ok(seeked_to) => {
+            // The decoder must be reset after a seek since the next packet will not follow the last
+            // packet decoded.
+            decoder.reset();
+            seeked_to.required_ts
         }
+        Err(err) => {
+            // Don't give-up on a seek error.
+            warn!("seek error: {}", err);
+            0
+        }
+    }
+}
+
+/// Options for playback.
+struct PlayOptions {
+    /// If set, start playback from the given position.
+    seek_pos: Option<SeekPosition>,
+    /// If set, only play the given number of seconds.
+    preview_time: Option<f64>,
+    /// If set, apply the given gain in decibels.
+    gain_db: Option<f32>,
+    /// The number of times to play the track, or `None` to play it forever.
+    loop_count: Option<u32>,
+    /// If `true`, display the playback progress.
+    show_progress: bool,
+}
+
+impl Default for PlayOptions {
+    fn default() -> Self {
+        PlayOptions {
+            seek_pos: None,
+            preview_time: None,
+            gain_db: None,
+            loop_count: Some(1),
+            show_progress: false,
+        }
+    }
+}
+
+/// How playback of a track ended.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum PlayEnd {
+    /// The track, or the preview of the track, was played to the end.
+    Finished,
+    /// Playback was stopped by the user.
+    Quit,
+}
+
+/// Converts an audio output error into an error that may be returned from playback.
+fn output_error(err: output::AudioOutputError) -> Error {
+    Error::IoError(std::io::Error::other(format!("audio output error: {:?}", err)))
+}
+
+/// Plays the selected track, or the default track if no track is selected, through the audio
+/// output. If the audio output is not open, it is opened once the first audio is decoded. The
+/// audio output is not flushed so that another track may follow. If present, playback is
+/// controlled by the given keyboard controls.
+fn play(
+    mut reader: Box<dyn FormatReader>,
+    track_num: Option<usize>,
+    play_opts: &PlayOptions,
+    output_opts: &OutputOptions,
+    decode_options: &DecoderOptions,
+    audio_output: &mut Option<Sink>,
+    controls: Option<&Controls>,
+) -> Result<PlayEnd> {
+    // If the audio output is already open, this track follows another.
+    if let Some(sink) = audio_output.as_mut() {
+        sink.output.next_track().map_err(output_error)?;
+    }
+
+    // Get the selected track.
+    let track = select_track(reader.as_ref(), track_num).unwrap();
+    let track_id = track.id;
+
+    // The reader may know the total duration of the default track by means other than the codec
+    // parameters of the track.
+    let total_duration = if reader.default_track().map(|default| default.id) == Some(track_id) {
+        reader.total_duration()
     }
     else {
+        TrackSummary::new(0, track, symphonia::default::get_codecs()).duration
+    };
+
+    let mut progress = Progress::new(track, total_duration, play_opts.show_progress);
+
+    let time_base = track_time_base(track);
+
+    // Create a decoder for the track.
+    let mut decoder = make_decoder(&track.codec_params, decode_options)?;
+
+    // If there is a seek position, seek the reader to the position specified and get the timestamp of
+    // the seeked position. All packets with a timestamp < the seeked position will not be played.
+    //
+    // After seeking the reader, packets are decoded and *samples* discarded up-to the exact
+    // *sample* indicated by required_ts.
+    let seek_pos = match play_opts.seek_pos {
+        Some(_) if !reader.seek_caps().is_seekable() => {
+            warn!("seeking is not supported by this format, ignoring the seek");
+            None
+        }
+        seek_pos => seek_pos,
+    };
+
+    let mut seek_ts = match seek_pos {
+        Some(seek_pos) => {
+            seek(reader.as_mut(), decoder.as_mut(), track_id, total_duration, seek_pos)
+        }
         // If not seeking, the seek timestamp is 0.
-        0
+        None => 0,
     };
 
+    // The number of times the track remains to be repeated, or `None` if repeating forever. When
+    // looping, playback restarts from the seek position, or the start of the track.
+    let mut n_repeats = play_opts.loop_count.map(|count| count.saturating_sub(1));
+
+    let loop_pos = seek_pos.unwrap_or(SeekPosition::Time(0.0));
+
+    if n_repeats != Some(0) && !reader.seek_caps().is_seekable() {
+        warn!("seeking is not supported by this format, ignoring the loop");
+        n_repeats = Some(0);
+    }
+
+    let mut preview = Preview::new(play_opts.preview_time);
+
+    // The timestamp playback has reached.
+    let mut play_ts = seek_ts;
+
+    // The buffer the gain is applied in, reused for every decoded packet.
+    let mut gain_buf = AudioBuffer::<f64>::unused();
+
     // Decode and play the packets belonging to the selected track.
     loop {
+        let mut quit = false;
+
+        if let Some(controls) = controls {
+            let mut paused = false;
+
+            // Handle all pending controls. While paused, nothing is written to the audio output,
+            // and the controls are waited on until playback is resumed.
+            while let Some(control) = if paused { controls.wait() } else { controls.poll() } {
+                match control {
+                    Control::TogglePause => paused = !paused,
+                    Control::Seek(delta) if reader.seek_caps().is_seekable() => {
+                        if let Some(time_base) = time_base {
+                            let time = f64::from(time_base.calc_time(play_ts)) + delta;
+                            let seek_pos = SeekPosition::Time(time.max(0.0));
+
+                            seek_ts = seek(
+                                reader.as_mut(),
+                                decoder.as_mut(),
+                                track_id,
+                                total_duration,
+                                seek_pos,
+                            );
+                            play_ts = seek_ts;
+                        }
+                    }
+                    Control::Seek(_) => (),
+                    Control::Quit => {
+                        quit = true;
+                        break;
+                    }
+                }
+            }
+        }
+
+        // Stop playback once the preview duration is reached, or when quitting.
+        if preview.is_done() || quit {
+            progress.finish();
+            decoder.close();
+
+            return Ok(if quit { PlayEnd::Quit } else { PlayEnd::Finished });
+        }
+
         // Get the next packet from the media container.
-        let packet = reader.next_packet()?;
+        let packet = match reader.next_packet() {
+            Ok(packet) => packet,
+            Err(Error::EndOfStream) if n_repeats != Some(0) => {
+                // Loop back to the start of playback.
+                n_repeats = n_repeats.map(|n| n - 1);
+                seek_ts =
+                    seek(reader.as_mut(), decoder.as_mut(), track_id, total_duration, loop_pos);
+                play_ts = seek_ts;
+                continue;
+            }
+            Err(err) => {
+                progress.finish();
+
+                if decode_options.verify {
+                    print_verification(decoder.as_mut());
+                }
+
+                decoder.close();
+
+                // The end of the stream is a normal termination.
+                return match err {
+                    Error::EndOfStream => Ok(PlayEnd::Finished),
+                    err => Err(err),
+                };
+            }
+        };
 
         // If the packet does not belong to the selected track, skip over it.
         if packet.track_id() != track_id {
@@ -235,25 +1468,89 @@ fn play(mut reader: Box<dyn FormatReader>, seek_time: Option<f64>, decode_option
         // Decode the packet into audio samples.
         match decoder.decode(&packet) {
             Ok(decoded) => {
-                // If the audio output is not open, try to open it.
-                if audio_output.is_none() {
-                    // Get the buffer specification. This is a description of the decoded audio
-                    // buffer's sample format.
-                    let spec = decoded.spec().clone();
+                // Get the buffer specification. This is a description of the decoded audio
+                // buffer's sample format.
+                let spec = *decoded.spec();
+
+                // Get the duration of the decoded buffer.
+                let duration = Duration::from(decoded.capacity() as u64);
+
+                // If the audio output is not open, or cannot play the decoded audio of this track,
+                // try to open it.
+                let reopen = match audio_output.as_mut() {
+                    Some(sink) if sink.accepts(spec, duration) => false,
+                    Some(_) if output_opts.wav_path.is_some() => {
+                        // Reopening the WAVE file would overwrite the audio already written.
+                        progress.finish();
+                        decoder.close();
+
+                        return unsupported_error(
+                            "the wave output cannot change its signal specification"
+                        );
+                    }
+                    Some(sink) => {
+                        info!("reopening the audio output for {} Hz, {} channel(s)",
+                              spec.rate, spec.channels.count());
 
-                    // Get the duration of the decoded buffer.
-                    let duration = Duration::from(decoded.capacity() as u64);
+                        // Play the remaining audio of the previous track before closing it.
+                        sink.output.flush();
+                        true
+                    }
+                    None => true,
+                };
 
+                if reopen {
                     // Try to open the audio output.
-                    audio_output = Some(output::try_open(spec, duration).unwrap());
+                    match open_output(spec, duration, output_opts) {
+                        Ok(sink) => *audio_output = Some(sink),
+                        Err(err) => {
+                            progress.finish();
+                            decoder.close();
+
+                            return Err(output_error(err));
+                        }
+                    }
                 }
 
-                // Write the decoded audio samples to the audio output if the presentation timestamp
-                // for the packet is >= the seeked position (0 if not seeking).
-                if packet.pts() >= seek_ts {
-                    if let Some(audio_output) = audio_output.as_mut() {
-                        audio_output.write(decoded).unwrap()
+                // The number of frames in the packet preceeding the seeked position (0 if not
+                // seeking). The timestamps are in the units of the track's time base, which may
+                // differ from the sample rate of the decoded audio.
+                let n_skip =
+                    ts_to_frames(seek_ts.saturating_sub(packet.pts()), time_base, spec.rate);
+
+                // Write the decoded audio samples to the audio output if the packet ends after the
+                // seeked position.
+                if n_skip < decoded.frames() as u64 {
+                    // Discard the samples preceeding the seeked position in the packet containing
+                    // it.
+                    let decoded = match n_skip {
+                        0 => decoded,
+                        n_frames => trim_start(decoded, n_frames as usize),
+                    };
+
+                    // When previewing, the final buffer is trimmed to end exactly at the preview
+                    // duration.
+                    let n_frames = preview.take(decoded.frames(), decoded.spec().rate);
+
+                    // Apply the normalization gain, if any.
+                    let decoded = match play_opts.gain_db {
+                        Some(gain_db) => apply_gain(&decoded, gain_db, &mut gain_buf),
+                        None => decoded,
+                    };
+
+                    let end_ts = packet.pts() + packet.duration();
+
+                    if let Some(sink) = audio_output.as_mut() {
+                        if let Err(err) = sink.output.write(decoded, n_frames) {
+                            progress.finish();
+                            decoder.close();
+
+                            return Err(output_error(err));
+                        }
                     }
+
+                    progress.update(end_ts);
+                    play_ts = end_ts;
                 }
             }
             Err(Error::DecodeError(err)) => {
@@ -262,10 +1559,7 @@ fn play(mut reader: Box<dyn FormatReader>, seek_time: Option<f64>, decode_option
                 warn!("decode error: {}", err);
             }
             Err(err) => {
-                // Flush the audio output (i.e., for end-of-stream "errors").
-                if let Some(audio_output) = audio_output.as_mut() {
-                    audio_output.flush()
-                }
+                progress.finish();
 
                 // Close the decoder.
                 decoder.close();
@@ -276,8 +1570,25 @@ fn play(mut reader: Box<dyn FormatReader>, seek_time: Option<f64>, decode_option
     }
 }
 
-fn pretty_print_format(path: &str, probed: &mut ProbeResult) {
+/// Prints the audio output devices, numbered from 1, along with their supported configurations.
+fn print_devices(devices: &[output::DeviceInfo]) {
+    for (idx, device) in devices.iter().enumerate() {
+        println!("[{:0>2}] {}", idx + 1, device.name);
+
+        for config in device.configs.iter() {
+            println!("     {}", config);
+        }
+    }
+}
+
+fn pretty_print_format(path: &str, probed: &mut ProbeResult, show_subcues: bool) {
     println!("+ {}", path);
+
+    if let Some(duration) = probed.format.total_duration() {
+        println!("|");
+        println!("| Duration: {}", format_time(duration));
+    }
+
     pretty_print_tracks(probed.format.tracks());
 
     // Prefer metadata that's provided in the container format, over other tags found during the
@@ -301,7 +1612,7 @@ fn pretty_print_format(path: &str, probed: &mut ProbeResult) {
         pretty_print_visuals(metadata_rev.visuals());
     }
 
-    pretty_print_cues(probed.format.cues());
+    pretty_print_cues(probed.format.cues(), show_subcues);
     println!("-");
 }
 
@@ -313,14 +1624,7 @@ fn pretty_print_tracks(tracks: &[Track]) {
         for (idx, track) in tracks.iter().enumerate() {
             let params = &track.codec_params;
 
-            print!("|     [{:0>2}] Codec:           ", idx + 1);
-
-            if let Some(codec) = symphonia::default::get_codecs().get_codec(params.codec) {
-                println!("{} ({})", codec.long_name, codec.short_name);
-            }
-            else {
-                println!("Unknown (#{})", params.codec);
-            }
+            println!("|     [{:0>2}] Codec:           {}", idx + 1, codec_name(params.codec));
 
             if let Some(sample_rate) = params.sample_rate {
                 println!("|          Sample Rate:     {}", sample_rate);
@@ -334,9 +1638,17 @@ fn pretty_print_tracks(tracks: &[Track]) {
             if let Some(bits_per_sample) = params.bits_per_sample {
                 println!("|          Bits per Sample: {}", bits_per_sample);
             }
+            if let Some(bit_rate) = params.bit_rate {
+                println!("|          Bit Rate:        {} kbps", f64::from(bit_rate) / 1000.0);
+            }
             if let Some(channels) = params.channels {
                 println!("|          Channel(s):      {}", channels.count());
-                println!("|          Channel Map:     {}", channels);
+
+                // Print the name of the layout, if it is well-known, along with the channel names.
+                match channels.as_layout() {
+                    Some(layout) => println!("|          Channel Map:     {:?} ({})", layout, channels),
+                    None => println!("|          Channel Map:     {}", channels),
+                }
             }
             if let Some(channel_layout) = params.channel_layout {
                 println!("|          Channel Layout:  {:?}", channel_layout);
@@ -349,45 +1661,68 @@ fn pretty_print_tracks(tracks: &[Track]) {
     }
 }
 
-fn pretty_print_cues(cues: &[Cue]) {
-    if !cues.is_empty() {
-        println!("|");
-        println!("| // Cues //");
+fn pretty_print_cues(cues: &[Cue], show_subcues: bool) {
+    print!("{}", format_cues(cues, show_subcues));
+}
 
-        for (idx, cue) in cues.iter().enumerate() {
-            println!("|     [{:0>2}] Track:      {}", idx + 1, cue.index);
-            println!("|          Timestamp:  {}", cue.start_ts);
+fn format_cue_tags(lines: &mut Vec<String>, tags: &[Tag], indent: usize) {
+    for (tidx, tag) in tags.iter().enumerate() {
+        if let Some(std_key) = tag.std_key {
+            lines.push(pretty_print_tag_item(tidx + 1, std_key.as_str(), &tag.value, indent));
+        }
+        else {
+            lines.push(pretty_print_tag_item(tidx + 1, &tag.key, &tag.value, indent));
+        }
+    }
+}
 
-            // Print tags associated with the Cue.
-            if !cue.tags.is_empty() {
-                println!("|          Tags:");
+/// Formats the list of cues. If `show_subcues` is set, the tags of all sub-cues are listed as well.
+fn format_cues(cues: &[Cue], show_subcues: bool) -> String {
+    if cues.is_empty() {
+        return String::new();
+    }
 
-                for (tidx, tag) in cue.tags.iter().enumerate() {
-                    if let Some(std_key) = tag.std_key {
-                        println!("{}", pretty_print_tag_item(tidx + 1, &format!("{:?}", std_key), &tag.value, 21));
-                    }
-                    else {
-                        println!("{}", pretty_print_tag_item(tidx + 1, &tag.key, &tag.value, 21));
-                    }
-                }
-            }
+    let mut lines = vec!["|".to_string(), "| // Cues //".to_string()];
 
-            // Print any sub-cues.
-            if !cue.points.is_empty() {
-                println!("|          Sub-Cues:");
+    for (idx, cue) in cues.iter().enumerate() {
+        lines.push(format!("|     [{:0>2}] Track:      {}", idx + 1, cue.index));
+        lines.push(format!("|          Timestamp:  {}", cue.start_ts));
 
-                for (ptidx, pt) in cue.points.iter().enumerate() {
-                    println!("|                      [{:0>2}] Offset:    {:?}", ptidx + 1, pt.start_offset_ts);
+        // Print tags associated with the Cue.
+        if !cue.tags.is_empty() {
+            lines.push("|          Tags:".to_string());
+            format_cue_tags(&mut lines, &cue.tags, 21);
+        }
 
-                    // Start the number of sub-cue tags, but don't print them.
-                    if !pt.tags.is_empty() {
-                        println!("|                           Sub-Tags:  {} (not listed)", pt.tags.len());
+        // Print any sub-cues.
+        if !cue.points.is_empty() {
+            lines.push("|          Sub-Cues:".to_string());
+
+            for (ptidx, pt) in cue.points.iter().enumerate() {
+                lines.push(format!(
+                    "|                      [{:0>2}] Offset:    {:?}",
+                    ptidx + 1,
+                    pt.start_offset_ts
+                ));
+
+                if !pt.tags.is_empty() {
+                    if show_subcues {
+                        lines.push("|                           Sub-Tags:".to_string());
+                        format_cue_tags(&mut lines, &pt.tags, 31);
+                    }
+                    else {
+                        // Count the number of sub-cue tags, but don't print them.
+                        lines.push(format!(
+                            "|                           Sub-Tags:  {} (not listed)",
+                            pt.tags.len()
+                        ));
                     }
                 }
             }
-
         }
     }
+
+    lines.iter().map(|line| format!("{}\n", line)).collect()
 }
 
 fn pretty_print_tags(tags: &[Tag]) {
@@ -400,7 +1735,7 @@ fn pretty_print_tags(tags: &[Tag]) {
         // Print tags with a standard tag key first, these are the most common tags.
         for tag in tags.iter().filter(| tag | tag.is_known()) {
             if let Some(std_key) = tag.std_key {
-                println!("{}", pretty_print_tag_item(idx, &format!("{:?}", std_key), &tag.value, 4));
+                println!("{}", pretty_print_tag_item(idx, std_key.as_str(), &tag.value, 4));
             }
             idx += 1;
         }
@@ -445,7 +1780,7 @@ fn pretty_print_visuals(visuals: &[Visual]) {
 
             for (tidx, tag) in visual.tags.iter().enumerate() {
                 if let Some(std_key) = tag.std_key {
-                    println!("{}", pretty_print_tag_item(tidx + 1, &format!("{:?}", std_key), &tag.value, 21));
+                    println!("{}", pretty_print_tag_item(tidx + 1, std_key.as_str(), &tag.value, 21));
                 }
                 else {
                     println!("{}", pretty_print_tag_item(tidx + 1, &tag.key, &tag.value, 21));
@@ -483,4 +1818,769 @@ fn pretty_print_tag_item(idx: usize, key: &str, value: &Value, indent: usize) ->
     }
 
     out
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind, Read};
+    use std::path::Path;
+
+    use symphonia::core::audio::Channels;
+    use symphonia::core::checksum::{Crc16Ansi, Crc32, Crc8Ccitt};
+    use symphonia::core::codecs::{CodecParameters, DecoderOptions, CODEC_TYPE_PCM_S16LE};
+    use symphonia::core::errors::{Error, Result};
+    use symphonia::core::io::{MediaSource, MediaSourceStream, Monitor, ReadOnlySource};
+    use symphonia::core::probe::{Hint, ProbeResult};
+    use symphonia::core::units::{Time, TimeBase};
+
+    use symphonia::core::formats::{Cue, CuePoint, SeekMode, Track};
+    use symphonia::core::meta::{ReplayGain, StandardTagKey, Tag, Value};
+
+    use super::{decode_only, find_tag_values, format_cues, format_time, open_input, parse_m3u};
+    use super::{app, play, run, select_track, ts_to_frames};
+    use super::{json, parse_volume, volume_gain_db, OutputOptions, PlayOptions, Progress};
+    use super::{parse_raw_pcm, replay_gain_db, track_time_base, ReplayGainMode, SeekPosition};
+
+    /// Builds a mono 16-bit PCM WAVE file of the given length.
+    fn make_wav(sample_rate: u32, n_frames: u32) -> Vec<u8> {
+        let data_len = 2 * n_frames;
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(b"RIFF");
+        buf.extend_from_slice(&(36 + data_len).to_le_bytes());
+        buf.extend_from_slice(b"WAVEfmt ");
+        buf.extend_from_slice(&16u32.to_le_bytes());
+        buf.extend_from_slice(&1u16.to_le_bytes());
+        buf.extend_from_slice(&1u16.to_le_bytes());
+        buf.extend_from_slice(&sample_rate.to_le_bytes());
+        buf.extend_from_slice(&(2 * sample_rate).to_le_bytes());
+        buf.extend_from_slice(&2u16.to_le_bytes());
+        buf.extend_from_slice(&16u16.to_le_bytes());
+        buf.extend_from_slice(b"data");
+        buf.extend_from_slice(&data_len.to_le_bytes());
+        buf.resize(buf.len() + data_len as usize, 0);
+        buf
+    }
+
+    /// Builds a mono 16-bit PCM WAVE file of the given length with an INFO list containing the
+    /// given tags.
+    fn make_wav_with_info(sample_rate: u32, n_frames: u32, tags: &[(&[u8; 4], &str)]) -> Vec<u8> {
+        let mut list = b"INFO".to_vec();
+
+        for &(id, value) in tags {
+            list.extend_from_slice(id);
+            list.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            list.extend_from_slice(value.as_bytes());
+
+            // Chunks are padded to an even length.
+            if value.len() % 2 != 0 {
+                list.push(0);
+            }
+        }
+
+        // Insert the INFO list after the format chunk.
+        let mut data = make_wav(sample_rate, n_frames);
+
+        let mut chunk = b"LIST".to_vec();
+        chunk.extend_from_slice(&(list.len() as u32).to_le_bytes());
+        chunk.extend(list);
+
+        data.splice(36..36, chunk);
+
+        let riff_len = data.len() as u32 - 8;
+        data[4..8].copy_from_slice(&riff_len.to_le_bytes());
+
+        data
+    }
+
+    /// Probes an in-memory file.
+    pub(crate) fn probe(data: Vec<u8>) -> ProbeResult {
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(data)), Default::default());
+
+        symphonia::default::get_probe()
+            .format(&Hint::new(), mss, &Default::default(), &Default::default())
+            .unwrap()
+    }
+
+    /// Builds an OGG page of a logical stream containing one packet.
+    fn make_ogg_page(serial: u32, sequence: u32, flags: u8, ts: u64, packet: &[u8]) -> Vec<u8> {
+        let mut page = b"OggS".to_vec();
+        page.extend_from_slice(&[0, flags]);
+        page.extend_from_slice(&ts.to_le_bytes());
+        page.extend_from_slice(&serial.to_le_bytes());
+        page.extend_from_slice(&sequence.to_le_bytes());
+        page.extend_from_slice(&[0; 4]);
+        page.extend_from_slice(&[1, packet.len() as u8]);
+        page.extend_from_slice(packet);
+
+        // The checksum is calculated over the page with the checksum field zeroed.
+        let mut crc32 = Crc32::new(0);
+        crc32.process_buf_bytes(&page);
+        page[22..26].copy_from_slice(&crc32.crc().to_le_bytes());
+        page
+    }
+
+    /// Builds a FLAC stream information metadata block, flagged as the last block if `is_last`,
+    /// for 16 mono 16-bit frames at 44.1 kHz.
+    fn make_flac_stream_info(is_last: bool) -> Vec<u8> {
+        let mut block = vec![if is_last { 0x80 } else { 0x00 }, 0x00, 0x00, 34];
+        block.extend_from_slice(&[0, 16, 0, 16]);
+        block.extend_from_slice(&[0; 6]);
+        block.extend_from_slice(&((44_100u64 << 44) | (15 << 36) | 16).to_be_bytes());
+        block.extend_from_slice(&[0; 16]);
+        block
+    }
+
+    /// Builds a FLAC frame of 16 mono 16-bit frames at 44.1 kHz where every sample has the given
+    /// value.
+    fn make_flac_frame(sample: i16) -> Vec<u8> {
+        // Fixed blocking, an 8-bit block length, 44.1kHz, mono, 16 bits per sample, and frame
+        // number 0.
+        let mut frame = vec![0xff, 0xf8, 0x69, 0x08, 0x00, 15];
+
+        let mut crc8 = Crc8Ccitt::new(0);
+        crc8.process_buf_bytes(&frame);
+        frame.push(crc8.crc());
+
+        // A verbatim subframe.
+        frame.push(0x02);
+
+        for _ in 0..16 {
+            frame.extend_from_slice(&sample.to_be_bytes());
+        }
+
+        let mut crc16 = Crc16Ansi::new(0);
+        crc16.process_buf_bytes(&frame);
+        frame.extend_from_slice(&crc16.crc().to_be_bytes());
+        frame
+    }
+
+    /// Builds a FLAC file with the given Vorbis comments, and 16 mono 16-bit frames at 44.1 kHz
+    /// where every sample has the given value.
+    fn make_tagged_flac(comments: &[&str], sample: i16) -> Vec<u8> {
+        // An empty vendor string, and then the comments.
+        let mut comment_block = vec![0; 4];
+        comment_block.extend_from_slice(&(comments.len() as u32).to_le_bytes());
+
+        for comment in comments {
+            comment_block.extend_from_slice(&(comment.len() as u32).to_le_bytes());
+            comment_block.extend_from_slice(comment.as_bytes());
+        }
+
+        let mut flac = b"fLaC".to_vec();
+        flac.extend(make_flac_stream_info(false));
+
+        // The last metadata block is the Vorbis comment block.
+        flac.push(0x84);
+        flac.extend_from_slice(&(comment_block.len() as u32).to_be_bytes()[1..]);
+        flac.extend(comment_block);
+
+        flac.extend(make_flac_frame(sample));
+        flac
+    }
+
+    /// Builds an OGG file with one OGG FLAC track for each of the given sample values. Each track
+    /// is 16 mono 16-bit frames at 44.1 kHz where every sample has the track's value.
+    fn make_multi_track_ogg(samples: &[i16]) -> Vec<u8> {
+        let mut ogg = Vec::new();
+
+        // The identification packets of all tracks come first.
+        for (serial, _) in samples.iter().enumerate() {
+            let mut packet = vec![0x7f];
+            packet.extend_from_slice(b"FLAC");
+            packet.extend_from_slice(&[1, 0, 0, 0]);
+            packet.extend_from_slice(b"fLaC");
+            packet.extend(make_flac_stream_info(true));
+
+            ogg.extend(make_ogg_page(serial as u32, 0, 0x02, 0, &packet));
+        }
+
+        for (serial, &sample) in samples.iter().enumerate() {
+            ogg.extend(make_ogg_page(serial as u32, 1, 0x04, 16, &make_flac_frame(sample)));
+        }
+
+        ogg
+    }
+
+    fn decode_preview(data: Vec<u8>, preview_time: f64) -> Result<u64> {
+        decode_only(probe(data).format, None, &DecoderOptions::default(), Some(preview_time))
+    }
+
+    #[test]
+    fn verify_preview_is_frame_exact() {
+        let n_frames = decode_preview(make_wav(48_000, 10 * 48_000), 1.0).unwrap();
+        assert_eq!(n_frames, 48_000);
+
+        let n_frames = decode_preview(make_wav(44_100, 10 * 44_100), 0.5).unwrap();
+        assert_eq!(n_frames, 22_050);
+    }
+
+    #[test]
+    fn verify_probe_json() {
+        let mut probed = probe(make_wav_with_info(8_000, 12_345, &[(b"INAM", "Title")]));
+
+        let json = json::format_probe("test.wav", &mut probed);
+
+        assert!(json.starts_with(r#"{"path":"test.wav","duration":1.543125,"tracks":[{"id":0,"#));
+        assert!(json.contains(r#""sample_rate":8000,"channels":1,"#));
+        assert!(json.contains(r#""bits_per_sample":16,"frames":12345,"bit_rate":128000,"#));
+        assert!(json.contains(r#""tags":[{"key":"INAM","std_key":"TrackTitle","value":"Title"}]"#));
+        assert!(json.ends_with(r#""visuals":[],"cues":[]}"#));
+    }
+
+    #[test]
+    fn verify_find_tag_values() {
+        let tags = [(b"INAM", "Title"), (b"IART", "Artist")];
+
+        let mut probed = probe(make_wav_with_info(8_000, 100, &tags));
+
+        // Only the title is found, by its standard tag key, or by its key.
+        assert_eq!(find_tag_values(&mut probed, "title"), ["Title"]);
+        assert_eq!(find_tag_values(&mut probed, "Track Title"), ["Title"]);
+        assert_eq!(find_tag_values(&mut probed, "inam"), ["Title"]);
+
+        assert_eq!(find_tag_values(&mut probed, "artist"), ["Artist"]);
+        assert!(find_tag_values(&mut probed, "album").is_empty());
+    }
+
+    #[test]
+    fn verify_show_subcues() {
+        let cues = [Cue {
+            index: 1,
+            start_ts: 0,
+            tags: vec![Tag::new(Some(StandardTagKey::TrackTitle), "TITLE", Value::from("Intro"))],
+            points: vec![
+                CuePoint {
+                    start_offset_ts: 588,
+                    tags: vec![Tag::new(None, "INDEX", Value::from("Pre-gap"))],
+                },
+                CuePoint {
+                    start_offset_ts: 44_100,
+                    tags: vec![Tag::new(Some(StandardTagKey::Comment), "COMMENT", Value::from("Verse"))],
+                },
+            ],
+        }];
+
+        let listed = format_cues(&cues, true);
+
+        assert!(listed.contains("[01] Offset:    588"));
+        assert!(listed.contains("[02] Offset:    44100"));
+        assert!(listed.contains("INDEX") && listed.contains("Pre-gap"));
+        assert!(listed.contains("Comment") && listed.contains("Verse"));
+        assert!(!listed.contains("not listed"));
+
+        // Without the option, the sub-cue tags are only counted.
+        let counted = format_cues(&cues, false);
+
+        assert!(counted.contains("[02] Offset:    44100"));
+        assert!(counted.contains("Sub-Tags:  1 (not listed)"));
+        assert!(!counted.contains("Pre-gap"));
+    }
+
+    #[test]
+    fn verify_total_duration() {
+        let probed = probe(make_wav(8_000, 12_345));
+
+        let duration = probed.format.total_duration().unwrap();
+
+        assert_eq!(duration.seconds, 1);
+        assert_eq!(format_time(duration), "0:00:01.543");
+
+        assert_eq!(format_time(Time::new(3723, 0.9996)), "1:02:04.000");
+    }
+
+    #[test]
+    fn verify_select_track() {
+        let probed = probe(make_wav(8_000, 100));
+
+        let reader = probed.format.as_ref();
+
+        // Tracks are numbered from 1.
+        assert_eq!(select_track(reader, None).map(|track| track.id), Some(0));
+        assert_eq!(select_track(reader, Some(1)).map(|track| track.id), Some(0));
+        assert!(select_track(reader, Some(0)).is_none());
+        assert!(select_track(reader, Some(2)).is_none());
+
+        let options = DecoderOptions::default();
+
+        assert_eq!(decode_only(probed.format, Some(1), &options, None).unwrap(), 100);
+    }
+
+    #[test]
+    fn verify_parse_seek_position() {
+        assert_eq!(SeekPosition::parse("12.5"), Ok(SeekPosition::Time(12.5)));
+        assert_eq!(SeekPosition::parse("2:05.5"), Ok(SeekPosition::Time(125.5)));
+        assert_eq!(SeekPosition::parse("@4096"), Ok(SeekPosition::Byte(4096)));
+        assert_eq!(SeekPosition::parse("50%"), Ok(SeekPosition::Fraction(0.5)));
+        assert!(SeekPosition::parse("101%").is_err());
+
+        assert_eq!(
+            SeekPosition::parse("1:2x"),
+            Err("invalid time '1:2x', expected SS.sss or MM:SS.sss".to_string())
+        );
+
+        for value in ["", "abc", "-1", "1:60", "1:-5", "inf", "@-1", "@abc"] {
+            assert!(SeekPosition::parse(value).is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn verify_forced_format_hint() {
+        let probe = |format: &str| {
+            let source = Box::new(Cursor::new(make_wav(8_000, 1_000)));
+            let mss = MediaSourceStream::new(source, Default::default());
+
+            // The extension does not identify the format.
+            let mut hint = Hint::new();
+            hint.with_extension("bin").force_format(format);
+
+            symphonia::default::get_probe()
+                .format(&hint, mss, &Default::default(), &Default::default())
+        };
+
+        let probed = probe("wave").unwrap();
+
+        let options = DecoderOptions::default();
+        assert_eq!(decode_only(probed.format, None, &options, None).unwrap(), 1_000);
+
+        // A forced format that does not support the input fails instead of falling back.
+        assert!(probe("flac").is_err());
+        assert!(probe("unknown").is_err());
+    }
+
+    #[test]
+    fn verify_parse_raw_pcm() {
+        let raw = parse_raw_pcm("s16le,44100,2").unwrap();
+
+        assert!(raw.format == CODEC_TYPE_PCM_S16LE);
+        assert_eq!(raw.rate, 44_100);
+        assert_eq!(raw.channels, Channels::FRONT_LEFT | Channels::FRONT_RIGHT);
+
+        assert_eq!(parse_raw_pcm("F32BE, 8000, 6").unwrap().channels, Channels::discrete(6));
+
+        assert!(parse_raw_pcm("s16le,44100").is_err());
+        assert!(parse_raw_pcm("s12le,44100,2").is_err());
+        assert!(parse_raw_pcm("s16le,0,2").is_err());
+        assert!(parse_raw_pcm("s16le,44100,0").is_err());
+    }
+
+    #[test]
+    fn verify_seek_fraction() {
+        let mut probed = probe(make_wav(8_000, 10_000));
+
+        // The seek lands on the packet containing the midpoint.
+        let seeked_to = probed.format.seek_fraction(SeekMode::Accurate, 0.5).unwrap();
+
+        assert_eq!(seeked_to.required_ts, 5_000);
+        assert!(seeked_to.actual_ts <= 5_000 && seeked_to.actual_ts > 5_000 - 1_152);
+        assert_eq!(probed.format.next_packet().unwrap().pts(), seeked_to.actual_ts);
+
+        assert!(probed.format.seek_fraction(SeekMode::Accurate, 1.5).is_err());
+        assert!(probed.format.seek_fraction(SeekMode::Accurate, f64::NAN).is_err());
+    }
+
+    #[test]
+    fn verify_ts_to_frames() {
+        // If the time base is the reciprocal of the sample rate, a timestamp is a frame count.
+        assert_eq!(ts_to_frames(1_000, Some(TimeBase::new(1, 8_000)), 8_000), 1_000);
+        assert_eq!(ts_to_frames(1_000, None, 8_000), 1_000);
+
+        // A 90 kHz time base with audio decoded at 48 kHz.
+        assert_eq!(ts_to_frames(90_000, Some(TimeBase::new(1, 90_000)), 48_000), 48_000);
+        assert_eq!(ts_to_frames(1_500, Some(TimeBase::new(1, 90_000)), 48_000), 800);
+    }
+
+    #[test]
+    fn verify_recover_source_after_probe() {
+        let mut data = make_wav(8_000, 1_000);
+
+        // Fill the audio data with a pattern so the position of the recovered source is known.
+        for (i, byte) in data[44..].iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+
+        let sources: [Box<dyn MediaSource>; 2] = [
+            Box::new(Cursor::new(data.clone())),
+            Box::new(ReadOnlySource::new(Cursor::new(data.clone()))),
+        ];
+
+        for source in sources {
+            let mss = MediaSourceStream::new(source, Default::default());
+
+            let probed = symphonia::default::get_probe()
+                .format(&Hint::new(), mss, &Default::default(), &Default::default())
+                .unwrap();
+
+            // The recovered source is positioned at the start of the audio data.
+            let mut rest = Vec::new();
+            probed.format.into_inner().into_inner().read_to_end(&mut rest).unwrap();
+
+            assert_eq!(rest, &data[44..]);
+        }
+    }
+
+    #[test]
+    fn verify_open_nonexistent_input() {
+        let mut hint = Hint::new();
+
+        let result = open_input("/nonexistent/file.flac", &mut hint);
+
+        match result {
+            Err(Error::IoError(err)) => assert_eq!(err.kind(), ErrorKind::NotFound),
+            _ => panic!("expected a not found error"),
+        }
+    }
+
+    #[test]
+    fn verify_decode_to_end_of_stream() {
+        let mut probed = probe(make_wav(8_000, 10_000));
+
+        let mut n_frames = 0;
+
+        let result = loop {
+            match probed.format.next_packet() {
+                Ok(packet) => n_frames += packet.duration(),
+                Err(err) => break err,
+            }
+        };
+
+        assert!(matches!(result, Error::EndOfStream));
+        assert_eq!(n_frames, 10_000);
+
+        // Decoding the whole file terminates normally.
+        assert_eq!(decode_preview(make_wav(8_000, 10_000), 10.0).unwrap(), 10_000);
+    }
+
+    #[test]
+    fn verify_packet_timestamps() {
+        let mut probed = probe(make_wav(8_000, 10_000));
+
+        let time_base = track_time_base(&probed.format.tracks()[0]).unwrap();
+
+        let mut next_ts = 0;
+        let mut n_packets = 0;
+
+        // Each packet starts where the previous packet ended.
+        while let Ok(packet) = probed.format.next_packet() {
+            assert_eq!(packet.pts(), next_ts);
+            assert!(packet.duration() > 0);
+
+            next_ts = packet.pts() + packet.duration();
+            n_packets += 1;
+        }
+
+        assert!(n_packets > 1);
+        assert_eq!(next_ts, 10_000);
+        assert_eq!(f64::from(time_base.calc_time(next_ts)), 1.25);
+    }
+
+    /// Builds a mono 16-bit PCM WAVE file of the given length where every sample has the given
+    /// value.
+    fn make_constant_wav(sample_rate: u32, n_frames: u32, sample: i16) -> Vec<u8> {
+        let mut data = make_wav(sample_rate, n_frames);
+
+        for s in data[44..].chunks_mut(2) {
+            s.copy_from_slice(&sample.to_le_bytes());
+        }
+
+        data
+    }
+
+    /// Gets the 16-bit samples of a WAVE file written by the player.
+    fn wav_samples(wav: &[u8]) -> Vec<i16> {
+        wav[44..].chunks(2).map(|s| i16::from_le_bytes([s[0], s[1]])).collect()
+    }
+
+    /// Plays the inputs in order to a WAVE file, and returns the contents of the file.
+    fn play_inputs_to_wav(
+        inputs: Vec<Vec<u8>>,
+        name: &str,
+        track_num: Option<usize>,
+        play_opts: &PlayOptions,
+        output_opts: OutputOptions,
+    ) -> Result<Vec<u8>> {
+        let path = std::env::temp_dir()
+            .join(format!("symphonia-play-{}-{}.wav", name, std::process::id()));
+
+        let output_opts = OutputOptions { wav_path: Some(path.clone()), ..output_opts };
+
+        let options = DecoderOptions::default();
+
+        let mut audio_output = None;
+
+        let result = inputs.into_iter().try_for_each(|data| {
+            let probed = probe(data);
+
+            let output = &mut audio_output;
+            play(probed.format, track_num, play_opts, &output_opts, &options, output, None)
+                .map(|_| ())
+        });
+
+        if let Some(sink) = audio_output.as_mut() {
+            sink.output.flush()
+        }
+
+        let wav = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        result.map(|_| wav)
+    }
+
+    /// Plays the data to a WAVE file with the given bits per sample, and returns the file.
+    fn play_to_wav(data: Vec<u8>, name: &str, play_opts: &PlayOptions, wav_bits: u16) -> Vec<u8> {
+        let output_opts = OutputOptions { wav_bits, ..Default::default() };
+        play_inputs_to_wav(vec![data], name, None, play_opts, output_opts).unwrap()
+    }
+
+    #[test]
+    fn verify_play_to_wav() {
+        let wav = play_to_wav(make_wav(8_000, 1_000), "wav", &Default::default(), 24);
+
+        let u16_at = |pos: usize| u16::from_le_bytes([wav[pos], wav[pos + 1]]);
+        let u32_at = |pos: usize| u32::from(u16_at(pos)) | u32::from(u16_at(pos + 2)) << 16;
+
+        // The header describes 1000 mono frames of 24-bit PCM at 8 kHz.
+        assert_eq!(wav.len(), 44 + 3_000);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(u32_at(4), 36 + 3_000);
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(u16_at(20), 1);
+        assert_eq!(u16_at(22), 1);
+        assert_eq!(u32_at(24), 8_000);
+        assert_eq!(u32_at(28), 24_000);
+        assert_eq!(u16_at(32), 3);
+        assert_eq!(u16_at(34), 24);
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(u32_at(40), 3_000);
+    }
+
+    #[test]
+    fn verify_play_selected_track() {
+        let ogg = make_multi_track_ogg(&[1_000, -2_000]);
+
+        let probed = probe(ogg.clone());
+
+        assert_eq!(probed.format.tracks().len(), 2);
+        assert!(select_track(probed.format.as_ref(), Some(3)).is_none());
+
+        let options = DecoderOptions::default();
+
+        assert_eq!(decode_only(probed.format, Some(2), &options, None).unwrap(), 16);
+
+        // Only the samples of the second track are played.
+        let output_opts = OutputOptions { wav_bits: 16, ..Default::default() };
+        let wav = play_inputs_to_wav(vec![ogg], "track", Some(2), &Default::default(), output_opts)
+            .unwrap();
+
+        assert_eq!(wav_samples(&wav), vec![-2_000; 16]);
+    }
+
+    #[test]
+    fn verify_output_open_error() {
+        // The audio output fails to open, which is an error instead of a panic.
+        let output_opts = OutputOptions { wav_bits: 12, ..Default::default() };
+
+        let inputs = vec![make_wav(8_000, 1_000)];
+        let result = play_inputs_to_wav(inputs, "bits", None, &Default::default(), output_opts);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn verify_volume() {
+        assert_eq!(parse_volume("0.5"), Ok(0.5));
+        assert_eq!(parse_volume("-1"), Ok(0.0));
+        assert_eq!(parse_volume("10"), Ok(2.0));
+        assert!(parse_volume("loud").is_err());
+        assert!(parse_volume("NaN").is_err());
+
+        assert_eq!(volume_gain_db(1.0), None);
+        assert_eq!(volume_gain_db(0.0), Some(f32::NEG_INFINITY));
+        assert!((volume_gain_db(0.5).unwrap() + 6.0206).abs() < 1e-3);
+        assert!((volume_gain_db(2.0).unwrap() - 6.0206).abs() < 1e-3);
+    }
+
+    #[test]
+    fn verify_loop() {
+        let data = make_wav(8_000, 10_000);
+
+        // Each loop plays the whole track.
+        let play_opts = PlayOptions { loop_count: Some(2), ..Default::default() };
+
+        let wav = play_to_wav(data.clone(), "loop", &play_opts, 16);
+        assert_eq!(wav.len(), 44 + 2 * 2 * 10_000);
+
+        // Each loop starts from the seek position.
+        let play_opts = PlayOptions {
+            seek_pos: Some(SeekPosition::Time(0.25)),
+            loop_count: Some(3),
+            ..Default::default()
+        };
+
+        let wav = play_to_wav(data, "loop-seek", &play_opts, 16);
+        assert_eq!(wav.len(), 44 + 3 * 2 * 8_000);
+    }
+
+    #[test]
+    fn verify_play_from_percentage() {
+        let data = make_wav(8_000, 10_000);
+
+        // Playback starts at the midpoint of the selected track.
+        let play_opts =
+            PlayOptions { seek_pos: Some(SeekPosition::Fraction(0.5)), ..Default::default() };
+
+        let wav = play_to_wav(data, "percentage", &play_opts, 16);
+        assert_eq!(wav.len(), 44 + 2 * 5_000);
+    }
+
+    #[test]
+    fn verify_progress_status() {
+        let mut params = CodecParameters::new();
+        params.with_sample_rate(44_100);
+
+        let track = Track::new(0, params);
+
+        let progress = Progress::new(&track, Some(Time::new(200, 0.5)), true);
+        assert_eq!(progress.status(65 * 44_100).unwrap(), "0:01:05 / 0:03:20 ( 32%) [ts: 2866500]");
+
+        // Only the elapsed time is known if the total duration is unknown.
+        let progress = Progress::new(&track, None, true);
+        assert_eq!(progress.status(3_661 * 44_100).unwrap(), "1:01:01 [ts: 161450100]");
+    }
+
+    #[test]
+    fn verify_replay_gain() {
+        let replay_gain = ReplayGain {
+            track_gain_db: Some(-6.0206),
+            track_peak: Some(0.9),
+            album_gain_db: Some(3.0),
+            album_peak: Some(0.9),
+        };
+
+        let (gain_db, mode) = replay_gain_db(&replay_gain, ReplayGainMode::Track).unwrap();
+        assert_eq!((gain_db, mode), (-6.0206, ReplayGainMode::Track));
+
+        // The album gain is limited so the peak does not clip.
+        let (gain_db, mode) = replay_gain_db(&replay_gain, ReplayGainMode::Album).unwrap();
+        assert!((gain_db - 0.9151).abs() < 1e-3);
+        assert_eq!(mode, ReplayGainMode::Album);
+
+        // The track gain is used if there is no album gain.
+        let track_only = ReplayGain { album_gain_db: None, ..replay_gain };
+
+        let (_, mode) = replay_gain_db(&track_only, ReplayGainMode::Album).unwrap();
+        assert_eq!(mode, ReplayGainMode::Track);
+
+        assert!(replay_gain_db(&ReplayGain::default(), ReplayGainMode::Track).is_none());
+
+        // Play a file tagged with a track gain of -6 dB, with the track ReplayGain enabled.
+        let tags = ["REPLAYGAIN_TRACK_GAIN=-6.00 dB", "REPLAYGAIN_ALBUM_GAIN=+3.00 dB"];
+
+        let dir = std::env::temp_dir();
+        let pid = std::process::id();
+        let in_path = dir.join(format!("symphonia-play-replaygain-{}.flac", pid));
+        let out_path = dir.join(format!("symphonia-play-replaygain-{}.wav", pid));
+
+        std::fs::write(&in_path, make_tagged_flac(&tags, 16_000)).unwrap();
+
+        let matches = app().get_matches_from(vec![
+            "symphonia-play",
+            "--replaygain",
+            "track",
+            "--output",
+            out_path.to_str().unwrap(),
+            in_path.to_str().unwrap(),
+        ]);
+
+        let mut audio_output = None;
+        let result = run(in_path.to_str().unwrap(), &matches, &mut audio_output, None);
+
+        if let Some(sink) = audio_output.as_mut() {
+            sink.output.flush()
+        }
+
+        let wav = std::fs::read(&out_path).unwrap();
+
+        std::fs::remove_file(&in_path).unwrap();
+        std::fs::remove_file(&out_path).unwrap();
+
+        assert!(result.is_ok());
+
+        // The track plays back at half amplitude.
+        let samples = wav_samples(&wav);
+
+        assert_eq!(samples.len(), 16);
+        assert!(samples.iter().all(|&s| (s - 8_019).abs() <= 1));
+    }
+
+    #[test]
+    fn verify_crossfade() {
+        let inputs = vec![
+            make_constant_wav(8_000, 1_000, 16_000),
+            make_constant_wav(8_000, 1_000, 8_000),
+        ];
+
+        // Crossfade over 200 frames.
+        let output_opts =
+            OutputOptions { wav_bits: 16, crossfade: Some(0.025), ..Default::default() };
+
+        let wav = play_inputs_to_wav(inputs, "crossfade", None, &Default::default(), output_opts);
+        let samples = wav_samples(&wav.unwrap());
+
+        // The end of the first track overlaps the start of the second track.
+        assert_eq!(samples.len(), 1_800);
+
+        assert!(samples[..800].iter().all(|&s| s == 16_000));
+        assert!(samples[1_000..].iter().all(|&s| s == 8_000));
+
+        // Both tracks contribute to the overlap. Halfway through the crossfade, both tracks are
+        // attenuated by 3 dB, which is louder than either track alone.
+        assert!((i32::from(samples[900]) - 16_971).abs() <= 2);
+
+        assert!(samples[800..1_000].iter().all(|&s| s > 8_000));
+        assert!(samples[800..1_000].windows(2).any(|w| w[0] != w[1]));
+    }
+
+    #[test]
+    fn verify_parse_m3u() {
+        let playlist =
+            "\u{feff}#EXTM3U\n#EXTINF:123,Artist - Title\nfirst.flac\r\n\n  b/second.wav \n";
+
+        let dir = Path::new("music");
+
+        assert_eq!(parse_m3u(playlist, dir), [
+            dir.join("first.flac").to_string_lossy(),
+            dir.join("b/second.wav").to_string_lossy(),
+        ]);
+    }
+
+    #[test]
+    fn verify_play_inputs_in_order() {
+        let inputs = vec![
+            make_constant_wav(8_000, 1_000, 1_000),
+            make_constant_wav(8_000, 500, -1_000),
+        ];
+
+        let output_opts = OutputOptions { wav_bits: 16, ..Default::default() };
+
+        let wav = play_inputs_to_wav(inputs, "inputs", None, &Default::default(), output_opts);
+        let samples = wav_samples(&wav.unwrap());
+
+        // Both inputs are played, one after the other.
+        assert_eq!(samples.len(), 1_500);
+        assert!(samples[..1_000].iter().all(|&s| s == 1_000));
+        assert!(samples[1_000..].iter().all(|&s| s == -1_000));
+
+        // The WAVE file cannot be reopened for an input with a different sample rate without
+        // overwriting the first input.
+        let inputs = vec![
+            make_constant_wav(8_000, 1_000, 1_000),
+            make_constant_wav(16_000, 500, 0),
+        ];
+
+        let output_opts = OutputOptions { wav_bits: 16, ..Default::default() };
+
+        let result =
+            play_inputs_to_wav(inputs, "inputs-spec", None, &Default::default(), output_opts);
+        assert!(matches!(result, Err(Error::Unsupported(_))));
+    }
+}
@@ -7,31 +7,57 @@
 
 //! Platform-dependant Audio Outputs
 
+use std::borrow::Cow;
+use std::io::{Seek, SeekFrom, Write};
 use std::result;
 
-use symphonia::core::audio::{AudioBufferRef, SignalSpec};
+use symphonia::core::audio::{AudioBuffer, AudioBufferRef, Channels, SampleBuffer, Signal};
+use symphonia::core::audio::SignalSpec;
+use symphonia::core::conv::IntoSample;
+use symphonia::core::dsp::downmix::{Downmixer, downmix_channels};
+use symphonia::core::resample::{Resampler, SincResampler};
+use symphonia::core::sample::Sample;
 use symphonia::core::units::Duration;
 
+use symphonia_format_wav::wave_channel_mask;
+
+use log::{error, info};
+
 pub trait AudioOutput {
-    fn write(&mut self, decoded: AudioBufferRef<'_>) -> Result<()>;
+    /// Write the first `n_frames` frames of the decoded audio buffer to the audio output.
+    fn write(&mut self, decoded: AudioBufferRef<'_>, n_frames: usize) -> Result<()>;
     fn flush(&mut self);
+
+    /// Signals that the next write is the start of a new track.
+    fn next_track(&mut self) -> Result<()> {
+        Ok(())
+    }
 }
 
 #[allow(dead_code)]
 #[derive(Debug)]
 pub enum AudioOutputError {
-    OpenStreamError,
-    PlayStreamError,
-    StreamClosedError,
+    OpenStream,
+    PlayStream,
+    StreamClosed,
+    Write,
 }
 
 pub type Result<T> = result::Result<T, AudioOutputError>;
 
-#[cfg(target_os = "linux")]
+/// A description of an audio output device.
+pub struct DeviceInfo {
+    /// The name of the device.
+    pub name: String,
+    /// Descriptions of the output configurations supported by the device.
+    pub configs: Vec<String>,
+}
+
+#[cfg(all(target_os = "linux", not(feature = "cpal-backend")))]
 mod pulseaudio {
-    use super::{AudioOutput, AudioOutputError, Result};
+    use super::{AudioOutput, AudioOutputError, ConvertedOutput, DeviceConfig, DeviceInfo, Result};
+    use super::coerce_spec;
 
-    use symphonia;
     use symphonia::core::audio::*;
     use symphonia::core::units::Duration;
 
@@ -46,19 +72,65 @@ mod pulseaudio {
     }
 
     impl PulseAudioOutput {
-        pub fn try_open(spec: SignalSpec, duration: Duration) -> Result<Box<dyn AudioOutput>> {
+        /// Lists the audio output devices. The PulseAudio simple API cannot enumerate sinks,
+        /// therefore only the default sink is listed. Any other sink may still be selected by
+        /// name.
+        pub fn list_devices() -> Result<Vec<DeviceInfo>> {
+            Ok(vec![DeviceInfo { name: "default".to_string(), configs: Vec::new() }])
+        }
+
+        /// Checks if the audio output device exists. Sinks selected by name are only checked
+        /// when the stream is opened, since they cannot be enumerated.
+        pub fn check_device(device: &str) -> Result<()> {
+            match device.parse::<usize>() {
+                Ok(1) | Err(_) => Ok(()),
+                Ok(_) => {
+                    error!("audio output device not found: {}", device);
+                    Err(AudioOutputError::OpenStream)
+                }
+            }
+        }
+
+        pub fn try_open(
+            spec: SignalSpec,
+            duration: Duration,
+            device: Option<&str>,
+        ) -> Result<Box<dyn AudioOutput>> {
+            // PulseAudio remixes and resamples streams to the sink itself, therefore any channel
+            // count and sample rate within its limits is accepted.
+            let configs = (1..=pulse::sample::Spec::CHANNELS_MAX)
+                .map(|n_channels| DeviceConfig {
+                    n_channels: usize::from(n_channels),
+                    min_rate: 1,
+                    max_rate: pulse::sample::Spec::RATE_MAX,
+                })
+                .collect::<Vec<_>>();
+
+            let out_spec = match coerce_spec(spec, &configs) {
+                Some((_, out_spec)) => out_spec,
+                None => {
+                    error!("audio output does not support {} channels", spec.channels.count());
+                    return Err(AudioOutputError::OpenStream);
+                }
+            };
+
+            let out_duration = ConvertedOutput::out_duration(spec, out_spec, duration);
+
             // An interleaved buffer is required to send data to PulseAudio. Use a SampleBuffer to
             // move data between Symphonia AudioBuffers and the byte buffers required by PulseAudio.
-            let sample_buf = RawSampleBuffer::<f32>::new(duration, spec);
+            let sample_buf = RawSampleBuffer::<f32>::new(out_duration, out_spec);
 
             // Create a PulseAudio stream specification.
             let pa_spec = pulse::sample::Spec {
                 format: pulse::sample::Format::FLOAT32NE,
-                channels: spec.channels.count() as u8,
-                rate: spec.rate,
+                channels: out_spec.channels.count() as u8,
+                rate: out_spec.rate,
             };
 
-            assert!(pa_spec.is_valid());
+            if !pa_spec.is_valid() {
+                error!("audio output does not support the signal specification");
+                return Err(AudioOutputError::OpenStream);
+            }
 
             // PulseAudio seems to not play very short audio buffers, use thse custom buffer
             // attributes for very short audio streams.
@@ -71,12 +143,16 @@ mod pulseaudio {
             //     fragsize: std::u32::MAX,
             // };
 
+            // The default sink is either not specified, or specified by its index in the list of
+            // devices.
+            let device = device.filter(|&device| device != "default" && device != "1");
+
             // Create a PulseAudio connection.
             let pa_result = psimple::Simple::new(
                 None,                               // Use default server
                 "Symphonia Player",                 // Application name
                 pulse::stream::Direction::Playback, // Playback stream
-                None,                               // Default playback device
+                device,                             // Playback device, or default if None
                 "Music",                            // Description of the stream
                 &pa_spec,                           // Signal specificaiton
                 None,                               // Default channel map
@@ -85,28 +161,41 @@ mod pulseaudio {
 
             match pa_result {
                 Ok(pa) => {
-                    Ok(Box::new(PulseAudioOutput { pa, sample_buf }))
+                    let output = Box::new(PulseAudioOutput { pa, sample_buf });
+
+                    if out_spec == spec {
+                        Ok(output)
+                    }
+                    else {
+                        Ok(Box::new(ConvertedOutput::try_new(output, spec, out_spec)?))
+                    }
                 }
                 Err(err) => {
                     error!("audio output stream open error: {}", err);
 
-                    Err(AudioOutputError::OpenStreamError)
+                    Err(AudioOutputError::OpenStream)
                 }
             }
         }
     }
 
     impl AudioOutput for PulseAudioOutput {
-        fn write(&mut self, decoded: AudioBufferRef<'_>) -> Result<()> {
+        fn write(&mut self, decoded: AudioBufferRef<'_>, n_frames: usize) -> Result<()> {
+            let n_channels = decoded.spec().channels.count();
+
             // Interleave samples from the audio buffer into the sample buffer.
             self.sample_buf.copy_interleaved_ref(decoded);
 
+            // Only write the requested number of frames.
+            let n_bytes = n_frames * n_channels * std::mem::size_of::<f32>();
+            let bytes = self.sample_buf.as_bytes();
+
             // Write interleaved samples to PulseAudio.
-            match self.pa.write(self.sample_buf.as_bytes()) {
+            match self.pa.write(&bytes[..n_bytes.min(bytes.len())]) {
                 Err(err) => {
                     error!("audio output stream write error: {}", err);
 
-                    Err(AudioOutputError::StreamClosedError)
+                    Err(AudioOutputError::StreamClosed)
                 }
                 _ => Ok(())
             }
@@ -119,11 +208,11 @@ mod pulseaudio {
     }
 }
 
-#[cfg(not(target_os = "linux"))]
+#[cfg(any(not(target_os = "linux"), feature = "cpal-backend"))]
 mod cpal {
-    use super::{AudioOutput, AudioOutputError, Result};
+    use super::{AudioOutput, AudioOutputError, ConvertedOutput, DeviceConfig, DeviceInfo, Result};
+    use super::coerce_spec;
 
-    use symphonia;
     use symphonia::core::audio::{AudioBufferRef, SampleBuffer, SignalSpec, RawSample};
     use symphonia::core::conv::ConvertibleSample;
     use symphonia::core::units::Duration;
@@ -147,43 +236,153 @@ mod cpal {
     impl AudioOutputSample for i16 { }
     impl AudioOutputSample for u16 { }
 
+    /// Selects an audio output device by its index in the list of devices, counting from 1, or
+    /// by its name. If no device is specified, the default audio output device is selected.
+    fn select_device(host: &cpal::Host, device: Option<&str>) -> Result<cpal::Device> {
+        let selected = match device {
+            Some(device) => {
+                let mut devices = match host.output_devices() {
+                    Ok(devices) => devices,
+                    Err(err) => {
+                        error!("failed to get audio output devices: {}", err);
+                        return Err(AudioOutputError::OpenStream);
+                    }
+                };
+
+                match device.parse::<usize>() {
+                    Ok(idx) => idx.checked_sub(1).and_then(|idx| devices.nth(idx)),
+                    Err(_) => devices.find(|d| d.name().ok().as_deref() == Some(device)),
+                }
+            }
+            None => host.default_output_device(),
+        };
+
+        match selected {
+            Some(selected) => Ok(selected),
+            None => {
+                error!("audio output device not found: {}", device.unwrap_or("default"));
+                Err(AudioOutputError::OpenStream)
+            }
+        }
+    }
+
     impl CpalAudioOutput {
-        pub fn try_open(spec: SignalSpec, duration: Duration) -> Result<Box<dyn AudioOutput>> {
+        /// Lists the audio output devices of the default host.
+        pub fn list_devices() -> Result<Vec<DeviceInfo>> {
+            let devices = match cpal::default_host().output_devices() {
+                Ok(devices) => devices,
+                Err(err) => {
+                    error!("failed to get audio output devices: {}", err);
+                    return Err(AudioOutputError::OpenStream);
+                }
+            };
+
+            let describe = |config: cpal::SupportedStreamConfigRange| {
+                format!(
+                    "{} channel(s), {} - {} Hz, {:?}",
+                    config.channels(),
+                    config.min_sample_rate().0,
+                    config.max_sample_rate().0,
+                    config.sample_format()
+                )
+            };
+
+            let infos = devices.map(|device| {
+                let name = device.name().unwrap_or_else(|_| "Unknown".to_string());
+
+                let configs = match device.supported_output_configs() {
+                    Ok(configs) => configs.map(describe).collect(),
+                    Err(_) => Vec::new(),
+                };
+
+                DeviceInfo { name, configs }
+            });
+
+            Ok(infos.collect())
+        }
+
+        /// Checks if the audio output device exists.
+        pub fn check_device(device: &str) -> Result<()> {
+            select_device(&cpal::default_host(), Some(device)).map(|_| ())
+        }
+
+        pub fn try_open(
+            spec: SignalSpec,
+            duration: Duration,
+            device: Option<&str>,
+        ) -> Result<Box<dyn AudioOutput>> {
             // Get default host.
             let host = cpal::default_host();
 
-            // Get the default audio output device.
-            let device = match host.default_output_device() {
-                Some(device) => device,
-                _ => {
-                    error!("failed to get default audio output device");
-                    return Err(AudioOutputError::OpenStreamError);
+            // Get the selected, or default, audio output device.
+            let device = select_device(&host, device)?;
+
+            let configs = match device.supported_output_configs() {
+                Ok(configs) => configs.collect::<Vec<_>>(),
+                Err(err) => {
+                    error!("failed to get supported audio output device configs: {}", err);
+                    return Err(AudioOutputError::OpenStream);
                 }
             };
 
-            let config = match device.default_output_config() {
-                Ok(config) => config,
-                Err(err) => {
-                    error!("failed to get default audio output device config: {}", err);
-                    return Err(AudioOutputError::OpenStreamError);
+            let device_configs = configs
+                .iter()
+                .map(|config| DeviceConfig {
+                    n_channels: usize::from(config.channels()),
+                    min_rate: config.min_sample_rate().0,
+                    max_rate: config.max_sample_rate().0,
+                })
+                .collect::<Vec<_>>();
+
+            // Select the config closest to the signal specification of the decoded audio. If the
+            // decoded audio does not match it exactly, it is converted.
+            let (config, out_spec) = match coerce_spec(spec, &device_configs) {
+                Some((idx, out_spec)) => {
+                    let rate = cpal::SampleRate(out_spec.rate);
+                    (configs[idx].clone().with_sample_rate(rate), out_spec)
+                }
+                None => {
+                    error!(
+                        "audio output device does not support {} channels",
+                        spec.channels.count()
+                    );
+                    return Err(AudioOutputError::OpenStream);
                 }
             };
 
+            // The maximum buffer duration of the audio output.
+            let out_duration = ConvertedOutput::out_duration(spec, out_spec, duration);
+
             // Select proper playback routine based on sample format.
-            match config.sample_format() {
+            let output = match config.sample_format() {
                 cpal::SampleFormat::F32 => {
-                    CpalAudioOutputImpl::<f32>::try_open(spec, duration, &device)
+                    CpalAudioOutputImpl::<f32>::try_open(out_spec, out_duration, &device)
                 }
                 cpal::SampleFormat::I16 => {
-                    CpalAudioOutputImpl::<i16>::try_open(spec, duration, &device)
+                    CpalAudioOutputImpl::<i16>::try_open(out_spec, out_duration, &device)
                 }
                 cpal::SampleFormat::U16 => {
-                    CpalAudioOutputImpl::<u16>::try_open(spec, duration, &device)
+                    CpalAudioOutputImpl::<u16>::try_open(out_spec, out_duration, &device)
                 }
+            }?;
+
+            if out_spec == spec {
+                Ok(output)
+            }
+            else {
+                Ok(Box::new(ConvertedOutput::try_new(output, spec, out_spec)?))
             }
         }
     }
 
+    /// The audio output for a cpal stream.
+    ///
+    /// Decoded audio is handed from the decode loop to the audio callback through a lock-free,
+    /// single-producer single-consumer ring buffer of interleaved samples. The decode loop is the
+    /// producer: `write` blocks until all samples have been written to the ring buffer, which
+    /// paces decoding to the rate the audio device consumes samples. The audio callback, running
+    /// on a thread owned by cpal, is the consumer: it never blocks, and if the ring buffer does not
+    /// contain enough samples to fill the device buffer, the remainder is filled with silence.
     struct CpalAudioOutputImpl<T: AudioOutputSample>
     where
         T: AudioOutputSample
@@ -208,10 +407,11 @@ mod cpal {
                 buffer_size: cpal::BufferSize::Default,
             };
 
-            // Instantiate a ring buffer capable of buffering 8K (arbitrarily chosen) samples.
+            // Instantiate a ring buffer capable of buffering 8K (arbitrarily chosen) samples. The
+            // producer half is kept by the audio output, and the consumer half is moved into the
+            // audio callback.
             let ring_buf = SpscRb::new(8 * 1024);
             let (ring_buf_producer, ring_buf_consumer) = (ring_buf.producer(), ring_buf.consumer());
-
             let stream_result = device.build_output_stream(
                 &config,
                 move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
@@ -229,7 +429,7 @@ mod cpal {
             if let Err(err) = stream_result {
                 error!("audio output stream open error: {}", err);
 
-                return Err(AudioOutputError::OpenStreamError);
+                return Err(AudioOutputError::OpenStream);
             }
 
             let stream = stream_result.unwrap();
@@ -238,7 +438,7 @@ mod cpal {
             if let Err(err) = stream.play() {
                 error!("audio output stream play error: {}", err);
 
-                return Err(AudioOutputError::PlayStreamError);
+                return Err(AudioOutputError::PlayStream);
             }
 
             let sample_buf = SampleBuffer::<T>::new(duration, spec);
@@ -249,16 +449,21 @@ mod cpal {
 
     impl<T: AudioOutputSample> AudioOutput for CpalAudioOutputImpl<T>
     {
-        fn write(&mut self, decoded: AudioBufferRef<'_>) -> Result<()> {
+        fn write(&mut self, decoded: AudioBufferRef<'_>, n_frames: usize) -> Result<()> {
+            let n_channels = decoded.spec().channels.count();
+
             // Audio samples must be interleaved for cpal. Interleave the samples in the audio
             // buffer into the sample buffer.
             self.sample_buf.copy_interleaved_ref(decoded);
 
+            // Only write the requested number of frames.
+            let n_samples = (n_frames * n_channels).min(self.sample_buf.len());
+
             let mut i = 0;
 
             // Write out all samples in the sample buffer to the ring buffer.
-            while i < self.sample_buf.len() {
-                let writeable_samples = &self.sample_buf.samples()[i..];
+            while i < n_samples {
+                let writeable_samples = &self.sample_buf.samples()[i..n_samples];
 
                 // Write as many samples as possible to the ring buffer. This blocks until some
                 // samples are written or the consumer has been destroyed (None is returned).
@@ -267,7 +472,7 @@ mod cpal {
                 }
                 else {
                     // Consumer destroyed, return an error.
-                    return Err(AudioOutputError::StreamClosedError);
+                    return Err(AudioOutputError::StreamClosed);
                 }
             }
 
@@ -279,14 +484,1026 @@ mod cpal {
             let _ = self.stream.pause();
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use cpal::traits::{DeviceTrait, HostTrait};
+
+        use super::CpalAudioOutput;
+
+        #[test]
+        fn verify_list_devices() {
+            // Not all machines have an audio output device, only verify the enumeration if there
+            // is a default device.
+            if let Some(default) = cpal::default_host().default_output_device() {
+                let name = default.name().unwrap();
+
+                let devices = CpalAudioOutput::list_devices().unwrap();
+
+                assert!(devices.iter().any(|info| info.name == name));
+                assert!(CpalAudioOutput::check_device("1").is_ok());
+                assert!(CpalAudioOutput::check_device(&name).is_ok());
+            }
+
+            // Devices are numbered from 1.
+            assert!(CpalAudioOutput::check_device("0").is_err());
+        }
+    }
 }
 
-#[cfg(target_os = "linux")]
-pub fn try_open(spec: SignalSpec, duration: Duration) -> Result<Box<dyn AudioOutput>> {
-    pulseaudio::PulseAudioOutput::try_open(spec, duration)
+/// A `ChunkedOutput` coalesces decoded audio buffers into chunks of a fixed number of frames
+/// before writing them to the wrapped audio output. This reduces the number of writes to the audio
+/// device when a codec produces many short buffers.
+pub struct ChunkedOutput {
+    inner: Box<dyn AudioOutput>,
+    buf: AudioBuffer<f32>,
+    chunk_len: usize,
 }
 
-#[cfg(not(target_os = "linux"))]
-pub fn try_open(spec: SignalSpec, duration: Duration) -> Result<Box<dyn AudioOutput>> {
-    cpal::CpalAudioOutput::try_open(spec, duration)
+impl ChunkedOutput {
+    /// Instantiate a `ChunkedOutput` that writes chunks of `chunk_len` frames to `inner`. The inner
+    /// audio output must accept writes of at least `chunk_len` frames.
+    pub fn new(inner: Box<dyn AudioOutput>, spec: SignalSpec, chunk_len: usize) -> Self {
+        assert!(chunk_len > 0, "chunk length must be greater than 0");
+
+        ChunkedOutput { inner, buf: AudioBuffer::new(chunk_len as Duration, spec), chunk_len }
+    }
+
+    /// Writes all buffered frames, if any, to the inner audio output.
+    fn write_buffered(&mut self) -> Result<()> {
+        let n_frames = self.buf.frames();
+
+        if n_frames > 0 {
+            self.inner.write(AudioBufferRef::F32(Cow::Borrowed(&self.buf)), n_frames)?;
+            self.buf.clear();
+        }
+
+        Ok(())
+    }
+}
+
+/// Appends `n_frames` frames starting at frame `offset` of the source buffer to the destination
+/// buffer.
+fn append_frames<S>(
+    src: &AudioBuffer<S>,
+    dst: &mut AudioBuffer<f32>,
+    offset: usize,
+    n_frames: usize,
+)
+where
+    S: Sample + IntoSample<f32>,
+{
+    let start = dst.frames();
+
+    dst.render_reserved(Some(n_frames));
+
+    for ch in 0..src.spec().channels.count() {
+        let src_ch = &src.chan(ch)[offset..offset + n_frames];
+
+        for (d, &s) in dst.chan_mut(ch)[start..].iter_mut().zip(src_ch) {
+            *d = s.into_sample();
+        }
+    }
+}
+
+impl AudioOutput for ChunkedOutput {
+    fn write(&mut self, decoded: AudioBufferRef<'_>, n_frames: usize) -> Result<()> {
+        // The buffered frames must share the signal specification of the decoded frames. If the
+        // specification changed, write out the buffered frames before buffering the new ones.
+        if decoded.spec() != self.buf.spec() {
+            self.write_buffered()?;
+            self.buf = AudioBuffer::new(self.chunk_len as Duration, *decoded.spec());
+        }
+
+        let n_frames = n_frames.min(decoded.frames());
+
+        let mut offset = 0;
+
+        while offset < n_frames {
+            let n_copy = (self.chunk_len - self.buf.frames()).min(n_frames - offset);
+
+            match decoded {
+                AudioBufferRef::U8(ref buf) => append_frames(buf, &mut self.buf, offset, n_copy),
+                AudioBufferRef::U16(ref buf) => append_frames(buf, &mut self.buf, offset, n_copy),
+                AudioBufferRef::U24(ref buf) => append_frames(buf, &mut self.buf, offset, n_copy),
+                AudioBufferRef::U32(ref buf) => append_frames(buf, &mut self.buf, offset, n_copy),
+                AudioBufferRef::S8(ref buf) => append_frames(buf, &mut self.buf, offset, n_copy),
+                AudioBufferRef::S16(ref buf) => append_frames(buf, &mut self.buf, offset, n_copy),
+                AudioBufferRef::S24(ref buf) => append_frames(buf, &mut self.buf, offset, n_copy),
+                AudioBufferRef::S32(ref buf) => append_frames(buf, &mut self.buf, offset, n_copy),
+                AudioBufferRef::F32(ref buf) => append_frames(buf, &mut self.buf, offset, n_copy),
+                AudioBufferRef::F64(ref buf) => append_frames(buf, &mut self.buf, offset, n_copy),
+            }
+
+            offset += n_copy;
+
+            // Write a full chunk to the audio output.
+            if self.buf.frames() == self.chunk_len {
+                self.write_buffered()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) {
+        // Write the remaining partial chunk before flushing. Flush is best-effort, therefore ignore
+        // the result.
+        let _ = self.write_buffered();
+        self.inner.flush();
+    }
+}
+
+/// A `WavOutput` writes decoded audio to a canonical RIFF/WAVE file of signed integer PCM
+/// samples. Audio with more than 2 channels, or discrete channels, is written using the
+/// WAVE_FORMAT_EXTENSIBLE format so that the speaker positions of the channels are preserved.
+///
+/// The sizes in the file header are only known once all audio has been written, therefore they are
+/// written when the output is flushed.
+pub struct WavOutput<W: Write + Seek> {
+    writer: W,
+    spec: SignalSpec,
+    sample_buf: SampleBuffer<i32>,
+    bits_per_sample: u16,
+    /// The number of bytes of audio data written.
+    data_len: u32,
+    bytes: Vec<u8>,
+}
+
+impl<W: Write + Seek> WavOutput<W> {
+    /// Instantiate a `WavOutput` that writes audio with the given signal specification to `writer`
+    /// using 16, 24, or 32 bits per sample.
+    pub fn try_new(
+        writer: W,
+        spec: SignalSpec,
+        duration: Duration,
+        bits_per_sample: u16,
+    ) -> Result<Self> {
+        if ![16, 24, 32].contains(&bits_per_sample) {
+            error!("unsupported wav bits per sample: {}", bits_per_sample);
+            return Err(AudioOutputError::OpenStream);
+        }
+
+        let sample_buf = SampleBuffer::<i32>::new(duration, spec);
+
+        let mut output =
+            WavOutput { writer, spec, sample_buf, bits_per_sample, data_len: 0, bytes: Vec::new() };
+
+        // Write a header with empty sizes, they are filled in when the output is flushed.
+        if let Err(err) = output.write_header() {
+            error!("wav header write error: {}", err);
+            return Err(AudioOutputError::OpenStream);
+        }
+
+        Ok(output)
+    }
+
+    /// Returns `true` if the header uses the WAVE_FORMAT_EXTENSIBLE format.
+    fn is_extensible(&self) -> bool {
+        self.spec.channels.count() > 2 || self.spec.channels.is_discrete()
+    }
+
+    /// Gets the length of the RIFF/WAVE header in bytes.
+    fn header_len(&self) -> u32 {
+        if self.is_extensible() { 68 } else { 44 }
+    }
+
+    /// Writes the 44 byte RIFF/WAVE header, or 68 byte header if using the WAVE_FORMAT_EXTENSIBLE
+    /// format, at the current position of the writer.
+    fn write_header(&mut self) -> std::io::Result<()> {
+        /// The sub-format GUID of PCM samples in the WAVE_FORMAT_EXTENSIBLE format.
+        const KSDATAFORMAT_SUBTYPE_PCM: [u8; 16] = [
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00,
+            0x80, 0x00, 0x00, 0xaa, 0x00, 0x38, 0x9b, 0x71,
+        ];
+
+        let n_channels = self.spec.channels.count() as u16;
+        let block_align = n_channels * (self.bits_per_sample / 8);
+
+        let header_len = self.header_len();
+
+        let mut header = Vec::with_capacity(header_len as usize);
+
+        header.extend_from_slice(b"RIFF");
+        header.extend_from_slice(&(header_len - 8 + self.data_len).to_le_bytes());
+        header.extend_from_slice(b"WAVE");
+
+        // The format chunk for PCM samples.
+        let (fmt_len, format) = if self.is_extensible() { (40u32, 0xfffeu16) } else { (16, 1) };
+
+        header.extend_from_slice(b"fmt ");
+        header.extend_from_slice(&fmt_len.to_le_bytes());
+        header.extend_from_slice(&format.to_le_bytes());
+        header.extend_from_slice(&n_channels.to_le_bytes());
+        header.extend_from_slice(&self.spec.rate.to_le_bytes());
+        header.extend_from_slice(&(self.spec.rate * u32::from(block_align)).to_le_bytes());
+        header.extend_from_slice(&block_align.to_le_bytes());
+        header.extend_from_slice(&self.bits_per_sample.to_le_bytes());
+
+        if self.is_extensible() {
+            // Channels without a speaker position are written with an unspecified channel mask.
+            let channel_mask = wave_channel_mask(self.spec.channels).unwrap_or(0);
+
+            header.extend_from_slice(&22u16.to_le_bytes());
+            header.extend_from_slice(&self.bits_per_sample.to_le_bytes());
+            header.extend_from_slice(&channel_mask.to_le_bytes());
+            header.extend_from_slice(&KSDATAFORMAT_SUBTYPE_PCM);
+        }
+
+        header.extend_from_slice(b"data");
+        header.extend_from_slice(&self.data_len.to_le_bytes());
+
+        self.writer.write_all(&header)
+    }
+
+    /// Rewrites the header with the final sizes, and then returns to the end of the file.
+    fn finalize_header(&mut self) -> std::io::Result<()> {
+        self.writer.seek(SeekFrom::Start(0))?;
+        self.write_header()?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()
+    }
+}
+
+impl<W: Write + Seek> AudioOutput for WavOutput<W> {
+    fn write(&mut self, decoded: AudioBufferRef<'_>, n_frames: usize) -> Result<()> {
+        // A WAVE file cannot change its signal specification mid-stream.
+        if *decoded.spec() != self.spec {
+            error!("wav output does not support a change of signal specification");
+            return Err(AudioOutputError::Write);
+        }
+
+        // Unlike an audio device, a file has no fixed buffer size, so grow the sample buffer if the
+        // decoded audio buffer is larger than expected.
+        if decoded.capacity() * self.spec.channels.count() > self.sample_buf.capacity() {
+            self.sample_buf = SampleBuffer::new(decoded.capacity() as Duration, self.spec);
+        }
+
+        // Interleave and convert the samples to 32-bit signed integers.
+        self.sample_buf.copy_interleaved_ref(decoded);
+
+        let n_samples = (n_frames * self.spec.channels.count()).min(self.sample_buf.len());
+
+        let bytes_per_sample = usize::from(self.bits_per_sample / 8);
+        let shift = 32 - self.bits_per_sample;
+
+        self.bytes.clear();
+
+        // Truncate each sample to the bits per sample, and write it in little-endian byte order.
+        for &s in &self.sample_buf.samples()[..n_samples] {
+            self.bytes.extend_from_slice(&(s >> shift).to_le_bytes()[..bytes_per_sample]);
+        }
+
+        // The RIFF chunk size is limited to 32 bits.
+        self.data_len = match self.data_len.checked_add(self.bytes.len() as u32) {
+            Some(data_len) if data_len <= u32::MAX - (self.header_len() - 8) => data_len,
+            _ => {
+                error!("wav output exceeds the maximum file size");
+                return Err(AudioOutputError::Write);
+            }
+        };
+
+        if let Err(err) = self.writer.write_all(&self.bytes) {
+            error!("wav output write error: {}", err);
+            return Err(AudioOutputError::Write);
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) {
+        // Flush is best-effort, but log any error since the file header may be incomplete.
+        if let Err(err) = self.finalize_header() {
+            error!("wav header write error: {}", err);
+        }
+    }
+}
+
+/// Gets the maximum number of frames resampled from a single buffer of `duration` frames at
+/// `in_rate` to `out_rate`.
+fn resampled_duration(in_rate: u32, out_rate: u32, duration: Duration) -> Duration {
+    // Allow for the frames retained by the resampler between buffers.
+    (duration + 64) * Duration::from(out_rate) / Duration::from(in_rate) + 1
+}
+
+/// Converts decoded audio to the signal specification of an audio output. The channels are
+/// downmixed, or upmixed if mono, and then the audio is resampled.
+struct SpecMatcher {
+    in_spec: SignalSpec,
+    in_buf: AudioBuffer<f32>,
+    /// Downmixes the decoded audio, if it has more channels than the audio output.
+    downmixer: Option<Downmixer>,
+    mixed_buf: AudioBuffer<f32>,
+    /// Resamples the decoded audio, if its sample rate differs from the audio output.
+    resampler: Option<SincResampler>,
+    out_buf: AudioBuffer<f32>,
+    /// The total number of frames input to, and output from, the resampler.
+    n_resampled_in: u64,
+    n_resampled_out: u64,
+}
+
+impl SpecMatcher {
+    fn try_new(in_spec: SignalSpec, out_spec: SignalSpec) -> Result<Self> {
+        let n_in_channels = in_spec.channels.count();
+        let n_out_channels = out_spec.channels.count();
+
+        // Channels are matched by position, so only a standard downmix, or an upmix of a mono
+        // signal to all channels, is possible.
+        let downmixer = if n_in_channels == n_out_channels || n_in_channels == 1 {
+            None
+        }
+        else {
+            match Downmixer::try_new(in_spec.channels, n_out_channels) {
+                Ok(downmixer) if downmixer.out_channels().count() == n_out_channels => {
+                    Some(downmixer)
+                }
+                _ => {
+                    error!(
+                        "cannot match {} channels to {} channels",
+                        n_in_channels,
+                        n_out_channels
+                    );
+                    return Err(AudioOutputError::Write);
+                }
+            }
+        };
+
+        let resampler = if in_spec.rate == out_spec.rate {
+            None
+        }
+        else {
+            match SincResampler::try_new(in_spec.rate, out_spec.rate, out_spec.channels) {
+                Ok(resampler) => Some(resampler),
+                Err(err) => {
+                    error!("failed to create resampler: {}", err);
+                    return Err(AudioOutputError::Write);
+                }
+            }
+        };
+
+        let mixed_spec = SignalSpec::new(in_spec.rate, out_spec.channels);
+
+        Ok(SpecMatcher {
+            in_spec,
+            in_buf: AudioBuffer::new(0, in_spec),
+            downmixer,
+            mixed_buf: AudioBuffer::new(0, mixed_spec),
+            resampler,
+            out_buf: AudioBuffer::new(0, out_spec),
+            n_resampled_in: 0,
+            n_resampled_out: 0,
+        })
+    }
+
+    /// Converts the first `n_frames` frames of the decoded audio buffer, and returns the converted
+    /// audio.
+    fn process(
+        &mut self,
+        decoded: AudioBufferRef<'_>,
+        n_frames: usize,
+    ) -> Result<&AudioBuffer<f32>> {
+        let n_frames = n_frames.min(decoded.frames());
+
+        if self.in_buf.capacity() < n_frames {
+            self.in_buf = AudioBuffer::new(n_frames as Duration, self.in_spec);
+            self.mixed_buf = AudioBuffer::new(n_frames as Duration, *self.mixed_buf.spec());
+        }
+
+        self.in_buf.clear();
+
+        match decoded {
+            AudioBufferRef::U8(ref buf) => append_frames(buf, &mut self.in_buf, 0, n_frames),
+            AudioBufferRef::U16(ref buf) => append_frames(buf, &mut self.in_buf, 0, n_frames),
+            AudioBufferRef::U24(ref buf) => append_frames(buf, &mut self.in_buf, 0, n_frames),
+            AudioBufferRef::U32(ref buf) => append_frames(buf, &mut self.in_buf, 0, n_frames),
+            AudioBufferRef::S8(ref buf) => append_frames(buf, &mut self.in_buf, 0, n_frames),
+            AudioBufferRef::S16(ref buf) => append_frames(buf, &mut self.in_buf, 0, n_frames),
+            AudioBufferRef::S24(ref buf) => append_frames(buf, &mut self.in_buf, 0, n_frames),
+            AudioBufferRef::S32(ref buf) => append_frames(buf, &mut self.in_buf, 0, n_frames),
+            AudioBufferRef::F32(ref buf) => append_frames(buf, &mut self.in_buf, 0, n_frames),
+            AudioBufferRef::F64(ref buf) => append_frames(buf, &mut self.in_buf, 0, n_frames),
+        }
+
+        match &self.downmixer {
+            Some(downmixer) => downmixer.process(&self.in_buf, &mut self.mixed_buf),
+            None => {
+                self.mixed_buf.clear();
+                self.mixed_buf.render_reserved(Some(n_frames));
+
+                // A mono signal is copied to all channels.
+                let n_in_channels = self.in_spec.channels.count();
+
+                for ch in 0..self.mixed_buf.spec().channels.count() {
+                    let src = self.in_buf.chan(ch.min(n_in_channels - 1));
+                    self.mixed_buf.chan_mut(ch).copy_from_slice(src);
+                }
+            }
+        }
+
+        let resampler = match self.resampler.as_mut() {
+            Some(resampler) => resampler,
+            None => return Ok(&self.mixed_buf),
+        };
+
+        let out_rate = self.out_buf.spec().rate;
+        let out_duration = resampled_duration(self.in_spec.rate, out_rate, n_frames as Duration);
+
+        if (self.out_buf.capacity() as Duration) < out_duration {
+            self.out_buf = AudioBuffer::new(out_duration, *self.out_buf.spec());
+        }
+
+        if let Err(err) = resampler.process(&self.mixed_buf, &mut self.out_buf) {
+            error!("resampling error: {}", err);
+            return Err(AudioOutputError::Write);
+        }
+
+        self.n_resampled_in += n_frames as u64;
+        self.n_resampled_out += self.out_buf.frames() as u64;
+
+        Ok(&self.out_buf)
+    }
+
+    /// Drains the frames retained by the resampler by resampling silence. Returns the drained
+    /// audio, or `None` once all frames have been drained.
+    fn drain(&mut self) -> Result<Option<&AudioBuffer<f32>>> {
+        let resampler = match self.resampler.as_mut() {
+            Some(resampler) => resampler,
+            None => return Ok(None),
+        };
+
+        // The number of output frames that correspond to all the input frames.
+        let out_rate = u64::from(self.out_buf.spec().rate);
+        let n_total = (self.n_resampled_in * out_rate).div_ceil(u64::from(self.in_spec.rate));
+
+        if self.n_resampled_out >= n_total {
+            return Ok(None);
+        }
+
+        self.mixed_buf.clear();
+        self.mixed_buf.silence(None);
+
+        if let Err(err) = resampler.process(&self.mixed_buf, &mut self.out_buf) {
+            error!("resampling error: {}", err);
+            return Err(AudioOutputError::Write);
+        }
+
+        // Frames resampled from the silence past the end of the input are discarded.
+        let n_frames = (self.out_buf.frames() as u64).min(n_total - self.n_resampled_out);
+
+        self.out_buf.truncate(n_frames as usize);
+        self.n_resampled_out += n_frames;
+
+        Ok(Some(&self.out_buf))
+    }
+}
+
+/// A range of output configurations supported by an audio output device.
+#[derive(Copy, Clone, Debug)]
+pub struct DeviceConfig {
+    /// The number of channels.
+    pub n_channels: usize,
+    /// The minimum sample rate.
+    pub min_rate: u32,
+    /// The maximum sample rate.
+    pub max_rate: u32,
+}
+
+/// Selects the audio output device configuration closest to the signal specification of the
+/// decoded audio. Returns the index of the selected configuration, and the signal specification
+/// the decoded audio must be converted to for it, or `None` if the channels cannot be converted
+/// for any configuration.
+///
+/// A configuration with the same number of channels is preferred, then a downmix to stereo or
+/// mono, and then an upmix of mono. Among those, a configuration supporting the sample rate is
+/// preferred, otherwise the audio is resampled to the closest supported sample rate.
+pub fn coerce_spec(spec: SignalSpec, configs: &[DeviceConfig]) -> Option<(usize, SignalSpec)> {
+    let n_channels = spec.channels.count();
+
+    let has_channels = |count| configs.iter().any(|config| config.n_channels == count);
+
+    let channels = if has_channels(n_channels) {
+        spec.channels
+    }
+    else if n_channels > 2 && has_channels(2) {
+        downmix_channels(spec.channels, 2)?
+    }
+    else if n_channels > 1 && has_channels(1) {
+        downmix_channels(spec.channels, 1)?
+    }
+    else if n_channels == 1 && has_channels(2) {
+        Channels::FRONT_LEFT | Channels::FRONT_RIGHT
+    }
+    else {
+        return None;
+    };
+
+    // The sample rate closest to that of the decoded audio for each usable configuration. For
+    // equally close sample rates, the higher one is preferred.
+    let (idx, rate) = configs
+        .iter()
+        .enumerate()
+        .filter(|(_, config)| config.n_channels == channels.count())
+        .map(|(idx, config)| (idx, spec.rate.max(config.min_rate).min(config.max_rate)))
+        .min_by_key(|&(_, rate)| {
+            (u64::from(rate.max(spec.rate) - rate.min(spec.rate)), std::cmp::Reverse(rate))
+        })?;
+
+    Some((idx, SignalSpec::new(rate, channels)))
+}
+
+/// A `ConvertedOutput` converts decoded audio to the signal specification of the wrapped audio
+/// output. This allows audio to be played on audio devices that do not support its native channels
+/// or sample rate.
+pub struct ConvertedOutput {
+    inner: Box<dyn AudioOutput>,
+    matcher: SpecMatcher,
+}
+
+impl ConvertedOutput {
+    /// Instantiate a `ConvertedOutput` that converts audio with the given signal specification to
+    /// `out_spec`. The inner audio output must accept writes of at least
+    /// `ConvertedOutput::out_duration` frames.
+    pub fn try_new(
+        inner: Box<dyn AudioOutput>,
+        spec: SignalSpec,
+        out_spec: SignalSpec,
+    ) -> Result<Self> {
+        let n_in = spec.channels.count();
+        let n_out = out_spec.channels.count();
+
+        if n_out < n_in {
+            info!("downmixing from {} to {} channels for the audio output", n_in, n_out);
+        }
+        else if n_out > n_in {
+            info!("upmixing from {} to {} channels for the audio output", n_in, n_out);
+        }
+
+        if out_spec.rate != spec.rate {
+            info!("resampling from {} Hz to {} Hz for the audio output", spec.rate, out_spec.rate);
+        }
+
+        let matcher = SpecMatcher::try_new(spec, out_spec)?;
+
+        Ok(ConvertedOutput { inner, matcher })
+    }
+
+    /// Gets the maximum number of frames written to the inner audio output by a single write of
+    /// `duration` frames with the signal specification `spec` converted to `out_spec`.
+    pub fn out_duration(spec: SignalSpec, out_spec: SignalSpec, duration: Duration) -> Duration {
+        if spec.rate == out_spec.rate {
+            duration
+        }
+        else {
+            resampled_duration(spec.rate, out_spec.rate, duration)
+        }
+    }
+}
+
+impl AudioOutput for ConvertedOutput {
+    fn write(&mut self, decoded: AudioBufferRef<'_>, n_frames: usize) -> Result<()> {
+        if *decoded.spec() != self.matcher.in_spec {
+            error!("converted output does not support a change of signal specification");
+            return Err(AudioOutputError::Write);
+        }
+
+        let buf = self.matcher.process(decoded, n_frames)?;
+
+        if buf.frames() > 0 {
+            self.inner.write(AudioBufferRef::F32(Cow::Borrowed(buf)), buf.frames())?;
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) {
+        // Flush is best-effort, therefore stop draining the resampler on any error.
+        while let Ok(Some(buf)) = self.matcher.drain() {
+            if buf.frames() == 0 {
+                continue;
+            }
+
+            if self.inner.write(AudioBufferRef::F32(Cow::Borrowed(buf)), buf.frames()).is_err() {
+                break;
+            }
+        }
+
+        self.inner.flush();
+    }
+}
+
+/// Gets the gains of the track fading in, and the track fading out, at the given position between
+/// 0.0 and 1.0 of a crossfade. The gains have equal power so that the loudness of uncorrelated
+/// tracks is constant throughout the crossfade.
+fn crossfade_gains(pos: f32) -> (f32, f32) {
+    let angle = std::f32::consts::FRAC_PI_2 * pos;
+    (angle.sin(), angle.cos())
+}
+
+/// A `CrossfadeOutput` crossfades the end of each track into the start of the next track before
+/// writing it to the wrapped audio output.
+///
+/// The last frames written, up-to the length of the crossfade, are held back. When the next track
+/// starts, the held frames are faded out while the start of the next track is faded in, and the
+/// two are mixed. Tracks with a different signal specification than the audio output are first
+/// converted to match it.
+pub struct CrossfadeOutput {
+    inner: Box<dyn AudioOutput>,
+    spec: SignalSpec,
+    /// The maximum number of frames written to the inner audio output at once.
+    duration: Duration,
+    /// The length of the crossfade in frames.
+    len: usize,
+    /// The frames held back, per channel.
+    held: Vec<Vec<f32>>,
+    /// The held frames of the previous track, per channel, that are being faded out.
+    tail: Vec<Vec<f32>>,
+    /// The number of frames of the previous track faded out so far.
+    tail_pos: usize,
+    /// Converts the audio of the current track to the signal specification of the audio output.
+    matcher: Option<SpecMatcher>,
+    /// The buffer for audio written to the inner audio output.
+    buf: AudioBuffer<f32>,
+    /// The buffers for the faded in, and faded out, audio of a crossfade.
+    fade_in: AudioBuffer<f32>,
+    fade_out: AudioBuffer<f32>,
+}
+
+impl CrossfadeOutput {
+    /// Instantiate a `CrossfadeOutput` that crossfades tracks over `len` frames, and writes audio
+    /// with the given signal specification to `inner`. The inner audio output must accept writes
+    /// of at least `duration` frames.
+    pub fn new(
+        inner: Box<dyn AudioOutput>,
+        spec: SignalSpec,
+        duration: Duration,
+        len: usize,
+    ) -> Self {
+        let n_channels = spec.channels.count();
+
+        CrossfadeOutput {
+            inner,
+            spec,
+            duration,
+            len,
+            held: vec![Vec::new(); n_channels],
+            tail: vec![Vec::new(); n_channels],
+            tail_pos: 0,
+            matcher: None,
+            buf: AudioBuffer::new(duration, spec),
+            // The previous track is held back for at most `len` frames, so a crossfade never
+            // exceeds `len` frames.
+            fade_in: AudioBuffer::new(len as Duration, spec),
+            fade_out: AudioBuffer::new(len as Duration, spec),
+        }
+    }
+
+    /// Ends the crossfade, if any, by fading out the remainder of the previous track over silence.
+    fn finish_fade(&mut self) {
+        let tail_len = self.tail[0].len();
+
+        for (held, tail) in self.held.iter_mut().zip(self.tail.iter_mut()) {
+            for (i, &s) in tail.iter().enumerate().skip(self.tail_pos) {
+                let (_, gain_out) = crossfade_gains(i as f32 / tail_len as f32);
+                held.push(gain_out * s);
+            }
+
+            tail.clear();
+        }
+
+        self.tail_pos = 0;
+    }
+
+    /// Writes the held frames, except the last `keep` frames, to the inner audio output.
+    fn write_held(&mut self, keep: usize) -> Result<()> {
+        while self.held[0].len() > keep {
+            let n_frames = (self.held[0].len() - keep).min(self.duration as usize);
+
+            self.buf.clear();
+            self.buf.render_reserved(Some(n_frames));
+
+            for (ch, held) in self.held.iter_mut().enumerate() {
+                self.buf.chan_mut(ch).copy_from_slice(&held[..n_frames]);
+                held.drain(..n_frames);
+            }
+
+            self.inner.write(AudioBufferRef::F32(Cow::Borrowed(&self.buf)), n_frames)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl AudioOutput for CrossfadeOutput {
+    fn write(&mut self, decoded: AudioBufferRef<'_>, n_frames: usize) -> Result<()> {
+        if self.matcher.as_ref().map(|matcher| matcher.in_spec) != Some(*decoded.spec()) {
+            self.matcher = Some(SpecMatcher::try_new(*decoded.spec(), self.spec)?);
+        }
+
+        let buf = self.matcher.as_mut().unwrap().process(decoded, n_frames)?;
+
+        // The number of frames of this buffer to crossfade with the previous track.
+        let tail_len = self.tail[0].len();
+        let n_fade = (tail_len - self.tail_pos).min(buf.frames());
+
+        if n_fade > 0 {
+            let fade_in = &mut self.fade_in;
+            let fade_out = &mut self.fade_out;
+
+            fade_in.clear();
+            fade_out.clear();
+
+            fade_in.render_reserved(Some(n_fade));
+            fade_out.render_reserved(Some(n_fade));
+
+            for (ch, tail) in self.tail.iter().enumerate() {
+                let fade_in_iter = fade_in.chan_mut(ch).iter_mut().zip(buf.chan(ch));
+                let fade_out_iter = fade_out.chan_mut(ch).iter_mut().zip(&tail[self.tail_pos..]);
+
+                for (i, ((d_in, &s_in), (d_out, &s_out))) in
+                    fade_in_iter.zip(fade_out_iter).enumerate()
+                {
+                    let (gain_in, gain_out) =
+                        crossfade_gains((self.tail_pos + i) as f32 / tail_len as f32);
+
+                    *d_in = gain_in * s_in;
+                    *d_out = gain_out * s_out;
+                }
+            }
+
+            if let Err(err) = fade_in.mix(fade_out) {
+                error!("crossfade error: {}", err);
+                return Err(AudioOutputError::Write);
+            }
+
+            self.tail_pos += n_fade;
+
+            for (ch, held) in self.held.iter_mut().enumerate() {
+                held.extend_from_slice(fade_in.chan(ch));
+            }
+        }
+
+        for (ch, held) in self.held.iter_mut().enumerate() {
+            held.extend_from_slice(&buf.chan(ch)[n_fade..]);
+        }
+
+        self.write_held(self.len)
+    }
+
+    fn next_track(&mut self) -> Result<()> {
+        // If the previous track was shorter than the crossfade, the crossfade with it ends early.
+        self.finish_fade();
+        self.write_held(self.len)?;
+
+        std::mem::swap(&mut self.held, &mut self.tail);
+
+        // The next track may have a different signal specification.
+        self.matcher = None;
+
+        Ok(())
+    }
+
+    fn flush(&mut self) {
+        self.finish_fade();
+
+        // Flush is best-effort, therefore ignore the result.
+        let _ = self.write_held(0);
+        self.inner.flush();
+    }
+}
+
+#[cfg(all(target_os = "linux", not(feature = "cpal-backend")))]
+pub fn list_devices() -> Result<Vec<DeviceInfo>> {
+    pulseaudio::PulseAudioOutput::list_devices()
+}
+
+#[cfg(any(not(target_os = "linux"), feature = "cpal-backend"))]
+pub fn list_devices() -> Result<Vec<DeviceInfo>> {
+    cpal::CpalAudioOutput::list_devices()
+}
+
+#[cfg(all(target_os = "linux", not(feature = "cpal-backend")))]
+pub fn check_device(device: &str) -> Result<()> {
+    pulseaudio::PulseAudioOutput::check_device(device)
+}
+
+#[cfg(any(not(target_os = "linux"), feature = "cpal-backend"))]
+pub fn check_device(device: &str) -> Result<()> {
+    cpal::CpalAudioOutput::check_device(device)
+}
+
+#[cfg(all(target_os = "linux", not(feature = "cpal-backend")))]
+pub fn try_open(
+    spec: SignalSpec,
+    duration: Duration,
+    device: Option<&str>,
+) -> Result<Box<dyn AudioOutput>> {
+    pulseaudio::PulseAudioOutput::try_open(spec, duration, device)
+}
+
+#[cfg(any(not(target_os = "linux"), feature = "cpal-backend"))]
+pub fn try_open(
+    spec: SignalSpec,
+    duration: Duration,
+    device: Option<&str>,
+) -> Result<Box<dyn AudioOutput>> {
+    cpal::CpalAudioOutput::try_open(spec, duration, device)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::cell::RefCell;
+    use std::io::Cursor;
+    use std::rc::Rc;
+
+    use symphonia::core::audio::{AudioBuffer, AudioBufferRef, Channels, Layout, Signal, SignalSpec};
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::codecs::DecoderOptions;
+
+    use super::{AudioOutput, ChunkedOutput, ConvertedOutput, DeviceConfig, Result, WavOutput};
+    use super::coerce_spec;
+    use crate::tests::probe;
+
+    /// An audio output that records the left channel of every write.
+    struct RecordingOutput {
+        writes: Rc<RefCell<Vec<Vec<f32>>>>,
+    }
+
+    impl AudioOutput for RecordingOutput {
+        fn write(&mut self, decoded: AudioBufferRef<'_>, n_frames: usize) -> Result<()> {
+            match decoded {
+                AudioBufferRef::F32(buf) => {
+                    self.writes.borrow_mut().push(buf.chan(0)[..n_frames].to_vec())
+                }
+                _ => panic!("chunks are not f32"),
+            }
+            Ok(())
+        }
+
+        fn flush(&mut self) { }
+    }
+
+    #[test]
+    fn verify_chunked_output() {
+        let spec = SignalSpec::new_with_layout(48_000, Layout::Stereo);
+
+        let writes = Rc::new(RefCell::new(Vec::new()));
+        let inner = Box::new(RecordingOutput { writes: writes.clone() });
+
+        let mut output = ChunkedOutput::new(inner, spec, 4096);
+
+        // Write 40 buffers of 256 frames, containing an increasing ramp of i16 samples.
+        let mut buf = AudioBuffer::<i16>::new(256, spec);
+
+        for i in 0..40 {
+            buf.clear();
+            buf.render(None, |planes, idx| {
+                let frame = (256 * i + idx) as i16;
+                planes.planes()[0][idx] = frame;
+                planes.planes()[1][idx] = -frame;
+                Ok(())
+            })
+            .unwrap();
+
+            output.write(AudioBufferRef::S16(Cow::Borrowed(&buf)), 256).unwrap();
+        }
+
+        // Only full chunks are written until the output is flushed.
+        assert_eq!(writes.borrow().len(), 2);
+
+        output.flush();
+
+        let writes = writes.borrow();
+
+        let lens = writes.iter().map(|w| w.len()).collect::<Vec<usize>>();
+        assert_eq!(lens, [4096, 4096, 2048]);
+
+        // All frames are written in order.
+        for (i, &s) in writes.iter().flatten().enumerate() {
+            assert_eq!(s, i as f32 / 32_768.0);
+        }
+    }
+
+    #[test]
+    fn verify_resampled_output() {
+        let spec = SignalSpec::new_with_layout(44_100, Layout::Stereo);
+
+        let writes = Rc::new(RefCell::new(Vec::new()));
+        let inner = Box::new(RecordingOutput { writes: writes.clone() });
+
+        let out_spec = SignalSpec::new_with_layout(48_000, Layout::Stereo);
+
+        let mut output = ConvertedOutput::try_new(inner, spec, out_spec).unwrap();
+
+        // Write 1 second of audio in buffers of 441 frames, only the first 400 frames of the last
+        // buffer are written.
+        let mut buf = AudioBuffer::<i16>::new(441, spec);
+        buf.render(None, |_, _| Ok(())).unwrap();
+
+        for i in 0..100 {
+            let n_frames = if i < 99 { 441 } else { 400 };
+            output.write(AudioBufferRef::S16(Cow::Borrowed(&buf)), n_frames).unwrap();
+        }
+
+        output.flush();
+
+        let writes = writes.borrow();
+
+        // No write exceeds the maximum output duration.
+        let max_len = ConvertedOutput::out_duration(spec, out_spec, 441) as usize;
+        assert!(writes.iter().all(|w| w.len() <= max_len));
+
+        // Once flushed, the frames retained by the resampler are also written.
+        let expected = ((44_100 - 41) * 48_000_usize).div_ceil(44_100);
+        let n_frames = writes.iter().map(|w| w.len()).sum::<usize>();
+
+        assert_eq!(n_frames, expected);
+    }
+
+    #[test]
+    fn verify_multichannel_wav_output() {
+        let spec = SignalSpec::new_with_layout(48_000, Layout::FivePointOne);
+
+        // Every sample of a channel is the channel's index plus 1.
+        let mut buf = AudioBuffer::<i16>::new(100, spec);
+        buf.render(None, |planes, idx| {
+            for (ch, plane) in planes.planes().iter_mut().enumerate() {
+                plane[idx] = 1 + ch as i16;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        let mut wav = Vec::new();
+
+        let mut output = WavOutput::try_new(Cursor::new(&mut wav), spec, 100, 16).unwrap();
+        output.write(AudioBufferRef::S16(Cow::Borrowed(&buf)), 100).unwrap();
+        output.flush();
+        drop(output);
+
+        // The extensible format is used, with the channel mask of 5.1 audio.
+        assert_eq!(wav.len(), 68 + 100 * 6 * 2);
+        assert_eq!(&wav[20..22], &0xfffe_u16.to_le_bytes());
+        assert_eq!(&wav[40..44], &0x3f_u32.to_le_bytes());
+
+        // Reading the file back preserves the channels, and their order.
+        let mut probed = probe(wav);
+
+        let params = probed.format.default_track().unwrap().codec_params.clone();
+        assert_eq!(params.channels, Some(spec.channels));
+
+        let mut decoder = symphonia::default::get_codecs()
+            .make(&params, &DecoderOptions::default())
+            .unwrap();
+
+        let packet = probed.format.next_packet().unwrap();
+        let decoded = decoder.decode(&packet).unwrap();
+
+        let n_frames = decoded.frames();
+        assert_eq!(n_frames, 100);
+
+        let mut samples = SampleBuffer::<i16>::new(n_frames as u64, *decoded.spec());
+        samples.copy_planar_ref(decoded);
+
+        for (ch, plane) in samples.samples().chunks(n_frames).enumerate() {
+            assert!(plane.iter().all(|&s| s == 1 + ch as i16));
+        }
+    }
+
+    #[test]
+    fn verify_coerce_spec() {
+        let spec = SignalSpec::new_with_layout(96_000, Layout::FivePointOne);
+        let stereo = Channels::FRONT_LEFT | Channels::FRONT_RIGHT;
+
+        // A stereo device at 44.1 kHz, or 48 kHz.
+        let configs = [
+            DeviceConfig { n_channels: 2, min_rate: 44_100, max_rate: 44_100 },
+            DeviceConfig { n_channels: 2, min_rate: 48_000, max_rate: 48_000 },
+        ];
+
+        // The 5.1 audio is downmixed to stereo, and resampled to the closest sample rate.
+        let (idx, out_spec) = coerce_spec(spec, &configs).unwrap();
+        assert_eq!(idx, 1);
+        assert_eq!(out_spec, SignalSpec::new(48_000, stereo));
+
+        // A device supporting 5.1 audio within a range of sample rates plays it natively.
+        let configs = [
+            DeviceConfig { n_channels: 2, min_rate: 8_000, max_rate: 192_000 },
+            DeviceConfig { n_channels: 6, min_rate: 8_000, max_rate: 192_000 },
+        ];
+
+        let (idx, out_spec) = coerce_spec(spec, &configs).unwrap();
+        assert_eq!(idx, 1);
+        assert_eq!(out_spec, spec);
+
+        // Mono audio is upmixed to a stereo device.
+        let mono = SignalSpec::new_with_layout(48_000, Layout::Mono);
+        let configs = [DeviceConfig { n_channels: 2, min_rate: 48_000, max_rate: 48_000 }];
+
+        assert_eq!(coerce_spec(mono, &configs).unwrap().1, SignalSpec::new(48_000, stereo));
+
+        // Audio without channels cannot be played.
+        let empty = SignalSpec::new(48_000, Channels::empty());
+        assert!(coerce_spec(empty, &configs).is_none());
+    }
 }
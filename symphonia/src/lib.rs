@@ -26,6 +26,7 @@
 //! |----------|--------------|---------|
 //! | ISO/MP4  | `isomp4`     | No      |
 //! | OGG      | `ogg`        | Yes     |
+//! | Raw PCM  | `pcm`        | Yes     |
 //! | Wave     | `wav`        | Yes     |
 //!
 //! ## Codecs
@@ -127,16 +128,20 @@ pub mod default {
         pub use symphonia_format_isomp4::IsoMp4Reader as IsoMp4Reader;
         #[cfg(feature = "mp3")]
         pub use symphonia_bundle_mp3::Mp3Reader as Mp3Reader;
+        #[cfg(feature = "pcm")]
+        pub use symphonia_codec_pcm::RawPcmReader as RawPcmReader;
         #[cfg(feature = "wav")]
         pub use symphonia_format_wav::WavReader as WavReader;
         #[cfg(feature = "ogg")]
         pub use symphonia_format_ogg::OggReader as OggReader;
     }
 
+    use std::borrow::Cow;
+
     use lazy_static::lazy_static;
 
     use symphonia_core::probe::Probe;
-    use symphonia_core::codecs::CodecRegistry;
+    use symphonia_core::codecs::{CodecRegistry, CodecType};
 
     lazy_static! {
         static ref CODEC_REGISTRY: CodecRegistry = {
@@ -174,6 +179,19 @@ pub mod default {
         &PROBE
     }
 
+    /// Gets the short name of a codec from the default `CodecRegistry`. If the codec is not
+    /// registered, the hexadecimal form of the `CodecType` (e.g., `CodecType(0x2001)`) is returned
+    /// instead.
+    ///
+    /// A `CodecType` cannot print its own name since the name is only known to the `Decoder` that
+    /// registered it.
+    pub fn codec_short_name(codec: CodecType) -> Cow<'static, str> {
+        match get_codecs().get_codec(codec) {
+            Some(descriptor) => Cow::Borrowed(descriptor.short_name),
+            None => Cow::Owned(format!("{:?}", codec)),
+        }
+    }
+
     /// Registers all the codecs selected by the `feature` flags in the includer's `Cargo.toml` on
     /// the provided `CodecRegistry`. If `features` is not set, the default set of Symphonia codecs
     /// is registered.
@@ -214,6 +232,9 @@ pub mod default {
         #[cfg(feature = "mp3")]
         probe.register_all::<formats::Mp3Reader>();
 
+        #[cfg(feature = "pcm")]
+        probe.register_all::<formats::RawPcmReader>();
+
         #[cfg(feature = "wav")]
         probe.register_all::<formats::WavReader>();
 
@@ -225,4 +246,19 @@ pub mod default {
     }
 }
 
-pub use symphonia_core as core;
\ No newline at end of file
+pub use symphonia_core as core;
+
+#[cfg(test)]
+mod tests {
+    use symphonia_core::codecs::{CODEC_TYPE_FLAC, CODEC_TYPE_WAVPACK};
+
+    use super::default::codec_short_name;
+
+    #[test]
+    fn verify_codec_short_name() {
+        assert_eq!(codec_short_name(CODEC_TYPE_FLAC), "flac");
+        // WavPack has no decoder, so it cannot be named.
+        assert_eq!(codec_short_name(CODEC_TYPE_WAVPACK), "CodecType(0x2001)");
+        assert_eq!(format!("{:?}", CODEC_TYPE_FLAC), "CodecType(0x2000)");
+    }
+}